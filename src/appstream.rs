@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::blocking::Client;
+use reqwest::header::USER_AGENT;
+
+use crate::spotlight::spotlight_cache_dir;
+
+const CATALOG_DIRS: &[&str] = &[
+    "/usr/share/swcatalog/xml",
+    "/var/lib/swcatalog/xml",
+    "/usr/share/app-info/xmls",
+    "/var/cache/app-info/xmls",
+];
+const SCREENSHOT_CACHE_DIR: &str = "screenshots";
+const ICON_CACHE_DIR: &str = "icons";
+const HTTP_TIMEOUT_SECS: u64 = 15;
+const MAX_SCREENSHOTS: usize = 4;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct AppStreamComponent {
+    pub id: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub screenshots: Vec<String>,
+    pub icon_url: Option<String>,
+}
+
+static CATALOG: Lazy<HashMap<String, AppStreamComponent>> = Lazy::new(load_catalog);
+
+static COMPONENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<component\b[^>]*>(.*?)</component>").unwrap());
+static ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<id>\s*([^<]+?)\s*</id>").unwrap());
+static PKGNAME_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<pkgname>\s*([^<]+?)\s*</pkgname>").unwrap());
+static SUMMARY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<summary>\s*(.*?)\s*</summary>").unwrap());
+static DESCRIPTION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<description>(.*?)</description>").unwrap());
+static SCREENSHOT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<screenshot\b[^>]*>(.*?)</screenshot>").unwrap());
+static IMAGE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)<image\b[^>]*\btype="(thumbnail|source)"[^>]*>\s*([^<]+?)\s*</image>"#)
+        .unwrap()
+});
+static REMOTE_ICON_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)<icon\b[^>]*\btype="remote"[^>]*>\s*([^<]+?)\s*</icon>"#).unwrap()
+});
+static TAG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<[^>]+>").unwrap());
+
+/// Returns the AppStream component shipped for `package`, if the system
+/// catalog describes one.
+pub(crate) fn lookup(package: &str) -> Option<AppStreamComponent> {
+    CATALOG.get(package).cloned()
+}
+
+/// Maps an AppStream component ID (e.g. `org.mozilla.firefox`) to the
+/// package that ships it. A trailing `.desktop` is ignored on both sides.
+pub(crate) fn resolve_component_id(id: &str) -> Option<String> {
+    let wanted = id.trim().trim_end_matches(".desktop");
+    if wanted.is_empty() {
+        return None;
+    }
+    CATALOG
+        .iter()
+        .find(|(_, component)| {
+            component
+                .id
+                .as_deref()
+                .is_some_and(|id| id.trim_end_matches(".desktop").eq_ignore_ascii_case(wanted))
+        })
+        .map(|(name, _)| name.clone())
+}
+
+/// Parses the system catalog if that hasn't happened yet. This runs a gzip
+/// subprocess and a regex pass over every file, so call it from a worker.
+pub(crate) fn load_catalog_now() {
+    Lazy::force(&CATALOG);
+}
+
+/// Returns the remote icon URL the catalog lists for `package`, without
+/// cloning the rest of the component. Until [`load_catalog_now`] has
+/// finished this returns `None` rather than parsing the catalog on the
+/// caller's thread.
+pub(crate) fn icon_url(package: &str) -> Option<&'static str> {
+    Lazy::get(&CATALOG)?.get(package)?.icon_url.as_deref()
+}
+
+/// Downloads a screenshot into the cache directory, reusing an earlier copy
+/// when one exists.
+pub(crate) fn fetch_screenshot(url: &str) -> Result<PathBuf, String> {
+    fetch_cached(url, SCREENSHOT_CACHE_DIR, "screenshot")
+}
+
+/// Downloads a remote icon into the cache directory, reusing an earlier copy
+/// when one exists.
+pub(crate) fn fetch_icon(url: &str) -> Result<PathBuf, String> {
+    fetch_cached(url, ICON_CACHE_DIR, "icon")
+}
+
+/// Path of a previously downloaded icon, if it is already on disk.
+pub(crate) fn cached_icon_path(url: &str) -> Option<PathBuf> {
+    let path = spotlight_cache_dir()?
+        .join(ICON_CACHE_DIR)
+        .join(cache_file_name(url));
+    path.is_file().then_some(path)
+}
+
+fn fetch_cached(url: &str, subdir: &str, kind: &str) -> Result<PathBuf, String> {
+    let dir = spotlight_cache_dir()
+        .map(|dir| dir.join(subdir))
+        .ok_or_else(|| "Unable to determine the cache directory.".to_string())?;
+    let path = dir.join(cache_file_name(url));
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    fs::create_dir_all(&dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+        .build()
+        .map_err(|err| format!("Failed to build HTTP client: {}", err))?;
+    let response = client
+        .get(url)
+        .header(
+            USER_AGENT,
+            "Nebula (https://github.com/Letdown2491/nebula-gtk)",
+        )
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| format!("Failed to download {}: {}", kind, err))?;
+    let bytes = response
+        .bytes()
+        .map_err(|err| format!("Failed to read {}: {}", kind, err))?;
+
+    let partial = path.with_extension("part");
+    fs::write(&partial, &bytes)
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(|err| format!("Failed to store {}: {}", kind, err))?;
+    Ok(path)
+}
+
+fn load_catalog() -> HashMap<String, AppStreamComponent> {
+    let mut catalog = HashMap::new();
+    for dir in CATALOG_DIRS {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let Some(content) = read_catalog_file(&path) else {
+                continue;
+            };
+            for (name, component) in parse_catalog(&content) {
+                catalog.entry(name).or_insert(component);
+            }
+        }
+    }
+    catalog
+}
+
+fn read_catalog_file(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    if name.ends_with(".xml.gz") {
+        let output = Command::new("gzip").arg("-dc").arg(path).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout).ok()
+    } else if name.ends_with(".xml") {
+        fs::read_to_string(path).ok()
+    } else {
+        None
+    }
+}
+
+fn parse_catalog(content: &str) -> Vec<(String, AppStreamComponent)> {
+    COMPONENT_RE
+        .captures_iter(content)
+        .filter_map(|caps| {
+            let body = caps.get(1)?.as_str();
+            let name = decode_entities(PKGNAME_RE.captures(body)?.get(1)?.as_str());
+            if name.is_empty() {
+                return None;
+            }
+
+            let id = ID_RE
+                .captures(body)
+                .and_then(|caps| caps.get(1))
+                .map(|value| decode_entities(value.as_str()));
+            let summary = SUMMARY_RE
+                .captures(body)
+                .and_then(|caps| caps.get(1))
+                .map(|value| decode_entities(value.as_str()))
+                .filter(|value| !value.is_empty());
+            let description = DESCRIPTION_RE
+                .captures(body)
+                .and_then(|caps| caps.get(1))
+                .and_then(|value| description_to_text(value.as_str()));
+            let screenshots = SCREENSHOT_RE
+                .captures_iter(body)
+                .filter_map(|caps| preferred_image(caps.get(1)?.as_str()))
+                .take(MAX_SCREENSHOTS)
+                .collect();
+            let icon_url = REMOTE_ICON_RE
+                .captures(body)
+                .and_then(|caps| caps.get(1))
+                .map(|value| decode_entities(value.as_str()))
+                .filter(|value| !value.is_empty());
+
+            Some((
+                name,
+                AppStreamComponent {
+                    id,
+                    summary,
+                    description,
+                    screenshots,
+                    icon_url,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn preferred_image(screenshot: &str) -> Option<String> {
+    let mut source = None;
+    for caps in IMAGE_RE.captures_iter(screenshot) {
+        let url = decode_entities(&caps[2]);
+        if &caps[1] == "thumbnail" {
+            return Some(url);
+        }
+        source.get_or_insert(url);
+    }
+    source
+}
+
+fn description_to_text(markup: &str) -> Option<String> {
+    let collapsed = markup.split_whitespace().collect::<Vec<_>>().join(" ");
+    let spaced = collapsed
+        .replace("</p>", "\n\n")
+        .replace("<li>", "\n• ")
+        .replace("</ul>", "\n\n")
+        .replace("</ol>", "\n\n");
+    let stripped = TAG_RE.replace_all(&spaced, "");
+    let paragraphs = stripped
+        .split("\n\n")
+        .map(|paragraph| {
+            paragraph
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| decode_entities(&paragraph))
+        .collect::<Vec<_>>();
+
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn cache_file_name(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    let extension = Path::new(url.split('?').next().unwrap_or(url))
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.len() <= 4)
+        .unwrap_or("png");
+    format!("{:016x}.{}", hasher.finish(), extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_component_keyed_by_pkgname() {
+        let xml = r#"<components>
+  <component type="desktop-application">
+    <id>org.example.Viewer</id>
+    <pkgname>viewer</pkgname>
+    <icon type="stock">viewer</icon>
+    <icon type="remote" width="64" height="64">https://example.org/viewer.png</icon>
+    <summary>View images</summary>
+    <summary xml:lang="de">Bilder ansehen</summary>
+    <description>
+      <p>A small &amp; fast
+        image viewer.</p>
+      <ul><li>Zoom</li><li>Rotate</li></ul>
+    </description>
+    <screenshots>
+      <screenshot type="default">
+        <image type="source">https://example.org/full.png</image>
+        <image type="thumbnail" width="624">https://example.org/thumb.png</image>
+      </screenshot>
+      <screenshot>
+        <image type="source">https://example.org/second.png</image>
+      </screenshot>
+    </screenshots>
+  </component>
+  <component type="font"><id>no.pkgname</id></component>
+</components>"#;
+
+        let parsed = parse_catalog(xml);
+        assert_eq!(parsed.len(), 1);
+        let (name, component) = &parsed[0];
+        assert_eq!(name, "viewer");
+        assert_eq!(component.id.as_deref(), Some("org.example.Viewer"));
+        assert_eq!(component.summary.as_deref(), Some("View images"));
+        assert_eq!(
+            component.description.as_deref(),
+            Some("A small & fast image viewer.\n\n• Zoom\n• Rotate")
+        );
+        assert_eq!(
+            component.screenshots,
+            vec![
+                "https://example.org/thumb.png".to_string(),
+                "https://example.org/second.png".to_string(),
+            ]
+        );
+        assert_eq!(
+            component.icon_url.as_deref(),
+            Some("https://example.org/viewer.png")
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::ffi::OsString;
+
+use gtk4::glib;
+use serde_json::{Value, json};
+
+use crate::types::PackageInfo;
+use crate::xbps::{run_xbps_check_updates, run_xbps_list_installed};
+
+enum ListQuery {
+    Updates,
+    Installed,
+}
+
+/// Handles the scripting flags (`--list-updates`, `--list-installed`,
+/// optionally with `--json`) without starting the GUI. Returns `None` when
+/// the arguments are meant for the application itself.
+pub(crate) fn run(arguments: &[OsString]) -> Option<glib::ExitCode> {
+    let mut query = None;
+    let mut json = false;
+    for arg in arguments.iter().skip(1).map(|arg| arg.to_string_lossy()) {
+        match arg.as_ref() {
+            "--list-updates" => query = Some(ListQuery::Updates),
+            "--list-installed" => query = Some(ListQuery::Installed),
+            "--json" => json = true,
+            _ => {}
+        }
+    }
+    let query = query?;
+
+    let result = match query {
+        ListQuery::Updates => run_xbps_check_updates(),
+        ListQuery::Installed => run_xbps_list_installed(),
+    };
+    let packages = match result {
+        Ok(packages) => packages,
+        Err(err) => {
+            if json {
+                println!("{}", json!({ "error": err }));
+            } else {
+                eprintln!("{err}");
+            }
+            return Some(glib::ExitCode::FAILURE);
+        }
+    };
+
+    if json {
+        let entries: Vec<Value> = packages.iter().map(package_json).collect();
+        match serde_json::to_string_pretty(&entries) {
+            Ok(text) => println!("{text}"),
+            Err(err) => {
+                eprintln!("Failed to serialize packages: {err}");
+                return Some(glib::ExitCode::FAILURE);
+            }
+        }
+    } else {
+        for pkg in &packages {
+            match (&query, pkg.previous_version.as_deref()) {
+                (ListQuery::Updates, Some(previous)) => {
+                    println!("{} {} -> {}", pkg.name, previous, pkg.version)
+                }
+                _ => println!("{} {}", pkg.name, pkg.version),
+            }
+        }
+    }
+    Some(glib::ExitCode::SUCCESS)
+}
+
+fn package_json(pkg: &PackageInfo) -> Value {
+    json!({
+        "name": pkg.name,
+        "version": pkg.version,
+        "previous_version": pkg.previous_version,
+        "description": pkg.description,
+        "installed": pkg.installed,
+        "pinned": pkg.pinned,
+        "download_size": pkg.download_size,
+        "download_bytes": pkg.download_bytes,
+        "repository": pkg.repository,
+    })
+}
@@ -1,4 +1,6 @@
-use crate::types::PackageInfo;
+use chrono::{DateTime, Utc};
+
+use crate::types::{PackageInfo, PackageSource};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct DiscoverDependency {
@@ -11,11 +13,18 @@ pub(crate) struct DiscoverDetail {
     pub description: Option<String>,
     pub download: Option<String>,
     pub download_bytes: Option<u64>,
+    /// Unpacked size of the package once installed.
+    pub installed_bytes: Option<u64>,
     pub repository: Option<String>,
     pub homepage: Option<String>,
     pub maintainer: Option<String>,
     pub license: Option<String>,
     pub dependencies: Vec<DiscoverDependency>,
+    pub summary: Option<String>,
+    pub screenshots: Vec<String>,
+    /// Repositories offering the package; more than one means the install
+    /// source can be chosen.
+    pub sources: Vec<PackageSource>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -27,6 +36,7 @@ pub(crate) struct InstalledDetail {
     pub homepage: Option<String>,
     pub maintainer: Option<String>,
     pub license: Option<String>,
+    pub install_date: Option<DateTime<Utc>>,
     pub required_by: Vec<String>,
     pub required_by_error: Option<String>,
 }
@@ -0,0 +1,67 @@
+const MATCH_SCORE: i32 = 10;
+const CONSECUTIVE_BONUS: i32 = 15;
+const BOUNDARY_BONUS: i32 = 10;
+const MAX_GAP_PENALTY: i32 = 5;
+const MAX_LEADING_PENALTY: i32 = 10;
+
+/// Scores `needle` as a subsequence of `haystack`, rewarding runs of
+/// consecutive characters and matches at word starts. Both strings are
+/// expected to be lowercase already. Returns `None` when not every character
+/// of `needle` appears in order, or when the match is too scattered to be
+/// useful (`min_per_char` is the average score each character must earn).
+pub(crate) fn subsequence_score(haystack: &str, needle: &str, min_per_char: i32) -> Option<i32> {
+    let mut needle_chars = needle.chars().filter(|ch| !ch.is_whitespace()).peekable();
+    needle_chars.peek()?;
+
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (index, ch) in haystack.chars().enumerate() {
+        let Some(&wanted) = needle_chars.peek() else {
+            break;
+        };
+        if ch == wanted {
+            score += MATCH_SCORE;
+            match last_match {
+                Some(last) if last + 1 == index => score += CONSECUTIVE_BONUS,
+                Some(last) => score -= ((index - last - 1) as i32).min(MAX_GAP_PENALTY),
+                None => score -= (index as i32).min(MAX_LEADING_PENALTY),
+            }
+            if previous.is_none_or(is_word_separator) {
+                score += BOUNDARY_BONUS;
+            }
+            matched += 1;
+            last_match = Some(index);
+            needle_chars.next();
+        }
+        previous = Some(ch);
+    }
+
+    if needle_chars.peek().is_some() || score < matched * min_per_char {
+        return None;
+    }
+    Some(score)
+}
+
+fn is_word_separator(ch: char) -> bool {
+    matches!(ch, '-' | '_' | '.' | ' ' | '/' | '+')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_tight_matches_above_scattered_ones() {
+        let tight = subsequence_score("firefox", "firefx", 12).unwrap();
+        let loose = subsequence_score("fire-and-flex", "firefx", 0).unwrap();
+        assert!(tight > loose);
+        assert_eq!(subsequence_score("firefox", "foxfire", 0), None);
+        assert_eq!(
+            subsequence_score("a long package description text", "lgx", 12),
+            None
+        );
+    }
+}
@@ -1,20 +1,148 @@
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use glib::prelude::Cast;
-use gtk::glib;
+use gtk::{gdk, gio, glib};
 use gtk4 as gtk;
 use libadwaita as adw;
 use libadwaita::prelude::*;
+use once_cell::sync::Lazy;
 
+use crate::appstream;
 use crate::categories::icon_resource_for_package;
 use crate::details::{DiscoverDetail, InstalledDetail};
+use crate::fuzzy;
+use crate::spotlight::parse_build_date_field;
 use crate::types::PackageInfo;
 use crate::xbps::{
-    format_download_size, format_size, query_package_metadata, query_pkgsize_bytes,
-    query_repo_package_info, run_xbps_query_dependencies, run_xbps_query_required_by,
+    format_download_size, format_size, query_package_metadata, query_package_sources,
+    query_pkgsize_bytes, query_repo_package_info, run_xbps_query_dependencies,
+    run_xbps_query_required_by, split_package_identifier, with_deadline,
 };
 
+thread_local! {
+    /// Textures already decoded for list rows, keyed by resource path or
+    /// cached icon file so large list rebuilds do not decode them again.
+    static ICON_TEXTURES: RefCell<HashMap<String, gdk::Texture>> = RefCell::new(HashMap::new());
+}
+
+/// Remote icons looked up this session. Rows consult this instead of the
+/// filesystem, so each icon is queued for download once; failed downloads
+/// are tried again after [`ICON_RETRY_AFTER`].
+static ICON_FILES: Lazy<Mutex<HashMap<String, IconFile>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Hands icon downloads to a fixed pool of [`ICON_WORKERS`] threads, so a
+/// large list rebuild doesn't start a thread per icon.
+static ICON_QUEUE: Lazy<mpsc::Sender<&'static str>> = Lazy::new(|| {
+    let (sender, receiver) = mpsc::channel::<&'static str>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    for _ in 0..ICON_WORKERS {
+        let receiver = Arc::clone(&receiver);
+        thread::spawn(move || {
+            loop {
+                let next = receiver
+                    .lock()
+                    .ok()
+                    .and_then(|receiver| receiver.recv().ok());
+                let Some(url) = next else {
+                    break;
+                };
+                let entry = match appstream::fetch_icon(url) {
+                    Ok(path) => IconFile::Cached(path),
+                    Err(_) => IconFile::Failed(Instant::now()),
+                };
+                if let Ok(mut files) = ICON_FILES.lock() {
+                    files.insert(url.to_string(), entry);
+                }
+            }
+        });
+    }
+    sender
+});
+
+const ICON_WORKERS: usize = 4;
+const ICON_RETRY_AFTER: Duration = Duration::from_secs(10 * 60);
+
+enum IconFile {
+    Cached(PathBuf),
+    Queued,
+    Failed(Instant),
+}
+
+/// Trims a package row for the compact list density: one subtitle line and
+/// a smaller icon. The padding comes from `.nebula-compact` in style.css.
+pub(crate) fn compact_package_row(row: &adw::ActionRow, icon: &gtk::Image) {
+    row.set_subtitle_lines(1);
+    icon.set_pixel_size(24);
+}
+
+/// Tag marking an installed package that has an update waiting.
+pub(crate) fn update_badge() -> gtk::Label {
+    let badge = gtk::Label::new(Some("Update"));
+    badge.add_css_class("tag");
+    badge.add_css_class("accent");
+    badge.set_valign(gtk::Align::Center);
+    badge.set_tooltip_text(Some("A newer version is available."));
+    badge
+}
+
+/// Builds the icon shown next to a package in list rows. AppStream icons are
+/// used once the catalog is loaded and the icon is cached on disk; until
+/// then the bundled category icon is shown and the remote icon is fetched in
+/// the background for the next rebuild.
+pub(crate) fn package_icon(package: &str) -> gtk::Image {
+    let texture = appstream::icon_url(package)
+        .and_then(cached_or_fetch_icon)
+        .and_then(|path| {
+            let key = path.to_string_lossy().into_owned();
+            cached_texture(&key, || {
+                gdk::Texture::from_file(&gio::File::for_path(&path)).ok()
+            })
+        })
+        .or_else(|| {
+            let resource = icon_resource_for_package(package);
+            cached_texture(resource, || Some(gdk::Texture::from_resource(resource)))
+        });
+    gtk::Image::from_paintable(texture.as_ref())
+}
+
+fn cached_texture(key: &str, load: impl FnOnce() -> Option<gdk::Texture>) -> Option<gdk::Texture> {
+    if let Some(texture) = ICON_TEXTURES.with(|cache| cache.borrow().get(key).cloned()) {
+        return Some(texture);
+    }
+    let texture = load()?;
+    ICON_TEXTURES.with(|cache| {
+        cache.borrow_mut().insert(key.to_string(), texture.clone());
+    });
+    Some(texture)
+}
+
+fn cached_or_fetch_icon(url: &'static str) -> Option<PathBuf> {
+    let Ok(mut files) = ICON_FILES.lock() else {
+        return None;
+    };
+    match files.get(url) {
+        Some(IconFile::Cached(path)) => return Some(path.clone()),
+        Some(IconFile::Queued) => return None,
+        Some(IconFile::Failed(at)) if at.elapsed() < ICON_RETRY_AFTER => return None,
+        _ => {}
+    }
+    if let Some(path) = appstream::cached_icon_path(url) {
+        files.insert(url.to_string(), IconFile::Cached(path.clone()));
+        return Some(path);
+    }
+    files.insert(url.to_string(), IconFile::Queued);
+    drop(files);
+    let _ = ICON_QUEUE.send(url);
+    None
+}
+
 pub(crate) fn clear_listbox(list: &gtk::ListBox) {
     while let Some(child) = list.first_child() {
         list.remove(&child);
@@ -64,6 +192,22 @@ pub(crate) fn set_download_label(
     }
 }
 
+/// Names the first few packages for a dialog and counts the rest.
+pub(crate) fn summarize_package_list(packages: &[String]) -> String {
+    const SHOWN: usize = 5;
+    let list = packages
+        .iter()
+        .take(SHOWN)
+        .map(|pkg| pkg.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if packages.len() > SHOWN {
+        format!("{}, and {} more", list, packages.len() - SHOWN)
+    } else {
+        list
+    }
+}
+
 pub(crate) fn format_relative_time(timestamp: DateTime<Utc>) -> String {
     let now = Utc::now();
     let delta = now.signed_duration_since(timestamp);
@@ -154,15 +298,111 @@ pub(crate) fn set_link_label(label: &gtk::Label, url: Option<&str>) {
     }
 }
 
-pub(crate) fn package_matches_filter(pkg: &PackageInfo, filter_lower: &str) -> bool {
+pub(crate) fn build_copy_link_button() -> gtk::Button {
+    let button = gtk::Button::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text("Copy link")
+        .valign(gtk::Align::Start)
+        .build();
+    button.add_css_class("flat");
+    button.set_focus_on_click(false);
+    button
+}
+
+pub(crate) fn build_template_link_button() -> gtk::Button {
+    let button = gtk::Button::builder()
+        .icon_name("text-x-script-symbolic")
+        .tooltip_text("View build template on GitHub")
+        .has_frame(false)
+        .build();
+    button.add_css_class("flat");
+    button.set_focus_on_click(false);
+    button.set_valign(gtk::Align::Center);
+    button
+}
+
+pub(crate) fn build_copy_command_button() -> gtk::Button {
+    let button = gtk::Button::builder()
+        .icon_name("utilities-terminal-symbolic")
+        .tooltip_text("Copy equivalent command")
+        .has_frame(false)
+        .build();
+    button.add_css_class("flat");
+    button.set_focus_on_click(false);
+    button.set_valign(gtk::Align::Center);
+    button
+}
+
+/// Links to the template `package` is built from in void-packages. `source`
+/// is its `sourcepkg`; subpackages need it, since GitHub doesn't follow the
+/// srcpkgs symlinks that point them at the main template.
+pub(crate) fn package_template_url(package: &str, source: Option<&str>) -> String {
+    let name = source
+        .map(str::trim)
+        .filter(|source| !source.is_empty())
+        .unwrap_or(package);
+    format!(
+        "https://github.com/void-linux/void-packages/tree/master/srcpkgs/{}",
+        template_name(name)
+    )
+}
+
+/// Strips a suffix that looks like an xbps "version_revision".
+fn template_name(package: &str) -> String {
+    let (name, version) = split_package_identifier(package);
+    if version.contains('_') {
+        name
+    } else {
+        package.to_string()
+    }
+}
+
+/// Full name, version and description for a list row whose title and
+/// subtitle may be truncated.
+pub(crate) fn package_row_tooltip(pkg: &PackageInfo) -> String {
+    let mut tooltip = if pkg.version.is_empty() {
+        pkg.name.clone()
+    } else {
+        format!("{} {}", pkg.name, pkg.version)
+    };
+    if !pkg.description.is_empty() {
+        tooltip.push('\n');
+        tooltip.push_str(&pkg.description);
+    }
+    tooltip
+}
+
+/// Ranks how well a package matches a lowercase filter; higher is better and
+/// `None` means it should be hidden. Exact and substring hits on the name
+/// come first, then description and version hits, then fuzzy matches.
+pub(crate) fn package_match_score(pkg: &PackageInfo, filter_lower: &str) -> Option<i32> {
     let needle = filter_lower.trim();
     if needle.is_empty() {
-        return true;
+        return Some(0);
     }
 
-    pkg.name_lower.contains(needle)
-        || pkg.version_lower.contains(needle)
-        || pkg.description_lower.contains(needle)
+    let name = &*pkg.name_lower;
+    if name == needle {
+        return Some(1000);
+    }
+    if name.starts_with(needle) {
+        return Some(900);
+    }
+    if name.contains(needle) {
+        return Some(800);
+    }
+    if pkg.description_lower.contains(needle) {
+        return Some(600);
+    }
+    if pkg.version_lower.contains(needle) {
+        return Some(500);
+    }
+
+    // Descriptions are long, so only near-contiguous fuzzy matches count.
+    if let Some(score) = fuzzy::subsequence_score(name, needle, 12) {
+        return Some(200 + score.min(299));
+    }
+    fuzzy::subsequence_score(&pkg.description_lower, needle, 20).map(|score| score.min(199))
 }
 
 pub(crate) fn query_installed_detail(
@@ -192,10 +432,54 @@ pub(crate) fn query_installed_detail(
     detail.homepage = metadata.homepage;
     detail.maintainer = metadata.maintainer;
     detail.license = metadata.license;
+    detail.install_date = metadata
+        .install_date
+        .as_deref()
+        .and_then(parse_build_date_field);
 
     Ok(detail)
 }
 
+/// How long a detail pane waits for its package queries before giving up.
+const DETAIL_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Extra wait for a fetch to return once its queries have been killed.
+const DETAIL_FETCH_GRACE: Duration = Duration::from_secs(2);
+
+/// Error recorded for a detail fetch that ran past `DETAIL_FETCH_TIMEOUT`.
+pub(crate) const DETAIL_TIMEOUT_ERROR: &str = "timed out";
+
+/// Runs a detail query on its own thread with its xbps queries bounded by
+/// `DETAIL_FETCH_TIMEOUT`. A query still running at the timeout is killed
+/// and the fetch fails with `DETAIL_TIMEOUT_ERROR`.
+pub(crate) fn fetch_detail_with_timeout<T, F>(fetch: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let started = Instant::now();
+        let result = with_deadline(DETAIL_FETCH_TIMEOUT, fetch);
+        // Some lookups fall back to defaults when a query fails, so a
+        // killed query doesn't always surface as an error.
+        let result = if started.elapsed() >= DETAIL_FETCH_TIMEOUT {
+            Err(DETAIL_TIMEOUT_ERROR.to_string())
+        } else {
+            result
+        };
+        let _ = tx.send(result);
+    });
+
+    match rx.recv_timeout(DETAIL_FETCH_TIMEOUT + DETAIL_FETCH_GRACE) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(DETAIL_TIMEOUT_ERROR.to_string()),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err("detail query stopped unexpectedly".to_string())
+        }
+    }
+}
+
 pub(crate) fn query_discover_detail(package: &str) -> Result<DiscoverDetail, String> {
     let info = query_repo_package_info(package)?;
     let dependency_names = run_xbps_query_dependencies(package)
@@ -206,6 +490,7 @@ pub(crate) fn query_discover_detail(package: &str) -> Result<DiscoverDetail, Str
 
     let mut detail = DiscoverDetail::with_dependencies(&info, dependency_names);
     detail.download_bytes = info.download_bytes;
+    detail.installed_bytes = query_pkgsize_bytes(package).ok().flatten();
     detail.download = info
         .download_size
         .clone()
@@ -219,6 +504,15 @@ pub(crate) fn query_discover_detail(package: &str) -> Result<DiscoverDetail, Str
     detail.maintainer = metadata.maintainer;
     detail.license = metadata.license;
     detail.repository = metadata.repository.or(info.repository.clone());
+    detail.sources = query_package_sources(package).unwrap_or_default();
+
+    if let Some(component) = appstream::lookup(package) {
+        if component.description.is_some() {
+            detail.description = component.description;
+        }
+        detail.summary = component.summary;
+        detail.screenshots = component.screenshots;
+    }
 
     Ok(detail)
 }
@@ -246,7 +540,7 @@ fn build_spotlight_row(pkg: &PackageInfo) -> adw::ActionRow {
     row.set_focusable(true);
     row.set_tooltip_text(Some("Open details for this package."));
 
-    let icon = gtk::Image::from_resource(icon_resource_for_package(&pkg.name));
+    let icon = package_icon(&pkg.name);
     icon.set_pixel_size(28);
     icon.set_margin_end(12);
     icon.set_valign(gtk::Align::Center);
@@ -283,3 +577,39 @@ fn build_spotlight_row(pkg: &PackageInfo) -> adw::ActionRow {
 
     row
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRCPKGS: &str = "https://github.com/void-linux/void-packages/tree/master/srcpkgs/";
+
+    #[test]
+    fn template_url_uses_package_name() {
+        assert_eq!(package_template_url("xbps", None), format!("{SRCPKGS}xbps"));
+        assert_eq!(
+            package_template_url("xbps-0.60.5_1", None),
+            format!("{SRCPKGS}xbps")
+        );
+        assert_eq!(
+            package_template_url("gtk4-devel", None),
+            format!("{SRCPKGS}gtk4-devel")
+        );
+    }
+
+    #[test]
+    fn template_url_follows_source_package() {
+        assert_eq!(
+            package_template_url("gtk4-devel", Some("gtk4-4.18.6_1\n")),
+            format!("{SRCPKGS}gtk4")
+        );
+        assert_eq!(
+            package_template_url("libxbps", Some("xbps")),
+            format!("{SRCPKGS}xbps")
+        );
+        assert_eq!(
+            package_template_url("xbps", Some("  ")),
+            format!("{SRCPKGS}xbps")
+        );
+    }
+}
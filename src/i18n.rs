@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+use gettextrs::{LocaleCategory, bind_textdomain_codeset, bindtextdomain, setlocale, textdomain};
+
+const GETTEXT_PACKAGE: &str = "nebula";
+
+/// Where compiled catalogs are installed. Packagers can point this at a
+/// different prefix at build time.
+const LOCALE_DIR: &str = match option_env!("NEBULA_LOCALEDIR") {
+    Some(dir) => dir,
+    None => "/usr/share/locale",
+};
+
+/// Selects the user's locale and binds Nebula's message catalog. Without an
+/// installed catalog every lookup returns the English literal unchanged.
+pub(crate) fn init() {
+    setlocale(LocaleCategory::LcAll, "");
+    if let Err(err) = bindtextdomain(GETTEXT_PACKAGE, LOCALE_DIR) {
+        eprintln!("Failed to bind translations: {}", err);
+        return;
+    }
+    let _ = bind_textdomain_codeset(GETTEXT_PACKAGE, "UTF-8");
+    let _ = textdomain(GETTEXT_PACKAGE);
+}
+
+/// Translates a user-facing string. Extra arguments replace the `{}`
+/// placeholders of the translated text in order, so translators may move
+/// them but not reorder them.
+macro_rules! tr {
+    ($msgid:literal) => {
+        gettextrs::gettext($msgid)
+    };
+    ($msgid:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            gettextrs::gettext($msgid),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+/// Plural-aware `tr!`: picks the form for `n` and fills its placeholders.
+macro_rules! trn {
+    ($singular:literal, $plural:literal, $n:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            gettextrs::ngettext($singular, $plural, $n as u32),
+            &[$(&$arg as &dyn std::fmt::Display),+],
+        )
+    };
+}
+
+pub(crate) use tr;
+pub(crate) use trn;
+
+pub(crate) fn fill(template: String, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template.as_str();
+    while let Some(pos) = rest.find("{}") {
+        result.push_str(&rest[..pos]);
+        match args.next() {
+            Some(arg) => result.push_str(&arg.to_string()),
+            None => result.push_str("{}"),
+        }
+        rest = &rest[pos + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_in_order() {
+        let text = fill("{} of {} packages".to_string(), &[&3, &"12"]);
+        assert_eq!(text, "3 of 12 packages");
+    }
+
+    #[test]
+    fn keeps_placeholders_without_arguments() {
+        assert_eq!(fill("{} left".to_string(), &[]), "{} left");
+    }
+}
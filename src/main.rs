@@ -1,15 +1,23 @@
+mod appstream;
 mod categories;
+mod cli;
 mod details;
+mod fuzzy;
 mod helpers;
+mod i18n;
 mod mirrors;
+mod restart_check;
 mod settings;
 mod spotlight;
 mod state;
 mod types;
 mod ui;
+mod update_history;
 mod waypoint;
 mod xbps;
 
+use std::ffi::OsString;
+
 use adw::prelude::*;
 use gtk4::gio;
 use gtk4::glib;
@@ -20,14 +28,75 @@ use crate::ui::build_ui;
 const APP_ID: &str = "tech.geektoshi.Nebula";
 
 fn main() -> glib::ExitCode {
+    // Scripting flags answer on stdout and exit before any GUI setup.
+    let arguments: Vec<OsString> = std::env::args_os().collect();
+    if let Some(code) = cli::run(&arguments) {
+        return code;
+    }
+
+    i18n::init();
     adw::init().expect("Failed to initialize libadwaita");
 
     let app = adw::Application::builder()
         .application_id(APP_ID)
-        .flags(gio::ApplicationFlags::FLAGS_NONE)
+        .flags(gio::ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
-    app.connect_activate(build_ui);
+    app.connect_activate(|app| {
+        // A second launch activates the running instance; reuse its window.
+        if let Some(window) = app.active_window() {
+            window.present();
+        } else {
+            build_ui(app);
+        }
+    });
+
+    app.connect_command_line(|app, command_line| {
+        let target = match launch_target(&command_line.arguments()) {
+            Ok(target) => target,
+            Err(err) => {
+                eprintln!("{err}");
+                return glib::ExitCode::FAILURE;
+            }
+        };
+
+        app.activate();
+        match target {
+            Some(LaunchTarget::Package(package)) => {
+                app.activate_action("show-package", Some(&package.to_variant()));
+            }
+            Some(LaunchTarget::Uri(uri)) => {
+                app.activate_action("open-uri", Some(&uri.to_variant()));
+            }
+            None => {}
+        }
+        glib::ExitCode::SUCCESS
+    });
 
     app.run()
 }
+
+enum LaunchTarget {
+    Package(String),
+    Uri(String),
+}
+
+/// Reads `--show <package>` (or `--show=<package>`) or a package URI such as
+/// `appstream://org.mozilla.firefox` from the command line.
+fn launch_target(arguments: &[OsString]) -> Result<Option<LaunchTarget>, String> {
+    let mut args = arguments.iter().skip(1).map(|arg| arg.to_string_lossy());
+    let mut target = None;
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--show=") {
+            target = Some(LaunchTarget::Package(value.to_string()));
+        } else if arg == "--show" {
+            let value = args
+                .next()
+                .ok_or_else(|| "--show requires a package name".to_string())?;
+            target = Some(LaunchTarget::Package(value.to_string()));
+        } else if !arg.starts_with('-') && arg.contains(':') {
+            target = Some(LaunchTarget::Uri(arg.to_string()));
+        }
+    }
+    Ok(target)
+}
@@ -1,6 +1,12 @@
 use once_cell::sync::Lazy;
+use reqwest::blocking::Client;
+use reqwest::header::USER_AGENT;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::Path;
 use std::process::Command;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 
 use crate::xbps::run_privileged_command;
 
@@ -63,10 +69,93 @@ const MIRRORS: &[MirrorDefinition] = &[
     },
 ];
 
+/// Optional repositories published alongside the main one on every mirror.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RepositoryClass {
+    Nonfree,
+    Multilib,
+    MultilibNonfree,
+    Debug,
+}
+
+impl RepositoryClass {
+    pub(crate) const ALL: [RepositoryClass; 4] = [
+        RepositoryClass::Nonfree,
+        RepositoryClass::Multilib,
+        RepositoryClass::MultilibNonfree,
+        RepositoryClass::Debug,
+    ];
+
+    pub(crate) fn title(self) -> &'static str {
+        match self {
+            RepositoryClass::Nonfree => "Nonfree",
+            RepositoryClass::Multilib => "Multilib",
+            RepositoryClass::MultilibNonfree => "Multilib nonfree",
+            RepositoryClass::Debug => "Debug symbols",
+        }
+    }
+
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            RepositoryClass::Nonfree => "Software with restrictive licenses.",
+            RepositoryClass::Multilib => "32-bit libraries for running i686 software.",
+            RepositoryClass::MultilibNonfree => "32-bit builds of nonfree software.",
+            RepositoryClass::Debug => "Debugging symbols for packages (-dbg).",
+        }
+    }
+
+    /// Path of the class below the mirror, nested under the main
+    /// repository of `arch` (e.g. `current/musl/nonfree`).
+    fn suffix(self, arch: &str) -> String {
+        let main = main_suffix(arch);
+        match self {
+            RepositoryClass::Nonfree => format!("{}/nonfree", main),
+            RepositoryClass::Multilib => format!("{}/multilib", main),
+            RepositoryClass::MultilibNonfree => format!("{}/multilib/nonfree", main),
+            RepositoryClass::Debug => format!("{}/debug", main),
+        }
+    }
+
+    /// Same names as the files shipped by the void-repo-* packages, so ours
+    /// in /etc/xbps.d take precedence over theirs in /usr/share/xbps.d.
+    fn config_file(self) -> &'static str {
+        match self {
+            RepositoryClass::Nonfree => "/etc/xbps.d/10-repository-nonfree.conf",
+            RepositoryClass::Multilib => "/etc/xbps.d/10-repository-multilib.conf",
+            RepositoryClass::MultilibNonfree => "/etc/xbps.d/10-repository-multilib-nonfree.conf",
+            RepositoryClass::Debug => "/etc/xbps.d/10-repository-debug.conf",
+        }
+    }
+
+    /// Whether xbps currently reads a file for this class, either one we
+    /// wrote or the one shipped by its void-repo-* package.
+    fn is_configured(self) -> bool {
+        let file = Path::new(self.config_file());
+        let shipped = file
+            .file_name()
+            .map(|name| Path::new(SHIPPED_CONFIG_DIR).join(name).exists())
+            .unwrap_or(false);
+        file.exists() || shipped
+    }
+
+    fn is_multilib(self) -> bool {
+        matches!(
+            self,
+            RepositoryClass::Multilib | RepositoryClass::MultilibNonfree
+        )
+    }
+}
+
 const MAIN_SUFFIX: &str = "current";
+const LATENCY_TIMEOUT_SECS: u64 = 10;
 const REPOSITORY_FILE: &str = "/etc/xbps.d/00-repository-main.conf";
+const SHIPPED_CONFIG_DIR: &str = "/usr/share/xbps.d";
 
 static ACTIVE_REPOSITORIES: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static ACTIVE_MIRROR_IDS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static ACTIVE_CLASSES: Lazy<RwLock<Vec<RepositoryClass>>> = Lazy::new(|| RwLock::new(Vec::new()));
+static NATIVE_ARCH: Lazy<String> = Lazy::new(detect_native_architecture);
 
 pub(crate) fn tier1_mirrors() -> Vec<&'static MirrorDefinition> {
     MIRRORS
@@ -109,6 +198,27 @@ pub(crate) fn repository_url(mirror: &MirrorDefinition, suffix: &str) -> String
     format!("{}/{}", base, suffix)
 }
 
+/// Times a HEAD request against the mirror's main repository.
+pub(crate) fn measure_latency(mirror: &MirrorDefinition) -> Result<Duration, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(LATENCY_TIMEOUT_SECS))
+        .build()
+        .map_err(|err| format!("Failed to build HTTP client: {}", err))?;
+    let url = format!("{}/", repository_url(mirror, MAIN_SUFFIX));
+
+    let started = Instant::now();
+    client
+        .head(&url)
+        .header(
+            USER_AGENT,
+            "Nebula (https://github.com/Letdown2491/nebula-gtk)",
+        )
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| format!("{} is unreachable: {}", humanize_base_url(mirror), err))?;
+    Ok(started.elapsed())
+}
+
 pub(crate) fn configure_query_command(command: &mut Command) {
     let repos = active_repositories();
     if repos.is_empty() {
@@ -131,10 +241,37 @@ pub(crate) fn install_repository_args() -> Vec<String> {
 }
 
 pub(crate) fn set_active_mirrors_by_ids(ids: &[String]) {
+    if let Ok(mut lock) = ACTIVE_MIRROR_IDS.write() {
+        *lock = ids.to_vec();
+    }
+    refresh_active_repositories();
+}
+
+pub(crate) fn set_active_repository_classes(classes: &[RepositoryClass]) {
+    if let Ok(mut lock) = ACTIVE_CLASSES.write() {
+        *lock = classes.to_vec();
+    }
+    refresh_active_repositories();
+}
+
+fn refresh_active_repositories() {
+    let ids = ACTIVE_MIRROR_IDS
+        .read()
+        .map(|ids| ids.clone())
+        .unwrap_or_default();
+    let classes = ACTIVE_CLASSES
+        .read()
+        .map(|classes| classes.clone())
+        .unwrap_or_default();
+
+    let arch = native_architecture();
     let mut repos = Vec::new();
-    for id in ids {
+    for id in &ids {
         if let Some(def) = find_mirror(id) {
-            repos.push(repository_url(def, MAIN_SUFFIX));
+            repos.push(repository_url(def, &main_suffix(arch)));
+            for class in enabled_classes(&classes) {
+                repos.push(repository_url(def, &class.suffix(arch)));
+            }
         }
     }
     if let Ok(mut lock) = ACTIVE_REPOSITORIES.write() {
@@ -142,6 +279,127 @@ pub(crate) fn set_active_mirrors_by_ids(ids: &[String]) {
     }
 }
 
+/// Repository classes this machine can use. Multilib only exists for
+/// glibc x86_64.
+pub(crate) fn available_repository_classes() -> Vec<RepositoryClass> {
+    let multilib = NATIVE_ARCH.as_str() == "x86_64";
+    RepositoryClass::ALL
+        .into_iter()
+        .filter(|class| multilib || !class.is_multilib())
+        .collect()
+}
+
+/// Well-known void-packages templates that only ship in the nonfree
+/// repository.
+const NONFREE_PACKAGES: &[&str] = &[
+    "discord",
+    "spotify",
+    "steam",
+    "nvidia",
+    "nvidia470",
+    "nvidia390",
+    "unrar",
+    "intel-ucode",
+    "broadcom-wl-dkms",
+    "sublime-text4",
+    "sublime-merge",
+    "minecraft-launcher",
+];
+
+/// Suggests an optional repository that likely provides `query` when a
+/// search comes back empty. Nothing is suggested once the repository is
+/// already enabled.
+pub(crate) fn suggested_repository_for(
+    query: &str,
+    enabled: &[RepositoryClass],
+) -> Option<RepositoryClass> {
+    let query = query.trim().to_ascii_lowercase();
+    if query.is_empty() || enabled.contains(&RepositoryClass::Nonfree) {
+        return None;
+    }
+    let known = NONFREE_PACKAGES
+        .iter()
+        .any(|name| *name == query || (query.len() >= 4 && name.starts_with(&query)));
+    known.then_some(RepositoryClass::Nonfree)
+}
+
+fn enabled_classes(classes: &[RepositoryClass]) -> Vec<RepositoryClass> {
+    available_repository_classes()
+        .into_iter()
+        .filter(|class| classes.contains(class))
+        .collect()
+}
+
+/// Architectures Void Linux builds packages for.
+pub(crate) const VOID_ARCHITECTURES: [&str; 9] = [
+    "x86_64",
+    "x86_64-musl",
+    "i686",
+    "aarch64",
+    "aarch64-musl",
+    "armv7l",
+    "armv7l-musl",
+    "armv6l",
+    "armv6l-musl",
+];
+
+/// The architecture xbps installs for, which honours `XBPS_ARCH`.
+pub(crate) fn native_architecture() -> &'static str {
+    NATIVE_ARCH.as_str()
+}
+
+/// Main and nonfree repositories of `arch` on the first selected mirror.
+pub(crate) fn architecture_repositories(arch: &str) -> Vec<String> {
+    let mirror_id = ACTIVE_MIRROR_IDS
+        .read()
+        .ok()
+        .and_then(|ids| ids.first().cloned())
+        .unwrap_or_else(|| default_mirror_id().to_string());
+    let Some(mirror) = find_mirror(&mirror_id).or_else(|| find_mirror(default_mirror_id())) else {
+        return Vec::new();
+    };
+    vec![
+        repository_url(mirror, &main_suffix(arch)),
+        repository_url(mirror, &RepositoryClass::Nonfree.suffix(arch)),
+    ]
+}
+
+/// Main repository path of `arch`. musl and aarch64 builds live in their
+/// own subdirectories of `current`.
+fn main_suffix(arch: &str) -> String {
+    if arch.starts_with("aarch64") {
+        format!("{}/aarch64", MAIN_SUFFIX)
+    } else if arch.ends_with("-musl") {
+        format!("{}/musl", MAIN_SUFFIX)
+    } else {
+        MAIN_SUFFIX.to_string()
+    }
+}
+
+fn detect_native_architecture() -> String {
+    Command::new("xbps-uhelper")
+        .arg("arch")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|arch| !arch.is_empty())
+        .unwrap_or_else(|| env::consts::ARCH.to_string())
+}
+
+/// Works out which optional repositories the given repository URLs cover.
+pub(crate) fn detect_repository_classes(urls: &[String]) -> Vec<RepositoryClass> {
+    let arch = native_architecture();
+    RepositoryClass::ALL
+        .into_iter()
+        .filter(|class| {
+            let suffix = format!("/{}", class.suffix(arch));
+            urls.iter()
+                .any(|url| url.trim_end_matches('/').ends_with(&suffix))
+        })
+        .collect()
+}
+
 pub(crate) fn active_repositories() -> Vec<String> {
     match ACTIVE_REPOSITORIES.read() {
         Ok(repos) => repos.clone(),
@@ -190,20 +448,48 @@ pub(crate) fn map_urls_to_ids(urls: &[String]) -> Vec<String> {
     result
 }
 
-pub(crate) fn write_repository_config(ids: &[String]) -> Result<(), String> {
+/// Writes the main repository file and one file per optional repository
+/// class. Disabled classes that xbps currently reads get an empty override,
+/// so a void-repo-* package can't re-enable them behind Nebula's back;
+/// classes that were never configured are left alone.
+pub(crate) fn write_repository_config(
+    ids: &[String],
+    classes: &[RepositoryClass],
+) -> Result<(), String> {
     let mirrors: Vec<&MirrorDefinition> = ids.iter().filter_map(|id| find_mirror(id)).collect();
 
     if mirrors.is_empty() {
         return Err("Select at least one mirror before saving.".to_string());
     }
 
+    let arch = native_architecture();
+    let main = main_suffix(arch);
     let content = mirrors
         .iter()
-        .map(|mirror| format!("repository={}", repository_url(mirror, MAIN_SUFFIX)))
+        .map(|mirror| format!("repository={}", repository_url(mirror, &main)))
         .collect::<Vec<_>>()
         .join("\n");
 
-    let script = format!("cat <<'EOF' > {REPOSITORY_FILE}\n{}\nEOF\n", content);
+    let mut script = format!("cat <<'EOF' > {REPOSITORY_FILE}\n{}\nEOF\n", content);
+    let enabled = enabled_classes(classes);
+    for class in available_repository_classes() {
+        let content = if enabled.contains(&class) {
+            mirrors
+                .iter()
+                .map(|mirror| format!("repository={}", repository_url(mirror, &class.suffix(arch))))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else if class.is_configured() {
+            "# Disabled in Nebula".to_string()
+        } else {
+            continue;
+        };
+        script.push_str(&format!(
+            "cat <<'EOF' > {}\n{}\nEOF\n",
+            class.config_file(),
+            content
+        ));
+    }
     let args: Vec<&str> = vec!["-c", script.as_str()];
     run_privileged_command("sh", &args)?;
     Ok(())
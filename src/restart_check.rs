@@ -0,0 +1,80 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+/// Directories packages install code into. Deleted mappings elsewhere are
+/// usually shared memory or temporary files rather than replaced libraries.
+const SYSTEM_PREFIXES: [&str; 6] = ["/usr/", "/lib/", "/lib32/", "/lib64/", "/bin/", "/opt/"];
+
+const DELETED_SUFFIX: &str = " (deleted)";
+
+/// Names of running processes that still map a binary or library an update
+/// has since replaced. Only processes whose maps are readable are checked,
+/// which without root means the user's own session.
+pub(crate) fn processes_needing_restart() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    let mut names = BTreeSet::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name();
+        let Some(pid) = file_name.to_str().filter(|pid| pid.parse::<u32>().is_ok()) else {
+            continue;
+        };
+        let Ok(maps) = fs::read_to_string(format!("/proc/{}/maps", pid)) else {
+            continue;
+        };
+        if deleted_system_mappings(&maps).is_empty() {
+            continue;
+        }
+        if let Ok(comm) = fs::read_to_string(format!("/proc/{}/comm", pid)) {
+            let comm = comm.trim();
+            if !comm.is_empty() {
+                names.insert(comm.to_string());
+            }
+        }
+    }
+    names.into_iter().collect()
+}
+
+/// Paths in a `/proc/<pid>/maps` listing that were unlinked from a system
+/// directory after being mapped.
+fn deleted_system_mappings(maps: &str) -> Vec<&str> {
+    let mut paths: Vec<&str> = maps
+        .lines()
+        .filter_map(|line| {
+            // The path is the sixth column and may itself contain spaces.
+            let path = line.splitn(6, char::is_whitespace).nth(5)?.trim_start();
+            let path = path.strip_suffix(DELETED_SUFFIX)?;
+            SYSTEM_PREFIXES
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+                .then_some(path)
+        })
+        .collect();
+    paths.dedup();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_replaced_libraries() {
+        let maps = "\
+55d0c0a00000-55d0c0a02000 r--p 00000000 fe:01 1311 /usr/bin/foo
+7f1e2c000000-7f1e2c028000 r--p 00000000 fe:01 2050 /usr/lib/libc.so.6 (deleted)
+7f1e2c028000-7f1e2c1bd000 r-xp 00028000 fe:01 2050 /usr/lib/libc.so.6 (deleted)
+7f1e2d000000-7f1e2d001000 rw-s 00000000 00:01 4096 /memfd:pulseaudio (deleted)
+7f1e2e000000-7f1e2e001000 rw-s 00000000 00:19 77 /dev/shm/cache (deleted)
+7ffd4a000000-7ffd4a021000 rw-p 00000000 00:00 0 [stack]
+";
+        assert_eq!(deleted_system_mappings(maps), vec!["/usr/lib/libc.so.6"]);
+    }
+
+    #[test]
+    fn ignores_processes_without_deleted_files() {
+        let maps = "7f1e2c000000-7f1e2c028000 r--p 00000000 fe:01 2050 /usr/lib/libc.so.6\n";
+        assert!(deleted_system_mappings(maps).is_empty());
+    }
+}
@@ -1,10 +1,15 @@
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
+use chrono::{DateTime, Utc};
 use libadwaita as adw;
 use serde::{Deserialize, Serialize};
 
+use crate::mirrors::RepositoryClass;
+use crate::spotlight::SpotlightCategory;
+
 const APP_SETTINGS_FILE: &str = "settings.json";
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -23,8 +28,20 @@ impl Default for StartPagePreference {
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateCheckFrequency {
+    Hourly,
     Daily,
     Weekly,
+    Custom(u32),
+}
+
+impl UpdateCheckFrequency {
+    pub const MIN_CUSTOM_HOURS: u32 = 1;
+    pub const MAX_CUSTOM_HOURS: u32 = 24 * 30;
+
+    /// Builds a custom interval, clamped to a sensible range of hours.
+    pub fn custom(hours: u32) -> Self {
+        UpdateCheckFrequency::Custom(hours.clamp(Self::MIN_CUSTOM_HOURS, Self::MAX_CUSTOM_HOURS))
+    }
 }
 
 impl Default for UpdateCheckFrequency {
@@ -33,6 +50,16 @@ impl Default for UpdateCheckFrequency {
     }
 }
 
+/// How tightly package rows are packed in the Discover, Installed and
+/// Updates lists.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ThemePreference {
@@ -80,6 +107,14 @@ pub struct AppSettings {
     #[serde(default)]
     pub window_height: Option<i32>,
     #[serde(default)]
+    pub window_maximized: bool,
+    /// Divider positions between the package lists and their detail panes,
+    /// once the user has dragged them.
+    #[serde(default)]
+    pub discover_pane_position: Option<i32>,
+    #[serde(default)]
+    pub installed_pane_position: Option<i32>,
+    #[serde(default)]
     pub start_page: StartPagePreference,
     #[serde(default)]
     pub last_page: Option<String>,
@@ -87,18 +122,80 @@ pub struct AppSettings {
     pub auto_check_enabled: bool,
     #[serde(default)]
     pub auto_check_frequency: UpdateCheckFrequency,
+    #[serde(default)]
+    pub auto_install_updates: bool,
     #[serde(default = "default_confirm_pref")]
     pub confirm_install: bool,
     #[serde(default = "default_confirm_pref")]
     pub confirm_remove: bool,
     #[serde(default)]
+    pub install_summary: bool,
+    #[serde(default)]
+    pub remove_recursive: bool,
+    #[serde(default)]
+    pub remove_orphans: bool,
+    #[serde(default)]
     pub theme_preference: ThemePreference,
     #[serde(default = "default_notify_updates")]
     pub notify_updates: bool,
     #[serde(default)]
     pub mirror_selection: Vec<String>,
+    #[serde(default)]
+    pub repository_classes: Vec<RepositoryClass>,
     #[serde(default = "default_waypoint_before_upgrades")]
     pub waypoint_before_upgrades: bool,
+    #[serde(default)]
+    pub last_visit_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub mirror_stats: HashMap<String, MirrorStat>,
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    #[serde(default = "default_show_spotlight")]
+    pub show_spotlight: bool,
+    #[serde(default)]
+    pub run_updates_in_terminal: bool,
+    #[serde(default)]
+    pub spotlight_category_order: Vec<SpotlightCategory>,
+    #[serde(default)]
+    pub hidden_spotlight_categories: Vec<SpotlightCategory>,
+    #[serde(default = "default_first_run")]
+    pub first_run: bool,
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+    #[serde(default)]
+    pub group_installed_subpackages: bool,
+    #[serde(default)]
+    pub high_contrast: bool,
+    #[serde(default = "default_font_scale_percent")]
+    pub font_scale_percent: u32,
+    #[serde(default)]
+    pub exclude_kernel_from_update_all: bool,
+    #[serde(default = "default_mark_available_updates")]
+    pub mark_available_updates: bool,
+    #[serde(default = "default_spotlight_window_days")]
+    pub spotlight_window_days: u32,
+    #[serde(default = "default_spotlight_recent_limit")]
+    pub spotlight_recent_limit: usize,
+    #[serde(default)]
+    pub recently_removed: Vec<RemovedPackage>,
+    #[serde(default)]
+    pub list_density: ListDensity,
+}
+
+/// Last latency measurement for a mirror. `latency_ms` is `None` when the
+/// mirror could not be reached.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MirrorStat {
+    pub latency_ms: Option<u64>,
+    pub measured_at: DateTime<Utc>,
+}
+
+/// A package removed through Nebula, remembered so it can be reinstalled
+/// later. Newest entries come first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RemovedPackage {
+    pub name: String,
+    pub removed_at: DateTime<Utc>,
 }
 
 fn default_auto_check_enabled() -> bool {
@@ -113,6 +210,34 @@ fn default_notify_updates() -> bool {
     true
 }
 
+fn default_show_spotlight() -> bool {
+    true
+}
+
+fn default_first_run() -> bool {
+    true
+}
+
+fn default_min_free_space_mb() -> u64 {
+    1024
+}
+
+fn default_font_scale_percent() -> u32 {
+    100
+}
+
+fn default_spotlight_window_days() -> u32 {
+    crate::spotlight::SPOTLIGHT_WINDOW_DAYS
+}
+
+fn default_spotlight_recent_limit() -> usize {
+    crate::spotlight::SPOTLIGHT_RECENT_LIMIT
+}
+
+fn default_mark_available_updates() -> bool {
+    true
+}
+
 fn default_waypoint_before_upgrades() -> bool {
     crate::waypoint::should_enable_integration()
 }
@@ -122,16 +247,42 @@ impl Default for AppSettings {
         Self {
             window_width: None,
             window_height: None,
+            window_maximized: false,
+            discover_pane_position: None,
+            installed_pane_position: None,
             start_page: StartPagePreference::Discover,
             last_page: Some("discover".to_string()),
             auto_check_enabled: default_auto_check_enabled(),
             auto_check_frequency: UpdateCheckFrequency::Daily,
+            auto_install_updates: false,
             confirm_install: default_confirm_pref(),
             confirm_remove: default_confirm_pref(),
+            install_summary: false,
+            remove_recursive: false,
+            remove_orphans: false,
             theme_preference: ThemePreference::System,
             notify_updates: default_notify_updates(),
             mirror_selection: Vec::new(),
+            repository_classes: Vec::new(),
             waypoint_before_upgrades: default_waypoint_before_upgrades(),
+            last_visit_at: None,
+            mirror_stats: HashMap::new(),
+            favorites: Vec::new(),
+            show_spotlight: default_show_spotlight(),
+            run_updates_in_terminal: false,
+            spotlight_category_order: Vec::new(),
+            hidden_spotlight_categories: Vec::new(),
+            first_run: default_first_run(),
+            min_free_space_mb: default_min_free_space_mb(),
+            group_installed_subpackages: false,
+            high_contrast: false,
+            font_scale_percent: default_font_scale_percent(),
+            exclude_kernel_from_update_all: false,
+            mark_available_updates: default_mark_available_updates(),
+            spotlight_window_days: default_spotlight_window_days(),
+            spotlight_recent_limit: default_spotlight_recent_limit(),
+            recently_removed: Vec::new(),
+            list_density: ListDensity::Comfortable,
         }
     }
 }
@@ -164,7 +315,7 @@ pub fn save_app_settings(settings: &AppSettings) -> Result<(), String> {
     fs::write(&path, data).map_err(|err| format!("Failed to write settings: {}", err))
 }
 
-fn app_config_dir() -> Option<PathBuf> {
+pub(crate) fn app_config_dir() -> Option<PathBuf> {
     if let Ok(custom) = env::var("NEBULA_STORE_CONFIG_DIR") {
         let trimmed = custom.trim();
         if !trimmed.is_empty() {
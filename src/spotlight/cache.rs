@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::types::{PackageInfo, lowercase_cache};
 
+use super::categories::SpotlightCategory;
+
 const SPOTLIGHT_CACHE_FILE: &str = "spotlight.json";
 pub(crate) const SPOTLIGHT_CACHE_VERSION: u32 = 1;
 pub(crate) const SPOTLIGHT_CACHE_MAX_ENTRIES: usize = 4096;
@@ -16,6 +18,9 @@ pub(crate) const SPOTLIGHT_CACHE_MAX_ENTRIES: usize = 4096;
 pub struct SpotlightCache {
     pub generated_at: Option<DateTime<Utc>>,
     pub packages: HashMap<String, PackageInfo>,
+    /// Repository packages that belong to each category, kept apart from
+    /// `packages` so they don't count as recent arrivals.
+    pub category_packages: HashMap<SpotlightCategory, Vec<PackageInfo>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -23,6 +28,8 @@ struct SpotlightCacheFile {
     version: u32,
     generated_at: Option<String>,
     packages: Vec<SpotlightCacheEntryData>,
+    #[serde(default)]
+    category_packages: HashMap<SpotlightCategory, Vec<SpotlightCacheEntryData>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -56,37 +63,15 @@ pub(crate) fn load_spotlight_cache_from_disk() -> SpotlightCache {
     cache.generated_at = file.generated_at.as_deref().and_then(parse_cached_datetime);
 
     for entry in file.packages {
-        if entry.name.is_empty() {
-            continue;
+        if let Some(info) = package_from_entry(entry) {
+            cache.packages.insert(info.name.clone(), info);
         }
+    }
 
-        let build_date = entry.build_date.as_deref().and_then(parse_cached_datetime);
-        let first_seen = entry.first_seen.as_deref().and_then(parse_cached_datetime);
-
-        let name = entry.name;
-        let version = entry.version;
-        let description = entry.description;
-        let repository = entry.repository;
-
-        let info = PackageInfo {
-            name_lower: lowercase_cache(&name),
-            version_lower: lowercase_cache(&version),
-            description_lower: lowercase_cache(&description),
-            name,
-            version,
-            description,
-            installed: false,
-            pinned: false,
-            previous_version: None,
-            download_size: None,
-            changelog: None,
-            download_bytes: None,
-            repository,
-            build_date,
-            first_seen,
-        };
-
-        cache.packages.insert(info.name.clone(), info);
+    for (category, entries) in file.category_packages {
+        let packages: Vec<PackageInfo> =
+            entries.into_iter().filter_map(package_from_entry).collect();
+        cache.category_packages.insert(category, packages);
     }
 
     prune_spotlight_cache(&mut cache);
@@ -105,23 +90,19 @@ pub(crate) fn save_spotlight_cache_to_disk(cache: &SpotlightCache) -> Result<(),
         }
     }
 
-    let packages: Vec<SpotlightCacheEntryData> = cache
-        .packages
-        .values()
-        .map(|info| SpotlightCacheEntryData {
-            name: info.name.clone(),
-            version: info.version.clone(),
-            description: info.description.clone(),
-            repository: info.repository.clone(),
-            build_date: info.build_date.as_ref().map(format_cached_datetime),
-            first_seen: info.first_seen.as_ref().map(format_cached_datetime),
-        })
+    let packages: Vec<SpotlightCacheEntryData> =
+        cache.packages.values().map(entry_from_package).collect();
+    let category_packages = cache
+        .category_packages
+        .iter()
+        .map(|(category, packages)| (*category, packages.iter().map(entry_from_package).collect()))
         .collect();
 
     let file = SpotlightCacheFile {
         version: SPOTLIGHT_CACHE_VERSION,
         generated_at: cache.generated_at.as_ref().map(format_cached_datetime),
         packages,
+        category_packages,
     };
 
     let data = serde_json::to_string_pretty(&file)
@@ -130,6 +111,49 @@ pub(crate) fn save_spotlight_cache_to_disk(cache: &SpotlightCache) -> Result<(),
     fs::write(&path, data).map_err(|err| format!("Failed to write spotlight cache: {}", err))
 }
 
+fn package_from_entry(entry: SpotlightCacheEntryData) -> Option<PackageInfo> {
+    if entry.name.is_empty() {
+        return None;
+    }
+
+    let build_date = entry.build_date.as_deref().and_then(parse_cached_datetime);
+    let first_seen = entry.first_seen.as_deref().and_then(parse_cached_datetime);
+
+    let name = entry.name;
+    let version = entry.version;
+    let description = entry.description;
+    let repository = entry.repository;
+
+    Some(PackageInfo {
+        name_lower: lowercase_cache(&name),
+        version_lower: lowercase_cache(&version),
+        description_lower: lowercase_cache(&description),
+        name,
+        version,
+        description,
+        installed: false,
+        pinned: false,
+        previous_version: None,
+        download_size: None,
+        changelog: None,
+        download_bytes: None,
+        repository,
+        build_date,
+        first_seen,
+    })
+}
+
+fn entry_from_package(info: &PackageInfo) -> SpotlightCacheEntryData {
+    SpotlightCacheEntryData {
+        name: info.name.clone(),
+        version: info.version.clone(),
+        description: info.description.clone(),
+        repository: info.repository.clone(),
+        build_date: info.build_date.as_ref().map(format_cached_datetime),
+        first_seen: info.first_seen.as_ref().map(format_cached_datetime),
+    }
+}
+
 pub(crate) fn prune_spotlight_cache(cache: &mut SpotlightCache) {
     if cache.packages.len() <= SPOTLIGHT_CACHE_MAX_ENTRIES {
         return;
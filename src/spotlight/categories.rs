@@ -1,4 +1,10 @@
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+use serde::{Deserialize, Serialize};
+
+use crate::categories::package_category;
+use crate::types::{PackageInfo, lowercase_cache};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SpotlightCategory {
     Browsers,
     Chat,
@@ -43,6 +49,57 @@ pub(crate) fn category_allowlist(category: SpotlightCategory) -> &'static [&'sta
     }
 }
 
+/// Categories from the harvested package map (`crate::categories`) that
+/// make up each spotlight category.
+fn harvested_categories(category: SpotlightCategory) -> &'static [&'static str] {
+    match category {
+        SpotlightCategory::Browsers => &["Browsers"],
+        SpotlightCategory::Chat => &["Chat"],
+        SpotlightCategory::Email => &["E-mail"],
+        SpotlightCategory::Games => &["Gaming"],
+        SpotlightCategory::Graphics => &["Graphics", "Photos"],
+        SpotlightCategory::Music => &["Music"],
+        SpotlightCategory::Productivity => &["Productivity", "Office"],
+        SpotlightCategory::Utilities => &["Tools and Utilities"],
+        SpotlightCategory::Video => &["Video"],
+    }
+}
+
+/// The spotlight category a repository package belongs to. The curated
+/// allowlists win; otherwise the harvested package category decides.
+pub(crate) fn spotlight_category_for_package(name: &str) -> Option<SpotlightCategory> {
+    let categories = all_spotlight_categories();
+    if let Some(category) = categories
+        .iter()
+        .find(|category| category_allowlist(**category).contains(&name))
+    {
+        return Some(*category);
+    }
+    let harvested = package_category(name);
+    categories
+        .iter()
+        .find(|category| harvested_categories(**category).contains(&harvested))
+        .copied()
+}
+
+/// Orders a category's packages with the curated picks first, in allowlist
+/// order, followed by the rest alphabetically.
+pub(crate) fn sort_category_packages(category: SpotlightCategory, packages: &mut Vec<PackageInfo>) {
+    let picks = category_allowlist(category);
+    let rank = |name: &str| {
+        picks
+            .iter()
+            .position(|pick| *pick == name)
+            .unwrap_or(picks.len())
+    };
+    packages.sort_by(|a, b| {
+        rank(&a.name)
+            .cmp(&rank(&b.name))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    packages.dedup_by(|a, b| a.name == b.name);
+}
+
 pub(crate) fn all_spotlight_categories() -> &'static [SpotlightCategory] {
     &[
         SpotlightCategory::Browsers,
@@ -57,6 +114,32 @@ pub(crate) fn all_spotlight_categories() -> &'static [SpotlightCategory] {
     ]
 }
 
+/// Returns every category, following `saved` first and appending any the
+/// saved order doesn't mention yet.
+pub(crate) fn ordered_spotlight_categories(saved: &[SpotlightCategory]) -> Vec<SpotlightCategory> {
+    let mut ordered: Vec<SpotlightCategory> = Vec::new();
+    for category in saved.iter().chain(all_spotlight_categories()) {
+        if !ordered.contains(category) {
+            ordered.push(*category);
+        }
+    }
+    ordered
+}
+
+pub(crate) fn category_icon_resource(category: SpotlightCategory) -> &'static str {
+    match category {
+        SpotlightCategory::Browsers => "/tech/geektoshi/Nebula/icons/browsers.svg",
+        SpotlightCategory::Chat => "/tech/geektoshi/Nebula/icons/chat.svg",
+        SpotlightCategory::Email => "/tech/geektoshi/Nebula/icons/email.svg",
+        SpotlightCategory::Games => "/tech/geektoshi/Nebula/icons/games.svg",
+        SpotlightCategory::Graphics => "/tech/geektoshi/Nebula/icons/graphics.svg",
+        SpotlightCategory::Music => "/tech/geektoshi/Nebula/icons/music.svg",
+        SpotlightCategory::Productivity => "/tech/geektoshi/Nebula/icons/productivity.svg",
+        SpotlightCategory::Utilities => "/tech/geektoshi/Nebula/icons/utilities.svg",
+        SpotlightCategory::Video => "/tech/geektoshi/Nebula/icons/video.svg",
+    }
+}
+
 pub(crate) fn category_display_name(category: SpotlightCategory) -> &'static str {
     match category {
         SpotlightCategory::Browsers => "Browsers",
@@ -70,3 +153,81 @@ pub(crate) fn category_display_name(category: SpotlightCategory) -> &'static str
         SpotlightCategory::Video => "Video",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordered_categories_keep_saved_order_and_append_missing() {
+        let saved = [SpotlightCategory::Video, SpotlightCategory::Chat];
+        let ordered = ordered_spotlight_categories(&saved);
+        assert_eq!(ordered.len(), all_spotlight_categories().len());
+        assert_eq!(ordered[0], SpotlightCategory::Video);
+        assert_eq!(ordered[1], SpotlightCategory::Chat);
+        assert_eq!(ordered[2], SpotlightCategory::Browsers);
+    }
+
+    #[test]
+    fn ordered_categories_ignore_duplicates() {
+        let saved = [SpotlightCategory::Games, SpotlightCategory::Games];
+        let ordered = ordered_spotlight_categories(&saved);
+        assert_eq!(ordered.len(), all_spotlight_categories().len());
+        assert_eq!(
+            ordered
+                .iter()
+                .filter(|category| **category == SpotlightCategory::Games)
+                .count(),
+            1
+        );
+    }
+
+    fn listed(name: &str) -> PackageInfo {
+        PackageInfo {
+            name_lower: lowercase_cache(name),
+            version_lower: lowercase_cache("1.0_1"),
+            description_lower: lowercase_cache(""),
+            name: name.to_string(),
+            version: "1.0_1".to_string(),
+            description: String::new(),
+            installed: false,
+            pinned: false,
+            previous_version: None,
+            download_size: None,
+            changelog: None,
+            download_bytes: None,
+            repository: None,
+            build_date: None,
+            first_seen: None,
+        }
+    }
+
+    #[test]
+    fn allowlisted_packages_map_to_their_category() {
+        assert_eq!(
+            spotlight_category_for_package("firefox"),
+            Some(SpotlightCategory::Browsers)
+        );
+        assert_eq!(
+            spotlight_category_for_package("obs-studio"),
+            Some(SpotlightCategory::Video)
+        );
+    }
+
+    #[test]
+    fn category_packages_list_picks_first() {
+        let mut packages = vec![
+            listed("qutebrowser"),
+            listed("chromium"),
+            listed("firefox"),
+            listed("chromium"),
+            listed("epiphany"),
+        ];
+        sort_category_packages(SpotlightCategory::Browsers, &mut packages);
+        let names: Vec<&str> = packages.iter().map(|pkg| pkg.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["firefox", "chromium", "epiphany", "qutebrowser"]
+        );
+    }
+}
@@ -12,8 +12,13 @@ use reqwest::blocking::Client;
 use reqwest::header::{ACCEPT, USER_AGENT};
 
 use crate::mirrors::configure_query_command;
+use crate::types::{PackageInfo, lowercase_cache};
 use crate::xbps::split_package_identifier;
 
+use super::categories::{
+    SpotlightCategory, sort_category_packages, spotlight_category_for_package,
+};
+
 #[derive(Clone, Debug)]
 pub struct RemotePackageMetadata {
     pub name: String,
@@ -209,6 +214,65 @@ fn fetch_remote_spotlight_metadata_with_xbps() -> Result<Vec<RemotePackageMetada
     Ok(records.into_values().collect())
 }
 
+/// Packages listed per category, curated picks first.
+const CATEGORY_PACKAGE_LIMIT: usize = 60;
+
+/// Sorts every repository package into the spotlight categories, using the
+/// curated allowlists and the harvested package categories rather than a
+/// keyword search.
+pub(crate) fn fetch_category_packages()
+-> Result<HashMap<SpotlightCategory, Vec<PackageInfo>>, String> {
+    let mut command = Command::new("xbps-query");
+    command.arg("-R");
+    configure_query_command(&mut command);
+    command.args(["--regex", "-s", "."]);
+    let output = command
+        .output()
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let mut grouped: HashMap<SpotlightCategory, Vec<PackageInfo>> = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((name, version, description)) = parse_search_listing_line(line) else {
+            continue;
+        };
+        if name.ends_with("-devel") || name.ends_with("-dbg") || name.ends_with("-doc") {
+            continue;
+        }
+        let Some(category) = spotlight_category_for_package(&name) else {
+            continue;
+        };
+        grouped.entry(category).or_default().push(PackageInfo {
+            name_lower: lowercase_cache(&name),
+            version_lower: lowercase_cache(&version),
+            description_lower: lowercase_cache(&description),
+            name,
+            version,
+            description,
+            installed: false,
+            pinned: false,
+            previous_version: None,
+            download_size: None,
+            changelog: None,
+            download_bytes: None,
+            repository: None,
+            build_date: None,
+            first_seen: None,
+        });
+    }
+
+    for (category, packages) in grouped.iter_mut() {
+        sort_category_packages(*category, packages);
+        packages.truncate(CATEGORY_PACKAGE_LIMIT);
+    }
+
+    Ok(grouped)
+}
+
 fn extract_package_name(title: &str) -> Option<String> {
     if let Some(idx) = title.find("srcpkgs/") {
         let mut name = String::new();
@@ -346,7 +410,7 @@ fn parse_build_date_listing_line(
     Some((name, version, build_date, repository))
 }
 
-fn parse_build_date_field(value: &str) -> Option<DateTime<Utc>> {
+pub(crate) fn parse_build_date_field(value: &str) -> Option<DateTime<Utc>> {
     let trimmed = value.trim().trim_matches(|c| c == '"' || c == '\'');
     if trimmed.is_empty() {
         return None;
@@ -5,10 +5,16 @@ mod refresh;
 
 pub(crate) use cache::{
     SpotlightCache, load_spotlight_cache_from_disk, save_spotlight_cache_to_disk,
+    spotlight_cache_dir,
 };
-pub(crate) use categories::{SpotlightCategory, category_display_name};
+pub(crate) use categories::{
+    SpotlightCategory, category_display_name, category_icon_resource, ordered_spotlight_categories,
+};
+pub(crate) use metadata::parse_build_date_field;
 pub(crate) use refresh::{
-    build_category_results, compute_spotlight_sections, refresh_spotlight_cache,
+    SPOTLIGHT_LIMIT_CHOICES, SPOTLIGHT_RECENT_LIMIT, SPOTLIGHT_WINDOW_CHOICES,
+    SPOTLIGHT_WINDOW_DAYS, build_category_results, compute_new_since, compute_spotlight_sections,
+    refresh_spotlight_cache,
 };
 
 pub(crate) const SPOTLIGHT_REFRESH_INTERVAL_HOURS: i64 = 24;
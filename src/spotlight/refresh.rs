@@ -6,10 +6,15 @@ use crate::types::{PackageInfo, lowercase_cache};
 
 use super::cache::{SpotlightCache, prune_spotlight_cache};
 use super::categories::{SpotlightCategory, all_spotlight_categories, category_allowlist};
-use super::metadata::{RemotePackageMetadata, fetch_remote_spotlight_metadata};
+use super::metadata::{
+    RemotePackageMetadata, fetch_category_packages, fetch_remote_spotlight_metadata,
+};
 
-pub(crate) const SPOTLIGHT_WINDOW_DAYS: i64 = 7;
+pub(crate) const SPOTLIGHT_WINDOW_DAYS: u32 = 7;
 pub(crate) const SPOTLIGHT_RECENT_LIMIT: usize = 25;
+/// Lookback windows and list sizes offered for the recent list.
+pub(crate) const SPOTLIGHT_WINDOW_CHOICES: [u32; 3] = [7, 14, 30];
+pub(crate) const SPOTLIGHT_LIMIT_CHOICES: [usize; 4] = [10, 25, 50, 100];
 
 #[derive(Clone, Debug)]
 pub struct SpotlightRefreshOutcome {
@@ -25,6 +30,17 @@ pub(crate) fn build_category_results(
     let mut results = HashMap::new();
 
     for category in all_spotlight_categories() {
+        if let Some(packages) = cache
+            .category_packages
+            .get(category)
+            .filter(|packages| !packages.is_empty())
+        {
+            results.insert(*category, packages.clone());
+            continue;
+        }
+
+        // Until the repository listing has been cached, fall back to the
+        // curated picks that happen to be among the recent packages.
         let mut packages = Vec::new();
         for name in category_allowlist(*category) {
             if let Some(info) = cache.packages.get(*name) {
@@ -37,11 +53,16 @@ pub(crate) fn build_category_results(
     results
 }
 
+/// Packages built within the last `window_days`, newest first and at most
+/// `limit` of them. Falls back to the newest builds overall when nothing is
+/// that recent.
 pub(crate) fn compute_spotlight_sections(
     cache: &SpotlightCache,
     now: DateTime<Utc>,
+    window_days: u32,
+    limit: usize,
 ) -> Vec<PackageInfo> {
-    let window_start = now - Duration::days(SPOTLIGHT_WINDOW_DAYS);
+    let window_start = now - Duration::days(i64::from(window_days));
 
     let mut recent: Vec<PackageInfo> = cache
         .packages
@@ -66,13 +87,39 @@ pub(crate) fn compute_spotlight_sections(
                 .then_with(|| a.name.cmp(&b.name))
         });
     }
-    recent.truncate(SPOTLIGHT_RECENT_LIMIT);
+    recent.truncate(limit);
 
     recent
 }
 
+/// Packages this cache first recorded after `since`, newest builds first.
+/// `first_seen` is stamped by the refresh that first lists a package, so
+/// this is "new since the cache last looked", not the date a package
+/// reached the repositories. Version bumps of packages seen earlier are not
+/// included.
+pub(crate) fn compute_new_since(cache: &SpotlightCache, since: DateTime<Utc>) -> Vec<PackageInfo> {
+    let mut added: Vec<PackageInfo> = cache
+        .packages
+        .values()
+        .filter(|pkg| pkg.first_seen.map_or(false, |dt| dt > since))
+        .cloned()
+        .collect();
+
+    added.sort_by(|a, b| {
+        b.build_date
+            .cmp(&a.build_date)
+            .then_with(|| b.first_seen.cmp(&a.first_seen))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    added.truncate(SPOTLIGHT_RECENT_LIMIT);
+
+    added
+}
+
 pub(crate) fn refresh_spotlight_cache(
     mut cache: SpotlightCache,
+    window_days: u32,
+    limit: usize,
 ) -> Result<SpotlightRefreshOutcome, String> {
     let now = Utc::now();
     let remote_packages = fetch_remote_spotlight_metadata()?;
@@ -134,10 +181,14 @@ pub(crate) fn refresh_spotlight_cache(
     prune_spotlight_cache(&mut cache);
     cache.generated_at = Some(now);
 
+    match fetch_category_packages() {
+        Ok(category_packages) => cache.category_packages = category_packages,
+        Err(err) => eprintln!("Failed to refresh spotlight categories: {}", err),
+    }
+
     let categories = build_category_results(&cache);
 
-    let mut recent = compute_spotlight_sections(&cache, now);
-    recent.truncate(SPOTLIGHT_RECENT_LIMIT);
+    let recent = compute_spotlight_sections(&cache, now, window_days, limit);
 
     #[cfg(debug_assertions)]
     {
@@ -160,10 +211,70 @@ pub(crate) fn refresh_spotlight_cache(
 mod tests {
     use super::*;
 
+    fn cached_package(name: &str, first_seen: DateTime<Utc>) -> PackageInfo {
+        PackageInfo {
+            name_lower: lowercase_cache(name),
+            version_lower: lowercase_cache("1.0_1"),
+            description_lower: lowercase_cache(""),
+            name: name.to_string(),
+            version: "1.0_1".to_string(),
+            description: String::new(),
+            installed: false,
+            pinned: false,
+            previous_version: None,
+            download_size: None,
+            changelog: None,
+            download_bytes: None,
+            repository: None,
+            build_date: Some(first_seen),
+            first_seen: Some(first_seen),
+        }
+    }
+
+    #[test]
+    fn compute_new_since_only_lists_later_additions() {
+        let last_visit = Utc::now() - Duration::days(3);
+        let mut cache = SpotlightCache::default();
+        for (name, age_days) in [("old", 10), ("newer", 1), ("newest", 0)] {
+            let seen = Utc::now() - Duration::days(age_days);
+            cache
+                .packages
+                .insert(name.to_string(), cached_package(name, seen));
+        }
+
+        let names: Vec<String> = compute_new_since(&cache, last_visit)
+            .into_iter()
+            .map(|pkg| pkg.name)
+            .collect();
+        assert_eq!(names, vec!["newest".to_string(), "newer".to_string()]);
+    }
+
+    #[test]
+    fn compute_spotlight_sections_respects_window_and_limit() {
+        let mut cache = SpotlightCache::default();
+        for (name, age_days) in [("a", 1), ("b", 3), ("c", 10), ("d", 20)] {
+            let built = Utc::now() - Duration::days(age_days);
+            cache
+                .packages
+                .insert(name.to_string(), cached_package(name, built));
+        }
+
+        let names = |window_days, limit| -> Vec<String> {
+            compute_spotlight_sections(&cache, Utc::now(), window_days, limit)
+                .into_iter()
+                .map(|pkg| pkg.name)
+                .collect()
+        };
+        assert_eq!(names(7, 25), vec!["a", "b"]);
+        assert_eq!(names(14, 25), vec!["a", "b", "c"]);
+        assert_eq!(names(30, 2), vec!["a", "b"]);
+    }
+
     #[test]
     fn refresh_spotlight_cache_produces_spotlight_lists() {
         let cache = SpotlightCache::default();
-        let outcome = refresh_spotlight_cache(cache).expect("refresh spotlight cache");
+        let outcome = refresh_spotlight_cache(cache, SPOTLIGHT_WINDOW_DAYS, SPOTLIGHT_RECENT_LIMIT)
+            .expect("refresh spotlight cache");
         assert!(
             !outcome.recent.is_empty(),
             "expected recent spotlight entries"
@@ -1,5 +1,7 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::mpsc;
 use std::thread;
@@ -8,23 +10,49 @@ use gtk4 as gtk;
 use libadwaita as adw;
 
 use adw::prelude::*;
+use gtk::gio;
 use gtk::glib::{self, Propagation};
 use gtk::pango;
 
+use crate::helpers::{
+    clear_listbox, format_relative_time, package_template_url, summarize_package_list,
+};
 use crate::mirrors::{
-    default_mirror_id, detect_active_repositories, find_mirror, humanize_base_url, map_urls_to_ids,
-    set_active_mirrors_by_ids, tier1_mirrors, tor_mirrors, write_repository_config,
+    MirrorDefinition, RepositoryClass, available_repository_classes, default_mirror_id,
+    detect_active_repositories, detect_repository_classes, find_mirror, humanize_base_url,
+    map_urls_to_ids, measure_latency, native_architecture, set_active_mirrors_by_ids,
+    set_active_repository_classes, tier1_mirrors, tor_mirrors, write_repository_config,
+};
+use crate::settings::{
+    AppSettings, ListDensity, MirrorStat, StartPagePreference, UpdateCheckFrequency,
+    save_app_settings,
 };
-use crate::settings::{AppSettings, StartPagePreference, UpdateCheckFrequency, save_app_settings};
 use crate::spotlight::{
-    SpotlightCategory, build_category_results, compute_spotlight_sections,
+    SPOTLIGHT_LIMIT_CHOICES, SPOTLIGHT_WINDOW_CHOICES, SpotlightCategory, build_category_results,
+    category_display_name, compute_new_since, compute_spotlight_sections,
     load_spotlight_cache_from_disk,
 };
-use crate::state::types::{AppMessage, AppState, InstalledFilter, RemoveOrigin};
-use crate::types::{CommandResult, PackageInfo};
-use crate::ui::AppWidgets;
-use crate::xbps::{run_xbps_install, run_xbps_remove, run_xbps_remove_packages};
-use chrono::Utc;
+use crate::state::controller::tools::MaintenanceTask;
+use crate::state::types::{
+    AppMessage, AppState, InstalledFilter, QueuedOperation, RemovalRequest, RemoveOrigin,
+};
+use crate::types::{CommandResult, PackageInfo, RemoveOptions};
+use crate::ui::{AppWidgets, FONT_SCALE_STEPS, apply_font_scale, apply_high_contrast_css_class};
+use crate::update_history::load_update_history;
+use crate::xbps::{
+    PrivilegeFailure, classify_privilege_failure, find_other_architectures, find_polkit_agent,
+    format_size, parse_alternatives_list, parse_installed_package_names, parse_removed_packages,
+    privilege_failure_reported, query_removal_dependents, query_source_package,
+    remove_command_line, run_xbps_install, run_xbps_install_packages, run_xbps_remove,
+    run_xbps_remove_packages, run_xbps_version, split_available_packages, start_polkit_agent,
+    transaction_free_space,
+};
+use chrono::{DateTime, Local, Utc};
+
+/// Lines of the update log included in a diagnostics report.
+const DIAGNOSTICS_LOG_LINES: usize = 40;
+
+const COPY_COMMAND_RESPONSE: gtk::ResponseType = gtk::ResponseType::Other(1);
 
 pub(crate) struct AppController {
     pub(crate) widgets: AppWidgets,
@@ -38,11 +66,20 @@ pub(crate) struct AppController {
     pub(crate) discover_buttons: RefCell<HashMap<String, gtk::Button>>,
     pub(crate) discover_row_stacks: RefCell<HashMap<String, gtk::Stack>>,
     pub(crate) discover_progress_bars: RefCell<HashMap<String, gtk::ProgressBar>>,
+    pub(crate) favorite_buttons: RefCell<HashMap<String, gtk::Button>>,
+    pub(crate) discover_select_checks: RefCell<HashMap<String, gtk::CheckButton>>,
+    pub(crate) category_buttons: RefCell<Vec<(SpotlightCategory, gtk::ToggleButton)>>,
+    pub(crate) compare_grid: RefCell<Option<gtk::Grid>>,
+    pub(crate) config_conflicts_list: RefCell<Option<gtk::ListBox>>,
     pub(crate) preferences_window: RefCell<Option<adw::PreferencesWindow>>,
     pub(crate) mirrors_window: RefCell<Option<adw::PreferencesWindow>>,
+    pub(crate) mirror_rows: RefCell<HashMap<String, adw::ActionRow>>,
+    pub(crate) mirror_test_button: RefCell<Option<gtk::Button>>,
     pub(crate) about_dialog: RefCell<Option<adw::MessageDialog>>,
+    pub(crate) alternatives_dialog: RefCell<Option<gtk::Dialog>>,
     pub(crate) update_log_buffer: RefCell<Option<gtk::TextBuffer>>,
     pub(crate) update_log_view: RefCell<Option<gtk::TextView>>,
+    pub(crate) update_log_chooser: RefCell<Option<gtk::FileChooserNative>>,
 }
 
 impl AppController {
@@ -56,21 +93,38 @@ impl AppController {
         let mut state = AppState::default();
         let cache = load_spotlight_cache_from_disk();
         let now = Utc::now();
-        let recent = compute_spotlight_sections(&cache, now);
+        let (window_days, limit) = {
+            let settings_ref = settings.borrow();
+            (
+                settings_ref.spotlight_window_days,
+                settings_ref.spotlight_recent_limit,
+            )
+        };
+        let recent = compute_spotlight_sections(&cache, now, window_days, limit);
         let categories = build_category_results(&cache);
 
         state.spotlight_cache = cache;
         state.spotlight_recent = recent;
         state.spotlight_categories = categories;
         state.spotlight_last_refresh = state.spotlight_cache.generated_at;
+        state.update_log_timestamps = true;
         {
             let settings_ref = settings.borrow();
+            state.last_visit = settings_ref.last_visit_at;
+            state.spotlight_new = settings_ref
+                .last_visit_at
+                .map(|since| compute_new_since(&state.spotlight_cache, since))
+                .unwrap_or_default();
             state.auto_check_enabled = settings_ref.auto_check_enabled;
             state.auto_check_frequency = settings_ref.auto_check_frequency;
+            state.auto_install_updates = settings_ref.auto_install_updates;
             state.confirm_install = settings_ref.confirm_install;
             state.confirm_remove = settings_ref.confirm_remove;
+            state.install_summary = settings_ref.install_summary;
             state.start_page_preference = settings_ref.start_page;
             state.notify_updates = settings_ref.notify_updates;
+            state.show_spotlight = settings_ref.show_spotlight;
+            state.run_updates_in_terminal = settings_ref.run_updates_in_terminal;
         }
         state.installed_row_buttons_visible = true;
         state.discover_row_buttons_visible = true;
@@ -87,11 +141,20 @@ impl AppController {
             discover_buttons: RefCell::new(HashMap::new()),
             discover_row_stacks: RefCell::new(HashMap::new()),
             discover_progress_bars: RefCell::new(HashMap::new()),
+            favorite_buttons: RefCell::new(HashMap::new()),
+            discover_select_checks: RefCell::new(HashMap::new()),
+            category_buttons: RefCell::new(Vec::new()),
+            compare_grid: RefCell::new(None),
+            config_conflicts_list: RefCell::new(None),
             preferences_window: RefCell::new(None),
             mirrors_window: RefCell::new(None),
+            mirror_rows: RefCell::new(HashMap::new()),
+            mirror_test_button: RefCell::new(None),
             about_dialog: RefCell::new(None),
+            alternatives_dialog: RefCell::new(None),
             update_log_buffer: RefCell::new(None),
             update_log_view: RefCell::new(None),
+            update_log_chooser: RefCell::new(None),
         }
     }
 
@@ -106,6 +169,16 @@ impl AppController {
                     controller.on_discover_search_changed(entry.text().to_string());
                 }
             ));
+        self.widgets
+            .discover
+            .search_descriptions_toggle
+            .connect_toggled(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |button| {
+                    controller.on_search_descriptions_toggled(button.is_active());
+                }
+            ));
 
         self.widgets
             .installed
@@ -213,171 +286,327 @@ impl AppController {
                     controller.on_discover_detail_close();
                 }
             ));
+        let recent_keys = gtk::EventControllerKey::new();
+        recent_keys.connect_key_pressed(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |_, key, _, modifiers| controller.on_spotlight_recent_key_pressed(key, modifiers)
+        ));
         self.widgets
             .discover
             .spotlight_recent_list
-            .connect_row_selected(glib::clone!(
+            .add_controller(recent_keys);
+        self.widgets
+            .discover
+            .spotlight_new_toggle
+            .connect_toggled(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |_, row| {
-                    controller.on_spotlight_recent_selected(row.cloned());
+                move |button| {
+                    controller.on_spotlight_new_toggled(button.is_active());
                 }
             ));
         self.widgets
             .discover
-            .spotlight_recent_list
-            .connect_row_activated(glib::clone!(
+            .detail_homepage_copy_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |_, row| {
-                    controller.on_spotlight_row_activated(row);
+                move |_| {
+                    controller.copy_homepage_link(controller.discover_detail_homepage());
                 }
             ));
         self.widgets
             .discover
-            .spotlight_recent_back_button
+            .spotlight_recent_detail_homepage_copy_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_discover_detail_back();
+                    controller.copy_homepage_link(controller.discover_detail_homepage());
+                }
+            ));
+        self.widgets
+            .installed
+            .detail_homepage_copy_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.copy_homepage_link(controller.installed_detail_homepage());
+                }
+            ));
+        self.widgets
+            .updates
+            .detail_homepage_copy_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.copy_homepage_link(controller.updates_detail_homepage());
                 }
             ));
         self.widgets
             .discover
-            .spotlight_recent_close_button
+            .detail_template_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_spotlight_recent_close();
+                    controller.open_package_template(controller.discover_detail_package_name());
                 }
             ));
         self.widgets
             .discover
-            .spotlight_recent_action_button
+            .spotlight_recent_template_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_discover_primary_action();
+                    controller.open_package_template(controller.discover_detail_package_name());
                 }
             ));
         self.widgets
             .discover
-            .category_browsers_button
-            .connect_toggled(glib::clone!(
+            .detail_command_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Browsers,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.copy_command_line(controller.discover_detail_command());
                 }
             ));
         self.widgets
             .discover
-            .category_chat_button
-            .connect_toggled(glib::clone!(
+            .spotlight_recent_command_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.copy_command_line(controller.discover_detail_command());
+                }
+            ));
+        self.widgets
+            .installed
+            .detail_command_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.copy_command_line(controller.installed_detail_command());
+                }
+            ));
+        self.widgets
+            .updates
+            .detail_command_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.copy_command_line(controller.updates_detail_command());
+                }
+            ));
+        self.widgets
+            .installed
+            .detail_template_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    let package = controller.state.borrow().installed_detail_package.clone();
+                    controller.open_package_template(package);
+                }
+            ));
+        self.widgets
+            .installed
+            .detail_files_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_files_requested();
+                }
+            ));
+        self.widgets
+            .updates
+            .detail_template_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller
-                        .handle_spotlight_category_toggle(SpotlightCategory::Chat, btn.is_active());
+                move |_| {
+                    let package = controller.state.borrow().updates_detail_package.clone();
+                    controller.open_package_template(package);
                 }
             ));
         self.widgets
             .discover
-            .category_games_button
-            .connect_toggled(glib::clone!(
+            .no_results_repo_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Games,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.show_mirrors();
                 }
             ));
         self.widgets
             .discover
-            .category_graphics_button
-            .connect_toggled(glib::clone!(
+            .install_selected_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Graphics,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.on_install_selected_requested();
                 }
             ));
         self.widgets
             .discover
-            .category_email_button
-            .connect_toggled(glib::clone!(
+            .queue_selected_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Email,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.on_queue_selected_installs();
                 }
             ));
         self.widgets
             .discover
-            .category_music_button
-            .connect_toggled(glib::clone!(
+            .compare_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Music,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.show_compare_dialog();
                 }
             ));
+        // Divider positions are saved with the window size on close, so
+        // dragging doesn't rewrite the settings file on every step.
         self.widgets
             .discover
-            .category_productivity_button
-            .connect_toggled(glib::clone!(
+            .content_row
+            .connect_position_notify(glib::clone!(
+                #[weak(rename_to = controller)]
+                self,
+                move |paned| {
+                    if paned.is_position_set() {
+                        controller.settings.borrow_mut().discover_pane_position =
+                            Some(paned.position());
+                    }
+                }
+            ));
+        self.widgets
+            .installed
+            .content_row
+            .connect_position_notify(glib::clone!(
+                #[weak(rename_to = controller)]
+                self,
+                move |paned| {
+                    if paned.is_position_set() {
+                        controller.settings.borrow_mut().installed_pane_position =
+                            Some(paned.position());
+                    }
+                }
+            ));
+        self.widgets
+            .discover
+            .detail_source_dropdown
+            .connect_selected_notify(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Productivity,
-                        btn.is_active(),
-                    );
+                move |dropdown| {
+                    controller.on_install_source_selected(dropdown.selected());
                 }
             ));
         self.widgets
             .discover
-            .category_utilities_button
-            .connect_toggled(glib::clone!(
+            .browse_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Utilities,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.on_browse_all_requested();
                 }
             ));
         self.widgets
             .discover
-            .category_video_button
-            .connect_toggled(glib::clone!(
+            .detail_favorite_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |btn| {
-                    controller.handle_spotlight_category_toggle(
-                        SpotlightCategory::Video,
-                        btn.is_active(),
-                    );
+                move |_| {
+                    controller.toggle_discover_detail_favorite();
+                }
+            ));
+        self.widgets
+            .discover
+            .spotlight_recent_favorite_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.toggle_discover_detail_favorite();
+                }
+            ));
+        self.widgets
+            .discover
+            .favorites_list
+            .connect_row_activated(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_, row| {
+                    controller.on_favorite_row_activated(row);
+                }
+            ));
+        self.widgets
+            .discover
+            .recently_viewed_list
+            .connect_row_activated(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_, row| {
+                    controller.on_recently_viewed_row_activated(row);
+                }
+            ));
+        self.widgets
+            .discover
+            .spotlight_recent_list
+            .connect_row_activated(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_, row| {
+                    controller.on_spotlight_row_activated(row);
+                }
+            ));
+        self.widgets
+            .discover
+            .spotlight_recent_back_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_discover_detail_back();
+                }
+            ));
+        self.widgets
+            .discover
+            .spotlight_recent_close_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_spotlight_recent_close();
+                }
+            ));
+        self.widgets
+            .discover
+            .spotlight_recent_action_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_discover_primary_action();
                 }
             ));
+        self.rebuild_category_buttons();
 
         self.widgets
             .discover
@@ -423,6 +652,17 @@ impl AppController {
                 }
             ));
 
+        self.widgets
+            .updates
+            .search_entry
+            .connect_search_changed(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |entry| {
+                    controller.on_updates_search_changed(entry.text().to_string());
+                }
+            ));
+
         self.widgets
             .tools
             .cleanup_button
@@ -449,86 +689,213 @@ impl AppController {
         }
         self.widgets
             .tools
-            .pkgdb_button
+            .cache_clear_all_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_pkgdb_requested();
+                    controller.on_cache_clear_all_requested();
                 }
             ));
         self.widgets
             .tools
-            .reconfigure_button
+            .pkgdb_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_reconfigure_requested();
+                    controller.on_pkgdb_requested();
                 }
             ));
         self.widgets
             .tools
-            .alternatives_button
+            .integrity_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_alternatives_requested();
+                    controller.on_integrity_check_requested();
                 }
             ));
-
         self.widgets
-            .installed
-            .filter_dropdown
-            .connect_selected_notify(glib::clone!(
+            .tools
+            .pkgdb_repair_button
+            .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
-                move |dropdown| {
-                    controller.on_installed_filter_changed(dropdown.selected());
+                move |_| {
+                    controller.on_pkgdb_repair_requested();
                 }
             ));
-
         self.widgets
-            .installed
-            .remove_selected_button
+            .tools
+            .reconfigure_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_installed_remove_selected();
+                    controller.on_reconfigure_requested();
                 }
             ));
-
         self.widgets
-            .installed
-            .detail_back_button
+            .tools
+            .alternatives_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_installed_detail_back();
+                    controller.on_alternatives_requested();
                 }
             ));
         self.widgets
-            .installed
-            .detail_close_button
+            .tools
+            .hold_all_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_installed_detail_close();
+                    controller.on_hold_all_requested(true);
                 }
             ));
-
         self.widgets
-            .installed
-            .detail_remove_button
+            .tools
+            .unhold_all_button
             .connect_clicked(glib::clone!(
                 #[strong(rename_to = controller)]
                 self,
                 move |_| {
-                    controller.on_installed_detail_remove();
+                    controller.on_hold_all_requested(false);
+                }
+            ));
+        self.widgets
+            .tools
+            .sync_repos_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_sync_repos_requested();
+                }
+            ));
+
+        self.widgets
+            .installed
+            .filter_dropdown
+            .connect_selected_notify(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |dropdown| {
+                    controller.on_installed_filter_changed(dropdown.selected());
+                }
+            ));
+
+        self.widgets
+            .installed
+            .group_variants_button
+            .set_active(self.settings.borrow().group_installed_subpackages);
+        self.widgets
+            .installed
+            .group_variants_button
+            .connect_toggled(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |button| {
+                    controller.set_group_installed_subpackages(button.is_active());
+                }
+            ));
+
+        self.widgets
+            .installed
+            .remove_selected_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_remove_selected();
+                }
+            ));
+
+        self.widgets
+            .installed
+            .queue_selected_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_queue_selected_removals();
+                }
+            ));
+
+        self.widgets
+            .changes_apply_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_apply_pending_changes();
+                }
+            ));
+        self.widgets
+            .changes_clear_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.clear_pending_changes();
+                }
+            ));
+
+        self.widgets
+            .installed
+            .hold_selected_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_hold_selected(true);
+                }
+            ));
+
+        self.widgets
+            .installed
+            .unhold_selected_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_hold_selected(false);
+                }
+            ));
+
+        self.widgets
+            .installed
+            .detail_back_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_detail_back();
+                }
+            ));
+        self.widgets
+            .installed
+            .detail_close_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_detail_close();
+                }
+            ));
+
+        self.widgets
+            .installed
+            .detail_remove_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.on_installed_detail_remove();
                 }
             ));
 
@@ -601,6 +968,86 @@ impl AppController {
                     controller.update_all_packages();
                 }
             ));
+        self.widgets
+            .updates
+            .select_all_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.set_all_updates_selected(true);
+                }
+            ));
+        self.widgets
+            .updates
+            .select_none_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.set_all_updates_selected(false);
+                }
+            ));
+        self.widgets
+            .updates
+            .log_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.show_update_log_dialog();
+                }
+            ));
+        self.widgets
+            .updates
+            .config_banner
+            .connect_button_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.show_config_conflicts_dialog();
+                }
+            ));
+        self.widgets
+            .updates
+            .restart_banner
+            .connect_button_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.dismiss_restart_banner();
+                }
+            ));
+        self.widgets
+            .updates
+            .xbps_banner
+            .connect_button_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.update_xbps_first();
+                }
+            ));
+        self.widgets
+            .updates
+            .history_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.show_update_history_dialog();
+                }
+            ));
+        self.widgets
+            .updates
+            .retry_failed_button
+            .connect_clicked(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |_| {
+                    controller.retry_failed_updates();
+                }
+            ));
 
         self.widgets
             .updates
@@ -768,6 +1215,23 @@ impl AppController {
         }
     }
 
+    pub(crate) fn set_auto_install_updates(&self, enabled: bool, persist: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.auto_install_updates = enabled;
+            if !enabled {
+                state.auto_install_pending = false;
+            }
+        }
+        if persist {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.auto_install_updates = enabled;
+            }
+            self.persist_settings();
+        }
+    }
+
     pub(crate) fn set_confirm_install(&self, enabled: bool, persist: bool) {
         {
             let mut state = self.state.borrow_mut();
@@ -796,83 +1260,401 @@ impl AppController {
         }
     }
 
-    pub(crate) fn set_notify_updates(self: &Rc<Self>, enabled: bool, persist: bool) {
+    pub(crate) fn set_install_summary(&self, enabled: bool, persist: bool) {
         {
             let mut state = self.state.borrow_mut();
-            state.notify_updates = enabled;
-            state.updates_notification_sent = false;
+            state.install_summary = enabled;
         }
-        self.withdraw_updates_notification();
         if persist {
             {
                 let mut settings = self.settings.borrow_mut();
-                settings.notify_updates = enabled;
+                settings.install_summary = enabled;
             }
             self.persist_settings();
         }
     }
 
-    pub(crate) fn set_waypoint_before_upgrades(&self, enabled: bool, persist: bool) {
-        if persist {
-            {
-                let mut settings = self.settings.borrow_mut();
-                settings.waypoint_before_upgrades = enabled;
+    pub(crate) fn set_min_free_space_mb(&self, megabytes: u64) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.min_free_space_mb == megabytes {
+                return;
             }
-            self.persist_settings();
+            settings.min_free_space_mb = megabytes;
         }
+        self.persist_settings();
     }
 
-    pub(crate) fn confirm_action<F>(
-        self: &Rc<Self>,
-        heading: &str,
-        body: &str,
-        confirm_label: &str,
-        on_confirm: F,
-    ) where
-        F: FnOnce(&Rc<Self>) + 'static,
-    {
-        let dialog = gtk::MessageDialog::builder()
-            .text(heading)
-            .secondary_text(body)
-            .message_type(gtk::MessageType::Question)
-            .modal(true)
-            .build();
-        dialog.set_transient_for(Some(&self.window));
-        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
-        dialog.add_button(confirm_label, gtk::ResponseType::Accept);
-        dialog.set_default_response(gtk::ResponseType::Accept);
-        let controller_weak = Rc::downgrade(self);
-        let callback = Rc::new(RefCell::new(Some(on_confirm)));
-        dialog.connect_response(move |dlg, response| {
-            dlg.close();
-            if response == gtk::ResponseType::Accept {
-                if let Some(controller) = controller_weak.upgrade() {
-                    if let Some(callback) = callback.borrow_mut().take() {
-                        callback(&controller);
-                    }
-                }
+    pub(crate) fn remove_options(&self) -> RemoveOptions {
+        let settings = self.settings.borrow();
+        RemoveOptions {
+            recursive: settings.remove_recursive,
+            orphans: settings.remove_orphans,
+        }
+    }
+
+    pub(crate) fn set_remove_options(&self, options: RemoveOptions) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.remove_recursive == options.recursive
+                && settings.remove_orphans == options.orphans
+            {
+                return;
             }
-        });
-        dialog.present();
+            settings.remove_recursive = options.recursive;
+            settings.remove_orphans = options.orphans;
+        }
+        self.persist_settings();
     }
 
-    pub(crate) fn begin_install(self: &Rc<Self>, package: PackageInfo) {
-        self.execute_install(package);
+    /// Spells out what a removal will take with it under the current
+    /// Preferences, for the confirmation dialogs.
+    pub(crate) fn remove_behavior_note(&self) -> String {
+        let options = self.remove_options();
+        let mut lines = Vec::new();
+        if options.recursive {
+            lines.push("Dependencies that no other package needs are removed as well.");
+        } else {
+            lines.push("Dependencies are kept, even if nothing else needs them.");
+        }
+        if options.orphans {
+            lines.push("Orphaned packages on the system are cleaned up too.");
+        }
+        // xbps-remove has no way to purge configuration files.
+        lines.push("Configuration files you have changed are kept.");
+        lines.join("\n")
     }
 
-    pub(crate) fn execute_install(self: &Rc<Self>, package: PackageInfo) {
+    pub(crate) fn set_high_contrast(&self, enabled: bool) {
         {
-            let mut state = self.state.borrow_mut();
-            if state.install_in_progress {
+            let mut settings = self.settings.borrow_mut();
+            if settings.high_contrast == enabled {
                 return;
             }
-            state.install_in_progress = true;
-            state.installing_package = Some(package.name.clone());
+            settings.high_contrast = enabled;
         }
+        apply_high_contrast_css_class(&self.window, enabled);
+        self.persist_settings();
+    }
 
-        // Track the operation start
+    pub(crate) fn compact_lists(&self) -> bool {
+        self.settings.borrow().list_density == ListDensity::Compact
+    }
+
+    pub(crate) fn set_list_density(self: &Rc<Self>, density: ListDensity) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.list_density == density {
+                return;
+            }
+            settings.list_density = density;
+        }
+        self.persist_settings();
+        self.apply_list_density();
+        self.rebuild_search_list();
+        self.rebuild_installed_list();
+        self.rebuild_updates_list();
+    }
+
+    /// Marks the package lists for the compact row padding in style.css.
+    pub(crate) fn apply_list_density(&self) {
+        let compact = self.compact_lists();
+        let lists: [&gtk::Widget; 3] = [
+            self.widgets.discover.list.upcast_ref(),
+            self.widgets.installed.list_view.upcast_ref(),
+            self.widgets.updates.list.upcast_ref(),
+        ];
+        for list in lists {
+            if compact {
+                list.add_css_class("nebula-compact");
+            } else {
+                list.remove_css_class("nebula-compact");
+            }
+        }
+    }
+
+    /// Puts the list/detail dividers back where they were last dragged to.
+    pub(crate) fn restore_pane_positions(&self) {
+        // Copied out first: setting a position notifies the handlers that
+        // record it in the settings.
+        let (discover, installed) = {
+            let settings = self.settings.borrow();
+            (
+                settings.discover_pane_position,
+                settings.installed_pane_position,
+            )
+        };
+        if let Some(position) = discover {
+            self.widgets.discover.content_row.set_position(position);
+        }
+        if let Some(position) = installed {
+            self.widgets.installed.content_row.set_position(position);
+        }
+    }
+
+    pub(crate) fn set_font_scale_percent(&self, percent: u32) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.font_scale_percent == percent {
+                return;
+            }
+            settings.font_scale_percent = percent;
+        }
+        apply_font_scale(percent);
+        self.persist_settings();
+    }
+
+    pub(crate) fn set_notify_updates(self: &Rc<Self>, enabled: bool, persist: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.notify_updates = enabled;
+            state.updates_notification_sent = false;
+        }
+        self.withdraw_updates_notification();
+        if persist {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.notify_updates = enabled;
+            }
+            self.persist_settings();
+        }
+    }
+
+    pub(crate) fn set_run_updates_in_terminal(&self, enabled: bool, persist: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.run_updates_in_terminal = enabled;
+        }
+        if persist {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.run_updates_in_terminal = enabled;
+            }
+            self.persist_settings();
+        }
+    }
+
+    pub(crate) fn set_exclude_kernel_from_update_all(&self, enabled: bool) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.exclude_kernel_from_update_all == enabled {
+                return;
+            }
+            settings.exclude_kernel_from_update_all = enabled;
+        }
+        self.persist_settings();
+    }
+
+    pub(crate) fn marks_available_updates(&self) -> bool {
+        self.settings.borrow().mark_available_updates
+    }
+
+    pub(crate) fn set_mark_available_updates(self: &Rc<Self>, enabled: bool) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.mark_available_updates == enabled {
+                return;
+            }
+            settings.mark_available_updates = enabled;
+        }
+        self.persist_settings();
+        self.rebuild_search_list();
+        self.rebuild_installed_list();
+    }
+
+    pub(crate) fn set_spotlight_window_days(self: &Rc<Self>, days: u32) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.spotlight_window_days == days {
+                return;
+            }
+            settings.spotlight_window_days = days;
+        }
+        self.persist_settings();
+        self.recompute_spotlight_recent();
+    }
+
+    pub(crate) fn set_spotlight_recent_limit(self: &Rc<Self>, limit: usize) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.spotlight_recent_limit == limit {
+                return;
+            }
+            settings.spotlight_recent_limit = limit;
+        }
+        self.persist_settings();
+        self.recompute_spotlight_recent();
+    }
+
+    pub(crate) fn set_show_spotlight(self: &Rc<Self>, enabled: bool, persist: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.show_spotlight = enabled;
+        }
+        if persist {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.show_spotlight = enabled;
+            }
+            self.persist_settings();
+        }
+        if enabled {
+            self.maybe_refresh_spotlight(false);
+        } else {
+            self.cancel_spotlight_retry();
+        }
+        self.update_discover_layout();
+    }
+
+    pub(crate) fn set_waypoint_before_upgrades(&self, enabled: bool, persist: bool) {
+        if persist {
+            {
+                let mut settings = self.settings.borrow_mut();
+                settings.waypoint_before_upgrades = enabled;
+            }
+            self.persist_settings();
+        }
+    }
+
+    pub(crate) fn confirm_action<F>(
+        self: &Rc<Self>,
+        heading: &str,
+        body: &str,
+        confirm_label: &str,
+        on_confirm: F,
+    ) where
+        F: FnOnce(&Rc<Self>) + 'static,
+    {
+        self.confirm_command(heading, body, confirm_label, None, on_confirm);
+    }
+
+    /// Like [`Self::confirm_action`], with a "Copy Command" button that puts
+    /// the terminal equivalent of the action on the clipboard.
+    pub(crate) fn confirm_command<F>(
+        self: &Rc<Self>,
+        heading: &str,
+        body: &str,
+        confirm_label: &str,
+        command: Option<String>,
+        on_confirm: F,
+    ) -> gtk::MessageDialog
+    where
+        F: FnOnce(&Rc<Self>) + 'static,
+    {
+        let command =
+            command.map(|command| Box::new(move || command.clone()) as Box<dyn Fn() -> String>);
+        self.confirm_command_with(heading, body, confirm_label, command, on_confirm)
+    }
+
+    /// Like [`Self::confirm_command`], but builds the command when it is
+    /// copied, so it can follow options picked in the dialog.
+    pub(crate) fn confirm_command_with<F>(
+        self: &Rc<Self>,
+        heading: &str,
+        body: &str,
+        confirm_label: &str,
+        command: Option<Box<dyn Fn() -> String>>,
+        on_confirm: F,
+    ) -> gtk::MessageDialog
+    where
+        F: FnOnce(&Rc<Self>) + 'static,
+    {
+        let dialog = gtk::MessageDialog::builder()
+            .text(heading)
+            .secondary_text(body)
+            .message_type(gtk::MessageType::Question)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(Some(&self.window));
+        if command.is_some() {
+            dialog.add_button("Copy Command", COPY_COMMAND_RESPONSE);
+        }
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        dialog.add_button(confirm_label, gtk::ResponseType::Accept);
+        dialog.set_default_response(gtk::ResponseType::Accept);
+        let controller_weak = Rc::downgrade(self);
+        let callback = Rc::new(RefCell::new(Some(on_confirm)));
+        dialog.connect_response(move |dlg, response| {
+            if response == COPY_COMMAND_RESPONSE {
+                // Copying leaves the dialog open so the action can still run.
+                if let Some(controller) = controller_weak.upgrade() {
+                    controller.copy_command_line(command.as_ref().map(|build| build()));
+                }
+                return;
+            }
+            dlg.close();
+            if response == gtk::ResponseType::Accept {
+                if let Some(controller) = controller_weak.upgrade() {
+                    if let Some(callback) = callback.borrow_mut().take() {
+                        callback(&controller);
+                    }
+                }
+            }
+        });
+        dialog.present();
+        dialog
+    }
+
+    /// Measures free space on a worker for [`Self::disk_space_warning`], so
+    /// `df` never runs on the UI thread.
+    pub(crate) fn refresh_free_space(&self) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = transaction_free_space();
+            let _ = sender.send(AppMessage::FreeSpaceMeasured { result });
+        });
+    }
+
+    /// Describes the shortfall when a transaction of about `required_bytes`
+    /// would leave less than the configured free space on the tightest
+    /// filesystem it touches. A threshold of zero turns the check off, and
+    /// nothing is reported until free space has been measured.
+    pub(crate) fn disk_space_warning(&self, required_bytes: u64) -> Option<String> {
+        let headroom_mb = self.settings.borrow().min_free_space_mb;
+        if headroom_mb == 0 {
+            return None;
+        }
+        let headroom_bytes = headroom_mb.saturating_mul(1024 * 1024);
+        let space = self.state.borrow().free_space.clone()?;
+        if space.available_bytes >= required_bytes.saturating_add(headroom_bytes) {
+            return None;
+        }
+        Some(format!(
+            "Disk space is low: {} is free on {}. This needs about {}, and Nebula is set to keep {} free.",
+            format_size(space.available_bytes),
+            space.mount_point,
+            format_size(required_bytes),
+            format_size(headroom_bytes)
+        ))
+    }
+
+    pub(crate) fn begin_install(self: &Rc<Self>, package: PackageInfo) {
+        self.execute_install(package);
+    }
+
+    pub(crate) fn execute_install(self: &Rc<Self>, package: PackageInfo) {
+        if self.queue_if_busy(QueuedOperation::Install(package.clone())) {
+            return;
+        }
+        {
+            let mut state = self.state.borrow_mut();
+            state.install_in_progress = true;
+            state.installing_package = Some(package.name.clone());
+        }
+        self.refresh_busy_indicator();
+
+        // Track the operation start
         use crate::state::types::OperationType;
-        let command = format!("xbps-install -y {}", package.name);
+        let source = self
+            .state
+            .borrow()
+            .install_sources
+            .get(&package.name)
+            .cloned();
+        let command = match source.as_deref() {
+            Some(repository) => format!("xbps-install -R {} -y {}", repository, package.name),
+            None => format!("xbps-install -y {}", package.name),
+        };
         self.start_operation_tracking(package.name.clone(), OperationType::Install, command);
 
         self.rebuild_search_list();
@@ -884,7 +1666,7 @@ impl AppController {
         let sender = self.sender.clone();
         let package_name = package.name.clone();
         thread::spawn(move || {
-            let result = run_xbps_install(&package_name);
+            let result = run_xbps_install(&package_name, source.as_deref());
             let _ = sender.send(AppMessage::InstallFinished {
                 package: package_name,
                 result,
@@ -892,15 +1674,128 @@ impl AppController {
         });
     }
 
-    pub(crate) fn execute_remove(self: &Rc<Self>, package: String, origin: RemoveOrigin) {
+    /// Holds a single-package request back while another install, removal
+    /// or update is running. Returns true when the request must not start
+    /// now, either because it was queued or because the package is already
+    /// being worked on.
+    fn queue_if_busy(self: &Rc<Self>, operation: QueuedOperation) -> bool {
+        let message = {
+            let mut state = self.state.borrow_mut();
+            if !state.package_transaction_running() {
+                return false;
+            }
+            let package = operation.package();
+            if state.is_queued(package)
+                || state.installing_package.as_deref() == Some(package)
+                || state.removing_packages.contains(package)
+            {
+                return true;
+            }
+            let message = match &operation {
+                QueuedOperation::Install(pkg) => format!("Queued install of {}.", pkg.name),
+                QueuedOperation::Remove { package, .. } => {
+                    format!("Queued removal of {}.", package)
+                }
+            };
+            state.operation_queue.push_back(operation);
+            message
+        };
+        self.show_toast(&message);
+        self.refresh_discover_row_progress();
+        self.rebuild_installed_list();
+        true
+    }
+
+    /// Starts the oldest queued request once nothing else holds the package
+    /// database.
+    pub(crate) fn start_next_queued_operation(self: &Rc<Self>) {
+        let next = {
+            let mut state = self.state.borrow_mut();
+            if state.package_transaction_running() {
+                return;
+            }
+            state.operation_queue.pop_front()
+        };
+        match next {
+            Some(QueuedOperation::Install(package)) => self.execute_install(package),
+            Some(QueuedOperation::Remove { package, origin }) => {
+                self.execute_remove(package, origin)
+            }
+            None => {}
+        }
+    }
+
+    pub(crate) fn execute_install_batch(self: &Rc<Self>, packages: Vec<String>) {
+        if packages.is_empty() {
+            return;
+        }
+
         {
             let mut state = self.state.borrow_mut();
-            if state.remove_in_progress {
+            if state.install_in_progress {
                 return;
             }
+            state.install_in_progress = true;
+        }
+        self.refresh_busy_indicator();
+
+        use crate::state::types::OperationType;
+        let command = format!("xbps-install -y {}", packages.join(" "));
+        for package in &packages {
+            self.start_operation_tracking(package.clone(), OperationType::Install, command.clone());
+        }
+
+        self.rebuild_search_list();
+        self.refresh_discover_install_widgets();
+
+        let message = format!(
+            "Installing {} selected package{}…",
+            packages.len(),
+            if packages.len() == 1 { "" } else { "s" }
+        );
+        self.set_footer_message(Some(&message));
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let (packages, skipped) = split_available_packages(&packages);
+            let result = if packages.is_empty() {
+                Err("None of the packages are available from the enabled repositories.".to_string())
+            } else {
+                let line_sender = sender.clone();
+                run_xbps_install_packages(&packages, |line| {
+                    let _ = line_sender.send(AppMessage::InstallLogLine { line });
+                })
+            };
+            // Only explains the skipped names, so it must not hold up the
+            // install itself.
+            let other_architectures = if skipped.is_empty() {
+                HashMap::new()
+            } else {
+                find_other_architectures(&skipped)
+            };
+            let _ = sender.send(AppMessage::InstallBatchFinished {
+                packages,
+                skipped,
+                other_architectures,
+                result,
+            });
+        });
+    }
+
+    pub(crate) fn execute_remove(self: &Rc<Self>, package: String, origin: RemoveOrigin) {
+        let queued = QueuedOperation::Remove {
+            package: package.clone(),
+            origin,
+        };
+        if self.queue_if_busy(queued) {
+            return;
+        }
+        {
+            let mut state = self.state.borrow_mut();
             state.remove_in_progress = true;
             state.removing_packages.insert(package.clone());
         }
+        self.refresh_busy_indicator();
 
         // Track the operation start
         use crate::state::types::OperationType;
@@ -922,9 +1817,10 @@ impl AppController {
         self.refresh_discover_install_widgets();
         self.restore_discover_focus_for(&package);
 
+        let options = self.remove_options();
         let sender = self.sender.clone();
         thread::spawn(move || {
-            let result = run_xbps_remove(&package);
+            let result = run_xbps_remove(&package, options);
             let _ = sender.send(AppMessage::RemoveFinished { package, result });
         });
     }
@@ -944,6 +1840,7 @@ impl AppController {
                 state.removing_packages.insert(pkg.clone());
             }
         }
+        self.refresh_busy_indicator();
 
         self.update_installed_selection_ui();
 
@@ -968,10 +1865,11 @@ impl AppController {
             }
         }
 
+        let options = self.remove_options();
         let sender = self.sender.clone();
         let packages_for_thread = packages.clone();
         thread::spawn(move || {
-            let result = run_xbps_remove_packages(&packages_for_thread);
+            let result = run_xbps_remove_packages(&packages_for_thread, options);
             let _ = sender.send(AppMessage::RemoveBatchFinished {
                 packages: packages_for_thread,
                 result,
@@ -989,26 +1887,133 @@ impl AppController {
                     self.refresh_installed_packages();
                 }
             }
-            _ => {}
+            _ => {}
+        }
+
+        if let Some(name) = current.as_deref() {
+            self.record_last_page(name);
+        }
+    }
+
+    pub(crate) fn start_remove(self: &Rc<Self>, package: String, origin: RemoveOrigin) {
+        if self.state.borrow().should_confirm_remove() {
+            self.check_removal_impact(RemovalRequest::Single { package, origin });
+            return;
+        }
+
+        self.begin_remove(package, origin);
+    }
+
+    /// Looks up which installed packages depend on a removal before asking
+    /// the user to confirm it.
+    pub(crate) fn check_removal_impact(self: &Rc<Self>, request: RemovalRequest) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.removal_impact_pending {
+                return;
+            }
+            state.removal_impact_pending = true;
         }
 
-        if let Some(name) = current.as_deref() {
-            self.record_last_page(name);
-        }
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = query_removal_dependents(&request.packages());
+            let _ = sender.send(AppMessage::RemovalImpactLoaded { request, result });
+        });
     }
 
-    pub(crate) fn start_remove(self: &Rc<Self>, package: String, origin: RemoveOrigin) {
-        if self.state.borrow().confirm_remove {
-            let pkg_clone = package.clone();
-            let heading = format!("Remove \"{}\"?", package);
-            let body = "The package and its data will be removed from this system.";
-            self.confirm_action(&heading, body, "Remove", move |controller| {
-                controller.begin_remove(pkg_clone.clone(), origin);
+    fn finish_removal_impact(
+        self: &Rc<Self>,
+        request: RemovalRequest,
+        result: Result<Vec<String>, String>,
+    ) {
+        self.state.borrow_mut().removal_impact_pending = false;
+
+        let packages = request.packages();
+        let heading = if packages.len() == 1 {
+            format!("Remove \"{}\"?", packages[0])
+        } else {
+            format!("Remove {} selected packages?", packages.len())
+        };
+        let mut body = if packages.len() == 1 {
+            "The package and its data will be removed from this system.".to_string()
+        } else {
+            format!(
+                "The following packages will be removed: {}.\n\nAll packages and their data will be removed from this system.",
+                summarize_package_list(&packages)
+            )
+        };
+
+        body.push_str(&format!("\n\n{}", self.remove_behavior_note()));
+
+        let dependents = match result {
+            Ok(dependents) => dependents,
+            Err(err) => {
+                body.push_str(&format!(
+                    "\n\nNebula couldn't check which installed packages depend on this: {}",
+                    err
+                ));
+                Vec::new()
+            }
+        };
+
+        if dependents.is_empty() {
+            let command = Some(remove_command_line(&packages, self.remove_options()));
+            self.confirm_command(&heading, &body, "Remove", command, move |controller| {
+                controller.remove_requested(request)
             });
             return;
         }
 
-        self.begin_remove(package, origin);
+        // xbps-remove refuses to leave installed packages with a missing
+        // dependency. Removing the dependents as well has to be picked
+        // explicitly, and Enter only ever cancels.
+        body.push_str(&format!(
+            "\n\nThese installed packages depend on it and would break: {}.\n\nXBPS will refuse the removal unless they are removed as well.",
+            summarize_package_list(&dependents)
+        ));
+        let cascade_check = gtk::CheckButton::with_label(&format!(
+            "Also remove the {} dependent package{}",
+            dependents.len(),
+            if dependents.len() == 1 { "" } else { "s" }
+        ));
+        let mut cascade = packages.clone();
+        cascade.extend(dependents);
+        let check = cascade_check.clone();
+        let options = self.remove_options();
+        let cascade_command = cascade.clone();
+        let command: Box<dyn Fn() -> String> = Box::new(move || {
+            if check.is_active() {
+                remove_command_line(&cascade_command, options)
+            } else {
+                remove_command_line(&packages, options)
+            }
+        });
+        let check = cascade_check.clone();
+        let dialog = self.confirm_command_with(
+            &heading,
+            &body,
+            "Remove",
+            Some(command),
+            move |controller| {
+                if check.is_active() {
+                    controller.execute_remove_batch(cascade);
+                } else {
+                    controller.remove_requested(request);
+                }
+            },
+        );
+        dialog.set_default_response(gtk::ResponseType::Cancel);
+        if let Ok(area) = dialog.message_area().downcast::<gtk::Box>() {
+            area.append(&cascade_check);
+        }
+    }
+
+    fn remove_requested(self: &Rc<Self>, request: RemovalRequest) {
+        match request {
+            RemovalRequest::Single { package, origin } => self.begin_remove(package, origin),
+            RemovalRequest::Batch { packages } => self.execute_remove_batch(packages),
+        }
     }
 
     pub(crate) fn begin_remove(self: &Rc<Self>, package: String, origin: RemoveOrigin) {
@@ -1017,21 +2022,65 @@ impl AppController {
 
     pub(crate) fn handle_message(self: &Rc<Self>, msg: AppMessage) {
         match msg {
-            AppMessage::SearchFinished { query, result } => {
-                self.finish_search(query, result);
+            AppMessage::RepositoryIndexLoaded { result } => {
+                self.finish_repository_index(result);
+            }
+            AppMessage::SearchFinished {
+                sequence,
+                query,
+                result,
+            } => {
+                self.finish_search(sequence, query, result);
             }
             AppMessage::InstalledFinished { result } => {
                 self.finish_installed_refresh(result);
             }
             AppMessage::InstallFinished { package, result } => {
+                self.respond_to_privilege_failure(&result);
                 self.finish_install(package, result);
             }
             AppMessage::RemoveFinished { package, result } => {
+                self.respond_to_privilege_failure(&result);
                 self.finish_remove(package, result);
             }
+            AppMessage::InstallBatchFinished {
+                packages,
+                skipped,
+                other_architectures,
+                result,
+            } => {
+                self.respond_to_privilege_failure(&result);
+                self.finish_install_batch(packages, skipped, other_architectures, result);
+            }
+            AppMessage::InstallLogLine { line } => {
+                self.set_footer_message(Some(&line));
+            }
             AppMessage::RemoveBatchFinished { packages, result } => {
+                self.respond_to_privilege_failure(&result);
                 self.finish_remove_batch(packages, result);
             }
+            AppMessage::PendingChangesImpactLoaded { result } => {
+                self.finish_pending_changes_impact(result);
+            }
+            AppMessage::PendingChangesFinished {
+                installs,
+                removes,
+                result,
+            } => {
+                self.respond_to_privilege_failure(&result);
+                self.finish_pending_changes(installs, removes, result);
+            }
+            AppMessage::ConfigConflictResolved {
+                conflict,
+                resolution,
+                result,
+            } => {
+                self.respond_to_privilege_failure(&result);
+                self.finish_config_resolution(conflict, resolution, result);
+            }
+            AppMessage::RemovalImpactLoaded { request, result } => {
+                self.finish_removal_impact(request, result);
+            }
             AppMessage::PinOperationFinished {
                 package,
                 target_pinned,
@@ -1039,32 +2088,65 @@ impl AppController {
             } => {
                 self.finish_pin_toggle(package, target_pinned, result);
             }
+            AppMessage::PinBatchFinished {
+                packages,
+                target_pinned,
+                result,
+            } => {
+                self.finish_pin_batch(packages, target_pinned, result);
+            }
+            AppMessage::HeldPackagesLoaded { result } => {
+                self.finish_held_packages(result);
+            }
             AppMessage::InstalledDetailsLoaded { package, result } => {
                 self.finish_installed_detail(package, result);
             }
+            AppMessage::PackageFilesLoaded { package, result } => {
+                self.finish_package_files(package, result);
+            }
             AppMessage::UpdatesDetailLoaded { package, result } => {
                 self.finish_updates_detail(package, result);
             }
             AppMessage::UpdatesRefreshed {
                 packages,
+                sizes,
                 success,
                 error,
             } => {
-                self.finish_updates_refresh(packages, success, error);
+                self.finish_updates_refresh(packages, sizes, success, error);
             }
             AppMessage::UpdateFinished {
                 packages,
                 result,
                 all,
             } => {
+                self.respond_to_privilege_failure(&result);
                 self.finish_update(packages, result, all);
             }
             AppMessage::UpdateLogLine { line } => {
                 self.on_update_log_line(line);
             }
+            AppMessage::UpdateLogSaved { result } => {
+                self.finish_update_log_save(result);
+            }
+            AppMessage::UnattendedAuthorizationChecked { authorized } => {
+                self.finish_unattended_authorization(authorized);
+            }
             AppMessage::DiscoverDetailLoaded { package, result } => {
                 self.finish_discover_detail(package, result);
             }
+            AppMessage::ScreenshotLoaded { url, result } => {
+                self.finish_screenshot_loaded(url, result);
+            }
+            AppMessage::AppStreamCatalogLoaded => {
+                self.finish_appstream_catalog();
+            }
+            AppMessage::PackageUriResolved { uri, package } => {
+                self.finish_package_uri(uri, package);
+            }
+            AppMessage::PackageTemplateResolved { package, source } => {
+                self.finish_package_template(&package, source.as_deref());
+            }
             AppMessage::SpotlightLoaded {
                 recent,
                 categories,
@@ -1077,15 +2159,168 @@ impl AppController {
                 self.finish_spotlight_failed(error);
             }
             AppMessage::MaintenanceFinished { task, result } => {
+                self.respond_to_privilege_failure(&result);
                 self.finish_maintenance(task, result);
             }
+            AppMessage::AlternativeSetFinished {
+                group,
+                package,
+                result,
+            } => {
+                self.respond_to_privilege_failure(&result);
+                self.finish_alternative_set(group, package, result);
+            }
+            AppMessage::IntegrityCheckFinished { result } => {
+                self.finish_integrity_check(result);
+            }
+            AppMessage::CacheCleanupPlanned { result } => {
+                self.finish_cache_cleanup_plan(result);
+            }
+            AppMessage::CacheSizeLoaded { result } => {
+                self.finish_cache_size(result);
+            }
+            AppMessage::FreeSpaceMeasured { result } => {
+                self.state.borrow_mut().free_space = result.ok();
+            }
+            AppMessage::MaintenanceLogLine { line } => {
+                self.append_maintenance_log_line(&line);
+            }
+            AppMessage::RebootFinished { result } => {
+                self.respond_to_privilege_failure(&result);
+                self.finish_reboot_request(result);
+            }
+            AppMessage::RestartCheckFinished { processes } => {
+                self.finish_restart_check(processes);
+            }
+            AppMessage::HeldBlockersUnheld {
+                held,
+                packages,
+                all,
+                result,
+            } => {
+                self.finish_unhold_for_update(held, packages, all, result);
+            }
+            AppMessage::HoldAllProgress { done, total } => {
+                self.update_hold_all_progress(done, total);
+            }
             AppMessage::MirrorsDetected { mirrors } => {
                 self.finish_mirror_detection(mirrors);
             }
+            AppMessage::RepositoryClassesDetected { classes } => {
+                self.finish_repository_class_detection(classes);
+            }
+            AppMessage::MirrorStatsMeasured { stats } => {
+                self.finish_mirror_stats(stats);
+            }
             AppMessage::SnapshotComplete { result } => {
                 self.finish_snapshot_creation(result);
             }
         }
+        self.refresh_busy_indicator();
+        self.close_if_idle();
+    }
+
+    /// True when quitting now would cut off an xbps process mid-transaction.
+    /// Its output pipe closes with the app, which kills it half-way through.
+    pub(crate) fn close_would_interrupt(&self) -> bool {
+        self.state.borrow().has_package_operation()
+    }
+
+    /// Asked instead of closing while a transaction runs. Closing anyway
+    /// hides the window and lets the transaction finish before quitting.
+    pub(crate) fn confirm_close_during_operation(self: &Rc<Self>) {
+        if self.state.borrow().close_when_idle {
+            return;
+        }
+        self.confirm_action(
+            "An operation is running. Close anyway?",
+            "Nebula will close as soon as the running operation finishes, so the package database is not left half-updated. Queued operations will not start.",
+            "Close Anyway",
+            |controller| controller.close_when_idle(),
+        );
+    }
+
+    fn close_when_idle(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.close_when_idle = true;
+            state.operation_queue.clear();
+        }
+        self.window.set_visible(false);
+        self.close_if_idle();
+    }
+
+    /// Finishes a deferred close once the last transaction has reported back.
+    fn close_if_idle(&self) {
+        if self.state.borrow().close_when_idle && !self.close_would_interrupt() {
+            self.window.close();
+        }
+    }
+
+    pub(crate) fn setup_network_monitor(self: &Rc<Self>) {
+        let monitor = gio::NetworkMonitor::default();
+        self.apply_network_state(monitor.is_network_available());
+        monitor.connect_network_changed(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |_, available| {
+                controller.apply_network_state(available);
+            }
+        ));
+    }
+
+    fn apply_network_state(self: &Rc<Self>, available: bool) {
+        self.widgets.offline_banner.set_revealed(!available);
+
+        let (changed, retry_search, updates_busy) = {
+            let mut state = self.state.borrow_mut();
+            if state.offline == !available {
+                (false, false, false)
+            } else {
+                state.offline = !available;
+                let retry_search = available && std::mem::take(&mut state.pending_search);
+                (
+                    true,
+                    retry_search,
+                    state.update_in_progress || state.updates_loading,
+                )
+            }
+        };
+        if !changed {
+            return;
+        }
+
+        self.set_check_buttons_sensitive(!updates_busy);
+        self.update_update_controls();
+        let search_tooltip = if available {
+            None
+        } else {
+            Some("You're offline. Searches will run once the network is back.")
+        };
+        self.widgets
+            .discover
+            .search_entry
+            .set_tooltip_text(search_tooltip);
+
+        if available {
+            self.show_toast("Back online.");
+            if retry_search {
+                self.on_search_requested();
+            }
+        }
+    }
+
+    pub(crate) fn refresh_busy_indicator(&self) {
+        let busy = self.state.borrow().has_background_work();
+        let spinner = &self.widgets.busy_spinner;
+        if busy {
+            spinner.set_visible(true);
+            spinner.start();
+        } else {
+            spinner.stop();
+            spinner.set_visible(false);
+        }
+        self.refresh_pending_changes_bar();
     }
 
     fn finish_mirror_detection(self: &Rc<Self>, mirrors: Vec<String>) {
@@ -1130,6 +2365,16 @@ impl AppController {
         }
     }
 
+    fn finish_repository_class_detection(self: &Rc<Self>, classes: Vec<RepositoryClass>) {
+        if self.state.borrow().repository_classes == classes {
+            return;
+        }
+        self.state.borrow_mut().repository_classes = classes.clone();
+        set_active_repository_classes(&classes);
+        self.settings.borrow_mut().repository_classes = classes;
+        self.persist_settings();
+    }
+
     fn finish_snapshot_creation(self: &Rc<Self>, result: crate::waypoint::SnapshotResult) {
         use crate::waypoint::SnapshotResult;
 
@@ -1154,12 +2399,14 @@ impl AppController {
             return;
         };
 
+        let options = std::mem::take(&mut self.state.borrow_mut().snapshot_update_options);
+
         // Handle snapshot result
         match result {
             SnapshotResult::Success(snapshot_name) => {
                 self.show_toast(&format!("Snapshot created: {}", snapshot_name));
                 // Proceed with update
-                self.execute_update(package, from_all);
+                self.execute_update(package, from_all, options);
             }
             SnapshotResult::Failure(error) => {
                 // Show error toast with option to proceed anyway
@@ -1172,36 +2419,292 @@ impl AppController {
                 let controller = Rc::clone(self);
                 let package_clone = package.clone();
                 toast.connect_button_clicked(move |_| {
-                    controller.execute_update(package_clone.clone(), from_all);
+                    controller.execute_update(package_clone.clone(), from_all, options);
+                });
+
+                self.widgets.toast_overlay.add_toast(toast);
+            }
+            SnapshotResult::Timeout => {
+                // Show timeout toast with option to proceed anyway
+                let toast = adw::Toast::builder()
+                    .title("Snapshot creation timed out")
+                    .button_label("Update Anyway")
+                    .timeout(10)  // 10 seconds
+                    .build();
+
+                let controller = Rc::clone(self);
+                let package_clone = package.clone();
+                toast.connect_button_clicked(move |_| {
+                    controller.execute_update(package_clone.clone(), from_all, options);
+                });
+
+                self.widgets.toast_overlay.add_toast(toast);
+            }
+        }
+    }
+
+    pub(crate) fn show_alternatives_dialog(self: &Rc<Self>, output: &str) {
+        // A refresh after switching a provider replaces the open dialog.
+        let previous = self.alternatives_dialog.borrow_mut().take();
+        if let Some(previous) = previous {
+            previous.close();
+        }
+
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title("Alternatives")
+            .default_width(520)
+            .default_height(420)
+            .build();
+        dialog.add_button("Close", gtk::ResponseType::Close);
+        dialog.connect_response(|dialog, _| dialog.close());
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let info_label = gtk::Label::builder()
+            .label("Choose which package provides each command. Switching runs \"xbps-alternatives\" as root.")
+            .halign(gtk::Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .wrap_mode(pango::WrapMode::WordChar)
+            .build();
+        info_label.add_css_class("dim-label");
+        content.append(&info_label);
+        content.append(&self.docs_link_button(MaintenanceTask::Alternatives));
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(320)
+            .build();
+        scroller.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+
+        let groups = parse_alternatives_list(output);
+        if groups.is_empty() {
+            let empty_label = gtk::Label::builder()
+                .label("No alternatives were reported.")
+                .halign(gtk::Align::Center)
+                .valign(gtk::Align::Center)
+                .build();
+            empty_label.add_css_class("dim-label");
+            scroller.set_child(Some(&empty_label));
+        } else {
+            let list = gtk::ListBox::new();
+            list.set_selection_mode(gtk::SelectionMode::None);
+            list.add_css_class("boxed-list");
+            list.set_valign(gtk::Align::Start);
+            for group in groups {
+                let labels: Vec<&str> = group
+                    .providers
+                    .iter()
+                    .map(|provider| provider.pkgver.as_str())
+                    .collect();
+                let model = gtk::StringList::new(&labels);
+                let row = adw::ComboRow::builder()
+                    .title(group.name.as_str())
+                    .model(&model)
+                    .build();
+                if let Some(current) = group.providers.iter().position(|p| p.current) {
+                    row.set_selected(current as u32);
+                }
+                row.set_sensitive(group.providers.len() > 1);
+
+                let controller_weak = Rc::downgrade(self);
+                row.connect_selected_notify(move |row| {
+                    let Some(provider) = group.providers.get(row.selected() as usize) else {
+                        return;
+                    };
+                    if provider.current {
+                        return;
+                    }
+                    if let Some(controller) = controller_weak.upgrade() {
+                        controller
+                            .on_alternative_selected(group.name.clone(), provider.package.clone());
+                    }
                 });
+                list.append(&row);
+            }
+            scroller.set_child(Some(&list));
+        }
+        content.append(&scroller);
+
+        let controller_weak = Rc::downgrade(self);
+        dialog.connect_close_request(move |_| {
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.alternatives_dialog.borrow_mut().take();
+            }
+            Propagation::Proceed
+        });
+        self.alternatives_dialog.replace(Some(dialog.clone()));
+        dialog.present();
+    }
+
+    pub(crate) fn show_update_log_dialog(self: &Rc<Self>) {
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(false)
+            .title("Update log")
+            .default_width(640)
+            .default_height(420)
+            .build();
+        dialog.add_button("Close", gtk::ResponseType::Close);
+        dialog.connect_response(|dialog, _| dialog.close());
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let timestamps_toggle = gtk::CheckButton::builder()
+            .label("Show timestamps")
+            .active(self.state.borrow().update_log_timestamps)
+            .halign(gtk::Align::Start)
+            .build();
+        timestamps_toggle.connect_toggled(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |button| {
+                controller.state.borrow_mut().update_log_timestamps = button.is_active();
+                controller.refresh_update_log_buffer();
+            }
+        ));
+
+        let save_button = gtk::Button::builder()
+            .label("Save…")
+            .tooltip_text("Save the log to a file")
+            .build();
+        save_button.connect_clicked(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            #[weak]
+            dialog,
+            move |_| {
+                controller.choose_update_log_file(&dialog);
+            }
+        ));
+
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(12)
+            .build();
+        timestamps_toggle.set_hexpand(true);
+        toolbar.append(&timestamps_toggle);
+        toolbar.append(&save_button);
+        content.append(&toolbar);
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(320)
+            .build();
+
+        let buffer = gtk::TextBuffer::new(None);
+        let text_view = gtk::TextView::builder()
+            .buffer(&buffer)
+            .editable(false)
+            .monospace(true)
+            .wrap_mode(gtk::WrapMode::None)
+            .build();
+        text_view.set_cursor_visible(false);
+
+        scroller.set_child(Some(&text_view));
+        content.append(&scroller);
 
-                self.widgets.toast_overlay.add_toast(toast);
+        self.state.borrow_mut().update_log_follow_tail = true;
+        scroller.vadjustment().connect_value_changed(glib::clone!(
+            #[weak(rename_to = controller)]
+            self,
+            move |adjustment| {
+                // Follow new output only while the view sits at the bottom.
+                let at_end =
+                    adjustment.value() + adjustment.page_size() >= adjustment.upper() - 1.0;
+                controller.state.borrow_mut().update_log_follow_tail = at_end;
             }
-            SnapshotResult::Timeout => {
-                // Show timeout toast with option to proceed anyway
-                let toast = adw::Toast::builder()
-                    .title("Snapshot creation timed out")
-                    .button_label("Update Anyway")
-                    .timeout(10)  // 10 seconds
-                    .build();
+        ));
 
-                let controller = Rc::clone(self);
-                let package_clone = package.clone();
-                toast.connect_button_clicked(move |_| {
-                    controller.execute_update(package_clone.clone(), from_all);
-                });
+        self.update_log_buffer.replace(Some(buffer.clone()));
+        self.update_log_view.replace(Some(text_view));
+        self.populate_update_log_buffer(&buffer);
 
-                self.widgets.toast_overlay.add_toast(toast);
+        {
+            let controller = Rc::downgrade(self);
+            dialog.connect_close_request(move |_| {
+                if let Some(controller) = controller.upgrade() {
+                    controller.update_log_buffer.replace(None);
+                    controller.update_log_view.replace(None);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        dialog.present();
+    }
+
+    fn collect_diagnostics(&self) -> String {
+        let mut report = format!("Nebula {}\n", env!("CARGO_PKG_VERSION"));
+        match run_xbps_version() {
+            Ok(version) => report.push_str(&format!("{}\n", version)),
+            Err(err) => report.push_str(&format!("XBPS: unknown ({})\n", err)),
+        }
+
+        {
+            let settings = self.settings.borrow();
+            let repositories: Vec<&str> = settings
+                .repository_classes
+                .iter()
+                .map(|class| class.title())
+                .collect();
+            report.push_str(&format!(
+                "Extra repositories: {}\n",
+                if repositories.is_empty() {
+                    "none".to_string()
+                } else {
+                    repositories.join(", ")
+                }
+            ));
+            if settings.mirror_selection.is_empty() {
+                report.push_str("Mirrors: system default\n");
+            } else {
+                report.push_str("Mirrors:\n");
+                for mirror in &settings.mirror_selection {
+                    report.push_str(&format!("  {}\n", mirror));
+                }
+            }
+        }
+
+        let state = self.state.borrow();
+        if state.update_log.is_empty() {
+            report.push_str("\nNo update has run this session.\n");
+        } else {
+            let skip = state.update_log.len().saturating_sub(DIAGNOSTICS_LOG_LINES);
+            report.push_str("\nRecent update log:\n");
+            for (timestamp, line) in state.update_log.iter().skip(skip) {
+                report.push_str(&format!(
+                    "[{}] {}\n",
+                    timestamp.with_timezone(&Local).format("%H:%M:%S"),
+                    line
+                ));
             }
         }
+        report
     }
 
-    pub(crate) fn show_alternatives_dialog(&self, output: &str) {
+    pub(crate) fn show_diagnostics_dialog(self: &Rc<Self>) {
+        let report = self.collect_diagnostics();
+
         let dialog = gtk::Dialog::builder()
             .transient_for(&self.window)
-            .modal(true)
-            .title("Available alternatives")
-            .default_width(520)
+            .modal(false)
+            .title("Report a Problem")
+            .default_width(640)
             .default_height(420)
             .build();
         dialog.add_button("Close", gtk::ResponseType::Close);
@@ -1215,14 +2718,38 @@ impl AppController {
         content.set_margin_end(12);
 
         let info_label = gtk::Label::builder()
-            .label("Here's what \"xbps-alternatives -l\" reported.")
+            .label("Paste this into your bug report. Check it first if your mirrors or log mention anything private.")
             .halign(gtk::Align::Start)
             .xalign(0.0)
             .wrap(true)
             .wrap_mode(pango::WrapMode::WordChar)
+            .hexpand(true)
             .build();
         info_label.add_css_class("dim-label");
-        content.append(&info_label);
+
+        let copy_button = gtk::Button::builder()
+            .label("Copy")
+            .tooltip_text("Copy the report to the clipboard")
+            .valign(gtk::Align::Center)
+            .build();
+        copy_button.connect_clicked(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            #[strong]
+            report,
+            move |_| {
+                controller.window.clipboard().set_text(&report);
+                controller.show_toast("Diagnostics copied to clipboard.");
+            }
+        ));
+
+        let toolbar = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(12)
+            .build();
+        toolbar.append(&info_label);
+        toolbar.append(&copy_button);
+        content.append(&toolbar);
 
         let scroller = gtk::ScrolledWindow::builder()
             .hexpand(true)
@@ -1231,13 +2758,7 @@ impl AppController {
             .build();
 
         let buffer = gtk::TextBuffer::new(None);
-        let trimmed = output.trim();
-        if trimmed.is_empty() {
-            buffer.set_text("No alternatives were reported.");
-        } else {
-            buffer.set_text(trimmed);
-        }
-
+        buffer.set_text(&report);
         let text_view = gtk::TextView::builder()
             .buffer(&buffer)
             .editable(false)
@@ -1252,6 +2773,160 @@ impl AppController {
         dialog.present();
     }
 
+    pub(crate) fn show_update_history_dialog(self: &Rc<Self>) {
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(false)
+            .title("Update history")
+            .default_width(640)
+            .default_height(420)
+            .build();
+        dialog.add_button("Close", gtk::ResponseType::Close);
+        dialog.connect_response(|dialog, _| dialog.close());
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let entries = load_update_history();
+        if entries.is_empty() {
+            let placeholder = gtk::Label::builder()
+                .label("No updates have been recorded yet.")
+                .vexpand(true)
+                .build();
+            placeholder.add_css_class("dim-label");
+            content.append(&placeholder);
+            dialog.present();
+            return;
+        }
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .valign(gtk::Align::Start)
+            .build();
+        list.add_css_class("boxed-list");
+
+        for entry in entries.iter().rev() {
+            let count = entry.packages.len();
+            let plural = if count == 1 { "" } else { "s" };
+            let mut title = match (entry.all, entry.success) {
+                (true, true) => "System update".to_string(),
+                (true, false) => "System update failed".to_string(),
+                (false, true) => format!("Updated {} package{}", count, plural),
+                (false, false) => format!("Failed to update {} package{}", count, plural),
+            };
+            if entry.unattended {
+                title.push_str(" (automatic)");
+            }
+            let mut subtitle = format!(
+                "{} · {}",
+                entry
+                    .finished_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M"),
+                entry.packages.join(", ")
+            );
+            if let Some(error) = &entry.error {
+                subtitle.push('\n');
+                subtitle.push_str(error);
+            }
+
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(glib::markup_escape_text(&subtitle).as_str())
+                .build();
+            row.set_subtitle_lines(3);
+            let icon = gtk::Image::from_icon_name(if entry.success {
+                "emblem-ok-symbolic"
+            } else {
+                "dialog-error-symbolic"
+            });
+            if !entry.success {
+                icon.add_css_class("error");
+            }
+            row.add_prefix(&icon);
+            list.append(&row);
+        }
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(320)
+            .child(&list)
+            .build();
+        content.append(&scroller);
+
+        dialog.present();
+    }
+
+    fn choose_update_log_file(self: &Rc<Self>, parent: &gtk::Dialog) {
+        let chooser = gtk::FileChooserNative::new(
+            Some("Save Update Log"),
+            Some(parent),
+            gtk::FileChooserAction::Save,
+            Some("Save"),
+            Some("Cancel"),
+        );
+        chooser.set_modal(true);
+        chooser.set_current_name(&format!(
+            "nebula-update-{}.log",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        chooser.connect_response(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |chooser, response| {
+                let path = chooser.file().and_then(|file| file.path());
+                controller.update_log_chooser.replace(None);
+                if response != gtk::ResponseType::Accept {
+                    return;
+                }
+                if let Some(path) = path {
+                    controller.save_update_log(path);
+                }
+            }
+        ));
+        chooser.show();
+        self.update_log_chooser.replace(Some(chooser));
+    }
+
+    fn save_update_log(&self, path: PathBuf) {
+        let contents = {
+            let state = self.state.borrow();
+            let mut contents = format!(
+                "Nebula update log\nSaved: {}\nCommand: {}\n\n",
+                Local::now().format("%Y-%m-%d %H:%M:%S %Z"),
+                state.update_log_command.as_deref().unwrap_or("—")
+            );
+            for (received, line) in &state.update_log {
+                contents.push_str(&format_update_log_line(
+                    received,
+                    line,
+                    state.update_log_timestamps,
+                ));
+                contents.push('\n');
+            }
+            contents
+        };
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = fs::write(&path, contents)
+                .map(|_| path)
+                .map_err(|err| format!("Failed to save update log: {}", err));
+            let _ = sender.send(AppMessage::UpdateLogSaved { result });
+        });
+    }
+
+    pub(crate) fn finish_update_log_save(&self, result: Result<PathBuf, String>) {
+        match result {
+            Ok(path) => self.show_toast(&format!("Update log saved to {}.", path.display())),
+            Err(err) => self.show_toast(&err),
+        }
+    }
 
     pub(crate) fn refresh_update_log_buffer(&self) {
         if let Some(buffer) = self.update_log_buffer.borrow().as_ref() {
@@ -1265,10 +2940,26 @@ impl AppController {
             if state.update_log.is_empty() {
                 "No update activity yet.".to_string()
             } else {
-                state.update_log.join("\n")
+                state
+                    .update_log
+                    .iter()
+                    .map(|(received, line)| {
+                        format_update_log_line(received, line, state.update_log_timestamps)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
             }
         };
         buffer.set_text(&text);
+        self.scroll_update_log_to_end(buffer);
+    }
+
+    /// Keeps the newest log line in view unless the user has scrolled away
+    /// from the bottom to read earlier output.
+    fn scroll_update_log_to_end(&self, buffer: &gtk::TextBuffer) {
+        if !self.state.borrow().update_log_follow_tail {
+            return;
+        }
         let iter = buffer.end_iter();
         buffer.place_cursor(&iter);
         if let Some(view) = self.update_log_view.borrow().as_ref() {
@@ -1278,28 +2969,23 @@ impl AppController {
         }
     }
 
-    pub(crate) fn append_update_log_buffer_line(&self, line: &str) {
-        let is_first_line = {
+    pub(crate) fn append_update_log_buffer_line(&self, received: &DateTime<Utc>, line: &str) {
+        let (is_first_line, show_timestamps) = {
             let state = self.state.borrow();
-            state.update_log.len() == 1
+            (state.update_log.len() == 1, state.update_log_timestamps)
         };
+        let line = format_update_log_line(received, line, show_timestamps);
 
         if let Some(buffer) = self.update_log_buffer.borrow().as_ref() {
             if is_first_line {
-                buffer.set_text(line);
+                buffer.set_text(&line);
             } else {
                 let mut iter = buffer.end_iter();
                 buffer.insert(&mut iter, "\n");
-                buffer.insert(&mut iter, line);
+                buffer.insert(&mut iter, &line);
             }
 
-            let iter = buffer.end_iter();
-            buffer.place_cursor(&iter);
-            if let Some(view) = self.update_log_view.borrow().as_ref() {
-                let mark = buffer.create_mark(None, &iter, false);
-                view.scroll_to_mark(&mark, 0.0, true, 1.0, 1.0);
-                buffer.delete_mark(&mark);
-            }
+            self.scroll_update_log_to_end(buffer);
         }
     }
 
@@ -1317,11 +3003,18 @@ impl AppController {
             state.installing_package = None;
         }
 
+        let reported = privilege_failure_reported(&result);
         let footer_message = match result {
             Ok(command) => {
                 if command.success() {
                     let message = format!("\"{}\" installed successfully.", package);
-                    self.show_toast(&format!("Installed {}.", package));
+                    if self.state.borrow().install_summary {
+                        let requested = [package.clone()];
+                        let dependencies = self.pulled_in_dependencies(&requested, &command);
+                        self.show_toast(&install_summary_message(&requested, &dependencies));
+                    } else {
+                        self.show_toast(&format!("Installed {}.", package));
+                    }
                     self.flag_installed_state(&package, true);
                     self.refresh_installed_packages();
                     Some(message)
@@ -1335,13 +3028,17 @@ impl AppController {
                     } else {
                         format!("Failed to install \"{}\": {}", package, detail)
                     };
-                    self.show_error_dialog("Install Failed", &message);
+                    if !reported {
+                        self.show_error_dialog("Install Failed", &message);
+                    }
                     Some(message)
                 }
             }
             Err(err) => {
                 let message = format!("Failed to install \"{}\": {}", package, err);
-                self.show_error_dialog("Install Failed", &message);
+                if !reported {
+                    self.show_error_dialog("Install Failed", &message);
+                }
                 Some(message)
             }
         };
@@ -1353,6 +3050,119 @@ impl AppController {
         if let Some(msg) = footer_message {
             self.set_footer_message(Some(&msg));
         }
+        self.start_next_queued_operation();
+    }
+
+    pub(crate) fn finish_install_batch(
+        self: &Rc<Self>,
+        packages: Vec<String>,
+        skipped: Vec<String>,
+        other_architectures: HashMap<String, Vec<String>>,
+        result: Result<CommandResult, String>,
+    ) {
+        for package in &packages {
+            self.complete_operation_tracking(package, &result);
+        }
+        let unavailable = Err("Not available from the enabled repositories.".to_string());
+        for package in &skipped {
+            self.complete_operation_tracking(package, &unavailable);
+        }
+        self.state.borrow_mut().install_in_progress = false;
+
+        let reported = privilege_failure_reported(&result);
+        let skipped_note = skipped_packages_note(&skipped, &other_architectures);
+        let footer_message = match result {
+            Ok(command) if command.success() => {
+                let message = if packages.len() == 1 {
+                    format!("\"{}\" installed successfully.", packages[0])
+                } else {
+                    format!("Installed {} packages successfully.", packages.len())
+                };
+                let message = match skipped_note.as_deref() {
+                    Some(note) => format!("{} {}", message, note),
+                    None => message,
+                };
+                if skipped_note.is_some() {
+                    self.show_error_dialog("Some Packages Were Skipped", &message);
+                } else if self.state.borrow().install_summary {
+                    let dependencies = self.pulled_in_dependencies(&packages, &command);
+                    self.show_toast(&install_summary_message(&packages, &dependencies));
+                } else {
+                    self.show_toast(&message);
+                }
+                self.begin_batch_ui_update();
+                for package in &packages {
+                    self.flag_installed_state(package, true);
+                }
+                self.end_batch_ui_update();
+                self.state
+                    .borrow_mut()
+                    .discover_selection
+                    .retain(|name| !packages.contains(name));
+                self.refresh_installed_packages();
+                message
+            }
+            Ok(command) => {
+                let mut detail = command.stderr.trim();
+                if detail.is_empty() {
+                    detail = command.stdout.trim();
+                }
+                let message = if detail.is_empty() {
+                    "Failed to install the selected packages.".to_string()
+                } else {
+                    format!("Failed to install the selected packages: {}", detail)
+                };
+                if !reported {
+                    self.show_install_batch_error(&message, skipped_note.as_deref());
+                }
+                message
+            }
+            Err(err) => {
+                let message = format!("Failed to install the selected packages: {}", err);
+                if !reported {
+                    self.show_install_batch_error(&message, skipped_note.as_deref());
+                }
+                message
+            }
+        };
+        self.update_discover_details();
+        self.refresh_updates(true);
+        self.rebuild_search_list();
+        self.refresh_discover_install_widgets();
+        self.set_footer_message(Some(&footer_message));
+        self.start_next_queued_operation();
+    }
+
+    fn show_install_batch_error(&self, message: &str, skipped_note: Option<&str>) {
+        match skipped_note {
+            Some(note) => {
+                self.show_error_dialog("Install Failed", &format!("{}\n\n{}", message, note))
+            }
+            None => self.show_error_dialog("Install Failed", message),
+        }
+    }
+
+    /// Packages a transaction installed besides the ones requested, read from
+    /// the xbps output and falling back to the cached dependency list.
+    fn pulled_in_dependencies(&self, requested: &[String], command: &CommandResult) -> Vec<String> {
+        let mut installed = parse_installed_package_names(command.stdout.lines());
+        if installed.is_empty() {
+            let state = self.state.borrow();
+            for detail in requested
+                .iter()
+                .filter_map(|name| state.discover_detail_cache.get(name))
+            {
+                for dependency in &detail.dependencies {
+                    if !state.installed_set.contains(&dependency.name)
+                        && !installed.contains(&dependency.name)
+                    {
+                        installed.push(dependency.name.clone());
+                    }
+                }
+            }
+        }
+        installed.retain(|name| !requested.contains(name));
+        installed
     }
 
     pub(crate) fn finish_remove(
@@ -1375,13 +3185,18 @@ impl AppController {
         self.rebuild_installed_list();
         self.update_installed_selection_ui();
 
+        let reported = privilege_failure_reported(&result);
         let footer_message = match result {
             Ok(command) => {
                 if command.success() {
                     let message = format!("\"{}\" removed successfully.", package);
                     self.set_installed_status_message(Some(message.clone()));
-                    self.show_toast(&format!("Removed {}.", package));
+                    self.show_removal_toast(
+                        &format!("Removed {}.", package),
+                        removed_package_set(std::slice::from_ref(&package), &command.stdout),
+                    );
                     self.flag_installed_state(&package, false);
+                    self.record_removed_packages(std::slice::from_ref(&package));
                     self.refresh_installed_packages();
                     Some(message)
                 } else {
@@ -1394,13 +3209,17 @@ impl AppController {
                     } else {
                         format!("Failed to remove \"{}\": {}", package, detail)
                     };
-                    self.show_error_dialog("Removal Failed", &message);
+                    if !reported {
+                        self.show_error_dialog("Removal Failed", &message);
+                    }
                     Some(message)
                 }
             }
             Err(err) => {
                 let message = format!("Failed to remove \"{}\": {}", package, err);
-                self.show_error_dialog("Removal Failed", &message);
+                if !reported {
+                    self.show_error_dialog("Removal Failed", &message);
+                }
                 Some(message)
             }
         };
@@ -1412,6 +3231,7 @@ impl AppController {
         if let Some(msg) = footer_message {
             self.set_footer_message(Some(&msg));
         }
+        self.start_next_queued_operation();
     }
 
     pub(crate) fn finish_remove_batch(
@@ -1434,6 +3254,7 @@ impl AppController {
         self.rebuild_installed_list();
         self.update_installed_selection_ui();
 
+        let reported = privilege_failure_reported(&result);
         let footer_message = match result {
             Ok(command) => {
                 if command.success() {
@@ -1443,14 +3264,21 @@ impl AppController {
                         "Selected packages removed successfully.".to_string()
                     };
                     self.set_installed_status_message(Some(message.clone()));
-                    if packages.len() == 1 {
-                        self.show_toast(&format!("Removed {}.", packages[0]));
+                    let toast_message = if packages.len() == 1 {
+                        format!("Removed {}.", packages[0])
                     } else {
-                        self.show_toast("Selected packages removed.");
-                    }
+                        "Selected packages removed.".to_string()
+                    };
+                    self.show_removal_toast(
+                        &toast_message,
+                        removed_package_set(&packages, &command.stdout),
+                    );
+                    self.begin_batch_ui_update();
                     for pkg in &packages {
                         self.flag_installed_state(pkg, false);
                     }
+                    self.end_batch_ui_update();
+                    self.record_removed_packages(&packages);
                     self.refresh_installed_packages();
                     Some(message)
                 } else {
@@ -1463,13 +3291,17 @@ impl AppController {
                     } else {
                         format!("Failed to remove selected packages: {}", detail)
                     };
-                    self.show_error_dialog("Removal Failed", &message);
+                    if !reported {
+                        self.show_error_dialog("Removal Failed", &message);
+                    }
                     Some(message)
                 }
             }
             Err(err) => {
                 let message = format!("Failed to remove selected packages: {}", err);
-                self.show_error_dialog("Removal Failed", &message);
+                if !reported {
+                    self.show_error_dialog("Removal Failed", &message);
+                }
                 Some(message)
             }
         };
@@ -1494,6 +3326,7 @@ impl AppController {
         if let Some(msg) = footer_message {
             self.set_footer_message(Some(&msg));
         }
+        self.start_next_queued_operation();
     }
 
     fn restore_discover_focus_for(self: &Rc<Self>, package: &str) {
@@ -1528,31 +3361,183 @@ impl AppController {
             stored_ids
         };
 
-        initial_ids.retain(|id| find_mirror(id.as_str()).is_some());
+        initial_ids.retain(|id| find_mirror(id.as_str()).is_some());
+
+        if initial_ids.is_empty() {
+            initial_ids.push(default_mirror_id().to_string());
+        }
+
+        let classes = self.settings.borrow().repository_classes.clone();
+        self.state.borrow_mut().repository_classes = classes.clone();
+        set_active_repository_classes(&classes);
+
+        if let Err(err) = self.apply_mirror_selection(initial_ids.clone(), true, false) {
+            eprintln!("Failed to initialize mirrors: {}", err);
+        }
+
+        let sender = self.sender.clone();
+        thread::spawn(move || match detect_active_repositories() {
+            Ok(urls) => {
+                let mirrors = map_urls_to_ids(&urls);
+                let classes = detect_repository_classes(&urls);
+                let _ = sender.send(AppMessage::MirrorsDetected { mirrors });
+                let _ = sender.send(AppMessage::RepositoryClassesDetected { classes });
+            }
+            Err(err) => {
+                eprintln!("Failed to detect active repositories: {}", err);
+            }
+        });
+    }
+
+    pub(crate) fn show_toast(&self, message: &str) {
+        let toast = adw::Toast::builder().title(message).timeout(5).build();
+        self.widgets.toast_overlay.add_toast(toast);
+    }
+
+    fn show_removal_toast(self: &Rc<Self>, message: &str, packages: Vec<String>) {
+        self.state.borrow_mut().undo_removal_packages = packages.clone();
+        let toast = adw::Toast::builder()
+            .title(message)
+            .button_label("Undo")
+            .timeout(8)
+            .build();
+        let controller_weak = Rc::downgrade(self);
+        toast.connect_button_clicked(move |_| {
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.undo_removal();
+            }
+        });
+        let controller_weak = Rc::downgrade(self);
+        toast.connect_dismissed(move |_| {
+            if let Some(controller) = controller_weak.upgrade() {
+                let mut state = controller.state.borrow_mut();
+                if state.undo_removal_packages == packages {
+                    state.undo_removal_packages.clear();
+                }
+            }
+        });
+        self.widgets.toast_overlay.add_toast(toast);
+    }
+
+    fn undo_removal(self: &Rc<Self>) {
+        let packages = std::mem::take(&mut self.state.borrow_mut().undo_removal_packages);
+        if packages.is_empty() {
+            return;
+        }
+        if self.state.borrow().install_in_progress {
+            self.show_toast("Couldn't undo the removal while another installation is running.");
+            return;
+        }
+        self.execute_install_batch(packages);
+    }
+
+    pub(crate) fn copy_homepage_link(&self, homepage: Option<String>) {
+        let Some(homepage) = homepage.filter(|url| !url.trim().is_empty()) else {
+            return;
+        };
+        self.window.clipboard().set_text(homepage.trim());
+        self.show_toast("Homepage link copied to clipboard.");
+    }
+
+    pub(crate) fn copy_command_line(&self, command: Option<String>) {
+        let Some(command) = command.filter(|command| !command.is_empty()) else {
+            return;
+        };
+        self.window.clipboard().set_text(&command);
+        self.show_toast("Command copied to clipboard.");
+    }
+
+    /// Looks up the source package on a worker first, since subpackages
+    /// share the template of the package they are split from.
+    pub(crate) fn open_package_template(&self, package: Option<String>) {
+        let Some(package) = package else {
+            return;
+        };
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let source = query_source_package(&package);
+            let _ = sender.send(AppMessage::PackageTemplateResolved { package, source });
+        });
+    }
+
+    fn finish_package_template(&self, package: &str, source: Option<&str>) {
+        let uri = package_template_url(package, source);
+        if let Err(err) = gio::AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>)
+        {
+            self.show_toast(&format!("Couldn't open the build template: {}", err));
+        }
+    }
+
+    /// Reacts to failures that happened before the command ran: a dismissed
+    /// prompt gets a gentle note, and a missing polkit agent gets help
+    /// starting one (once per session, then a toast). The `finish_*`
+    /// handlers skip their error dialog for these; a pkexec that fails to
+    /// launch is left to them.
+    fn respond_to_privilege_failure(self: &Rc<Self>, result: &Result<CommandResult, String>) {
+        match classify_privilege_failure(result) {
+            Some(PrivilegeFailure::Cancelled) => {
+                self.show_toast("Authentication was cancelled. Nothing was changed.");
+            }
+            Some(PrivilegeFailure::NoAgent) => {
+                let already_shown =
+                    std::mem::replace(&mut self.state.borrow_mut().privilege_help_shown, true);
+                if already_shown {
+                    // The help dialog is not repeated, but each failed
+                    // action still needs saying so.
+                    self.show_toast("No authentication agent is running, so nothing was changed.");
+                } else {
+                    self.show_privilege_help_dialog();
+                }
+            }
+            Some(PrivilegeFailure::LaunchFailed | PrivilegeFailure::CommandFailed) | None => {}
+        }
+    }
 
-        if initial_ids.is_empty() {
-            initial_ids.push(default_mirror_id().to_string());
+    fn show_privilege_help_dialog(self: &Rc<Self>) {
+        let agent = find_polkit_agent();
+        let mut body = "Nebula uses pkexec to install, remove and update packages, which needs a polkit authentication agent to ask for your password. None is running in this session.".to_string();
+        match &agent {
+            Some(path) => body.push_str(&format!(
+                "\n\nNebula found {} and can start it now. To start it automatically, add it to your window manager's autostart.",
+                path.display()
+            )),
+            None => body.push_str(
+                "\n\nInstall an agent such as polkit-gnome or lxpolkit, start it from your window manager's autostart, and try again.",
+            ),
         }
 
-        if let Err(err) = self.apply_mirror_selection(initial_ids.clone(), true, false) {
-            eprintln!("Failed to initialize mirrors: {}", err);
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .heading("No Authentication Agent")
+            .body(&body)
+            .build();
+        dialog.add_response("close", "Close");
+        dialog.set_close_response("close");
+        if agent.is_some() {
+            dialog.add_response("start", "Start Agent");
+            dialog.set_response_appearance("start", adw::ResponseAppearance::Suggested);
+            dialog.set_default_response(Some("start"));
         }
 
-        let sender = self.sender.clone();
-        thread::spawn(move || match detect_active_repositories() {
-            Ok(urls) => {
-                let mirrors = map_urls_to_ids(&urls);
-                let _ = sender.send(AppMessage::MirrorsDetected { mirrors });
+        let controller_weak = Rc::downgrade(self);
+        dialog.connect_response(None, move |dialog, response| {
+            dialog.close();
+            let (Some(controller), Some(agent)) = (controller_weak.upgrade(), agent.as_ref())
+            else {
+                return;
+            };
+            if response != "start" {
+                return;
             }
-            Err(err) => {
-                eprintln!("Failed to detect active repositories: {}", err);
+            match start_polkit_agent(agent) {
+                Ok(()) => {
+                    controller.show_toast("Authentication agent started. Try the action again.")
+                }
+                Err(err) => controller.show_error_dialog("Couldn't Start Agent", &err),
             }
         });
-    }
-
-    pub(crate) fn show_toast(&self, message: &str) {
-        let toast = adw::Toast::builder().title(message).timeout(5).build();
-        self.widgets.toast_overlay.add_toast(toast);
+        dialog.present();
     }
 
     pub(crate) fn show_error_dialog(&self, title: &str, message: &str) {
@@ -1594,7 +3579,7 @@ impl AppController {
             let controller = Rc::downgrade(self);
             window.connect_close_request(move |_| {
                 if let Some(controller) = controller.upgrade() {
-                    controller.mirrors_window.replace(None);
+                    controller.forget_mirrors_window();
                 }
                 Propagation::Proceed
             });
@@ -1604,7 +3589,7 @@ impl AppController {
             let controller = Rc::downgrade(self);
             window.connect_destroy(move |_| {
                 if let Some(controller) = controller.upgrade() {
-                    controller.mirrors_window.replace(None);
+                    controller.forget_mirrors_window();
                 }
             });
         }
@@ -1617,6 +3602,21 @@ impl AppController {
             .title("Tier 1 Mirrors")
             .description("Select the primary Void Linux mirrors Nebula should use.")
             .build();
+        let test_button = gtk::Button::builder()
+            .label("Test Latency")
+            .valign(gtk::Align::Center)
+            .tooltip_text("Measure how quickly each mirror responds")
+            .build();
+        test_button.add_css_class("flat");
+        test_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = controller)]
+            self,
+            move |_| {
+                controller.refresh_mirror_stats();
+            }
+        ));
+        tier_group.set_header_suffix(Some(&test_button));
+        self.mirror_test_button.replace(Some(test_button));
         let tor_group = adw::PreferencesGroup::builder()
             .title("Tor Mirrors")
             .description("Requires the tor package. Follow https://docs.voidlinux.org/xbps/repositories/mirrors/tor.html before enabling these mirrors.")
@@ -1625,14 +3625,18 @@ impl AppController {
         {
             let selected = self.state.borrow().selected_mirror_ids.clone();
             let controller = Rc::downgrade(self);
+            let stats = self.settings.borrow().mirror_stats.clone();
             for mirror in tier1_mirrors() {
                 let controller = controller.clone();
-                let subtitle = humanize_base_url(mirror);
+                let subtitle = mirror_subtitle(mirror, stats.get(mirror.id));
                 let row = adw::ActionRow::builder()
                     .title(mirror.region)
                     .subtitle(&subtitle)
                     .activatable(true)
                     .build();
+                self.mirror_rows
+                    .borrow_mut()
+                    .insert(mirror.id.to_string(), row.clone());
                 let check = gtk::CheckButton::builder()
                     .valign(gtk::Align::Center)
                     .build();
@@ -1680,12 +3684,106 @@ impl AppController {
             }
         }
 
+        let repositories_group = adw::PreferencesGroup::builder()
+            .title("Repositories")
+            .description("Optional repositories served by the selected mirrors.")
+            .build();
+        {
+            let enabled = self.state.borrow().repository_classes.clone();
+            for class in available_repository_classes() {
+                let row = adw::ActionRow::builder()
+                    .title(class.title())
+                    .subtitle(class.description())
+                    .build();
+                let switch = gtk::Switch::builder()
+                    .valign(gtk::Align::Center)
+                    .active(enabled.contains(&class))
+                    .build();
+                let controller = Rc::downgrade(self);
+                switch.connect_active_notify(move |switch| {
+                    if let Some(controller) = controller.upgrade() {
+                        controller.set_repository_class_enabled(class, switch.is_active());
+                    }
+                });
+                row.add_suffix(&switch);
+                row.set_activatable_widget(Some(&switch));
+                repositories_group.add(&row);
+            }
+        }
+
         page.add(&tier_group);
         page.add(&tor_group);
+        page.add(&repositories_group);
         window.add(&page);
+        self.update_mirror_test_button();
         window.present();
     }
 
+    fn forget_mirrors_window(&self) {
+        self.mirrors_window.replace(None);
+        self.mirror_rows.borrow_mut().clear();
+        self.mirror_test_button.replace(None);
+    }
+
+    pub(crate) fn refresh_mirror_stats(self: &Rc<Self>) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.mirror_stats_refreshing {
+                return;
+            }
+            state.mirror_stats_refreshing = true;
+        }
+        self.update_mirror_test_button();
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let stats = tier1_mirrors()
+                .into_iter()
+                .map(|mirror| {
+                    let latency_ms = measure_latency(mirror)
+                        .map(|elapsed| elapsed.as_millis() as u64)
+                        .ok();
+                    let stat = MirrorStat {
+                        latency_ms,
+                        measured_at: Utc::now(),
+                    };
+                    (mirror.id.to_string(), stat)
+                })
+                .collect();
+            let _ = sender.send(AppMessage::MirrorStatsMeasured { stats });
+        });
+    }
+
+    fn finish_mirror_stats(self: &Rc<Self>, stats: Vec<(String, MirrorStat)>) {
+        self.state.borrow_mut().mirror_stats_refreshing = false;
+        {
+            let mut settings = self.settings.borrow_mut();
+            settings.mirror_stats.extend(stats.iter().cloned());
+        }
+        self.persist_settings();
+
+        let rows = self.mirror_rows.borrow();
+        for (id, stat) in &stats {
+            if let (Some(row), Some(mirror)) = (rows.get(id), find_mirror(id)) {
+                row.set_subtitle(&mirror_subtitle(mirror, Some(stat)));
+            }
+        }
+        drop(rows);
+        self.update_mirror_test_button();
+    }
+
+    fn update_mirror_test_button(&self) {
+        let refreshing = self.state.borrow().mirror_stats_refreshing;
+        if let Some(button) = self.mirror_test_button.borrow().as_ref() {
+            button.set_sensitive(!refreshing);
+            button.set_label(if refreshing {
+                "Testing…"
+            } else {
+                "Test Latency"
+            });
+        }
+    }
+
     pub(crate) fn handle_mirror_toggle(
         self: &Rc<Self>,
         mirror_id: &str,
@@ -1740,10 +3838,31 @@ impl AppController {
         });
     }
 
+    fn set_repository_class_enabled(self: &Rc<Self>, class: RepositoryClass, enabled: bool) {
+        let classes = {
+            let mut state = self.state.borrow_mut();
+            state.repository_classes.retain(|entry| *entry != class);
+            if enabled {
+                state.repository_classes.push(class);
+            }
+            state.repository_classes.clone()
+        };
+        set_active_repository_classes(&classes);
+        self.settings.borrow_mut().repository_classes = classes;
+        self.persist_settings();
+
+        let ids = self.state.borrow().selected_mirror_ids.clone();
+        self.start_mirror_write_worker(ids);
+        self.show_toast("Repositories updated.");
+        // Syncs the new repository indexes and drops packages from disabled ones.
+        self.refresh_updates(true);
+    }
+
     fn start_mirror_write_worker(self: &Rc<Self>, ids: Vec<String>) {
+        let classes = self.state.borrow().repository_classes.clone();
         let sender = self.sender.clone();
         thread::spawn(move || {
-            if let Err(err) = write_repository_config(&ids) {
+            if let Err(err) = write_repository_config(&ids, &classes) {
                 eprintln!("Failed to write repository config: {}", err);
             }
             let _ = sender.send(AppMessage::MirrorsDetected { mirrors: ids });
@@ -1863,6 +3982,70 @@ impl AppController {
         startup_group.add(&start_combo);
         general_page.add(&startup_group);
 
+        let discover_group = adw::PreferencesGroup::builder().title("Discover").build();
+        let spotlight_row = adw::ActionRow::builder()
+            .title("Show spotlight on Discover")
+            .subtitle("Favorites and recently updated packages below the search box.")
+            .build();
+        let spotlight_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        spotlight_switch.set_active(self.state.borrow().show_spotlight);
+        spotlight_row.add_suffix(&spotlight_switch);
+        spotlight_row.set_activatable_widget(Some(&spotlight_switch));
+        discover_group.add(&spotlight_row);
+
+        let window_labels: Vec<String> = SPOTLIGHT_WINDOW_CHOICES
+            .iter()
+            .map(|days| format!("{} days", days))
+            .collect();
+        let window_label_refs: Vec<&str> = window_labels.iter().map(String::as_str).collect();
+        let window_model = gtk::StringList::new(&window_label_refs);
+        let current_window = self.settings.borrow().spotlight_window_days;
+        let window_combo = adw::ComboRow::builder()
+            .title("Recent updates period")
+            .subtitle("How far back the recent list looks for updated packages.")
+            .model(&window_model)
+            .selected(
+                SPOTLIGHT_WINDOW_CHOICES
+                    .iter()
+                    .position(|days| *days == current_window)
+                    .unwrap_or(0) as u32,
+            )
+            .build();
+        discover_group.add(&window_combo);
+
+        let limit_labels: Vec<String> = SPOTLIGHT_LIMIT_CHOICES
+            .iter()
+            .map(|limit| limit.to_string())
+            .collect();
+        let limit_label_refs: Vec<&str> = limit_labels.iter().map(String::as_str).collect();
+        let limit_model = gtk::StringList::new(&limit_label_refs);
+        let current_limit = self.settings.borrow().spotlight_recent_limit;
+        let limit_combo = adw::ComboRow::builder()
+            .title("Recent updates shown")
+            .subtitle("The most packages the recent list shows at once.")
+            .model(&limit_model)
+            .selected(
+                SPOTLIGHT_LIMIT_CHOICES
+                    .iter()
+                    .position(|limit| *limit == current_limit)
+                    .unwrap_or(1) as u32,
+            )
+            .build();
+        discover_group.add(&limit_combo);
+        general_page.add(&discover_group);
+
+        let categories_group = adw::PreferencesGroup::builder()
+            .title("Categories")
+            .description("Choose which category shortcuts Discover shows, and in what order.")
+            .build();
+        let categories_list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+        categories_list.add_css_class("boxed-list");
+        self.populate_category_preferences(&categories_list);
+        categories_group.add(&categories_list);
+        general_page.add(&categories_group);
+
         let updates_group = adw::PreferencesGroup::builder()
             .title("Updates")
             .description("Control automatic update checks.")
@@ -1875,15 +4058,45 @@ impl AppController {
         auto_switch_row.add_suffix(&auto_switch);
         auto_switch_row.set_activatable_widget(Some(&auto_switch));
 
-        let frequency_model = gtk::StringList::new(&["Daily", "Weekly"]);
+        let frequency_model = gtk::StringList::new(&["Hourly", "Daily", "Weekly", "Custom"]);
         let freq_combo = adw::ComboRow::builder()
             .title("Frequency")
             .model(&frequency_model)
             .build();
         freq_combo.set_sensitive(self.state.borrow().auto_check_enabled);
 
+        let current_frequency = self.state.borrow().auto_check_frequency;
+        // The adjustment clamps out-of-range values from hand-edited settings.
+        let custom_hours = match current_frequency {
+            UpdateCheckFrequency::Custom(hours) => hours,
+            _ => 12,
+        };
+        let custom_row = adw::ActionRow::builder()
+            .title("Custom interval")
+            .subtitle("Hours between automatic checks.")
+            .build();
+        custom_row.set_activatable(false);
+        let custom_adjustment = gtk::Adjustment::new(
+            custom_hours as f64,
+            UpdateCheckFrequency::MIN_CUSTOM_HOURS as f64,
+            UpdateCheckFrequency::MAX_CUSTOM_HOURS as f64,
+            1.0,
+            6.0,
+            0.0,
+        );
+        let custom_spin = gtk::SpinButton::builder()
+            .adjustment(&custom_adjustment)
+            .valign(gtk::Align::Center)
+            .width_chars(3)
+            .numeric(true)
+            .build();
+        custom_row.add_suffix(&custom_spin);
+        custom_row.set_visible(matches!(current_frequency, UpdateCheckFrequency::Custom(_)));
+        custom_row.set_sensitive(self.state.borrow().auto_check_enabled);
+
         updates_group.add(&auto_switch_row);
         updates_group.add(&freq_combo);
+        updates_group.add(&custom_row);
 
         let notify_switch_row = adw::ActionRow::builder()
             .title("Show system notifications for new updates")
@@ -1894,6 +4107,55 @@ impl AppController {
         notify_switch_row.set_activatable_widget(Some(&notify_switch));
         updates_group.add(&notify_switch_row);
 
+        let auto_install_row = adw::ActionRow::builder()
+            .title("Automatically install updates")
+            .subtitle("Applies every update the scheduled check finds without asking. Updates can break packages or restart services while you work. Skipped when a password would be required.")
+            .build();
+        auto_install_row.set_subtitle_lines(3);
+        let auto_install_icon = gtk::Image::from_icon_name("dialog-warning-symbolic");
+        auto_install_icon.add_css_class("warning");
+        auto_install_row.add_prefix(&auto_install_icon);
+        let auto_install_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        auto_install_switch.set_active(self.state.borrow().auto_install_updates);
+        auto_install_row.add_suffix(&auto_install_switch);
+        auto_install_row.set_activatable_widget(Some(&auto_install_switch));
+        auto_install_row.set_sensitive(self.state.borrow().auto_check_enabled);
+        updates_group.add(&auto_install_row);
+
+        let terminal_switch_row = adw::ActionRow::builder()
+            .title("Run updates in a terminal window")
+            .subtitle(
+                "Lets you answer xbps-install prompts such as configuration file conflicts. \
+                 Per-package progress and the list of changed configuration files aren't \
+                 available in this mode.",
+            )
+            .build();
+        let terminal_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        terminal_switch.set_active(self.state.borrow().run_updates_in_terminal);
+        terminal_switch_row.add_suffix(&terminal_switch);
+        terminal_switch_row.set_activatable_widget(Some(&terminal_switch));
+        updates_group.add(&terminal_switch_row);
+
+        let kernel_switch_row = adw::ActionRow::builder()
+            .title("Exclude kernel packages from Update All")
+            .subtitle("Kernel updates stay in the list so you can install them individually")
+            .build();
+        let kernel_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        kernel_switch.set_active(self.settings.borrow().exclude_kernel_from_update_all);
+        kernel_switch_row.add_suffix(&kernel_switch);
+        kernel_switch_row.set_activatable_widget(Some(&kernel_switch));
+        updates_group.add(&kernel_switch_row);
+
+        let mark_updates_row = adw::ActionRow::builder()
+            .title("Mark packages with updates")
+            .subtitle("Show an Update badge on installed packages in Discover and Installed")
+            .build();
+        let mark_updates_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        mark_updates_switch.set_active(self.marks_available_updates());
+        mark_updates_row.add_suffix(&mark_updates_switch);
+        mark_updates_row.set_activatable_widget(Some(&mark_updates_switch));
+        updates_group.add(&mark_updates_row);
+
         // Waypoint integration (only show if btrfs + waypoint available)
         let waypoint_switch_opt = if crate::waypoint::should_enable_integration() {
             let waypoint_switch_row = adw::ActionRow::builder()
@@ -1932,10 +4194,115 @@ impl AppController {
         confirm_remove_row.add_suffix(&confirm_remove_switch);
         confirm_remove_row.set_activatable_widget(Some(&confirm_remove_switch));
 
+        let summary_row = adw::ActionRow::builder()
+            .title("Skip confirmation and show a summary")
+            .subtitle("Changes apply right away; a notice lists everything installed, including dependencies.")
+            .build();
+        let summary_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        let summary_enabled = self.state.borrow().install_summary;
+        summary_switch.set_active(summary_enabled);
+        summary_row.add_suffix(&summary_switch);
+        summary_row.set_activatable_widget(Some(&summary_switch));
+        confirm_install_row.set_sensitive(!summary_enabled);
+        confirm_remove_row.set_sensitive(!summary_enabled);
+
+        let remove_options = self.remove_options();
+        let remove_recursive_row = adw::ActionRow::builder()
+            .title("Remove unneeded dependencies")
+            .subtitle("Also remove dependencies that no other package needs.")
+            .build();
+        let remove_recursive_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        remove_recursive_switch.set_active(remove_options.recursive);
+        remove_recursive_row.add_suffix(&remove_recursive_switch);
+        remove_recursive_row.set_activatable_widget(Some(&remove_recursive_switch));
+
+        let remove_orphans_row = adw::ActionRow::builder()
+            .title("Clean up orphaned packages")
+            .subtitle("Also remove any package that was installed as a dependency and is no longer needed.")
+            .build();
+        remove_orphans_row.set_subtitle_lines(2);
+        let remove_orphans_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        remove_orphans_switch.set_active(remove_options.orphans);
+        remove_orphans_row.add_suffix(&remove_orphans_switch);
+        remove_orphans_row.set_activatable_widget(Some(&remove_orphans_switch));
+
+        let free_space_row = adw::ActionRow::builder()
+            .title("Minimum free disk space")
+            .subtitle("Warn before installs and updates that would leave less than this many megabytes free. Set to 0 to turn the warning off.")
+            .build();
+        free_space_row.set_subtitle_lines(2);
+        free_space_row.set_activatable(false);
+        let free_space_adjustment = gtk::Adjustment::new(
+            self.settings.borrow().min_free_space_mb as f64,
+            0.0,
+            102_400.0,
+            256.0,
+            1024.0,
+            0.0,
+        );
+        let free_space_spin = gtk::SpinButton::builder()
+            .adjustment(&free_space_adjustment)
+            .valign(gtk::Align::Center)
+            .width_chars(6)
+            .numeric(true)
+            .build();
+        free_space_row.add_suffix(&free_space_spin);
+
         install_group.add(&confirm_install_row);
         install_group.add(&confirm_remove_row);
+        install_group.add(&summary_row);
+        install_group.add(&remove_recursive_row);
+        install_group.add(&remove_orphans_row);
+        install_group.add(&free_space_row);
         general_page.add(&install_group);
 
+        let accessibility_group = adw::PreferencesGroup::builder()
+            .title("Accessibility")
+            .build();
+        let contrast_row = adw::ActionRow::builder()
+            .title("High contrast")
+            .subtitle("Use full-strength text, solid borders and stronger focus outlines.")
+            .build();
+        let contrast_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+        contrast_switch.set_active(self.settings.borrow().high_contrast);
+        contrast_row.add_suffix(&contrast_switch);
+        contrast_row.set_activatable_widget(Some(&contrast_switch));
+
+        let scale_labels: Vec<String> = FONT_SCALE_STEPS
+            .iter()
+            .map(|percent| format!("{}%", percent))
+            .collect();
+        let scale_label_refs: Vec<&str> = scale_labels.iter().map(String::as_str).collect();
+        let scale_model = gtk::StringList::new(&scale_label_refs);
+        let current_scale = self.settings.borrow().font_scale_percent;
+        let scale_combo = adw::ComboRow::builder()
+            .title("Text size")
+            .subtitle("Scale text throughout Nebula.")
+            .model(&scale_model)
+            .selected(
+                FONT_SCALE_STEPS
+                    .iter()
+                    .position(|percent| *percent == current_scale)
+                    .unwrap_or(0) as u32,
+            )
+            .build();
+
+        let density_model = gtk::StringList::new(&["Comfortable", "Compact"]);
+        let density_combo = adw::ComboRow::builder()
+            .title("List density")
+            .subtitle("Compact rows fit more packages on screen.")
+            .model(&density_model)
+            .selected(match self.settings.borrow().list_density {
+                ListDensity::Comfortable => 0,
+                ListDensity::Compact => 1,
+            })
+            .build();
+
+        accessibility_group.add(&contrast_row);
+        accessibility_group.add(&scale_combo);
+        accessibility_group.add(&density_combo);
+        general_page.add(&accessibility_group);
+
         prefs.add(&general_page);
 
         {
@@ -1946,8 +4313,10 @@ impl AppController {
                 StartPagePreference::Discover => 0,
             };
             let initial_freq = match self.state.borrow().auto_check_frequency {
-                UpdateCheckFrequency::Daily => 0,
-                UpdateCheckFrequency::Weekly => 1,
+                UpdateCheckFrequency::Hourly => 0,
+                UpdateCheckFrequency::Daily => 1,
+                UpdateCheckFrequency::Weekly => 2,
+                UpdateCheckFrequency::Custom(_) => 3,
             };
             glib::idle_add_local(move || {
                 if let Some(combo) = start_combo_ref.upgrade() {
@@ -1972,20 +4341,47 @@ impl AppController {
 
         let controller_clone = Rc::clone(self);
         let freq_combo_clone = freq_combo.clone();
+        let custom_row_clone = custom_row.clone();
+        let auto_install_row_clone = auto_install_row.clone();
         auto_switch.connect_active_notify(move |switcher| {
             let active = switcher.is_active();
             controller_clone.set_auto_check_enabled(active, true);
             freq_combo_clone.set_sensitive(active);
+            custom_row_clone.set_sensitive(active);
+            auto_install_row_clone.set_sensitive(active);
+        });
+
+        let controller_clone = Rc::clone(self);
+        auto_install_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_auto_install_updates(switcher.is_active(), true);
         });
 
         let controller_clone = Rc::clone(self);
+        let custom_row_clone = custom_row.clone();
+        let custom_spin_clone = custom_spin.clone();
         freq_combo.connect_selected_notify(move |row| {
-            let frequency = if row.selected() == 1 {
-                UpdateCheckFrequency::Weekly
-            } else {
-                UpdateCheckFrequency::Daily
+            let frequency = match row.selected() {
+                0 => UpdateCheckFrequency::Hourly,
+                2 => UpdateCheckFrequency::Weekly,
+                3 => UpdateCheckFrequency::custom(custom_spin_clone.value_as_int().max(0) as u32),
+                _ => UpdateCheckFrequency::Daily,
             };
-            controller_clone.set_auto_check_frequency(frequency, true);
+            custom_row_clone.set_visible(matches!(frequency, UpdateCheckFrequency::Custom(_)));
+            if controller_clone.state.borrow().auto_check_frequency != frequency {
+                controller_clone.set_auto_check_frequency(frequency, true);
+            }
+        });
+
+        let controller_clone = Rc::clone(self);
+        custom_spin.connect_value_changed(move |spin| {
+            let current = controller_clone.state.borrow().auto_check_frequency;
+            if !matches!(current, UpdateCheckFrequency::Custom(_)) {
+                return;
+            }
+            let frequency = UpdateCheckFrequency::custom(spin.value_as_int().max(0) as u32);
+            if current != frequency {
+                controller_clone.set_auto_check_frequency(frequency, true);
+            }
         });
 
         let controller_clone = Rc::clone(self);
@@ -1998,11 +4394,96 @@ impl AppController {
             controller_clone.set_confirm_remove(switcher.is_active(), true);
         });
 
+        let controller_clone = Rc::clone(self);
+        let confirm_install_row_clone = confirm_install_row.clone();
+        let confirm_remove_row_clone = confirm_remove_row.clone();
+        summary_switch.connect_active_notify(move |switcher| {
+            let active = switcher.is_active();
+            controller_clone.set_install_summary(active, true);
+            confirm_install_row_clone.set_sensitive(!active);
+            confirm_remove_row_clone.set_sensitive(!active);
+        });
+
+        let controller_clone = Rc::clone(self);
+        remove_recursive_switch.connect_active_notify(move |switcher| {
+            let mut options = controller_clone.remove_options();
+            options.recursive = switcher.is_active();
+            controller_clone.set_remove_options(options);
+        });
+
+        let controller_clone = Rc::clone(self);
+        remove_orphans_switch.connect_active_notify(move |switcher| {
+            let mut options = controller_clone.remove_options();
+            options.orphans = switcher.is_active();
+            controller_clone.set_remove_options(options);
+        });
+
+        let controller_clone = Rc::clone(self);
+        free_space_spin.connect_value_changed(move |spin| {
+            controller_clone.set_min_free_space_mb(spin.value_as_int().max(0) as u64);
+        });
+
+        let controller_clone = Rc::clone(self);
+        contrast_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_high_contrast(switcher.is_active());
+        });
+
+        let controller_clone = Rc::clone(self);
+        window_combo.connect_selected_notify(move |row| {
+            if let Some(days) = SPOTLIGHT_WINDOW_CHOICES.get(row.selected() as usize) {
+                controller_clone.set_spotlight_window_days(*days);
+            }
+        });
+
+        let controller_clone = Rc::clone(self);
+        limit_combo.connect_selected_notify(move |row| {
+            if let Some(limit) = SPOTLIGHT_LIMIT_CHOICES.get(row.selected() as usize) {
+                controller_clone.set_spotlight_recent_limit(*limit);
+            }
+        });
+
+        let controller_clone = Rc::clone(self);
+        scale_combo.connect_selected_notify(move |row| {
+            if let Some(percent) = FONT_SCALE_STEPS.get(row.selected() as usize) {
+                controller_clone.set_font_scale_percent(*percent);
+            }
+        });
+
+        let controller_clone = Rc::clone(self);
+        density_combo.connect_selected_notify(move |row| {
+            let density = if row.selected() == 1 {
+                ListDensity::Compact
+            } else {
+                ListDensity::Comfortable
+            };
+            controller_clone.set_list_density(density);
+        });
+
         let controller_clone = Rc::clone(self);
         notify_switch.connect_active_notify(move |switcher| {
             controller_clone.set_notify_updates(switcher.is_active(), true);
         });
 
+        let controller_clone = Rc::clone(self);
+        terminal_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_run_updates_in_terminal(switcher.is_active(), true);
+        });
+
+        let controller_clone = Rc::clone(self);
+        kernel_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_exclude_kernel_from_update_all(switcher.is_active());
+        });
+
+        let controller_clone = Rc::clone(self);
+        mark_updates_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_mark_available_updates(switcher.is_active());
+        });
+
+        let controller_clone = Rc::clone(self);
+        spotlight_switch.connect_active_notify(move |switcher| {
+            controller_clone.set_show_spotlight(switcher.is_active(), true);
+        });
+
         if let Some(waypoint_switch) = waypoint_switch_opt {
             let controller_clone = Rc::clone(self);
             waypoint_switch.connect_active_notify(move |switcher| {
@@ -2013,6 +4494,54 @@ impl AppController {
         prefs.present();
     }
 
+    fn populate_category_preferences(self: &Rc<Self>, list: &gtk::ListBox) {
+        clear_listbox(list);
+        let layout = self.spotlight_category_layout();
+        let last = layout.len().saturating_sub(1);
+        for (index, (category, enabled)) in layout.into_iter().enumerate() {
+            let row = adw::ActionRow::builder()
+                .title(category_display_name(category))
+                .build();
+
+            let check = gtk::CheckButton::builder()
+                .active(enabled)
+                .valign(gtk::Align::Center)
+                .build();
+            let controller = Rc::downgrade(self);
+            check.connect_toggled(move |check| {
+                if let Some(controller) = controller.upgrade() {
+                    controller.set_spotlight_category_enabled(category, check.is_active());
+                }
+            });
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+
+            for (icon, tooltip, up, sensitive) in [
+                ("go-up-symbolic", "Move up", true, index > 0),
+                ("go-down-symbolic", "Move down", false, index < last),
+            ] {
+                let button = gtk::Button::builder()
+                    .icon_name(icon)
+                    .tooltip_text(tooltip)
+                    .valign(gtk::Align::Center)
+                    .sensitive(sensitive)
+                    .build();
+                button.add_css_class("flat");
+                let controller = Rc::downgrade(self);
+                let list = list.clone();
+                button.connect_clicked(move |_| {
+                    if let Some(controller) = controller.upgrade() {
+                        controller.move_spotlight_category(category, up);
+                        controller.populate_category_preferences(&list);
+                    }
+                });
+                row.add_suffix(&button);
+            }
+
+            list.append(&row);
+        }
+    }
+
     pub(crate) fn show_about_dialog(self: &Rc<Self>) {
         if let Some(existing) = self.about_dialog.borrow().as_ref() {
             existing.present();
@@ -2129,6 +4658,43 @@ impl AppController {
         dialog.present();
     }
 
+    /// Introduces the pages, mirrors and privilege prompts the first time
+    /// Nebula is opened. Dismissing it in any way marks the first run done.
+    pub(crate) fn show_welcome_if_first_run(self: &Rc<Self>) {
+        if !self.settings.borrow().first_run {
+            return;
+        }
+
+        let dialog = adw::MessageDialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .heading("Welcome to Nebula")
+            .body(
+                "Discover finds and installs software, Installed lists what is on this system, and Updates keeps it current. Tools has cache cleanup and package database repairs.\n\nDownloads come from the mirrors chosen under Mirrors in the main menu; pick one close to you for faster updates.\n\nInstalling, removing and updating packages needs administrator rights, so expect a password prompt for those actions.",
+            )
+            .build();
+        dialog.add_response("mirrors", "Choose Mirrors");
+        dialog.add_response("start", "Get Started");
+        dialog.set_response_appearance("start", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("start"));
+        dialog.set_close_response("start");
+
+        let controller_weak = Rc::downgrade(self);
+        dialog.connect_response(None, move |dialog, response| {
+            dialog.close();
+            let Some(controller) = controller_weak.upgrade() else {
+                return;
+            };
+            controller.settings.borrow_mut().first_run = false;
+            controller.persist_settings();
+            if response == "mirrors" {
+                controller.show_mirrors();
+            }
+        });
+
+        dialog.present();
+    }
+
     pub(crate) fn set_installed_status_message(&self, message: Option<String>) {
         {
             let mut state = self.state.borrow_mut();
@@ -2160,8 +4726,16 @@ impl AppController {
         }
 
         let interval = match frequency {
+            UpdateCheckFrequency::Hourly => 60 * 60,
             UpdateCheckFrequency::Daily => 24 * 60 * 60,
             UpdateCheckFrequency::Weekly => 7 * 24 * 60 * 60,
+            UpdateCheckFrequency::Custom(hours) => {
+                let hours = hours.clamp(
+                    UpdateCheckFrequency::MIN_CUSTOM_HOURS,
+                    UpdateCheckFrequency::MAX_CUSTOM_HOURS,
+                );
+                hours * 60 * 60
+            }
         };
 
         let weak_self = Rc::downgrade(self);
@@ -2186,6 +4760,10 @@ impl AppController {
         if !self.can_trigger_auto_check_now() {
             return;
         }
+        {
+            let mut state = self.state.borrow_mut();
+            state.auto_install_pending = state.auto_install_updates;
+        }
         self.refresh_updates(true);
     }
 
@@ -2221,7 +4799,17 @@ impl AppController {
                     .retain(|pkg| pkg.name != package_name);
             }
         }
+        if installed {
+            self.forget_removed_package(package_name);
+        }
+        if self.defer_to_batch() {
+            return;
+        }
+        self.refresh_package_views();
+    }
 
+    /// Redraws every view showing whether packages are installed.
+    fn refresh_package_views(self: &Rc<Self>) {
         self.rebuild_search_list();
         self.rebuild_installed_list();
         self.update_spotlight_installed_flags();
@@ -2229,4 +4817,134 @@ impl AppController {
         self.refresh_active_spotlight_category();
         self.update_discover_details();
     }
+
+    /// Holds back list rebuilds until the matching `end_batch_ui_update`,
+    /// so a change to many packages redraws once instead of per package.
+    /// Batches nest.
+    pub(crate) fn begin_batch_ui_update(&self) {
+        self.state.borrow_mut().ui_batch_depth += 1;
+    }
+
+    pub(crate) fn end_batch_ui_update(self: &Rc<Self>) {
+        let flush = {
+            let mut state = self.state.borrow_mut();
+            state.ui_batch_depth = state.ui_batch_depth.saturating_sub(1);
+            state.ui_batch_depth == 0 && std::mem::take(&mut state.ui_batch_dirty)
+        };
+        if flush {
+            self.refresh_package_views();
+        }
+    }
+
+    /// Whether a rebuild should wait for the open batch, remembering that
+    /// one was skipped so the batch ends with a full refresh.
+    pub(crate) fn defer_to_batch(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        if state.ui_batch_depth == 0 {
+            return false;
+        }
+        state.ui_batch_dirty = true;
+        true
+    }
+}
+
+fn format_update_log_line(received: &DateTime<Utc>, line: &str, show_timestamp: bool) -> String {
+    if show_timestamp {
+        format!(
+            "{}  {}",
+            received.with_timezone(&Local).format("%H:%M:%S"),
+            line
+        )
+    } else {
+        line.to_string()
+    }
+}
+
+/// Everything a removal took out, requested packages first, so undoing it
+/// also brings back the dependencies `xbps-remove -R` removed with them.
+fn removed_package_set(requested: &[String], output: &str) -> Vec<String> {
+    let mut removed = requested.to_vec();
+    for name in parse_removed_packages(output) {
+        if !removed.contains(&name) {
+            removed.push(name);
+        }
+    }
+    removed
+}
+
+/// Explains why packages were left out of a batch install, telling packages
+/// built only for other architectures apart from names nothing provides.
+fn skipped_packages_note(
+    skipped: &[String],
+    other_architectures: &HashMap<String, Vec<String>>,
+) -> Option<String> {
+    if skipped.is_empty() {
+        return None;
+    }
+    let mut lines = vec![format!(
+        "Skipped {} unavailable package{}:",
+        skipped.len(),
+        if skipped.len() == 1 { "" } else { "s" }
+    )];
+    let mut missing = Vec::new();
+    for name in skipped {
+        match other_architectures.get(name) {
+            Some(architectures) => lines.push(format!(
+                "{} is only available on {}, not {}.",
+                name,
+                architectures.join(", "),
+                native_architecture()
+            )),
+            None => missing.push(name.as_str()),
+        }
+    }
+    if !missing.is_empty() {
+        lines.push(format!(
+            "Not found in any repository: {}.",
+            missing.join(", ")
+        ));
+    }
+    Some(lines.join("\n"))
+}
+
+fn install_summary_message(packages: &[String], dependencies: &[String]) -> String {
+    let installed = if packages.len() == 1 {
+        packages[0].clone()
+    } else {
+        format!("{} packages", packages.len())
+    };
+    match dependencies.len() {
+        0 => format!("Installed {}.", installed),
+        count => format!(
+            "Installed {} with {} dependenc{}: {}.",
+            installed,
+            count,
+            if count == 1 { "y" } else { "ies" },
+            summarize_package_list(dependencies)
+        ),
+    }
+}
+
+fn mirror_subtitle(mirror: &MirrorDefinition, stat: Option<&MirrorStat>) -> String {
+    let url = humanize_base_url(mirror);
+    match stat {
+        Some(MirrorStat {
+            latency_ms: Some(latency),
+            measured_at,
+        }) => format!(
+            "{} · {} ms, tested {}",
+            url,
+            latency,
+            format_relative_time(*measured_at)
+        ),
+        Some(MirrorStat {
+            latency_ms: None,
+            measured_at,
+        }) => format!(
+            "{} · unreachable, tested {}",
+            url,
+            format_relative_time(*measured_at)
+        ),
+        None => url,
+    }
 }
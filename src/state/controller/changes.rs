@@ -0,0 +1,363 @@
+use std::rc::Rc;
+use std::thread;
+
+use gtk4 as gtk;
+
+use gtk::prelude::*;
+
+use crate::state::controller::AppController;
+use crate::state::types::{AppMessage, PendingChange};
+use crate::types::CommandResult;
+use crate::xbps::{
+    apply_changes_command_line, privilege_failure_reported, query_removal_dependents,
+    run_xbps_apply_changes,
+};
+
+impl AppController {
+    /// Moves the packages picked in Discover into the pending changes as
+    /// installs.
+    pub(crate) fn on_queue_selected_installs(self: &Rc<Self>) {
+        let packages = {
+            let mut state = self.state.borrow_mut();
+            let packages: Vec<String> = state
+                .discover_selection
+                .iter()
+                .filter(|name| !state.installed_set.contains(*name))
+                .cloned()
+                .collect();
+            state
+                .discover_selection
+                .retain(|name| !packages.contains(name));
+            packages
+        };
+        if packages.is_empty() {
+            return;
+        }
+        let count = packages.len();
+        for package in packages {
+            self.add_pending_change(PendingChange::Install(package));
+        }
+        self.show_toast(&format!(
+            "Added {} package{} to install to the pending changes.",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+        self.rebuild_search_list();
+        self.update_discover_selection_controls();
+    }
+
+    /// Moves the packages checked on the Installed page into the pending
+    /// changes as removals.
+    pub(crate) fn on_queue_selected_removals(self: &Rc<Self>) {
+        let mut packages: Vec<String> = {
+            let mut state = self.state.borrow_mut();
+            state.installed_selected.drain().collect()
+        };
+        if packages.is_empty() {
+            return;
+        }
+        packages.sort();
+        let count = packages.len();
+        for package in packages {
+            self.add_pending_change(PendingChange::Remove(package));
+        }
+        self.show_toast(&format!(
+            "Added {} package{} to remove to the pending changes.",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+        self.rebuild_installed_list();
+    }
+
+    /// Records `change`, replacing an earlier change for the same package so
+    /// a package is never both installed and removed.
+    fn add_pending_change(&self, change: PendingChange) {
+        {
+            let mut state = self.state.borrow_mut();
+            state
+                .pending_changes
+                .retain(|existing| existing.package() != change.package());
+            state.pending_changes.push(change);
+        }
+        self.refresh_pending_changes_bar();
+    }
+
+    pub(crate) fn clear_pending_changes(&self) {
+        self.state.borrow_mut().pending_changes.clear();
+        self.refresh_pending_changes_bar();
+    }
+
+    pub(crate) fn refresh_pending_changes_bar(&self) {
+        let (installs, removes, busy, applying) = {
+            let state = self.state.borrow();
+            let installs = state
+                .pending_changes
+                .iter()
+                .filter(|change| matches!(change, PendingChange::Install(_)))
+                .count();
+            (
+                installs,
+                state.pending_changes.len() - installs,
+                state.package_transaction_running(),
+                state.pending_changes_in_progress,
+            )
+        };
+
+        let mut parts = Vec::new();
+        if installs > 0 {
+            parts.push(format!("{} to install", installs));
+        }
+        if removes > 0 {
+            parts.push(format!("{} to remove", removes));
+        }
+        let text = if applying {
+            "Applying pending changes…".to_string()
+        } else {
+            format!("Pending changes: {}", parts.join(", "))
+        };
+        self.widgets.changes_label.set_text(&text);
+        self.widgets.changes_bar.set_revealed(!parts.is_empty());
+        self.widgets.changes_apply_button.set_sensitive(!busy);
+        self.widgets.changes_clear_button.set_sensitive(!busy);
+    }
+
+    pub(crate) fn on_apply_pending_changes(self: &Rc<Self>) {
+        let (installs, removes) = self.pending_change_lists();
+        if installs.is_empty() && removes.is_empty() {
+            return;
+        }
+        if removes.is_empty() {
+            self.confirm_pending_changes(Ok(Vec::new()));
+            return;
+        }
+
+        // Check what the removals would break before asking, as a single
+        // removal does.
+        {
+            let mut state = self.state.borrow_mut();
+            if state.removal_impact_pending {
+                return;
+            }
+            state.removal_impact_pending = true;
+        }
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = query_removal_dependents(&removes);
+            let _ = sender.send(AppMessage::PendingChangesImpactLoaded { result });
+        });
+    }
+
+    pub(crate) fn finish_pending_changes_impact(
+        self: &Rc<Self>,
+        result: Result<Vec<String>, String>,
+    ) {
+        self.state.borrow_mut().removal_impact_pending = false;
+        self.confirm_pending_changes(result);
+    }
+
+    /// Asks to apply the pending changes, naming installed packages that
+    /// depend on the queued removals. Removing those too is opt-in.
+    fn confirm_pending_changes(self: &Rc<Self>, impact: Result<Vec<String>, String>) {
+        let (installs, removes) = self.pending_change_lists();
+        if installs.is_empty() && removes.is_empty() {
+            return;
+        }
+
+        let mut body = String::new();
+        if !installs.is_empty() {
+            body.push_str(&format!("Install: {}\n", installs.join(", ")));
+        }
+        if !removes.is_empty() {
+            body.push_str(&format!("Remove: {}\n", removes.join(", ")));
+        }
+        body.push_str("\nAll changes run in one transaction after a single authorization.");
+        if !removes.is_empty() {
+            body.push_str(&format!("\n\n{}", self.remove_behavior_note()));
+        }
+        let dependents = match impact {
+            Ok(dependents) => dependents,
+            Err(err) => {
+                body.push_str(&format!(
+                    "\n\nNebula couldn't check which installed packages depend on the removals: {}",
+                    err
+                ));
+                Vec::new()
+            }
+        };
+
+        let options = self.remove_options();
+        if dependents.is_empty() {
+            let command = Some(apply_changes_command_line(&installs, &removes, options));
+            self.confirm_command(
+                "Apply Pending Changes?",
+                &body,
+                "Apply",
+                command,
+                |controller| controller.execute_pending_changes(),
+            );
+            return;
+        }
+
+        body.push_str(&format!(
+            "\n\nThese installed packages depend on the removals and would break: {}.\n\nXBPS will refuse the changes unless they are removed as well.",
+            dependents.join(", ")
+        ));
+        let cascade_check = gtk::CheckButton::with_label(&format!(
+            "Also remove the {} dependent package{}",
+            dependents.len(),
+            if dependents.len() == 1 { "" } else { "s" }
+        ));
+        let check = cascade_check.clone();
+        let cascade_dependents = dependents.clone();
+        let command: Box<dyn Fn() -> String> = Box::new(move || {
+            let mut removes = removes.clone();
+            if check.is_active() {
+                removes.extend(cascade_dependents.iter().cloned());
+            }
+            apply_changes_command_line(&installs, &removes, options)
+        });
+        let check = cascade_check.clone();
+        let dialog = self.confirm_command_with(
+            "Apply Pending Changes?",
+            &body,
+            "Apply",
+            Some(command),
+            move |controller| {
+                if check.is_active() {
+                    for package in dependents {
+                        controller.add_pending_change(PendingChange::Remove(package));
+                    }
+                }
+                controller.execute_pending_changes();
+            },
+        );
+        dialog.set_default_response(gtk::ResponseType::Cancel);
+        if let Ok(area) = dialog.message_area().downcast::<gtk::Box>() {
+            area.append(&cascade_check);
+        }
+    }
+
+    fn pending_change_lists(&self) -> (Vec<String>, Vec<String>) {
+        let state = self.state.borrow();
+        let mut installs = Vec::new();
+        let mut removes = Vec::new();
+        for change in &state.pending_changes {
+            match change {
+                PendingChange::Install(package) => installs.push(package.clone()),
+                PendingChange::Remove(package) => removes.push(package.clone()),
+            }
+        }
+        (installs, removes)
+    }
+
+    fn execute_pending_changes(self: &Rc<Self>) {
+        let (installs, removes) = self.pending_change_lists();
+        if installs.is_empty() && removes.is_empty() {
+            return;
+        }
+        {
+            let mut state = self.state.borrow_mut();
+            if state.package_transaction_running() {
+                drop(state);
+                self.show_toast(
+                    "Wait for the current operation to finish before applying changes.",
+                );
+                return;
+            }
+            state.install_in_progress = true;
+            state.remove_in_progress = true;
+            state.pending_changes_in_progress = true;
+            state.removing_packages.extend(removes.iter().cloned());
+        }
+        self.refresh_busy_indicator();
+        self.rebuild_search_list();
+        self.refresh_discover_install_widgets();
+        self.rebuild_installed_list();
+
+        self.set_footer_message(Some("Applying pending changes…"));
+        let options = self.remove_options();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_apply_changes(&installs, &removes, options);
+            let _ = sender.send(AppMessage::PendingChangesFinished {
+                installs,
+                removes,
+                result,
+            });
+        });
+    }
+
+    pub(crate) fn finish_pending_changes(
+        self: &Rc<Self>,
+        installs: Vec<String>,
+        removes: Vec<String>,
+        result: Result<CommandResult, String>,
+    ) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.install_in_progress = false;
+            state.remove_in_progress = false;
+            state.pending_changes_in_progress = false;
+            for package in &removes {
+                state.removing_packages.remove(package);
+            }
+        }
+        self.refresh_busy_indicator();
+
+        let reported = privilege_failure_reported(&result);
+        let footer_message = match result {
+            Ok(command) if command.success() => {
+                {
+                    let mut state = self.state.borrow_mut();
+                    state.pending_changes.retain(|change| {
+                        !installs.iter().any(|pkg| pkg == change.package())
+                            && !removes.iter().any(|pkg| pkg == change.package())
+                    });
+                }
+                self.begin_batch_ui_update();
+                for package in &installs {
+                    self.flag_installed_state(package, true);
+                }
+                for package in &removes {
+                    self.flag_installed_state(package, false);
+                }
+                self.end_batch_ui_update();
+                self.record_removed_packages(&removes);
+                let message = "Pending changes applied successfully.".to_string();
+                self.show_toast(&message);
+                message
+            }
+            Ok(command) => {
+                let mut detail = command.stderr.trim();
+                if detail.is_empty() {
+                    detail = command.stdout.trim();
+                }
+                let message = if detail.is_empty() {
+                    "Failed to apply the pending changes.".to_string()
+                } else {
+                    format!("Failed to apply the pending changes: {}", detail)
+                };
+                if !reported {
+                    self.show_error_dialog("Changes Failed", &message);
+                }
+                message
+            }
+            Err(err) => {
+                let message = format!("Failed to apply the pending changes: {}", err);
+                if !reported {
+                    self.show_error_dialog("Changes Failed", &message);
+                }
+                message
+            }
+        };
+
+        self.refresh_pending_changes_bar();
+        self.refresh_installed_packages();
+        self.refresh_updates(true);
+        self.update_discover_details();
+        self.rebuild_search_list();
+        self.refresh_discover_install_widgets();
+        self.set_footer_message(Some(&footer_message));
+        self.start_next_queued_operation();
+    }
+}
@@ -1,30 +1,51 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::thread;
+use std::time::Duration;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Local, Utc};
 use gtk4 as gtk;
 use libadwaita as adw;
 
 use adw::prelude::*;
+use gtk::gdk;
+use gtk::gio;
 use gtk::glib;
+use gtk::pango;
 use gtk::prelude::{ListBoxRowExt, WidgetExt};
 
-use crate::categories::icon_resource_for_package;
+use crate::appstream;
 use crate::details::DiscoverDetail;
 use crate::helpers::{
-    clear_listbox, detail_download_bytes, format_relative_time, populate_spotlight_list,
+    DETAIL_TIMEOUT_ERROR, clear_listbox, compact_package_row, detail_download_bytes,
+    format_relative_time, package_icon, package_row_tooltip, populate_spotlight_list,
     sanitize_contact_field, select_row_if_attached, set_download_label, set_link_label,
-    set_toggle_button_state,
+    set_toggle_button_state, summarize_package_list, update_badge,
 };
+use crate::i18n::{tr, trn};
+use crate::mirrors::suggested_repository_for;
 use crate::spotlight::{
     SPOTLIGHT_REFRESH_INTERVAL_HOURS, SpotlightCache, SpotlightCategory, category_display_name,
-    refresh_spotlight_cache, save_spotlight_cache_to_disk,
+    category_icon_resource, compute_new_since, compute_spotlight_sections,
+    ordered_spotlight_categories, refresh_spotlight_cache, save_spotlight_cache_to_disk,
 };
 use crate::state::controller::AppController;
-use crate::state::types::{AppMessage, DiscoverMode, RemoveOrigin};
+use crate::state::types::{AppMessage, AppState, DiscoverMode, RemoveOrigin};
 use crate::types::{PackageInfo, lowercase_cache};
-use crate::xbps::{format_size, run_xbps_query_search};
+use crate::ui::build_category_button;
+use crate::xbps::{
+    PACKAGE_NOT_FOUND_ERROR, format_size, install_command_line, pinned_install_command_line,
+    remove_command_line, run_xbps_query_all_packages, run_xbps_query_search,
+    run_xbps_query_search_descriptions, search_term_patterns,
+};
+
+const SPOTLIGHT_MAX_ATTEMPTS: u32 = 3;
+const SPOTLIGHT_RETRY_BASE_SECS: u32 = 5;
+const MAX_COMPARE_PACKAGES: usize = 3;
+const SEARCH_DEBOUNCE_MS: u64 = 400;
+const MAX_RECENTLY_VIEWED: usize = 8;
 
 impl AppController {
     pub(crate) fn on_discover_primary_action(self: &Rc<Self>) {
@@ -41,10 +62,30 @@ impl AppController {
     }
 
     pub(crate) fn on_search_requested(self: &Rc<Self>) {
-        let query = self.widgets.discover.search_entry.text().trim().to_string();
-        if query.is_empty() {
+        self.cancel_search_debounce();
+        let query = self
+            .widgets
+            .discover
+            .search_entry
+            .text()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if search_term_patterns(&query).is_empty() {
+            self.set_discover_status(Some(
+                "Type a package name or keyword to search the repository. Use * as a wildcard, as in \"python3-*\".",
+            ));
+            return;
+        }
+
+        let offline = {
+            let mut state = self.state.borrow_mut();
+            state.pending_search = state.offline;
+            state.offline
+        };
+        if offline {
             self.set_discover_status(Some(
-                "Type a package name or keyword to search the repository.",
+                "You're offline. This search will run once the network is back.",
             ));
             return;
         }
@@ -57,20 +98,23 @@ impl AppController {
             self.clear_spotlight_category();
         }
 
-        {
+        // A newer search supersedes one still running; its results are
+        // dropped in `finish_search` by comparing sequence numbers.
+        let sequence = {
             let mut state = self.state.borrow_mut();
-            if state.search_in_progress {
-                return;
-            }
             state.search_in_progress = true;
             state.discover_mode = DiscoverMode::Search;
-        }
+            state.search_sequence += 1;
+            state.last_search_query = Some(query.clone());
+            state.failed_search_query = None;
+            state.search_sequence
+        };
+        self.refresh_busy_indicator();
 
         self.update_discover_layout();
 
         let message = format!("Searching for \"{}\"…", query);
         self.set_discover_status(Some(&message));
-        self.widgets.discover.search_entry.set_editable(false);
         self.widgets.discover.search_spinner.set_visible(true);
         self.widgets.discover.search_spinner.start();
         let preserve_navigation = {
@@ -78,18 +122,82 @@ impl AppController {
             state.discover_detail_navigation_active || state.pending_discover_target.is_some()
         };
         self.clear_discover_details(preserve_navigation);
+        let search_descriptions = self.state.borrow().search_descriptions;
         let sender = self.sender.clone();
         thread::spawn(move || {
-            let result = run_xbps_query_search(&query);
-            let _ = sender.send(AppMessage::SearchFinished { query, result });
+            let result = if search_descriptions {
+                run_xbps_query_search_descriptions(&query)
+            } else {
+                run_xbps_query_search(&query)
+            };
+            let _ = sender.send(AppMessage::SearchFinished {
+                sequence,
+                query,
+                result,
+            });
         });
     }
 
+    fn cancel_search_debounce(&self) {
+        if let Some(source) = self.state.borrow_mut().search_debounce_source.take() {
+            source.remove();
+        }
+    }
+
+    /// Runs a search once typing has paused, unless the text still matches
+    /// the last query (for example after selecting a spotlight row).
+    fn schedule_live_search(self: &Rc<Self>, text: &str) {
+        self.cancel_search_debounce();
+        let query = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let unchanged = {
+            let state = self.state.borrow();
+            state.discover_mode == DiscoverMode::Search
+                && state.last_search_query.as_deref() == Some(query.as_str())
+        };
+        if unchanged {
+            return;
+        }
+
+        let source = glib::timeout_add_local_once(
+            Duration::from_millis(SEARCH_DEBOUNCE_MS),
+            glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move || {
+                    controller.state.borrow_mut().search_debounce_source = None;
+                    controller.on_search_requested();
+                }
+            ),
+        );
+        self.state.borrow_mut().search_debounce_source = Some(source);
+    }
+
+    pub(crate) fn on_search_descriptions_toggled(self: &Rc<Self>, active: bool) {
+        self.state.borrow_mut().search_descriptions = active;
+
+        let entry = &self.widgets.discover.search_entry;
+        entry.set_placeholder_text(Some(if active {
+            "Describe what you need, e.g. markdown editor"
+        } else {
+            "Search the Void Linux repositories"
+        }));
+
+        // Re-run an existing search so the results match the new mode. A
+        // search still running in the old mode is superseded and its results
+        // are dropped.
+        let rerun = self.state.borrow().discover_mode == DiscoverMode::Search;
+        if rerun && !entry.text().trim().is_empty() {
+            self.on_search_requested();
+        }
+    }
+
     pub(crate) fn on_discover_search_changed(self: &Rc<Self>, text: String) {
         if !text.trim().is_empty() {
+            self.schedule_live_search(&text);
             return;
         }
 
+        self.cancel_search_debounce();
         let should_reset = {
             let mut state = self.state.borrow_mut();
             if state.discover_mode != DiscoverMode::Search {
@@ -99,6 +207,8 @@ impl AppController {
                 state.search_results.clear();
                 state.selected_search = None;
                 state.search_in_progress = false;
+                state.search_sequence += 1;
+                state.last_search_query = None;
                 state.discover_detail_focus = None;
                 true
             }
@@ -110,7 +220,6 @@ impl AppController {
 
         self.widgets.discover.search_spinner.stop();
         self.widgets.discover.search_spinner.set_visible(false);
-        self.widgets.discover.search_entry.set_editable(true);
         self.rebuild_search_list();
         self.clear_discover_details(false);
         self.update_discover_layout();
@@ -133,13 +242,37 @@ impl AppController {
     }
 
     fn request_install_for_package(self: &Rc<Self>, package: PackageInfo) {
-        if self.state.borrow().confirm_install {
+        let required_bytes =
+            install_space_required(&self.state.borrow(), &package.name, package.download_bytes);
+        let space_warning = self.disk_space_warning(required_bytes.unwrap_or(0));
+        if space_warning.is_some() || self.state.borrow().should_confirm_install() {
             let pkg_clone = package.clone();
             let heading = format!("Install \"{}\"?", package.name);
-            let body = "Nebula will install this package and any required dependencies.";
-            self.confirm_action(&heading, body, "Install", move |controller| {
-                controller.begin_install(pkg_clone);
-            });
+            let mut body =
+                String::from("Nebula will install this package and any required dependencies.");
+            let source = self
+                .state
+                .borrow()
+                .install_sources
+                .get(&package.name)
+                .cloned();
+            if let Some(repository) = source.as_deref() {
+                body.push_str(&format!("\n\nIt will be installed from {}.", repository));
+            }
+            if let Some(warning) = space_warning {
+                body.push_str("\n\n");
+                body.push_str(&warning);
+            }
+            let command = pinned_install_command_line(&[package.name.clone()], source.as_deref());
+            self.confirm_command(
+                &heading,
+                &body,
+                "Install",
+                Some(command),
+                move |controller| {
+                    controller.begin_install(pkg_clone);
+                },
+            );
             return;
         }
 
@@ -201,12 +334,16 @@ impl AppController {
 
     pub(crate) fn finish_search(
         self: &Rc<Self>,
+        sequence: u64,
         query: String,
         result: Result<Vec<PackageInfo>, String>,
     ) {
+        if sequence != self.state.borrow().search_sequence {
+            return;
+        }
+
         self.widgets.discover.search_spinner.stop();
         self.widgets.discover.search_spinner.set_visible(false);
-        self.widgets.discover.search_entry.set_editable(true);
         {
             let mut state = self.state.borrow_mut();
             state.search_in_progress = false;
@@ -237,19 +374,46 @@ impl AppController {
                 };
 
                 let results_len = self.state.borrow().search_results.len();
+                let repo_button = &self.widgets.discover.no_results_repo_button;
                 if results_len == 0 {
                     self.clear_discover_details(navigation_active);
-                    let message = format!("No packages matched \"{}\".", query);
+                    let suggestion = {
+                        let state = self.state.borrow();
+                        suggested_repository_for(&query, &state.repository_classes)
+                    };
+                    let (message, description) = match suggestion {
+                        Some(class) => (
+                            tr!(
+                                "No packages matched \"{}\". It may be in the {} repository.",
+                                query,
+                                class.title().to_lowercase()
+                            ),
+                            tr!(
+                                "\"{}\" is usually provided by the {} repository, which is not enabled. Enable it in the repository settings and search again.",
+                                query,
+                                class.title().to_lowercase()
+                            ),
+                        ),
+                        None => (
+                            tr!("No packages matched \"{}\".", query),
+                            tr!(
+                                "No packages matched \"{}\". Try a different search term.",
+                                query
+                            ),
+                        ),
+                    };
                     self.set_discover_status(Some(&message));
+                    repo_button.set_visible(suggestion.is_some());
 
                     // Update the no-results page description
-                    let description = format!("No packages matched \"{}\". Try a different search term.", query);
                     self.widgets.discover.no_results_page.set_description(Some(&description));
                 } else {
-                    let message = format!(
-                        "Found {} package{} for \"{}\".",
+                    repo_button.set_visible(false);
+                    let message = trn!(
+                        "Found {} package for \"{}\".",
+                        "Found {} packages for \"{}\".",
+                        results_len,
                         results_len,
-                        if results_len == 1 { "" } else { "s" },
                         query
                     );
                     self.set_discover_status(Some(&message));
@@ -270,8 +434,141 @@ impl AppController {
                 self.clear_search_results();
                 let message = format!("Could not search: {}", err);
                 self.set_discover_status(Some(&message));
-                self.show_toast("Search failed.");
+                self.state.borrow_mut().failed_search_query = Some(query);
+                self.show_search_retry_toast();
+            }
+        }
+    }
+
+    fn show_search_retry_toast(self: &Rc<Self>) {
+        let toast = adw::Toast::builder()
+            .title(tr!("Search failed."))
+            .button_label(tr!("Retry"))
+            .timeout(8)
+            .build();
+        toast.connect_button_clicked(glib::clone!(
+            #[weak(rename_to = controller)]
+            self,
+            move |_| controller.retry_failed_search()
+        ));
+        self.widgets.toast_overlay.add_toast(toast);
+    }
+
+    /// Runs the last failed search again, restoring its text in the search
+    /// entry if it has been edited since.
+    fn retry_failed_search(self: &Rc<Self>) {
+        let Some(query) = self.state.borrow_mut().failed_search_query.take() else {
+            return;
+        };
+        let entry = &self.widgets.discover.search_entry;
+        if entry.text() != query.as_str() {
+            entry.set_text(&query);
+        }
+        self.on_search_requested();
+    }
+
+    fn update_discover_appstream(self: &Rc<Self>, detail: Option<&DiscoverDetail>) {
+        let summary_label = &self.widgets.discover.detail_summary;
+        let scroller = &self.widgets.discover.detail_screenshots_scroller;
+        let screenshots_box = &self.widgets.discover.detail_screenshots_box;
+
+        match detail.and_then(|detail| detail.summary.as_deref()) {
+            Some(summary) => {
+                summary_label.set_text(summary);
+                summary_label.set_visible(true);
+            }
+            None => {
+                summary_label.set_text("");
+                summary_label.set_visible(false);
+            }
+        }
+
+        while let Some(child) = screenshots_box.first_child() {
+            screenshots_box.remove(&child);
+        }
+
+        let urls = detail
+            .map(|detail| detail.screenshots.clone())
+            .unwrap_or_default();
+        let mut to_fetch = Vec::new();
+        {
+            let mut state = self.state.borrow_mut();
+            for url in &urls {
+                if state.screenshot_failed.contains(url) {
+                    continue;
+                }
+                if let Some(path) = state.screenshot_cache.get(url) {
+                    let picture = gtk::Picture::for_filename(path);
+                    picture.set_can_shrink(true);
+                    picture.set_size_request(240, 150);
+                    picture.add_css_class("card");
+                    screenshots_box.append(&picture);
+                } else {
+                    let spinner = gtk::Spinner::new();
+                    spinner.set_size_request(240, 150);
+                    spinner.start();
+                    screenshots_box.append(&spinner);
+                    if state.screenshot_loading.insert(url.clone()) {
+                        to_fetch.push(url.clone());
+                    }
+                }
+            }
+        }
+        scroller.set_visible(screenshots_box.first_child().is_some());
+
+        for url in to_fetch {
+            let sender = self.sender.clone();
+            thread::spawn(move || {
+                let result = appstream::fetch_screenshot(&url);
+                let _ = sender.send(AppMessage::ScreenshotLoaded { url, result });
+            });
+        }
+    }
+
+    /// Loads the AppStream catalog on a worker. List rows show the bundled
+    /// category icons until it is ready.
+    pub(crate) fn load_appstream_catalog(self: &Rc<Self>) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            appstream::load_catalog_now();
+            let _ = sender.send(AppMessage::AppStreamCatalogLoaded);
+        });
+    }
+
+    /// Rebuilds the package lists so their rows pick up AppStream icons.
+    pub(crate) fn finish_appstream_catalog(self: &Rc<Self>) {
+        self.rebuild_search_list();
+        self.update_spotlight_views();
+        self.rebuild_installed_list();
+        self.rebuild_updates_list();
+    }
+
+    pub(crate) fn finish_screenshot_loaded(
+        self: &Rc<Self>,
+        url: String,
+        result: Result<PathBuf, String>,
+    ) {
+        let visible = {
+            let mut state = self.state.borrow_mut();
+            state.screenshot_loading.remove(&url);
+            match result {
+                Ok(path) => {
+                    state.screenshot_cache.insert(url.clone(), path);
+                }
+                Err(err) => {
+                    eprintln!("Failed to load screenshot {}: {}", url, err);
+                    state.screenshot_failed.insert(url.clone());
+                }
             }
+            state
+                .discover_detail_focus
+                .as_ref()
+                .and_then(|pkg| state.discover_detail_cache.get(&pkg.name))
+                .is_some_and(|detail| detail.screenshots.contains(&url))
+        };
+
+        if visible {
+            self.update_discover_details();
         }
     }
 
@@ -280,6 +577,31 @@ impl AppController {
         package: String,
         result: Result<DiscoverDetail, String>,
     ) {
+        let requested = {
+            let mut state = self.state.borrow_mut();
+            if state.requested_package.as_deref() == Some(package.as_str()) {
+                state.requested_package.take()
+            } else {
+                None
+            }
+        };
+        if let (Some(_), Err(error)) = (&requested, &result) {
+            {
+                let mut state = self.state.borrow_mut();
+                state.discover_detail_loading.remove(&package);
+            }
+            self.on_discover_detail_close();
+            let message = if error == PACKAGE_NOT_FOUND_ERROR {
+                tr!("No package named \"{}\" was found.", package)
+            } else if error == DETAIL_TIMEOUT_ERROR {
+                tr!("Details unavailable (timed out).")
+            } else {
+                tr!("Couldn't load details for \"{}\": {}", package, error)
+            };
+            self.show_toast(&message);
+            return;
+        }
+
         {
             let mut state = self.state.borrow_mut();
             state.discover_detail_loading.remove(&package);
@@ -341,6 +663,7 @@ impl AppController {
 
         self.update_discover_details();
         self.update_spotlight_recent_detail();
+        self.refresh_compare_view();
     }
 
     pub(crate) fn set_discover_status(&self, _message: Option<&str>) {
@@ -389,6 +712,65 @@ impl AppController {
         true
     }
 
+    /// Opens the Discover detail for a package named on the command line.
+    pub(crate) fn show_requested_package(self: &Rc<Self>, package: String) {
+        self.set_active_page("discover");
+        self.window.present();
+
+        let package = package.trim().to_string();
+        let valid = !package.is_empty()
+            && package
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '+'));
+        if !valid {
+            self.show_toast(&format!("\"{}\" is not a valid package name.", package));
+            return;
+        }
+
+        self.state.borrow_mut().requested_package = Some(package.clone());
+        if self.focus_discover_package(&package, false) {
+            return;
+        }
+        self.open_discover_dependency_detail(package);
+    }
+
+    /// Handles `appstream://<component-id>` and `xbps://<package>` links.
+    pub(crate) fn open_package_uri(self: &Rc<Self>, uri: String) {
+        let Some((scheme, rest)) = uri.split_once(':') else {
+            self.finish_package_uri(uri, None);
+            return;
+        };
+        let target = rest
+            .trim_start_matches('/')
+            .trim_end_matches('/')
+            .to_string();
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "xbps" if !target.is_empty() => self.show_requested_package(target),
+            "appstream" if !target.is_empty() => {
+                self.set_active_page("discover");
+                self.window.present();
+                let sender = self.sender.clone();
+                thread::spawn(move || {
+                    let package = appstream::resolve_component_id(&target);
+                    let _ = sender.send(AppMessage::PackageUriResolved { uri, package });
+                });
+            }
+            _ => self.finish_package_uri(uri, None),
+        }
+    }
+
+    pub(crate) fn finish_package_uri(self: &Rc<Self>, uri: String, package: Option<String>) {
+        match package {
+            Some(package) => self.show_requested_package(package),
+            None => {
+                self.set_active_page("discover");
+                self.window.present();
+                self.show_toast(&format!("Couldn't find a package for \"{}\".", uri));
+            }
+        }
+    }
+
     pub(crate) fn on_discover_dependency_clicked(self: &Rc<Self>, package: String) {
         let package = package.trim();
         if package.is_empty() {
@@ -475,13 +857,9 @@ impl AppController {
     }
 
     pub(crate) fn refresh_discover_install_widgets(&self) {
-        let busy = {
-            let state = self.state.borrow();
-            state.install_in_progress || state.remove_in_progress
-        };
-        for button in self.discover_buttons.borrow().values() {
-            button.set_sensitive(!busy);
-        }
+        // Row buttons stay clickable while something runs; requests made in
+        // the meantime are queued by `execute_install`/`execute_remove`.
+        self.update_discover_selection_controls();
         self.refresh_discover_row_progress();
     }
 
@@ -498,21 +876,29 @@ impl AppController {
     }
 
     fn update_discover_row_progress_for_package(&self, package: &str) {
-        let (installing, removing, installing_package, removing_packages, detail_focus, buttons_visible) =
-            {
-                let state = self.state.borrow();
-                (
-                    state.install_in_progress,
-                    state.remove_in_progress,
-                    state.installing_package.clone(),
-                    state.removing_packages.clone(),
-                    state
-                        .discover_detail_focus
-                        .as_ref()
-                        .map(|pkg| pkg.name.clone()),
-                    state.discover_row_buttons_visible,
-                )
-            };
+        let (
+            installing,
+            removing,
+            installing_package,
+            removing_packages,
+            queued,
+            detail_focus,
+            buttons_visible,
+        ) = {
+            let state = self.state.borrow();
+            (
+                state.install_in_progress,
+                state.remove_in_progress,
+                state.installing_package.clone(),
+                state.removing_packages.clone(),
+                state.is_queued(package),
+                state
+                    .discover_detail_focus
+                    .as_ref()
+                    .map(|pkg| pkg.name.clone()),
+                state.discover_row_buttons_visible,
+            )
+        };
 
         let stack = self.discover_row_stacks.borrow().get(package).cloned();
         let progress = self
@@ -534,6 +920,9 @@ impl AppController {
         } else if removing && removing_packages.contains(package) {
             show_progress = true;
             label = "Removing…";
+        } else if queued {
+            show_progress = true;
+            label = "Queued";
         }
 
         if let Some(progress) = progress {
@@ -597,6 +986,9 @@ impl AppController {
     }
 
     pub(crate) fn rebuild_search_list(self: &Rc<Self>) {
+        if self.defer_to_batch() {
+            return;
+        }
         let list = &self.widgets.discover.list;
         while let Some(child) = list.first_child() {
             list.remove(&child);
@@ -614,10 +1006,12 @@ impl AppController {
         self.discover_buttons.borrow_mut().clear();
         self.discover_row_stacks.borrow_mut().clear();
         self.discover_progress_bars.borrow_mut().clear();
+        self.discover_select_checks.borrow_mut().clear();
         for pkg in &results {
             let row = self.build_discover_row(pkg);
             list.append(&row);
         }
+        self.update_discover_selection_controls();
 
         if let Some(idx) = selected_idx {
             if let Some(row) = list.row_at_index(idx as i32) {
@@ -644,7 +1038,9 @@ impl AppController {
             glib::markup_escape_text(&pkg.description).to_string()
         };
 
-        let subtitle = format!("{}\n{}", version_line, description);
+        let compact = self.compact_lists();
+        let separator = if compact { " • " } else { "\n" };
+        let subtitle = format!("{}{}{}", version_line, separator, description);
 
         let row = adw::ActionRow::builder()
             .title(title.as_str())
@@ -654,11 +1050,44 @@ impl AppController {
         row.set_focusable(false);
         row.set_title_lines(1);
         row.set_subtitle_lines(2);
+        row.set_tooltip_text(Some(&package_row_tooltip(pkg)));
+
+        let has_update = self
+            .state
+            .borrow()
+            .available_update_names
+            .contains(&pkg.name);
+        if pkg.installed && has_update && self.marks_available_updates() {
+            row.add_suffix(&update_badge());
+        }
 
-        let icon = gtk::Image::from_resource(icon_resource_for_package(&pkg.name));
+        let select_check = gtk::CheckButton::builder()
+            .tooltip_text("Select to compare or install together")
+            .valign(gtk::Align::Center)
+            .build();
+        select_check.set_active(
+            self.state
+                .borrow()
+                .discover_selection
+                .iter()
+                .any(|name| *name == pkg.name),
+        );
+        let weak_self = Rc::downgrade(self);
+        let package = pkg.name.clone();
+        select_check.connect_toggled(move |check| {
+            if let Some(controller) = weak_self.upgrade() {
+                controller.on_discover_selection_toggled(&package, check.is_active());
+            }
+        });
+        row.add_prefix(&select_check);
+
+        let icon = package_icon(&pkg.name);
         icon.set_pixel_size(32);
         icon.set_margin_end(12);
         icon.set_valign(gtk::Align::Center);
+        if compact {
+            compact_package_row(&row, &icon);
+        }
         row.add_prefix(&icon);
 
         let button = gtk::Button::builder().width_request(140).build();
@@ -673,7 +1102,7 @@ impl AppController {
             } else {
                 button.set_label("Install");
                 button.add_css_class("suggested-action");
-                button.set_sensitive(!state.install_in_progress);
+                button.set_sensitive(!state.is_queued(&pkg.name));
                 button.set_tooltip_text(Some("Install this package."));
             }
         }
@@ -718,25 +1147,531 @@ impl AppController {
         self.discover_progress_bars
             .borrow_mut()
             .insert(pkg.name.clone(), progress);
+        self.discover_select_checks
+            .borrow_mut()
+            .insert(pkg.name.clone(), select_check);
 
         row
     }
 
+    fn on_discover_selection_toggled(&self, package: &str, active: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            let selected = state.discover_selection.iter().any(|name| name == package);
+            if active && !selected {
+                state.discover_selection.push(package.to_string());
+            } else if !active && selected {
+                state.discover_selection.retain(|name| name != package);
+            }
+        }
+        self.update_discover_selection_controls();
+    }
+
+    /// Shows the Compare and Install Selected buttons once packages are
+    /// picked in the search results.
+    pub(crate) fn update_discover_selection_controls(&self) {
+        let (selection, not_installed, busy) = {
+            let state = self.state.borrow();
+            let not_installed = state
+                .discover_selection
+                .iter()
+                .filter(|name| !state.installed_set.contains(*name))
+                .count();
+            (
+                state.discover_selection.clone(),
+                not_installed,
+                state.install_in_progress || state.remove_in_progress,
+            )
+        };
+
+        let compare_button = &self.widgets.discover.compare_button;
+        let can_compare = (2..=MAX_COMPARE_PACKAGES).contains(&selection.len());
+        compare_button.set_visible(!selection.is_empty());
+        compare_button.set_sensitive(can_compare);
+        compare_button.set_label(&format!("Compare ({})", selection.len()));
+        compare_button.set_tooltip_text(Some(if can_compare {
+            "Compare the selected packages side by side"
+        } else {
+            "Select two or three packages to compare"
+        }));
+
+        let install_button = &self.widgets.discover.install_selected_button;
+        install_button.set_visible(not_installed > 0);
+        install_button.set_sensitive(!busy);
+        install_button.set_label(&format!("Install Selected ({})", not_installed));
+        self.widgets
+            .discover
+            .queue_selected_button
+            .set_visible(not_installed > 0);
+    }
+
+    pub(crate) fn on_install_selected_requested(self: &Rc<Self>) {
+        let (packages, total_bytes, unknown) = {
+            let state = self.state.borrow();
+            let packages: Vec<String> = state
+                .discover_selection
+                .iter()
+                .filter(|name| !state.installed_set.contains(*name))
+                .cloned()
+                .collect();
+            let mut total_bytes = 0;
+            let mut unknown = 0;
+            for name in &packages {
+                match install_space_required(&state, name, None) {
+                    Some(bytes) => total_bytes += bytes,
+                    None => unknown += 1,
+                }
+            }
+            (packages, total_bytes, unknown)
+        };
+        if packages.is_empty() {
+            return;
+        }
+
+        let space_warning = self.disk_space_warning(total_bytes);
+        if space_warning.is_none() && !self.state.borrow().should_confirm_install() {
+            self.execute_install_batch(packages);
+            return;
+        }
+
+        let mut size_line = format!("Disk space needed: {}", format_size(total_bytes));
+        if unknown > 0 {
+            size_line.push_str(&format!(
+                ", plus {} package{} of unknown size",
+                unknown,
+                if unknown == 1 { "" } else { "s" }
+            ));
+        }
+        let heading = format!(
+            "Install {} package{}?",
+            packages.len(),
+            if packages.len() == 1 { "" } else { "s" }
+        );
+        let mut body = format!(
+            "Nebula will install {} and any required dependencies in one transaction.\n\n{}.",
+            summarize_package_list(&packages),
+            size_line
+        );
+        if let Some(warning) = space_warning {
+            body.push_str("\n\n");
+            body.push_str(&warning);
+        }
+        let command = install_command_line(&packages);
+        self.confirm_command(
+            &heading,
+            &body,
+            "Install",
+            Some(command),
+            move |controller| {
+                controller.execute_install_batch(packages);
+            },
+        );
+    }
+
+    /// Opens the full package list, loading it from the repositories the
+    /// first time.
+    pub(crate) fn on_browse_all_requested(self: &Rc<Self>) {
+        let load = {
+            let mut state = self.state.borrow_mut();
+            if state.repository_index_loading {
+                return;
+            }
+            state.repository_index_loading = state.repository_index.is_empty();
+            state.repository_index_loading
+        };
+        if !load {
+            self.show_browse_all_dialog();
+            return;
+        }
+
+        self.widgets.discover.browse_button.set_sensitive(false);
+        self.refresh_busy_indicator();
+        self.set_footer_message(Some(&tr!("Loading the full package list…")));
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_query_all_packages();
+            let _ = sender.send(AppMessage::RepositoryIndexLoaded { result });
+        });
+    }
+
+    pub(crate) fn finish_repository_index(
+        self: &Rc<Self>,
+        result: Result<Vec<PackageInfo>, String>,
+    ) {
+        self.state.borrow_mut().repository_index_loading = false;
+        self.widgets.discover.browse_button.set_sensitive(true);
+        self.set_footer_message(None);
+        match result {
+            Ok(packages) if packages.is_empty() => {
+                self.show_error_dialog(
+                    &tr!("Browse Failed"),
+                    &tr!(
+                        "The repositories returned no packages. Sync them from the Updates page and try again."
+                    ),
+                );
+            }
+            Ok(packages) => {
+                self.state.borrow_mut().repository_index = packages;
+                self.show_browse_all_dialog();
+            }
+            Err(err) => {
+                self.show_error_dialog(
+                    &tr!("Browse Failed"),
+                    &tr!("Failed to list the repository packages: {}", err),
+                );
+            }
+        }
+    }
+
+    /// Lists the whole repository in a virtualized view. Rows only hold an
+    /// index into `repository_index`, so filtering thousands of packages never
+    /// copies them, and details load once a package is opened in Discover.
+    fn show_browse_all_dialog(self: &Rc<Self>) {
+        let count = self.state.borrow().repository_index.len();
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title(tr!("All Packages"))
+            .default_width(720)
+            .default_height(560)
+            .build();
+        dialog.add_button(&tr!("Close"), gtk::ResponseType::Close);
+        dialog.connect_response(|dialog, _| dialog.close());
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let filter_entry = gtk::SearchEntry::builder()
+            .placeholder_text(tr!("Filter by name or description"))
+            .hexpand(true)
+            .build();
+        content.append(&filter_entry);
+
+        let summary = gtk::Label::builder().xalign(0.0).build();
+        summary.add_css_class("dim-label");
+        content.append(&summary);
+
+        let store = gio::ListStore::new::<glib::BoxedAnyObject>();
+        let items: Vec<glib::BoxedAnyObject> = (0..count).map(glib::BoxedAnyObject::new).collect();
+        store.extend_from_slice(&items);
+
+        let terms: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+        let controller_weak = Rc::downgrade(self);
+        let filter_terms = Rc::clone(&terms);
+        let filter = gtk::CustomFilter::new(move |item| {
+            let terms = filter_terms.borrow();
+            if terms.is_empty() {
+                return true;
+            }
+            let Some(controller) = controller_weak.upgrade() else {
+                return false;
+            };
+            let Some(boxed) = item.downcast_ref::<glib::BoxedAnyObject>() else {
+                return false;
+            };
+            let index = *boxed.borrow::<usize>();
+            let state = controller.state.borrow();
+            state.repository_index.get(index).is_some_and(|pkg| {
+                terms.iter().all(|term| {
+                    pkg.name_lower.contains(term.as_str())
+                        || pkg.description_lower.contains(term.as_str())
+                })
+            })
+        });
+        let filter_model = gtk::FilterListModel::new(Some(store), Some(filter.clone()));
+        // Filters in chunks so typing stays responsive on the full index.
+        filter_model.set_incremental(true);
+
+        let update_summary = glib::clone!(
+            #[weak]
+            summary,
+            move |model: &gtk::FilterListModel| {
+                let shown = model.n_items() as usize;
+                summary.set_text(&if shown == count {
+                    trn!("{} package", "{} packages", count, count)
+                } else {
+                    trn!(
+                        "Showing {} of {} package",
+                        "Showing {} of {} packages",
+                        count,
+                        shown,
+                        count
+                    )
+                });
+            }
+        );
+        update_summary(&filter_model);
+        filter_model.connect_items_changed(move |model, _, _, _| update_summary(model));
+
+        filter_entry.connect_search_changed(glib::clone!(
+            #[weak]
+            filter,
+            #[strong]
+            terms,
+            move |entry| {
+                *terms.borrow_mut() = entry
+                    .text()
+                    .to_lowercase()
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect();
+                filter.changed(gtk::FilterChange::Different);
+            }
+        ));
+
+        let factory = gtk::SignalListItemFactory::new();
+        factory.connect_setup(|_, list_item| {
+            let row = adw::ActionRow::builder().build();
+            row.set_title_lines(1);
+            row.set_subtitle_lines(1);
+            list_item.set_child(Some(&row));
+        });
+        factory.connect_bind(glib::clone!(
+            #[weak(rename_to = controller)]
+            self,
+            move |_, list_item| {
+                let Some(row) = list_item.child().and_downcast::<adw::ActionRow>() else {
+                    return;
+                };
+                let Some(boxed) = list_item.item().and_downcast::<glib::BoxedAnyObject>() else {
+                    return;
+                };
+                let index = *boxed.borrow::<usize>();
+                let state = controller.state.borrow();
+                let Some(pkg) = state.repository_index.get(index) else {
+                    return;
+                };
+                let mut subtitle = if state.installed_set.contains(&pkg.name) {
+                    tr!("Installed • {}", pkg.version)
+                } else {
+                    pkg.version.clone()
+                };
+                if !pkg.description.is_empty() {
+                    subtitle.push_str(" • ");
+                    subtitle.push_str(&pkg.description);
+                }
+                row.set_title(&glib::markup_escape_text(&pkg.name));
+                row.set_subtitle(&glib::markup_escape_text(&subtitle));
+                row.set_tooltip_text(Some(&package_row_tooltip(pkg)));
+            }
+        ));
+
+        let selection = gtk::SingleSelection::new(Some(filter_model.clone()));
+        selection.set_autoselect(false);
+        let list_view = gtk::ListView::new(Some(selection), Some(factory));
+        list_view.add_css_class("boxed-list");
+        list_view.connect_activate(glib::clone!(
+            #[weak(rename_to = controller)]
+            self,
+            #[weak]
+            dialog,
+            #[weak]
+            filter_model,
+            move |_, position| {
+                let Some(boxed) = filter_model
+                    .item(position)
+                    .and_downcast::<glib::BoxedAnyObject>()
+                else {
+                    return;
+                };
+                let index = *boxed.borrow::<usize>();
+                let name = controller
+                    .state
+                    .borrow()
+                    .repository_index
+                    .get(index)
+                    .map(|pkg| pkg.name.clone());
+                if let Some(name) = name {
+                    dialog.close();
+                    controller.show_requested_package(name);
+                }
+            }
+        ));
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .child(&list_view)
+            .build();
+        content.append(&scroller);
+
+        dialog.present();
+        filter_entry.grab_focus();
+    }
+
+    pub(crate) fn show_compare_dialog(self: &Rc<Self>) {
+        let selection = self.state.borrow().discover_selection.clone();
+        if selection.len() < 2 {
+            return;
+        }
+        for package in &selection {
+            self.request_discover_detail(package);
+        }
+
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title("Compare packages")
+            .default_width(760)
+            .default_height(480)
+            .build();
+        dialog.add_button("Clear Selection", gtk::ResponseType::Reject);
+        dialog.add_button("Close", gtk::ResponseType::Close);
+
+        let content = dialog.content_area();
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let grid = gtk::Grid::builder()
+            .column_spacing(24)
+            .row_spacing(12)
+            .column_homogeneous(true)
+            .build();
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(320)
+            .build();
+        scroller.set_child(Some(&grid));
+        content.append(&scroller);
+
+        self.compare_grid.replace(Some(grid));
+        self.refresh_compare_view();
+
+        dialog.connect_response(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |dialog, response| {
+                if response == gtk::ResponseType::Reject {
+                    controller.clear_discover_selection();
+                }
+                dialog.close();
+            }
+        ));
+        dialog.connect_close_request(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |_| {
+                controller.compare_grid.replace(None);
+                glib::Propagation::Proceed
+            }
+        ));
+        dialog.present();
+    }
+
+    fn clear_discover_selection(&self) {
+        self.state.borrow_mut().discover_selection.clear();
+        for check in self.discover_select_checks.borrow().values() {
+            check.set_active(false);
+        }
+        self.update_discover_selection_controls();
+    }
+
+    /// Rebuilds the comparison columns from cached details; called again as
+    /// background detail queries complete.
+    pub(crate) fn refresh_compare_view(&self) {
+        let Some(grid) = self.compare_grid.borrow().clone() else {
+            return;
+        };
+        while let Some(child) = grid.first_child() {
+            grid.remove(&child);
+        }
+
+        let state = self.state.borrow();
+        let field_labels = ["Version", "Download size", "Dependencies", "Description"];
+        for (index, label) in field_labels.iter().enumerate() {
+            let heading = gtk::Label::builder()
+                .label(*label)
+                .halign(gtk::Align::Start)
+                .valign(gtk::Align::Start)
+                .build();
+            heading.add_css_class("dim-label");
+            grid.attach(&heading, 0, index as i32 + 1, 1, 1);
+        }
+
+        for (column, package) in state.discover_selection.iter().enumerate() {
+            let column = column as i32 + 1;
+            let known = state.search_results.iter().find(|pkg| pkg.name == *package);
+            let detail = state.discover_detail_cache.get(package);
+            let loading = state.discover_detail_loading.contains(package);
+            let fallback = if loading { "Loading…" } else { "—" };
+
+            let version = detail
+                .and_then(|detail| detail.version.clone())
+                .or_else(|| known.map(|pkg| pkg.version.clone()))
+                .filter(|version| !version.is_empty())
+                .unwrap_or_else(|| fallback.to_string());
+            let download = detail
+                .and_then(|detail| {
+                    detail
+                        .download
+                        .clone()
+                        .or_else(|| detail.download_bytes.map(format_size))
+                })
+                .unwrap_or_else(|| fallback.to_string());
+            let dependencies = detail
+                .map(|detail| detail.dependencies.len().to_string())
+                .unwrap_or_else(|| fallback.to_string());
+            let description = detail
+                .and_then(|detail| detail.description.clone())
+                .or_else(|| known.map(|pkg| pkg.description.clone()))
+                .filter(|description| !description.is_empty())
+                .unwrap_or_else(|| fallback.to_string());
+
+            let name_label = gtk::Label::builder()
+                .label(package.as_str())
+                .halign(gtk::Align::Start)
+                .wrap(true)
+                .wrap_mode(pango::WrapMode::WordChar)
+                .build();
+            name_label.add_css_class("title-4");
+            grid.attach(&name_label, column, 0, 1, 1);
+
+            for (row, value) in [version, download, dependencies, description]
+                .iter()
+                .enumerate()
+            {
+                let value_label = gtk::Label::builder()
+                    .label(value.as_str())
+                    .halign(gtk::Align::Start)
+                    .valign(gtk::Align::Start)
+                    .xalign(0.0)
+                    .wrap(true)
+                    .wrap_mode(pango::WrapMode::WordChar)
+                    .selectable(true)
+                    .build();
+                grid.attach(&value_label, column, row as i32 + 1, 1, 1);
+            }
+        }
+    }
+
     pub(crate) fn update_discover_layout(&self) {
-        let (mode, has_results, is_searching) = {
+        let (mode, has_results, is_searching, show_spotlight) = {
             let state = self.state.borrow();
             (
                 state.discover_mode,
                 !state.search_results.is_empty(),
                 state.discover_mode == DiscoverMode::Search,
+                state.show_spotlight,
             )
         };
 
-        let spotlight_visible = mode == DiscoverMode::Spotlight;
+        let spotlight_visible = mode == DiscoverMode::Spotlight && show_spotlight;
         self.widgets
             .discover
             .spotlight_section_box
             .set_visible(spotlight_visible);
+        self.sync_favorites_section();
+        self.widgets
+            .discover
+            .spotlight_status_row
+            .set_visible(show_spotlight);
 
         // Show search results stack if we're in search mode
         let show_search_area = is_searching;
@@ -747,6 +1682,8 @@ impl AppController {
         self.widgets.discover.content_row.set_visible(show_search_area);
         self.widgets.discover.content_row.set_vexpand(show_search_area);
 
+        self.sync_discover_detail_pane();
+
         // Switch between list and no-results within the stack
         if has_results {
             self.widgets
@@ -761,6 +1698,29 @@ impl AppController {
         }
     }
 
+    /// Keeps the detail pane beside the search results. On wide windows it
+    /// stays open with a placeholder; narrow windows only show it while a
+    /// package is selected.
+    fn sync_discover_detail_pane(&self) {
+        let visible = {
+            let state = self.state.borrow();
+            state.discover_mode == DiscoverMode::Search
+                && (!state.discover_narrow_layout || state.discover_detail_focus.is_some())
+        };
+        self.widgets.discover.detail_frame.set_visible(visible);
+    }
+
+    pub(crate) fn set_discover_narrow_layout(&self, narrow: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.discover_narrow_layout == narrow {
+                return;
+            }
+            state.discover_narrow_layout = narrow;
+        }
+        self.sync_discover_detail_pane();
+    }
+
     pub(crate) fn clear_search_results(self: &Rc<Self>) {
         let mut state = self.state.borrow_mut();
         state.search_results.clear();
@@ -794,7 +1754,160 @@ impl AppController {
         self.rebuild_search_list();
     }
 
+    /// Homepage of the package shown in the Discover or spotlight detail pane.
+    pub(crate) fn discover_detail_homepage(&self) -> Option<String> {
+        let state = self.state.borrow();
+        let focus = state.discover_detail_focus.as_ref()?;
+        state
+            .discover_detail_cache
+            .get(&focus.name)
+            .and_then(|detail| detail.homepage.clone())
+    }
+
+    pub(crate) fn discover_detail_package_name(&self) -> Option<String> {
+        let state = self.state.borrow();
+        state
+            .discover_detail_focus
+            .as_ref()
+            .map(|pkg| pkg.name.clone())
+    }
+
+    /// Installs the focused package, or removes it when it is already
+    /// installed, matching what the detail pane's action button would do.
+    pub(crate) fn discover_detail_command(&self) -> Option<String> {
+        let state = self.state.borrow();
+        let package = state.discover_detail_focus.as_ref()?;
+        let packages = [package.name.clone()];
+        Some(if package.installed {
+            remove_command_line(&packages, self.remove_options())
+        } else {
+            let source = state.install_sources.get(&package.name);
+            pinned_install_command_line(&packages, source.map(String::as_str))
+        })
+    }
+
+    /// Shows the repository picker when the focused package is offered by
+    /// more than one repository. The first entry is the one xbps would pick.
+    fn refresh_install_source_row(&self) {
+        let (sources, pinned) = {
+            let state = self.state.borrow();
+            let focus = state
+                .discover_detail_focus
+                .as_ref()
+                .filter(|pkg| !pkg.installed);
+            let sources = focus
+                .and_then(|pkg| state.discover_detail_cache.get(&pkg.name))
+                .map(|detail| detail.sources.clone())
+                .unwrap_or_default();
+            let pinned = focus
+                .and_then(|pkg| state.install_sources.get(&pkg.name))
+                .and_then(|repo| sources.iter().position(|source| &source.repository == repo));
+            (sources, pinned)
+        };
+        let row = &self.widgets.discover.detail_source_row;
+        let dropdown = &self.widgets.discover.detail_source_dropdown;
+        if sources.len() < 2 {
+            row.set_visible(false);
+            return;
+        }
+
+        let labels: Vec<String> = sources
+            .iter()
+            .map(|source| format!("{} ({})", source.repository, source.version))
+            .collect();
+        let unchanged = dropdown
+            .model()
+            .and_downcast::<gtk::StringList>()
+            .is_some_and(|model| {
+                model.n_items() as usize == labels.len()
+                    && labels.iter().enumerate().all(|(idx, label)| {
+                        model
+                            .string(idx as u32)
+                            .is_some_and(|text| text.as_str() == label)
+                    })
+            });
+        if !unchanged {
+            // Swapping the model selects the first entry, which drops the
+            // pin until the selection is restored below.
+            let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+            dropdown.set_model(Some(&gtk::StringList::new(&label_refs)));
+        }
+        let selected = pinned.unwrap_or(0) as u32;
+        if dropdown.selected() != selected {
+            dropdown.set_selected(selected);
+        }
+        row.set_visible(true);
+    }
+
+    pub(crate) fn on_install_source_selected(&self, position: u32) {
+        let mut state = self.state.borrow_mut();
+        let Some(package) = state
+            .discover_detail_focus
+            .as_ref()
+            .map(|pkg| pkg.name.clone())
+        else {
+            return;
+        };
+        let Some(repository) = state
+            .discover_detail_cache
+            .get(&package)
+            .and_then(|detail| detail.sources.get(position as usize))
+            .map(|source| source.repository.clone())
+        else {
+            return;
+        };
+        if position == 0 {
+            state.install_sources.remove(&package);
+        } else {
+            state.install_sources.insert(package, repository);
+        }
+        drop(state);
+        self.refresh_signature_row();
+    }
+
+    /// Shows whether the repository the package comes from is signed. xbps
+    /// checks that signature before it installs anything from it.
+    fn refresh_signature_row(&self) {
+        let signed = {
+            let state = self.state.borrow();
+            state.discover_detail_focus.as_ref().and_then(|pkg| {
+                let sources = &state.discover_detail_cache.get(&pkg.name)?.sources;
+                let pinned = state.install_sources.get(&pkg.name);
+                sources
+                    .iter()
+                    .find(|source| Some(&source.repository) == pinned)
+                    .or_else(|| sources.first())
+                    .map(|source| source.signed)
+            })
+        };
+        let row = &self.widgets.discover.detail_signature_row;
+        let value = &self.widgets.discover.detail_signature_value;
+        let Some(signed) = signed else {
+            row.set_visible(false);
+            return;
+        };
+        if signed {
+            value.set_text(&tr!("Verified"));
+            value.remove_css_class("warning");
+            value.add_css_class("success");
+            value.set_tooltip_text(Some(&tr!(
+                "The repository is signed; xbps checks its RSA signature before installing."
+            )));
+        } else {
+            value.set_text(&tr!("Unsigned repository"));
+            value.remove_css_class("success");
+            value.add_css_class("warning");
+            value.set_tooltip_text(Some(&tr!(
+                "xbps cannot verify packages from this repository."
+            )));
+        }
+        row.set_visible(true);
+    }
+
     pub(crate) fn update_discover_details(self: &Rc<Self>) {
+        self.update_favorite_buttons();
+        self.refresh_install_source_row();
+        self.refresh_signature_row();
         let stack = &self.widgets.discover.detail_stack;
         let button = &self.widgets.discover.detail_action_button;
         let version_value = &self.widgets.discover.detail_version_value;
@@ -847,8 +1960,9 @@ impl AppController {
         };
 
         if let Some(pkg) = pkg {
+            self.note_recently_viewed(&pkg.name);
             stack.set_visible_child_name("detail");
-            self.widgets.discover.detail_frame.set_visible(true);
+            self.sync_discover_detail_pane();
             self.widgets.discover.detail_close_button.set_visible(true);
             self.widgets
                 .discover
@@ -934,7 +2048,11 @@ impl AppController {
                 dependencies_placeholder.set_text("Dependency information unavailable.");
                 dependencies_list.set_visible(false);
                 dependencies_stack.set_visible_child_name("placeholder");
-                description.set_text(&format!("Could not load package details: {}", error));
+                if error == DETAIL_TIMEOUT_ERROR {
+                    description.set_text("Details unavailable (timed out).");
+                } else {
+                    description.set_text(&format!("Could not load package details: {}", error));
+                }
             } else {
                 let version_text = detail
                     .as_ref()
@@ -1078,11 +2196,18 @@ impl AppController {
                 }
             }
 
+            let appstream_detail = if loading || error.is_some() {
+                None
+            } else {
+                detail.as_ref()
+            };
+            self.update_discover_appstream(appstream_detail);
+
             return;
         }
 
         stack.set_visible_child_name("placeholder");
-        self.widgets.discover.detail_frame.set_visible(false);
+        self.sync_discover_detail_pane();
         self.widgets.discover.detail_close_button.set_visible(false);
         self.widgets
             .discover
@@ -1138,10 +2263,14 @@ impl AppController {
             state.discover_detail_errors.remove(&package_name);
             state.discover_detail_loading.insert(package_name.clone());
         }
+        self.refresh_busy_indicator();
 
         let sender = self.sender.clone();
         thread::spawn(move || {
-            let result = crate::helpers::query_discover_detail(&package_name);
+            let query_name = package_name.clone();
+            let result = crate::helpers::fetch_detail_with_timeout(move || {
+                crate::helpers::query_discover_detail(&query_name)
+            });
             let _ = sender.send(AppMessage::DiscoverDetailLoaded {
                 package: package_name,
                 result,
@@ -1169,7 +2298,7 @@ impl AppController {
             .discover
             .detail_stack
             .set_visible_child_name("placeholder");
-        self.widgets.discover.detail_frame.set_visible(false);
+        self.sync_discover_detail_pane();
         self.widgets.discover.detail_close_button.set_visible(false);
         self.widgets
             .discover
@@ -1242,13 +2371,18 @@ impl AppController {
         let cache_snapshot = {
             let mut state = self.state.borrow_mut();
             state.spotlight_loading = false;
+            state.spotlight_retry_attempt = 0;
             state.spotlight_recent = recent;
             state.spotlight_categories = categories;
             state.spotlight_cache = cache;
             state.spotlight_last_refresh = Some(refreshed_at);
+            state.spotlight_new = state
+                .last_visit
+                .map(|since| compute_new_since(&state.spotlight_cache, since))
+                .unwrap_or_default();
             if let Some(selected) = state.spotlight_recent_selected.clone() {
                 if !state
-                    .spotlight_recent
+                    .visible_spotlight_recent()
                     .iter()
                     .any(|pkg| pkg.name == selected)
                 {
@@ -1268,9 +2402,36 @@ impl AppController {
     }
 
     pub(crate) fn finish_spotlight_failed(self: &Rc<Self>, error: String) {
+        let attempt = {
+            let mut state = self.state.borrow_mut();
+            state.spotlight_retry_attempt += 1;
+            state.spotlight_retry_attempt
+        };
+
+        if attempt < SPOTLIGHT_MAX_ATTEMPTS {
+            // Keep the loading state while waiting so only the final failure
+            // reaches the status label.
+            let delay = SPOTLIGHT_RETRY_BASE_SECS << (attempt - 1);
+            eprintln!(
+                "Spotlight refresh failed (attempt {} of {}), retrying in {}s: {}",
+                attempt, SPOTLIGHT_MAX_ATTEMPTS, delay, error
+            );
+            let weak_self = Rc::downgrade(self);
+            let source = glib::timeout_add_seconds_local(delay, move || {
+                if let Some(controller) = weak_self.upgrade() {
+                    controller.state.borrow_mut().spotlight_retry_source = None;
+                    controller.start_spotlight_refresh();
+                }
+                glib::ControlFlow::Break
+            });
+            self.state.borrow_mut().spotlight_retry_source = Some(source);
+            return;
+        }
+
         {
             let mut state = self.state.borrow_mut();
             state.spotlight_loading = false;
+            state.spotlight_retry_attempt = 0;
         }
 
         eprintln!("Spotlight refresh failed: {}", error);
@@ -1289,9 +2450,13 @@ impl AppController {
     }
 
     pub(crate) fn maybe_refresh_spotlight(self: &Rc<Self>, force: bool) {
+        if force {
+            self.cancel_spotlight_retry();
+        }
+
         let should_refresh = {
             let state = self.state.borrow();
-            if state.spotlight_loading {
+            if state.spotlight_loading || !state.show_spotlight {
                 return;
             }
             let has_cached_data = !state.spotlight_recent.is_empty();
@@ -1314,67 +2479,153 @@ impl AppController {
         {
             let mut state = self.state.borrow_mut();
             state.spotlight_loading = true;
+            state.spotlight_retry_attempt = 0;
+        }
+        self.start_spotlight_refresh();
+    }
+
+    /// Drops a scheduled retry so a manual refresh doesn't overlap with it.
+    pub(crate) fn cancel_spotlight_retry(&self) {
+        let mut state = self.state.borrow_mut();
+        if let Some(source) = state.spotlight_retry_source.take() {
+            source.remove();
+            state.spotlight_loading = false;
+            state.spotlight_retry_attempt = 0;
         }
+    }
+
+    fn start_spotlight_refresh(self: &Rc<Self>) {
+        self.refresh_busy_indicator();
         self.update_spotlight_views();
 
         let cache = {
             let state = self.state.borrow();
             state.spotlight_cache.clone()
         };
+        let (window_days, limit) = {
+            let settings = self.settings.borrow();
+            (
+                settings.spotlight_window_days,
+                settings.spotlight_recent_limit,
+            )
+        };
         let sender = self.sender.clone();
-        thread::spawn(move || match refresh_spotlight_cache(cache) {
-            Ok(outcome) => {
-                let _ = sender.send(AppMessage::SpotlightLoaded {
+        thread::spawn(move || {
+            let message = match refresh_spotlight_cache(cache, window_days, limit) {
+                Ok(outcome) => AppMessage::SpotlightLoaded {
                     recent: outcome.recent,
                     categories: outcome.categories,
                     cache: outcome.cache,
                     refreshed_at: outcome.refreshed_at,
-                });
-            }
-            Err(error) => {
-                let _ = sender.send(AppMessage::SpotlightFailed { error });
-            }
+                },
+                Err(error) => AppMessage::SpotlightFailed { error },
+            };
+            let _ = sender.send(message);
         });
     }
 
     pub(crate) fn set_category_button_state(self: &Rc<Self>, active: Option<SpotlightCategory>) {
-        let widgets = &self.widgets.discover;
-        set_toggle_button_state(
-            &widgets.category_browsers_button,
-            active == Some(SpotlightCategory::Browsers),
-        );
-        set_toggle_button_state(
-            &widgets.category_chat_button,
-            active == Some(SpotlightCategory::Chat),
-        );
-        set_toggle_button_state(
-            &widgets.category_email_button,
-            active == Some(SpotlightCategory::Email),
-        );
-        set_toggle_button_state(
-            &widgets.category_games_button,
-            active == Some(SpotlightCategory::Games),
-        );
-        set_toggle_button_state(
-            &widgets.category_graphics_button,
-            active == Some(SpotlightCategory::Graphics),
-        );
-        set_toggle_button_state(
-            &widgets.category_music_button,
-            active == Some(SpotlightCategory::Music),
-        );
-        set_toggle_button_state(
-            &widgets.category_productivity_button,
-            active == Some(SpotlightCategory::Productivity),
-        );
-        set_toggle_button_state(
-            &widgets.category_utilities_button,
-            active == Some(SpotlightCategory::Utilities),
-        );
-        set_toggle_button_state(
-            &widgets.category_video_button,
-            active == Some(SpotlightCategory::Video),
-        );
+        for (category, button) in self.category_buttons.borrow().iter() {
+            set_toggle_button_state(button, active == Some(*category));
+        }
+    }
+
+    /// Rebuilds the category shortcuts from the enabled categories, in the
+    /// order saved in preferences.
+    pub(crate) fn rebuild_category_buttons(self: &Rc<Self>) {
+        let list = &self.widgets.discover.categories_list;
+        while let Some(child) = list.first_child() {
+            list.remove(&child);
+        }
+
+        let enabled: Vec<SpotlightCategory> = self
+            .spotlight_category_layout()
+            .into_iter()
+            .filter_map(|(category, enabled)| enabled.then_some(category))
+            .collect();
+        let active = self.state.borrow().active_spotlight_category;
+
+        let mut buttons: Vec<(SpotlightCategory, gtk::ToggleButton)> = Vec::new();
+        for category in enabled {
+            let button = build_category_button(
+                category_icon_resource(category),
+                category_display_name(category),
+            );
+            if let Some((_, first)) = buttons.first() {
+                button.set_group(Some(first));
+            }
+            button.set_active(active == Some(category));
+            button.connect_toggled(glib::clone!(
+                #[strong(rename_to = controller)]
+                self,
+                move |btn| {
+                    controller.handle_spotlight_category_toggle(category, btn.is_active());
+                }
+            ));
+            list.append(&button);
+            buttons.push((category, button));
+        }
+        let active_hidden = active
+            .map(|category| !buttons.iter().any(|(shown, _)| *shown == category))
+            .unwrap_or(false);
+        self.category_buttons.replace(buttons);
+
+        if active_hidden {
+            self.clear_spotlight_category();
+        }
+    }
+
+    /// Every category in display order, paired with whether it is shown.
+    pub(crate) fn spotlight_category_layout(&self) -> Vec<(SpotlightCategory, bool)> {
+        let settings = self.settings.borrow();
+        ordered_spotlight_categories(&settings.spotlight_category_order)
+            .into_iter()
+            .map(|category| {
+                let hidden = settings.hidden_spotlight_categories.contains(&category);
+                (category, !hidden)
+            })
+            .collect()
+    }
+
+    pub(crate) fn set_spotlight_category_enabled(
+        self: &Rc<Self>,
+        category: SpotlightCategory,
+        enabled: bool,
+    ) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            settings
+                .hidden_spotlight_categories
+                .retain(|hidden| *hidden != category);
+            if !enabled {
+                settings.hidden_spotlight_categories.push(category);
+            }
+        }
+        self.persist_settings();
+        self.rebuild_category_buttons();
+    }
+
+    /// Moves `category` one place earlier (`up`) or later in the saved order.
+    pub(crate) fn move_spotlight_category(self: &Rc<Self>, category: SpotlightCategory, up: bool) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            let mut order = ordered_spotlight_categories(&settings.spotlight_category_order);
+            let Some(index) = order.iter().position(|entry| *entry == category) else {
+                return;
+            };
+            let target = if up {
+                index.checked_sub(1)
+            } else {
+                Some(index + 1).filter(|next| *next < order.len())
+            };
+            let Some(target) = target else {
+                return;
+            };
+            order.swap(index, target);
+            settings.spotlight_category_order = order;
+        }
+        self.persist_settings();
+        self.rebuild_category_buttons();
     }
 
     pub(crate) fn update_spotlight_installed_flags(self: &Rc<Self>) {
@@ -1387,7 +2638,12 @@ impl AppController {
 
         {
             let mut state = self.state.borrow_mut();
-            for pkg in &mut state.spotlight_recent {
+            let state = &mut *state;
+            for pkg in state
+                .spotlight_recent
+                .iter_mut()
+                .chain(state.spotlight_new.iter_mut())
+            {
                 let installed_flag = installed.contains(&pkg.name);
                 if pkg.installed != installed_flag {
                     pkg.installed = installed_flag;
@@ -1422,7 +2678,7 @@ impl AppController {
             None
         };
 
-        let packages = {
+        let mut packages = {
             let state = self.state.borrow();
             state
                 .spotlight_categories
@@ -1438,9 +2694,14 @@ impl AppController {
                 state.spotlight_status_backup = status_snapshot;
             }
             state.active_spotlight_category = Some(category);
+            // Category entries come from the spotlight cache, which doesn't
+            // know what is installed.
+            for pkg in &mut packages {
+                pkg.installed = state.installed_set.contains(&pkg.name);
+            }
             state.search_results = packages;
             state.selected_search = None;
-            state.discover_mode = DiscoverMode::Spotlight;
+            state.discover_mode = DiscoverMode::Search;
             state.discover_detail_focus = None;
         }
 
@@ -1453,15 +2714,15 @@ impl AppController {
 
         let label = if count == 0 {
             format!(
-                "No spotlight picks found for {}.",
+                "No packages found in {} yet. They appear after the next spotlight refresh.",
                 category_display_name(category)
             )
         } else {
             format!(
-                "Showing {} {} spotlight pick{}.",
+                "Showing {} package{} in {}.",
                 count,
-                category_display_name(category).to_lowercase(),
-                if count == 1 { "" } else { "s" }
+                if count == 1 { "" } else { "s" },
+                category_display_name(category)
             )
         };
 
@@ -1532,7 +2793,6 @@ impl AppController {
     ) {
         if active {
             self.apply_spotlight_category(category, true);
-            self.run_category_search(category);
         } else {
             let should_clear = {
                 let state = self.state.borrow();
@@ -1544,31 +2804,37 @@ impl AppController {
         }
     }
 
-    pub(crate) fn run_category_search(self: &Rc<Self>, category: SpotlightCategory) {
-        let query = match category {
-            SpotlightCategory::Browsers => "web browser",
-            SpotlightCategory::Chat => "chat",
-            SpotlightCategory::Games => "game",
-            SpotlightCategory::Email => "email",
-            SpotlightCategory::Graphics => "graphics",
-            SpotlightCategory::Music => "music",
-            SpotlightCategory::Productivity => "productivity",
-            SpotlightCategory::Utilities => "utility",
-            SpotlightCategory::Video => "video",
-        };
-
-        self.widgets.discover.search_entry.set_text(query);
-        self.on_search_requested();
-    }
-
-    pub(crate) fn on_spotlight_recent_selected(self: &Rc<Self>, row: Option<gtk::ListBoxRow>) {
-        let Some(row) = row else {
-            return;
-        };
-        if !row.is_selected() {
-            return;
+    /// Arrow keys move through the recent list and Enter opens a row (both
+    /// handled by the list box). Ctrl+Enter also installs or removes the
+    /// focused package, and Escape closes the open detail.
+    pub(crate) fn on_spotlight_recent_key_pressed(
+        self: &Rc<Self>,
+        key: gdk::Key,
+        modifiers: gdk::ModifierType,
+    ) -> glib::Propagation {
+        match key {
+            gdk::Key::Return | gdk::Key::KP_Enter
+                if modifiers.contains(gdk::ModifierType::CONTROL_MASK) =>
+            {
+                let list = &self.widgets.discover.spotlight_recent_list;
+                let row = list
+                    .focus_child()
+                    .and_then(|child| child.downcast::<gtk::ListBoxRow>().ok())
+                    .or_else(|| list.selected_row());
+                let Some(row) = row else {
+                    return glib::Propagation::Proceed;
+                };
+                list.select_row(Some(&row));
+                self.activate_spotlight_recent_row(&row);
+                self.on_discover_primary_action();
+                glib::Propagation::Stop
+            }
+            gdk::Key::Escape if self.state.borrow().spotlight_recent_selected.is_some() => {
+                self.on_spotlight_recent_close();
+                glib::Propagation::Stop
+            }
+            _ => glib::Propagation::Proceed,
         }
-        self.activate_spotlight_recent_row(&row);
     }
 
     pub(crate) fn on_spotlight_row_activated(self: &Rc<Self>, row: &gtk::ListBoxRow) {
@@ -1592,7 +2858,10 @@ impl AppController {
         }
         let pkg = {
             let state = self.state.borrow();
-            state.spotlight_recent.get(index as usize).cloned()
+            state
+                .visible_spotlight_recent()
+                .get(index as usize)
+                .cloned()
         };
 
         if let Some(pkg) = pkg {
@@ -1647,7 +2916,12 @@ impl AppController {
     }
 
     pub(crate) fn on_spotlight_recent_close(self: &Rc<Self>) {
+        let row = self.widgets.discover.spotlight_recent_list.selected_row();
         self.clear_spotlight_recent_selection();
+        // Hand focus back to the row the detail was opened from.
+        if let Some(row) = row {
+            row.grab_focus();
+        }
     }
 
     pub(crate) fn clear_spotlight_recent_selection(self: &Rc<Self>) {
@@ -1696,7 +2970,7 @@ impl AppController {
 
         let has_items = {
             let state = self.state.borrow();
-            !state.spotlight_recent.is_empty()
+            !state.visible_spotlight_recent().is_empty()
         };
 
         self.widgets
@@ -1708,11 +2982,103 @@ impl AppController {
         self.update_discover_details();
     }
 
+    fn spotlight_recent_description(&self) -> String {
+        let days = self.settings.borrow().spotlight_window_days;
+        tr!(
+            "Packages updated in the past {} days will appear here.",
+            days
+        )
+    }
+
+    pub(crate) fn refresh_spotlight_recent_placeholder(&self) {
+        if self.state.borrow().spotlight_show_new {
+            return;
+        }
+        self.widgets
+            .discover
+            .spotlight_recent_placeholder
+            .set_description(Some(&self.spotlight_recent_description()));
+    }
+
+    /// Rebuilds the recent list from the cached metadata after its period or
+    /// size changes; no network refresh is needed.
+    pub(crate) fn recompute_spotlight_recent(self: &Rc<Self>) {
+        let (window_days, limit) = {
+            let settings = self.settings.borrow();
+            (
+                settings.spotlight_window_days,
+                settings.spotlight_recent_limit,
+            )
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.spotlight_recent =
+                compute_spotlight_sections(&state.spotlight_cache, Utc::now(), window_days, limit);
+            if let Some(selected) = state.spotlight_recent_selected.clone() {
+                if !state
+                    .visible_spotlight_recent()
+                    .iter()
+                    .any(|pkg| pkg.name == selected)
+                {
+                    state.spotlight_recent_selected = None;
+                }
+            }
+        }
+        self.refresh_spotlight_recent_placeholder();
+        self.update_spotlight_views();
+    }
+
+    pub(crate) fn on_spotlight_new_toggled(self: &Rc<Self>, active: bool) {
+        let last_visit = {
+            let mut state = self.state.borrow_mut();
+            if state.spotlight_show_new == active {
+                return;
+            }
+            state.spotlight_show_new = active;
+            state.spotlight_recent_selected = None;
+            state.last_visit
+        };
+
+        let heading = &self.widgets.discover.spotlight_recent_heading;
+        let placeholder = &self.widgets.discover.spotlight_recent_placeholder;
+        if active {
+            // Based on when Nebula first saw a package, not on when it
+            // reached the repositories.
+            let since =
+                last_visit.map(|since| since.with_timezone(&Local).format("%Y-%m-%d").to_string());
+            let (title, description) = match since {
+                Some(date) => (
+                    tr!("Newly seen since {}", date),
+                    tr!(
+                        "Packages Nebula first sees in the repositories after {} will appear here.",
+                        date
+                    ),
+                ),
+                None => (
+                    tr!("Newly seen packages"),
+                    tr!(
+                        "Packages Nebula first sees in the repositories will appear here from your next visit."
+                    ),
+                ),
+            };
+            heading.set_text(&title);
+            placeholder.set_title(&tr!("Nothing newly seen"));
+            placeholder.set_description(Some(&description));
+        } else {
+            heading.set_text("Recent package updates");
+            placeholder.set_title("Nothing updated recently");
+            placeholder.set_description(Some(&self.spotlight_recent_description()));
+        }
+
+        self.widgets.discover.spotlight_recent_list.unselect_all();
+        self.update_spotlight_views();
+    }
+
     pub(crate) fn update_spotlight_views(self: &Rc<Self>) {
         let (recent, loading, last_refresh, active_category, selected_recent) = {
             let state = self.state.borrow();
             (
-                state.spotlight_recent.clone(),
+                state.visible_spotlight_recent().to_vec(),
                 state.spotlight_loading,
                 state.spotlight_last_refresh,
                 state.active_spotlight_category,
@@ -1721,6 +3087,7 @@ impl AppController {
         };
 
         self.set_category_button_state(active_category);
+        self.rebuild_favorites_list();
 
         let spinner = &self.widgets.discover.spotlight_spinner;
         let status_label = &self.widgets.discover.spotlight_status;
@@ -1857,6 +3224,289 @@ impl AppController {
         }
     }
 
+    pub(crate) fn is_favorite(&self, package: &str) -> bool {
+        self.settings
+            .borrow()
+            .favorites
+            .iter()
+            .any(|name| name == package)
+    }
+
+    /// Stars or unstars the package shown in the Discover or spotlight
+    /// detail pane.
+    pub(crate) fn toggle_discover_detail_favorite(self: &Rc<Self>) {
+        let Some(package) = self
+            .state
+            .borrow()
+            .discover_detail_focus
+            .as_ref()
+            .map(|pkg| pkg.name.clone())
+        else {
+            return;
+        };
+
+        let added = {
+            let mut settings = self.settings.borrow_mut();
+            if let Some(index) = settings.favorites.iter().position(|name| *name == package) {
+                settings.favorites.remove(index);
+                false
+            } else {
+                settings.favorites.push(package.clone());
+                settings.favorites.sort();
+                true
+            }
+        };
+        self.persist_settings();
+
+        if added {
+            self.show_toast(&format!("Added {} to favorites.", package));
+        } else {
+            self.show_toast(&format!("Removed {} from favorites.", package));
+        }
+        self.update_favorite_buttons();
+        self.rebuild_favorites_list();
+    }
+
+    pub(crate) fn update_favorite_buttons(&self) {
+        let focus = self
+            .state
+            .borrow()
+            .discover_detail_focus
+            .as_ref()
+            .map(|pkg| pkg.name.clone());
+        let favorite = focus.as_deref().is_some_and(|name| self.is_favorite(name));
+        let (icon, tooltip) = if favorite {
+            ("starred-symbolic", "Remove from favorites")
+        } else {
+            ("non-starred-symbolic", "Add to favorites")
+        };
+
+        for button in [
+            &self.widgets.discover.detail_favorite_button,
+            &self.widgets.discover.spotlight_recent_favorite_button,
+        ] {
+            button.set_visible(focus.is_some());
+            button.set_icon_name(icon);
+            button.set_tooltip_text(Some(tooltip));
+        }
+    }
+
+    pub(crate) fn rebuild_favorites_list(self: &Rc<Self>) {
+        let list = &self.widgets.discover.favorites_list;
+        clear_listbox(list);
+        self.favorite_buttons.borrow_mut().clear();
+
+        let favorites = self.settings.borrow().favorites.clone();
+        self.sync_favorites_section();
+
+        let packages: Vec<PackageInfo> = {
+            let state = self.state.borrow();
+            favorites
+                .iter()
+                .map(|name| favorite_package_info(&state, name))
+                .collect()
+        };
+        for pkg in &packages {
+            let row = self.build_favorite_row(pkg);
+            list.append(&row);
+        }
+    }
+
+    /// Favorites show above the spotlight, or in its place when it is
+    /// turned off, but not over search results.
+    fn sync_favorites_section(&self) {
+        let visible = self.state.borrow().discover_mode == DiscoverMode::Spotlight
+            && !self.settings.borrow().favorites.is_empty();
+        self.widgets.discover.favorites_section.set_visible(visible);
+    }
+
+    fn build_favorite_row(self: &Rc<Self>, pkg: &PackageInfo) -> adw::ActionRow {
+        let title = glib::markup_escape_text(&pkg.name);
+        let row = adw::ActionRow::builder().title(title.as_str()).build();
+        row.set_activatable(true);
+        row.set_title_lines(1);
+        row.set_subtitle_lines(1);
+
+        let mut subtitle_parts = Vec::new();
+        if pkg.installed {
+            subtitle_parts.push("Installed".to_string());
+        }
+        if !pkg.version.is_empty() {
+            subtitle_parts.push(format!("Version {}", pkg.version));
+        }
+        if !pkg.description.is_empty() {
+            subtitle_parts.push(pkg.description.clone());
+        }
+        if !subtitle_parts.is_empty() {
+            let subtitle = glib::markup_escape_text(&subtitle_parts.join(" • "));
+            row.set_subtitle(subtitle.as_str());
+        }
+
+        let icon = package_icon(&pkg.name);
+        icon.set_pixel_size(32);
+        icon.set_margin_end(12);
+        icon.set_valign(gtk::Align::Center);
+        row.add_prefix(&icon);
+
+        let queued = self.state.borrow().is_queued(&pkg.name);
+        let button = gtk::Button::builder().width_request(140).build();
+        button.set_valign(gtk::Align::Center);
+        button.set_sensitive(!queued);
+        if pkg.installed {
+            button.set_label("Remove");
+            button.add_css_class("destructive-action");
+            button.set_tooltip_text(Some("Remove this package."));
+        } else {
+            button.set_label("Install");
+            button.add_css_class("suggested-action");
+            button.set_tooltip_text(Some("Install this package."));
+        }
+
+        let weak_self = Rc::downgrade(self);
+        let package_info = pkg.clone();
+        button.connect_clicked(move |_| {
+            let Some(controller) = weak_self.upgrade() else {
+                return;
+            };
+            if package_info.installed {
+                controller.start_remove(package_info.name.clone(), RemoveOrigin::Discover);
+            } else {
+                controller.request_install_for_package(package_info.clone());
+            }
+        });
+
+        let unstar_button = gtk::Button::builder()
+            .icon_name("starred-symbolic")
+            .tooltip_text("Remove from favorites")
+            .has_frame(false)
+            .build();
+        unstar_button.add_css_class("flat");
+        unstar_button.set_valign(gtk::Align::Center);
+        let weak_self = Rc::downgrade(self);
+        let package = pkg.name.clone();
+        unstar_button.connect_clicked(move |_| {
+            if let Some(controller) = weak_self.upgrade() {
+                controller.remove_favorite(&package);
+            }
+        });
+
+        row.add_suffix(&button);
+        row.add_suffix(&unstar_button);
+        self.favorite_buttons
+            .borrow_mut()
+            .insert(pkg.name.clone(), button);
+
+        row
+    }
+
+    fn remove_favorite(self: &Rc<Self>, package: &str) {
+        self.settings
+            .borrow_mut()
+            .favorites
+            .retain(|name| name != package);
+        self.persist_settings();
+        self.update_favorite_buttons();
+        self.rebuild_favorites_list();
+    }
+
+    /// Moves `package` to the front of the recently viewed list.
+    fn note_recently_viewed(self: &Rc<Self>, package: &str) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.recently_viewed.first().map(String::as_str) == Some(package) {
+                return;
+            }
+            state.recently_viewed.retain(|name| name != package);
+            state.recently_viewed.insert(0, package.to_string());
+            state.recently_viewed.truncate(MAX_RECENTLY_VIEWED);
+        }
+        self.rebuild_recently_viewed_list();
+    }
+
+    pub(crate) fn rebuild_recently_viewed_list(&self) {
+        let list = &self.widgets.discover.recently_viewed_list;
+        clear_listbox(list);
+
+        let packages: Vec<PackageInfo> = {
+            let state = self.state.borrow();
+            state
+                .recently_viewed
+                .iter()
+                .map(|name| favorite_package_info(&state, name))
+                .collect()
+        };
+        self.widgets
+            .discover
+            .recently_viewed_section
+            .set_visible(!packages.is_empty());
+
+        for pkg in &packages {
+            let title = glib::markup_escape_text(&pkg.name);
+            let row = adw::ActionRow::builder().title(title.as_str()).build();
+            row.set_activatable(true);
+            row.set_title_lines(1);
+            row.set_subtitle_lines(1);
+            if !pkg.description.is_empty() {
+                let subtitle = glib::markup_escape_text(&pkg.description);
+                row.set_subtitle(subtitle.as_str());
+            }
+
+            let icon = package_icon(&pkg.name);
+            icon.set_pixel_size(32);
+            icon.set_margin_end(12);
+            icon.set_valign(gtk::Align::Center);
+            row.add_prefix(&icon);
+            list.append(&row);
+        }
+    }
+
+    /// Reopens the detail of a recently viewed package, whether or not it is
+    /// among the current search results.
+    pub(crate) fn on_recently_viewed_row_activated(self: &Rc<Self>, row: &gtk::ListBoxRow) {
+        let Some(name) = row
+            .child()
+            .and_then(|child| child.downcast::<adw::ActionRow>().ok())
+            .map(|action_row| action_row.title().to_string())
+        else {
+            return;
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.discover_detail_history.clear();
+            state.pending_discover_target = None;
+        }
+        if self.focus_discover_package(&name, true) {
+            self.update_discover_detail_back_button();
+            return;
+        }
+        self.open_discover_dependency_detail(name);
+    }
+
+    /// Lists the favorite on its own and opens its detail pane, the way a
+    /// category lists its packages, instead of searching for its name.
+    pub(crate) fn on_favorite_row_activated(self: &Rc<Self>, row: &gtk::ListBoxRow) {
+        let Some(name) = row
+            .child()
+            .and_then(|child| child.downcast::<adw::ActionRow>().ok())
+            .map(|action_row| action_row.title().to_string())
+        else {
+            return;
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            let pkg = favorite_package_info(&state, &name);
+            state.search_results = vec![pkg];
+            state.selected_search = None;
+            state.discover_mode = DiscoverMode::Search;
+            state.discover_detail_focus = None;
+            state.discover_detail_history.clear();
+            state.pending_discover_target = None;
+        }
+        self.rebuild_search_list();
+        self.update_discover_layout();
+        self.focus_discover_package(&name, false);
+    }
+
     pub(crate) fn update_spotlight_recent_detail(self: &Rc<Self>) {
         let (
             selected_recent,
@@ -2197,3 +3847,60 @@ impl AppController {
         }
     }
 }
+
+/// Disk space installing `name` takes: its download plus its unpacked size
+/// once the details have been loaded, the same measure updates use.
+/// Dependencies aren't counted. `None` while the download size is unknown.
+fn install_space_required(state: &AppState, name: &str, download: Option<u64>) -> Option<u64> {
+    let detail = state.discover_detail_cache.get(name);
+    let download = download
+        .or_else(|| detail.and_then(|detail| detail.download_bytes))
+        .or_else(|| {
+            state
+                .search_results
+                .iter()
+                .find(|pkg| pkg.name == name)
+                .and_then(|pkg| pkg.download_bytes)
+        })?;
+    let installed = detail
+        .and_then(|detail| detail.installed_bytes)
+        .unwrap_or(0);
+    Some(download.saturating_add(installed))
+}
+
+/// Best-known package info for a favorite, taken from the installed list,
+/// search results or spotlight cache, with the installed flag kept current.
+fn favorite_package_info(state: &AppState, name: &str) -> PackageInfo {
+    let known = state
+        .installed_packages
+        .iter()
+        .chain(state.search_results.iter())
+        .chain(state.spotlight_recent.iter())
+        .chain(state.spotlight_new.iter())
+        .find(|pkg| pkg.name == name)
+        .cloned();
+
+    let mut pkg = known.unwrap_or_else(|| {
+        let version = String::new();
+        let description = String::new();
+        PackageInfo {
+            name_lower: lowercase_cache(name),
+            version_lower: lowercase_cache(&version),
+            description_lower: lowercase_cache(&description),
+            name: name.to_string(),
+            version,
+            description,
+            installed: false,
+            pinned: false,
+            previous_version: None,
+            download_size: None,
+            changelog: None,
+            download_bytes: None,
+            repository: None,
+            build_date: None,
+            first_seen: None,
+        }
+    });
+    pkg.installed = state.installed_set.contains(name);
+    pkg
+}
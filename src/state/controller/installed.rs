@@ -1,24 +1,31 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::thread;
 
+use chrono::Local;
 use gtk4 as gtk;
 use libadwaita as adw;
 
 use adw::prelude::*;
-use gtk::glib;
+use gtk::{gio, glib, pango};
 
-use crate::categories::icon_resource_for_package;
 use crate::details::InstalledDetail;
 use crate::helpers::{
-    clear_listbox, format_relative_time, glib_datetime_to_chrono, package_matches_filter,
-    query_installed_detail, sanitize_contact_field, set_link_label,
+    DETAIL_TIMEOUT_ERROR, clear_listbox, compact_package_row, fetch_detail_with_timeout,
+    format_relative_time, glib_datetime_to_chrono, package_icon, package_match_score,
+    package_row_tooltip, query_installed_detail, sanitize_contact_field, set_link_label,
+    update_badge,
 };
 use crate::state::controller::AppController;
-use crate::state::types::{AppMessage, InstalledFilter, RemoveOrigin};
+use crate::state::types::{
+    AppMessage, InstalledFilter, PendingChange, RemovalRequest, RemoveOrigin,
+};
 use crate::types::{CommandResult, PackageInfo};
 use crate::xbps::{
-    format_download_size, run_xbps_list_installed, run_xbps_pkgdb_hold, run_xbps_pkgdb_unhold,
+    format_download_size, remove_command_line, run_xbps_list_installed, run_xbps_pkgdb_hold,
+    run_xbps_pkgdb_set_hold, run_xbps_pkgdb_unhold, run_xbps_query_files, run_xbps_query_held,
+    subpackage_parent,
 };
 
 impl AppController {
@@ -30,8 +37,20 @@ impl AppController {
             }
             state.installed_refresh_in_progress = true;
         }
+        self.refresh_busy_indicator();
+        // Every transaction ends with this refresh, so free space is
+        // measured again alongside it.
+        self.refresh_free_space();
 
         self.set_installed_status_message(Some("Refreshing installed packages…".to_string()));
+        // Large systems take a moment to list; keep an existing list visible
+        // and only swap in the placeholder when there is nothing to show yet.
+        if self.state.borrow().installed_packages.is_empty() {
+            self.widgets
+                .installed
+                .installed_results_stack
+                .set_visible_child_name("loading");
+        }
         let sender = self.sender.clone();
         thread::spawn(move || {
             let result = run_xbps_list_installed();
@@ -64,6 +83,28 @@ impl AppController {
         self.rebuild_installed_list();
     }
 
+    pub(crate) fn set_group_installed_subpackages(self: &Rc<Self>, enabled: bool) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            if settings.group_installed_subpackages == enabled {
+                return;
+            }
+            settings.group_installed_subpackages = enabled;
+        }
+        self.persist_settings();
+        self.rebuild_installed_list();
+    }
+
+    fn toggle_installed_variant_group(self: &Rc<Self>, parent: &str) {
+        {
+            let mut state = self.state.borrow_mut();
+            if !state.installed_expanded_groups.remove(parent) {
+                state.installed_expanded_groups.insert(parent.to_string());
+            }
+        }
+        self.rebuild_installed_list();
+    }
+
     pub(crate) fn on_installed_remove_selected(self: &Rc<Self>) {
         let packages = {
             let state = self.state.borrow();
@@ -78,41 +119,35 @@ impl AppController {
         }
 
         // Check if confirmation is required
-        if self.state.borrow().confirm_remove {
-            let pkg_count = packages.len();
-            let packages_clone = packages.clone();
+        if self.state.borrow().should_confirm_remove() {
+            self.check_removal_impact(RemovalRequest::Batch { packages });
+            return;
+        }
 
-            let heading = if pkg_count == 1 {
-                format!("Remove \"{}\"?", packages[0])
-            } else {
-                format!("Remove {} selected packages?", pkg_count)
-            };
+        self.execute_remove_batch(packages);
+    }
 
-            let body = if pkg_count == 1 {
-                "The package and its data will be removed from this system.".to_string()
-            } else {
-                let package_list = packages.iter()
-                    .take(5)
-                    .map(|s| s.as_str())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                if pkg_count > 5 {
-                    format!("The following packages will be removed: {}, and {} more.\n\nAll packages and their data will be removed from this system.",
-                        package_list, pkg_count - 5)
-                } else {
-                    format!("The following packages will be removed: {}.\n\nAll packages and their data will be removed from this system.",
-                        package_list)
-                }
-            };
+    pub(crate) fn on_installed_hold_selected(self: &Rc<Self>, target_pinned: bool) {
+        let packages = {
+            let state = self.state.borrow();
+            if state.pin_in_progress {
+                return;
+            }
+            let mut packages = state
+                .installed_selected
+                .iter()
+                .filter(|name| state.held_set.contains(*name) != target_pinned)
+                .cloned()
+                .collect::<Vec<_>>();
+            packages.sort();
+            packages
+        };
 
-            self.confirm_action(&heading, &body, "Remove", move |controller| {
-                controller.execute_remove_batch(packages_clone);
-            });
+        if packages.is_empty() {
             return;
         }
 
-        self.execute_remove_batch(packages);
+        self.execute_pin_batch(packages, target_pinned);
     }
 
     pub(crate) fn on_installed_row_selected(self: &Rc<Self>, position: Option<u32>) {
@@ -233,10 +268,14 @@ impl AppController {
             state.installed_detail_errors.remove(&package_name);
             state.installed_detail_loading.insert(package_name.clone());
         }
+        self.refresh_busy_indicator();
 
         let sender = self.sender.clone();
         thread::spawn(move || {
-            let result = query_installed_detail(&package_name, &installed_set);
+            let query_name = package_name.clone();
+            let result = fetch_detail_with_timeout(move || {
+                query_installed_detail(&query_name, &installed_set)
+            });
             let _ = sender.send(AppMessage::InstalledDetailsLoaded {
                 package: package_name,
                 result,
@@ -252,6 +291,7 @@ impl AppController {
             }
             state.pin_in_progress = true;
         }
+        self.refresh_busy_indicator();
 
         let action = if target_pinned {
             "Holding"
@@ -280,6 +320,82 @@ impl AppController {
         });
     }
 
+    fn execute_pin_batch(self: &Rc<Self>, packages: Vec<String>, target_pinned: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.pin_in_progress {
+                return;
+            }
+            state.pin_in_progress = true;
+        }
+        self.refresh_busy_indicator();
+
+        let action = if target_pinned {
+            "Holding"
+        } else {
+            "Unholding"
+        };
+        let message = format!(
+            "{action} {} package{}…",
+            packages.len(),
+            if packages.len() == 1 { "" } else { "s" }
+        );
+        self.set_installed_status_message(Some(message.clone()));
+        self.set_footer_message(Some(&message));
+
+        self.update_installed_details();
+        self.refresh_visible_installed_rows();
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_pkgdb_set_hold(&packages, target_pinned);
+            let _ = sender.send(AppMessage::PinBatchFinished {
+                packages,
+                target_pinned,
+                result,
+            });
+        });
+    }
+
+    pub(crate) fn refresh_held_packages(self: &Rc<Self>) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_query_held();
+            let _ = sender.send(AppMessage::HeldPackagesLoaded { result });
+        });
+    }
+
+    pub(crate) fn finish_held_packages(self: &Rc<Self>, result: Result<HashSet<String>, String>) {
+        let held = match result {
+            Ok(held) => held,
+            Err(err) => {
+                eprintln!("Failed to query held packages: {}", err);
+                return;
+            }
+        };
+
+        {
+            let mut state = self.state.borrow_mut();
+            for info in &mut state.installed_packages {
+                info.pinned = held.contains(&info.name);
+            }
+            for info in &mut state.search_results {
+                info.pinned = held.contains(&info.name);
+            }
+            for info in &mut state.available_updates {
+                info.pinned = held.contains(&info.name);
+            }
+            if let Some(focus) = state.discover_detail_focus.as_mut() {
+                focus.pinned = held.contains(&focus.name);
+            }
+            state.held_set = held;
+        }
+
+        self.rebuild_installed_list();
+        self.refresh_detail_pin_button();
+        self.update_installed_summary();
+    }
+
     pub(crate) fn finish_installed_refresh(
         self: &Rc<Self>,
         result: Result<Vec<PackageInfo>, String>,
@@ -292,18 +408,42 @@ impl AppController {
         match result {
             Ok(packages) => {
                 let mut state = self.state.borrow_mut();
-                state.installed_set = packages.iter().map(|pkg| pkg.name.clone()).collect();
+                // The focused row is stored by list position, which the new
+                // list reshuffles, so remember it by name instead.
+                let focused = state
+                    .selected_installed
+                    .and_then(|list_idx| state.installed_filtered.get(list_idx))
+                    .and_then(|orig_idx| state.installed_packages.get(*orig_idx))
+                    .map(|pkg| pkg.name.clone());
+                let installed_set: HashSet<String> =
+                    packages.iter().map(|pkg| pkg.name.clone()).collect();
+                state
+                    .installed_selected
+                    .retain(|name| installed_set.contains(name));
+                // Drop queued changes the system already reflects, such as
+                // the parts of a failed transaction that went through.
+                if !state.pending_changes_in_progress {
+                    state.pending_changes.retain(|change| match change {
+                        PendingChange::Install(package) => !installed_set.contains(package),
+                        PendingChange::Remove(package) => installed_set.contains(package),
+                    });
+                }
+                state.installed_set = installed_set;
                 state.installed_packages = packages;
                 state.installed_last_refresh = glib::DateTime::now_local().ok();
-                state.installed_selected.clear();
                 state.selected_installed = None;
                 drop(state);
                 self.update_search_installed_flags();
+                self.refresh_pending_changes_bar();
                 self.rebuild_installed_list();
+                if let Some(name) = focused {
+                    self.restore_installed_focus(&name);
+                }
                 self.update_installed_selection_ui();
                 self.update_spotlight_installed_flags();
                 self.update_spotlight_views();
                 self.set_installed_status_message(None);
+                self.refresh_held_packages();
             }
             Err(err) => {
                 self.clear_installed_results();
@@ -316,6 +456,31 @@ impl AppController {
         }
     }
 
+    /// Selects `package` again after a refresh if it is still installed and
+    /// visible under the current filters.
+    fn restore_installed_focus(self: &Rc<Self>, package: &str) {
+        let list_index = {
+            let state = self.state.borrow();
+            state
+                .installed_filtered
+                .iter()
+                .position(|orig_idx| state.installed_packages[*orig_idx].name == package)
+        };
+        let Some(list_index) = list_index else {
+            return;
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.selected_installed = Some(list_index);
+            // Keeps the detail history, as for back navigation.
+            state.installed_detail_navigation_active = true;
+        }
+        self.widgets
+            .installed
+            .list_selection
+            .set_selected(list_index as u32);
+    }
+
     fn clear_installed_results(self: &Rc<Self>) {
         let mut state = self.state.borrow_mut();
         state.installed_packages.clear();
@@ -340,6 +505,7 @@ impl AppController {
         package: String,
         result: Result<InstalledDetail, String>,
     ) {
+        let timed_out = matches!(&result, Err(err) if err == DETAIL_TIMEOUT_ERROR);
         {
             let mut state = self.state.borrow_mut();
             state.installed_detail_loading.remove(&package);
@@ -354,6 +520,12 @@ impl AppController {
             }
         }
 
+        if timed_out {
+            self.show_toast(&format!(
+                "Details for \"{}\" timed out. Select it again to retry.",
+                package
+            ));
+        }
         self.update_installed_details();
     }
 
@@ -431,6 +603,67 @@ impl AppController {
         if let Some(msg) = footer_message {
             self.set_footer_message(Some(&msg));
         }
+        self.refresh_held_packages();
+    }
+
+    pub(crate) fn finish_pin_batch(
+        self: &Rc<Self>,
+        packages: Vec<String>,
+        target_pinned: bool,
+        result: Result<CommandResult, String>,
+    ) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.pin_in_progress = false;
+        }
+
+        let count = packages.len();
+        let plural = if count == 1 { "" } else { "s" };
+        let verb = if target_pinned { "hold" } else { "unhold" };
+        let message = match result {
+            Ok(command) if command.success() => {
+                {
+                    let mut state = self.state.borrow_mut();
+                    state.installed_selected.clear();
+                }
+                let message = if target_pinned {
+                    format!("{} package{} held at the current version.", count, plural)
+                } else {
+                    format!("{} package{} will receive updates again.", count, plural)
+                };
+                self.show_toast(&message);
+                message
+            }
+            Ok(command) => {
+                let detail = command.stderr.trim();
+                let summary = if detail.is_empty() {
+                    command.stdout.trim()
+                } else {
+                    detail
+                };
+                let message = if summary.is_empty() {
+                    format!("Failed to {} {} package{}.", verb, count, plural)
+                } else {
+                    format!(
+                        "Failed to {} {} package{}: {}",
+                        verb, count, plural, summary
+                    )
+                };
+                self.show_error_dialog("Holding Failed", &message);
+                message
+            }
+            Err(err) => {
+                let message = format!("Failed to {} {} package{}: {}", verb, count, plural, err);
+                self.show_error_dialog("Holding Failed", &message);
+                message
+            }
+        };
+
+        self.set_installed_status_message(Some(message.clone()));
+        self.set_footer_message(Some(&message));
+        self.update_installed_details();
+        self.update_installed_summary();
+        self.refresh_held_packages();
     }
 
     pub(crate) fn update_installed_summary(&self) {
@@ -503,6 +736,33 @@ impl AppController {
             .installed
             .remove_selected_button
             .set_sensitive(can_remove);
+        self.widgets
+            .installed
+            .queue_selected_button
+            .set_sensitive(selected_count > 0 && !refreshing);
+
+        let (pin_in_progress, any_held, any_unheld) = {
+            let state = self.state.borrow();
+            let held = state
+                .installed_selected
+                .iter()
+                .filter(|name| state.held_set.contains(*name))
+                .count();
+            (
+                state.pin_in_progress,
+                held > 0,
+                held < state.installed_selected.len(),
+            )
+        };
+        let can_change_hold = !pin_in_progress && !remove_in_progress && !refreshing;
+        self.widgets
+            .installed
+            .hold_selected_button
+            .set_sensitive(can_change_hold && any_unheld);
+        self.widgets
+            .installed
+            .unhold_selected_button
+            .set_sensitive(can_change_hold && any_held);
 
         let (detail_pkg, updates_busy, pin_in_progress, refreshing) = {
             let state = self.state.borrow();
@@ -598,6 +858,150 @@ impl AppController {
         self.update_installed_summary();
     }
 
+    pub(crate) fn installed_detail_homepage(&self) -> Option<String> {
+        let state = self.state.borrow();
+        let package = state.installed_detail_package.as_ref()?;
+        state
+            .installed_detail_cache
+            .get(package)
+            .and_then(|detail| detail.homepage.clone())
+    }
+
+    pub(crate) fn installed_detail_command(&self) -> Option<String> {
+        let package = self.state.borrow().installed_detail_package.clone()?;
+        Some(remove_command_line(&[package], self.remove_options()))
+    }
+
+    pub(crate) fn on_installed_files_requested(self: &Rc<Self>) {
+        let Some(package) = self.state.borrow().installed_detail_package.clone() else {
+            return;
+        };
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_query_files(&package);
+            let _ = sender.send(AppMessage::PackageFilesLoaded { package, result });
+        });
+    }
+
+    pub(crate) fn finish_package_files(
+        &self,
+        package: String,
+        result: Result<Vec<String>, String>,
+    ) {
+        match result {
+            Ok(files) => self.show_package_files_dialog(&package, files),
+            Err(err) => self.show_error_dialog(
+                "Couldn't List Files",
+                &format!("Failed to list the files of {}: {}", package, err),
+            ),
+        }
+    }
+
+    /// Lists the files a package installed. "Open Location" shows the
+    /// selected file's folder, or the package's install prefix when nothing
+    /// is selected, in the default file manager.
+    fn show_package_files_dialog(&self, package: &str, files: Vec<String>) {
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title(format!("Files in {}", package))
+            .default_width(640)
+            .default_height(480)
+            .build();
+        dialog.add_button("Open Location", gtk::ResponseType::Accept);
+        dialog.add_button("Close", gtk::ResponseType::Close);
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let summary = gtk::Label::builder()
+            .label(format!(
+                "{} file{} installed.",
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            ))
+            .xalign(0.0)
+            .build();
+        summary.add_css_class("dim-label");
+        content.append(&summary);
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::Single)
+            .valign(gtk::Align::Start)
+            .build();
+        list.add_css_class("boxed-list");
+        for path in &files {
+            let label = gtk::Label::builder()
+                .label(path.as_str())
+                .xalign(0.0)
+                .ellipsize(pango::EllipsizeMode::Middle)
+                .tooltip_text(path.as_str())
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(12)
+                .margin_end(12)
+                .build();
+            label.add_css_class("monospace");
+            list.append(&label);
+        }
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        content.append(&scroller);
+
+        let prefix = install_prefix(&files);
+        let location = move |list: &gtk::ListBox| -> Option<PathBuf> {
+            match list.selected_row() {
+                Some(row) => files
+                    .get(row.index() as usize)
+                    .and_then(|path| existing_location(Path::new(path))),
+                None => prefix.clone(),
+            }
+        };
+        dialog.set_response_sensitive(gtk::ResponseType::Accept, location(&list).is_some());
+        let location = Rc::new(location);
+
+        list.connect_selected_rows_changed(glib::clone!(
+            #[weak]
+            dialog,
+            #[strong]
+            location,
+            move |list| {
+                dialog.set_response_sensitive(gtk::ResponseType::Accept, location(list).is_some());
+            }
+        ));
+
+        let toast_overlay = self.widgets.toast_overlay.clone();
+        dialog.connect_response(move |dialog, response| {
+            if response != gtk::ResponseType::Accept {
+                dialog.close();
+                return;
+            }
+            let Some(path) = location(&list) else {
+                return;
+            };
+            let uri = gio::File::for_path(&path).uri();
+            if let Err(err) =
+                gio::AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>)
+            {
+                toast_overlay.add_toast(adw::Toast::new(&format!(
+                    "Couldn't open {}: {}",
+                    path.display(),
+                    err
+                )));
+            }
+        });
+
+        dialog.present();
+    }
+
     pub(crate) fn update_installed_details(self: &Rc<Self>) {
         let (maybe_pkg, updates) = {
             let state = self.state.borrow();
@@ -710,6 +1114,8 @@ impl AppController {
                 }
             } else if loading {
                 "Loading…".to_string()
+            } else if error.as_deref() == Some(DETAIL_TIMEOUT_ERROR) {
+                "Unavailable (timed out)".to_string()
             } else if let Some(err) = error.as_ref() {
                 format!("Failed ({})", err)
             } else {
@@ -774,6 +1180,25 @@ impl AppController {
                     widgets.detail_license_value.set_visible(false);
                     widgets.detail_license_value.set_text("");
                 }
+
+                let widgets = &self.widgets.installed;
+                if let Some(installed_at) = detail_ref.install_date {
+                    widgets.detail_install_date_row.set_visible(true);
+                    widgets.detail_install_date_value.set_visible(true);
+                    widgets
+                        .detail_install_date_value
+                        .set_text(&format_relative_time(installed_at));
+                    widgets.detail_install_date_value.set_tooltip_text(Some(
+                        &installed_at
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string(),
+                    ));
+                } else {
+                    widgets.detail_install_date_row.set_visible(false);
+                    widgets.detail_install_date_value.set_visible(false);
+                    widgets.detail_install_date_value.set_text("");
+                }
             } else {
                 let widgets = &self.widgets.installed;
                 set_link_label(&widgets.detail_homepage_link, None);
@@ -782,6 +1207,8 @@ impl AppController {
                 widgets.detail_maintainer_row.set_visible(false);
                 widgets.detail_license_value.set_visible(false);
                 widgets.detail_license_row.set_visible(false);
+                widgets.detail_install_date_value.set_visible(false);
+                widgets.detail_install_date_row.set_visible(false);
             }
 
             let has_update = updates.contains(&pkg.name);
@@ -859,6 +1286,9 @@ impl AppController {
         widgets.detail_license_value.set_visible(false);
         widgets.detail_license_value.set_text("");
         widgets.detail_license_row.set_visible(false);
+        widgets.detail_install_date_value.set_visible(false);
+        widgets.detail_install_date_value.set_text("");
+        widgets.detail_install_date_row.set_visible(false);
         widgets.detail_remove_button.set_visible(false);
         widgets.detail_remove_button.set_sensitive(false);
         widgets.detail_update_button.set_visible(false);
@@ -1006,6 +1436,12 @@ impl AppController {
                     focus.pinned = pinned;
                 }
             }
+
+            if pinned {
+                state.held_set.insert(package.to_string());
+            } else {
+                state.held_set.remove(package);
+            }
         }
 
         self.rebuild_installed_list();
@@ -1170,29 +1606,90 @@ impl AppController {
     }
 
     pub(crate) fn rebuild_installed_list(self: &Rc<Self>) {
-        let (matched, status_message, selected_index, total_installed, filter_mode) = {
+        if self.defer_to_batch() {
+            return;
+        }
+        let group_variants = self.settings.borrow().group_installed_subpackages;
+        let (matched, status_message, selected_index, total_installed, filter_mode, refreshing) = {
             let mut state = self.state.borrow_mut();
             let filter_lower = state.installed_filter.to_lowercase();
             let filter_mode = state.installed_filter_mode;
             let total_installed = state.installed_packages.len();
 
-            let mut matched: Vec<usize> = state
+            let mut scored: Vec<(usize, i32)> = state
                 .installed_packages
                 .iter()
                 .enumerate()
-                .filter(|(_, pkg)| package_matches_filter(pkg, &filter_lower))
                 .filter(|(_, pkg)| {
                     filter_mode != InstalledFilter::Updates
                         || state.available_update_names.contains(&pkg.name)
                 })
-                .map(|(idx, _)| idx)
+                .filter_map(|(idx, pkg)| {
+                    package_match_score(pkg, &filter_lower).map(|score| (idx, score))
+                })
                 .collect();
 
-            matched.sort_by(|a, b| {
+            // Best matches first; ties (and an empty filter) fall back to name order.
+            scored.sort_by(|(a, score_a), (b, score_b)| {
                 let pkg_a = &state.installed_packages[*a];
                 let pkg_b = &state.installed_packages[*b];
-                pkg_a.name.cmp(&pkg_b.name)
+                score_b
+                    .cmp(score_a)
+                    .then_with(|| pkg_a.name.cmp(&pkg_b.name))
             });
+            let mut matched: Vec<usize> = scored.into_iter().map(|(idx, _)| idx).collect();
+
+            state.installed_variant_groups.clear();
+            if group_variants {
+                let matched_names: HashSet<&str> = matched
+                    .iter()
+                    .map(|idx| state.installed_packages[*idx].name.as_str())
+                    .collect();
+                let mut variants: HashMap<String, Vec<usize>> = HashMap::new();
+                let mut folded = HashSet::new();
+                for idx in &matched {
+                    let name = state.installed_packages[*idx].name.as_str();
+                    if let Some(parent) = subpackage_parent(name) {
+                        if matched_names.contains(parent) {
+                            variants.entry(parent.to_string()).or_default().push(*idx);
+                            folded.insert(*idx);
+                        }
+                    }
+                }
+                for children in variants.values_mut() {
+                    children.sort_by(|a, b| {
+                        state.installed_packages[*a]
+                            .name
+                            .cmp(&state.installed_packages[*b].name)
+                    });
+                }
+
+                // Keep each parent's variants directly below it when expanded.
+                matched = matched
+                    .into_iter()
+                    .filter(|idx| !folded.contains(idx))
+                    .flat_map(|idx| {
+                        let name = &state.installed_packages[idx].name;
+                        let children = variants
+                            .get(name)
+                            .filter(|_| state.installed_expanded_groups.contains(name))
+                            .cloned()
+                            .unwrap_or_default();
+                        std::iter::once(idx).chain(children)
+                    })
+                    .collect();
+                let groups = variants
+                    .into_iter()
+                    .map(|(parent, children)| {
+                        let names = children
+                            .into_iter()
+                            .map(|idx| state.installed_packages[idx].name.clone())
+                            .collect();
+                        (parent, names)
+                    })
+                    .collect();
+                state.installed_variant_groups = groups;
+            }
 
             state.installed_filtered = matched.clone();
             if let Some(selected) = state.selected_installed {
@@ -1203,7 +1700,9 @@ impl AppController {
             let selected_index = state.selected_installed;
 
             let filtered_count = matched.len();
-            let status_message = if total_installed == 0 {
+            let status_message = if total_installed == 0 && state.installed_refresh_in_progress {
+                Some("Loading installed packages…".to_string())
+            } else if total_installed == 0 {
                 Some("No packages are installed yet. Install something from Discover.".to_string())
             } else if filtered_count == 0 {
                 if filter_mode == InstalledFilter::Updates {
@@ -1221,7 +1720,14 @@ impl AppController {
                 ))
             };
 
-            (matched, status_message, selected_index, total_installed, filter_mode)
+            (
+                matched,
+                status_message,
+                selected_index,
+                total_installed,
+                filter_mode,
+                state.installed_refresh_in_progress,
+            )
         };
 
         let store = &self.widgets.installed.list_store;
@@ -1242,8 +1748,13 @@ impl AppController {
 
         self.set_installed_status_message(status_message.clone());
 
-        // Switch between list and no-results views
-        if matched.is_empty() {
+        // Switch between list, loading and no-results views
+        if matched.is_empty() && total_installed == 0 && refreshing {
+            self.widgets
+                .installed
+                .installed_results_stack
+                .set_visible_child_name("loading");
+        } else if matched.is_empty() {
             // Update the no-results page description based on context
             let description = if total_installed == 0 {
                 "No packages are installed yet. Install something from Discover."
@@ -1277,11 +1788,16 @@ impl AppController {
             remove_disabled,
             has_update,
             is_selected,
+            is_held,
+            is_queued,
             row_buttons_visible,
             detail_open,
             updates_busy,
             pin_in_progress,
             refreshing,
+            variants,
+            variants_expanded,
+            is_variant,
         ) = {
             let state = self.state.borrow();
             let Some(pkg) = state.installed_packages.get(package_index).cloned() else {
@@ -1290,21 +1806,36 @@ impl AppController {
             let remove_disabled = state.remove_in_progress || state.installed_refresh_in_progress;
             let has_update = state.available_update_names.contains(&pkg.name);
             let is_selected = state.installed_selected.contains(&pkg.name);
+            let is_held = state.held_set.contains(&pkg.name);
+            let is_queued = state.is_queued(&pkg.name);
             let row_buttons_visible = state.installed_row_buttons_visible;
             let detail_open = state.installed_detail_package.is_some();
             let updates_busy = state.update_in_progress || state.updates_loading;
             let pin_in_progress = state.pin_in_progress;
             let refreshing = state.installed_refresh_in_progress;
+            let variants = state
+                .installed_variant_groups
+                .get(&pkg.name)
+                .cloned()
+                .unwrap_or_default();
+            let variants_expanded = state.installed_expanded_groups.contains(&pkg.name);
+            let is_variant = subpackage_parent(&pkg.name)
+                .is_some_and(|parent| state.installed_variant_groups.contains_key(parent));
             (
                 pkg,
                 remove_disabled,
                 has_update,
                 is_selected,
+                is_held,
+                is_queued,
                 row_buttons_visible,
                 detail_open,
                 updates_busy,
                 pin_in_progress,
                 refreshing,
+                variants,
+                variants_expanded,
+                is_variant,
             )
         };
 
@@ -1325,11 +1856,14 @@ impl AppController {
         row.set_title_lines(1);
         row.set_subtitle_lines(2);
 
+        let mut tooltip = package_row_tooltip(&pkg);
         if let Some(changelog) = &pkg.changelog {
             if !changelog.is_empty() {
-                row.set_tooltip_text(Some(changelog));
+                tooltip.push_str("\n\n");
+                tooltip.push_str(changelog);
             }
         }
+        row.set_tooltip_text(Some(&tooltip));
 
         let weak_self = Rc::downgrade(self);
 
@@ -1343,11 +1877,14 @@ impl AppController {
             }
         });
 
-        let icon = gtk::Image::from_resource(icon_resource_for_package(&pkg.name));
+        let icon = package_icon(&pkg.name);
         icon.set_pixel_size(32);
         icon.set_margin_start(8);
         icon.set_margin_end(6);
         icon.set_valign(gtk::Align::Center);
+        if self.compact_lists() {
+            compact_package_row(&row, &icon);
+        }
 
         let prefix_box = gtk::Box::builder()
             .orientation(gtk::Orientation::Horizontal)
@@ -1356,9 +1893,68 @@ impl AppController {
             .build();
         prefix_box.append(&check_button);
         prefix_box.append(&icon);
+        if is_variant {
+            prefix_box.set_margin_start(24);
+        }
         row.add_prefix(&prefix_box);
 
+        if !variants.is_empty() {
+            let count_label = gtk::Label::new(Some(&format!(
+                "{} variant{}",
+                variants.len(),
+                if variants.len() == 1 { "" } else { "s" }
+            )));
+            let arrow = gtk::Image::from_icon_name(if variants_expanded {
+                "pan-down-symbolic"
+            } else {
+                "pan-end-symbolic"
+            });
+            let content = gtk::Box::builder()
+                .orientation(gtk::Orientation::Horizontal)
+                .spacing(4)
+                .build();
+            content.append(&count_label);
+            content.append(&arrow);
+
+            let expander_button = gtk::Button::builder().child(&content).build();
+            expander_button.add_css_class("flat");
+            expander_button.set_valign(gtk::Align::Center);
+            expander_button.set_tooltip_text(Some(&variants.join("\n")));
+            let parent = pkg.name.clone();
+            let weak_self = Rc::downgrade(self);
+            expander_button.connect_clicked(move |_| {
+                if let Some(controller) = weak_self.upgrade() {
+                    controller.toggle_installed_variant_group(&parent);
+                }
+            });
+            row.add_suffix(&expander_button);
+        }
+
+        if is_held {
+            let badge = gtk::Label::new(Some("Held"));
+            badge.add_css_class("tag");
+            badge.add_css_class("accent");
+            badge.set_valign(gtk::Align::Center);
+            badge.set_tooltip_text(Some("This package is held at its current version."));
+            row.add_suffix(&badge);
+        }
+
+        // The row's own Update button says as much when it is shown.
         let show_actions = row_buttons_visible && !detail_open;
+        if has_update && !show_actions && self.marks_available_updates() {
+            row.add_suffix(&update_badge());
+        }
+
+        if is_queued {
+            let badge = gtk::Label::new(Some("Queued"));
+            badge.add_css_class("tag");
+            badge.set_valign(gtk::Align::Center);
+            badge.set_tooltip_text(Some(
+                "This package's removal starts when the current operation finishes.",
+            ));
+            row.add_suffix(&badge);
+        }
+
         let actions_box = gtk::Box::builder()
             .orientation(gtk::Orientation::Horizontal)
             .spacing(6)
@@ -1429,7 +2025,7 @@ impl AppController {
 
         let remove_button = gtk::Button::builder().label("Remove").build();
         remove_button.add_css_class("destructive-action");
-        remove_button.set_sensitive(!remove_disabled);
+        remove_button.set_sensitive(!refreshing && !is_queued);
         remove_button.set_valign(gtk::Align::Center);
 
         let package_name = pkg.name.clone();
@@ -1495,3 +2091,34 @@ impl AppController {
         }
     }
 }
+
+/// The folder to show for an installed path: the path itself for a
+/// directory, otherwise its parent. `None` once it no longer exists.
+fn existing_location(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        return Some(path.to_path_buf());
+    }
+    if !path.exists() && !path.is_symlink() {
+        return None;
+    }
+    path.parent()
+        .filter(|parent| parent.is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Deepest folder containing every file of the package, so a package that
+/// installs under /usr/share/foo opens there rather than at /usr.
+fn install_prefix(files: &[String]) -> Option<PathBuf> {
+    let mut prefix: Option<PathBuf> = None;
+    for file in files {
+        let parent = Path::new(file).parent()?;
+        prefix = Some(match prefix {
+            None => parent.to_path_buf(),
+            Some(current) => current
+                .ancestors()
+                .find(|ancestor| parent.starts_with(ancestor))
+                .map(Path::to_path_buf)?,
+        });
+    }
+    prefix.filter(|path| path.is_dir())
+}
@@ -1,4 +1,5 @@
 pub(crate) mod app;
+pub(crate) mod changes;
 pub(crate) mod discover;
 pub(crate) mod installed;
 pub(crate) mod operations;
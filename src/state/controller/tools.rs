@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::rc::Rc;
 use std::thread;
 
@@ -5,17 +6,41 @@ use gtk4 as gtk;
 use libadwaita as adw;
 
 use adw::prelude::*;
+use gtk::{gio, glib};
 
 use chrono::{DateTime, Utc};
 
+use crate::helpers::{clear_listbox, format_relative_time};
+use crate::settings::RemovedPackage;
 use crate::state::controller::AppController;
 use crate::state::types::AppMessage;
 use crate::types::CommandResult;
 use crate::xbps::{
-    run_xbps_alternatives_list, run_xbps_pkgdb_check, run_xbps_reconfigure_all,
-    run_xbps_remove_cache, run_xbps_remove_orphans, summarize_output_line,
+    CacheCleanupPlan, IntegrityIssue, IntegrityIssueKind, cache_size_bytes, execute_cache_cleanup,
+    format_size, plan_cache_cleanup, privilege_failure_reported, run_xbps_alternatives_list,
+    run_xbps_alternatives_set, run_xbps_pkgdb_check, run_xbps_pkgdb_repair,
+    run_xbps_pkgdb_set_hold_batches, run_xbps_reconfigure_all, run_xbps_remove_cache,
+    run_xbps_remove_orphans, run_xbps_sync_repos, run_xbps_verify_integrity, summarize_output_line,
 };
 
+/// Void handbook pages behind the Tools page and the application menu's
+/// documentation entry.
+const VOID_DOCS_XBPS: &str = "https://docs.voidlinux.org/xbps/index.html";
+const VOID_DOCS_ADVANCED: &str = "https://docs.voidlinux.org/xbps/advanced-usage.html";
+const VOID_DOCS_REPOSITORIES: &str = "https://docs.voidlinux.org/xbps/repositories/index.html";
+const VOID_DOCS_TROUBLESHOOTING: &str =
+    "https://docs.voidlinux.org/xbps/troubleshooting/index.html";
+const XBPS_ALTERNATIVES_MANUAL: &str = "https://man.voidlinux.org/xbps-alternatives.1";
+const XBPS_RECONFIGURE_MANUAL: &str = "https://man.voidlinux.org/xbps-reconfigure.1";
+
+/// How many removed packages the Tools page remembers.
+const RECENTLY_REMOVED_LIMIT: usize = 30;
+
+/// Packages passed to each `xbps-pkgdb` call when holding or unholding
+/// everything, so progress can be reported between calls. All calls share
+/// one authorization prompt.
+const HOLD_ALL_BATCH_SIZE: usize = 100;
+
 impl AppController {
     pub(crate) fn on_cleanup_requested(self: &Rc<Self>) {
         self.start_maintenance_task(MaintenanceTask::Cleanup);
@@ -25,6 +50,21 @@ impl AppController {
         self.start_maintenance_task(MaintenanceTask::Pkgdb);
     }
 
+    pub(crate) fn on_integrity_check_requested(self: &Rc<Self>) {
+        self.start_maintenance_task(MaintenanceTask::Integrity);
+    }
+
+    pub(crate) fn on_pkgdb_repair_requested(self: &Rc<Self>) {
+        self.confirm_action(
+            "Repair package database?",
+            "This runs \"xbps-pkgdb -a\" as root and rewrites package metadata. Only use it to recover from a damaged package database.",
+            "Repair",
+            |controller| {
+                controller.start_maintenance_task(MaintenanceTask::PkgdbRepair);
+            },
+        );
+    }
+
     pub(crate) fn on_reconfigure_requested(self: &Rc<Self>) {
         self.start_maintenance_task(MaintenanceTask::Reconfigure);
     }
@@ -33,17 +73,299 @@ impl AppController {
         self.start_maintenance_task(MaintenanceTask::Alternatives);
     }
 
-    pub(crate) fn on_cache_clean_requested(self: &Rc<Self>, keep_n: u32) {
-        if keep_n == 1 {
-            // Use fast path with xbps-remove -o
-            self.start_maintenance_task(MaintenanceTask::CacheClean);
+    pub(crate) fn on_sync_repos_requested(self: &Rc<Self>) {
+        self.start_maintenance_task(MaintenanceTask::SyncRepos);
+    }
+
+    pub(crate) fn on_hold_all_requested(self: &Rc<Self>, hold: bool) {
+        let count = self.hold_all_targets(hold).len();
+        if count == 0 {
+            self.show_toast(if hold {
+                "Every installed package is already held."
+            } else {
+                "No packages are on hold."
+            });
+            return;
+        }
+        let (heading, body, label, task) = if hold {
+            (
+                "Hold all installed packages?",
+                format!(
+                    "{} package{} will stay at their current version until unheld, including through \"Update All\".",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                ),
+                "Hold All",
+                MaintenanceTask::HoldAll,
+            )
+        } else {
+            (
+                "Unhold all packages?",
+                format!(
+                    "{} held package{} will receive updates again.",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                ),
+                "Unhold All",
+                MaintenanceTask::UnholdAll,
+            )
+        };
+        self.confirm_action(heading, &body, label, move |controller| {
+            controller.start_maintenance_task(task);
+        });
+    }
+
+    /// Installed packages not yet held, or the held ones when unholding.
+    fn hold_all_targets(&self, hold: bool) -> Vec<String> {
+        let state = self.state.borrow();
+        let mut packages: Vec<String> = if hold {
+            state
+                .installed_packages
+                .iter()
+                .map(|pkg| pkg.name.clone())
+                .filter(|name| !state.held_set.contains(name))
+                .collect()
         } else {
-            // Use custom cleanup logic
-            self.start_cache_clean_keep_n(keep_n);
+            state.held_set.iter().cloned().collect()
+        };
+        packages.sort();
+        packages.dedup();
+        packages
+    }
+
+    pub(crate) fn update_hold_all_progress(&self, done: usize, total: usize) {
+        self.state.borrow_mut().hold_all_progress = Some((done, total));
+        self.update_tools_status_footer();
+    }
+
+    pub(crate) fn on_alternative_selected(self: &Rc<Self>, group: String, package: String) {
+        // The list is rebuilt once the switch finishes.
+        if let Some(dialog) = self.alternatives_dialog.borrow().as_ref() {
+            dialog.content_area().set_sensitive(false);
         }
+        self.state.borrow_mut().alternative_set_in_progress = true;
+        self.refresh_busy_indicator();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_alternatives_set(&group, &package);
+            let _ = sender.send(AppMessage::AlternativeSetFinished {
+                group,
+                package,
+                result,
+            });
+        });
     }
 
-    fn start_cache_clean_keep_n(self: &Rc<Self>, keep_n: u32) {
+    pub(crate) fn finish_alternative_set(
+        self: &Rc<Self>,
+        group: String,
+        package: String,
+        result: Result<CommandResult, String>,
+    ) {
+        self.state.borrow_mut().alternative_set_in_progress = false;
+        let failure = match &result {
+            Ok(command) if command.success() => None,
+            Ok(command) => {
+                let detail = if command.stderr.trim().is_empty() {
+                    command.stdout.trim()
+                } else {
+                    command.stderr.trim()
+                };
+                Some(detail.to_string())
+            }
+            Err(err) => Some(err.clone()),
+        };
+        // Cancelled or agent-less attempts were already reported.
+        let privilege_reported = privilege_failure_reported(&result);
+        match failure {
+            None => self.show_toast(&format!("{} is now provided by {}.", group, package)),
+            Some(_) if privilege_reported => {}
+            Some(detail) => {
+                let message = if detail.is_empty() {
+                    format!("Couldn't switch {} to {}.", group, package)
+                } else {
+                    format!("Couldn't switch {} to {}: {}", group, package, detail)
+                };
+                self.show_error_dialog("Alternative Not Changed", &message);
+            }
+        }
+        self.on_alternatives_requested();
+    }
+
+    pub(crate) fn on_cache_clean_requested(self: &Rc<Self>, keep_n: u32) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.maintenance_cache_clean.running || state.cache_clean_planning {
+                return;
+            }
+            state.cache_clean_planning = true;
+        }
+
+        // Work out what would be removed first so the user can review it.
+        // Keeping one version used to go straight to xbps-remove, but that
+        // drops whatever the repository index no longer lists rather than
+        // the files the preview shows, so every count runs from the plan.
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = plan_cache_cleanup(keep_n);
+            let _ = sender.send(AppMessage::CacheCleanupPlanned { result });
+        });
+    }
+
+    /// Plans removal of every cached package, current versions included.
+    pub(crate) fn on_cache_clear_all_requested(self: &Rc<Self>) {
+        self.on_cache_clean_requested(0);
+    }
+
+    pub(crate) fn finish_cache_cleanup_plan(
+        self: &Rc<Self>,
+        result: Result<CacheCleanupPlan, String>,
+    ) {
+        self.state.borrow_mut().cache_clean_planning = false;
+
+        match result {
+            Ok(plan) if plan.is_empty() && plan.keep_n == 0 => {
+                self.show_toast("The package cache is already empty.");
+            }
+            Ok(plan) if plan.is_empty() => {
+                self.show_toast(&format!(
+                    "Nothing to clean. No package has more than {} cached version(s).",
+                    plan.keep_n
+                ));
+            }
+            Ok(plan) if plan.keep_n == 0 => self.confirm_cache_clear_all(plan),
+            Ok(plan) => self.show_cache_cleanup_preview(plan),
+            Err(err) => self.show_error_dialog("Unable to read package cache", &err),
+        }
+    }
+
+    fn confirm_cache_clear_all(self: &Rc<Self>, plan: CacheCleanupPlan) {
+        let body = format!(
+            "All {} cached package file(s) will be deleted, freeing {}. Packages are downloaded again the next time they are installed or updated.",
+            plan.files.len(),
+            format_size(plan.total_size())
+        );
+        self.confirm_action(
+            "Clear entire package cache?",
+            &body,
+            "Clear cache",
+            move |controller| {
+                controller.start_cache_clean(plan);
+            },
+        );
+    }
+
+    fn show_cache_cleanup_preview(self: &Rc<Self>, plan: CacheCleanupPlan) {
+        let grouped = plan.by_package();
+        let summary = format!(
+            "{} file(s) from {} package(s) will be removed, freeing {}. The {} newest version(s) of each package are kept.",
+            plan.files.len(),
+            grouped.len(),
+            format_size(plan.total_size()),
+            plan.keep_n
+        );
+
+        let mut listing = String::new();
+        for (package, files) in &grouped {
+            listing.push_str(package);
+            listing.push('\n');
+            for file in files {
+                let name = file
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                listing.push_str(&format!("    {} ({})\n", name, format_size(file.size)));
+            }
+        }
+
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title("Clean package cache?")
+            .default_width(520)
+            .default_height(420)
+            .build();
+        dialog.add_button("Cancel", gtk::ResponseType::Cancel);
+        let confirm = dialog.add_button("Clean cache", gtk::ResponseType::Accept);
+        confirm.add_css_class("destructive-action");
+        dialog.set_default_response(gtk::ResponseType::Cancel);
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let info_label = gtk::Label::builder()
+            .label(&summary)
+            .halign(gtk::Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .wrap_mode(gtk::pango::WrapMode::WordChar)
+            .build();
+        content.append(&info_label);
+        content.append(&self.docs_link_button(MaintenanceTask::CacheClean));
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(280)
+            .build();
+        let buffer = gtk::TextBuffer::new(None);
+        buffer.set_text(listing.trim_end());
+        let text_view = gtk::TextView::builder()
+            .buffer(&buffer)
+            .editable(false)
+            .monospace(true)
+            .wrap_mode(gtk::WrapMode::None)
+            .build();
+        text_view.set_cursor_visible(false);
+        scroller.set_child(Some(&text_view));
+        content.append(&scroller);
+
+        let controller_weak = Rc::downgrade(self);
+        let plan = RefCell::new(Some(plan));
+        dialog.connect_response(move |dialog, response| {
+            dialog.close();
+            if response != gtk::ResponseType::Accept {
+                return;
+            }
+            if let (Some(controller), Some(plan)) =
+                (controller_weak.upgrade(), plan.borrow_mut().take())
+            {
+                controller.start_cache_clean(plan);
+            }
+        });
+
+        dialog.present();
+    }
+
+    pub(crate) fn refresh_cache_size(&self) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = cache_size_bytes()
+                .map_err(|err| format!("Failed to measure package cache: {}", err));
+            let _ = sender.send(AppMessage::CacheSizeLoaded { result });
+        });
+    }
+
+    pub(crate) fn finish_cache_size(&self, result: Result<u64, String>) {
+        let label = &self.widgets.tools.cache_size_label;
+        match result {
+            Ok(bytes) => {
+                label.set_text(&format!("Cache: {}", format_size(bytes)));
+                label.set_tooltip_text(Some("Current size of /var/cache/xbps"));
+            }
+            Err(err) => {
+                label.set_text("Cache: unknown");
+                label.set_tooltip_text(Some(&err));
+            }
+        }
+    }
+
+    fn start_cache_clean(self: &Rc<Self>, plan: CacheCleanupPlan) {
         {
             let mut state = self.state.borrow_mut();
             let action_state = &mut state.maintenance_cache_clean;
@@ -61,13 +383,11 @@ impl AppController {
         }
 
         self.update_tools_actions();
+        self.refresh_busy_indicator();
 
         let sender = self.sender.clone();
         thread::spawn(move || {
-            use crate::xbps::clean_cache_keep_n;
-            use crate::xbps::format_size;
-
-            let result = match clean_cache_keep_n(keep_n) {
+            let result = match execute_cache_cleanup(&plan) {
                 Ok((count, size)) => {
                     let size_str = format_size(size);
                     Ok(CommandResult {
@@ -92,9 +412,14 @@ impl AppController {
             let action_state = match task {
                 MaintenanceTask::Cleanup => &mut state.maintenance_cleanup,
                 MaintenanceTask::Pkgdb => &mut state.maintenance_pkgdb,
+                MaintenanceTask::Integrity => &mut state.maintenance_integrity,
+                MaintenanceTask::PkgdbRepair => &mut state.maintenance_pkgdb_repair,
                 MaintenanceTask::Reconfigure => &mut state.maintenance_reconfigure,
                 MaintenanceTask::Alternatives => &mut state.maintenance_alternatives,
                 MaintenanceTask::CacheClean => &mut state.maintenance_cache_clean,
+                MaintenanceTask::HoldAll => &mut state.maintenance_hold_all,
+                MaintenanceTask::UnholdAll => &mut state.maintenance_unhold_all,
+                MaintenanceTask::SyncRepos => &mut state.maintenance_sync_repos,
             };
 
             if action_state.running {
@@ -109,16 +434,60 @@ impl AppController {
             action_state.last_finished_at = None;
         }
 
+        if matches!(
+            task,
+            MaintenanceTask::PkgdbRepair | MaintenanceTask::SyncRepos
+        ) {
+            self.widgets.tools.repair_log_buffer.set_text("");
+            self.widgets
+                .tools
+                .repair_log_revealer
+                .set_reveal_child(true);
+        }
+
+        let hold_targets = match task {
+            MaintenanceTask::HoldAll => self.hold_all_targets(true),
+            MaintenanceTask::UnholdAll => self.hold_all_targets(false),
+            _ => Vec::new(),
+        };
+        if !hold_targets.is_empty() {
+            self.state.borrow_mut().hold_all_progress = Some((0, hold_targets.len()));
+        }
+
         self.update_tools_actions();
+        self.refresh_busy_indicator();
 
         let sender = self.sender.clone();
         thread::spawn(move || {
             let result = match task {
                 MaintenanceTask::Cleanup => run_xbps_remove_orphans(),
                 MaintenanceTask::Pkgdb => run_xbps_pkgdb_check(),
+                MaintenanceTask::Integrity => {
+                    let result = run_xbps_verify_integrity();
+                    let _ = sender.send(AppMessage::IntegrityCheckFinished { result });
+                    return;
+                }
+                MaintenanceTask::PkgdbRepair => run_xbps_pkgdb_repair(|line| {
+                    let _ = sender.send(AppMessage::MaintenanceLogLine { line });
+                }),
                 MaintenanceTask::Reconfigure => run_xbps_reconfigure_all(),
                 MaintenanceTask::Alternatives => run_xbps_alternatives_list(),
                 MaintenanceTask::CacheClean => run_xbps_remove_cache(),
+                MaintenanceTask::SyncRepos => run_xbps_sync_repos(|line| {
+                    let _ = sender.send(AppMessage::MaintenanceLogLine { line });
+                }),
+                MaintenanceTask::HoldAll | MaintenanceTask::UnholdAll => {
+                    let hold = matches!(task, MaintenanceTask::HoldAll);
+                    let total = hold_targets.len();
+                    run_xbps_pkgdb_set_hold_batches(
+                        &hold_targets,
+                        hold,
+                        HOLD_ALL_BATCH_SIZE,
+                        |done| {
+                            let _ = sender.send(AppMessage::HoldAllProgress { done, total });
+                        },
+                    )
+                }
             };
             let _ = sender.send(AppMessage::MaintenanceFinished { task, result });
         });
@@ -131,6 +500,7 @@ impl AppController {
     ) {
         let finished_at = Utc::now();
         let copy = maintenance_copy(task);
+        let reported = privilege_failure_reported(&result);
 
         let (success, status_message, toast_message, stdout_store, stderr_store) = match result {
             Ok(cmd_result) => {
@@ -183,8 +553,13 @@ impl AppController {
                 MaintenanceTask::Cleanup => &mut state.maintenance_cleanup,
                 MaintenanceTask::CacheClean => &mut state.maintenance_cache_clean,
                 MaintenanceTask::Pkgdb => &mut state.maintenance_pkgdb,
+                MaintenanceTask::Integrity => &mut state.maintenance_integrity,
+                MaintenanceTask::PkgdbRepair => &mut state.maintenance_pkgdb_repair,
                 MaintenanceTask::Reconfigure => &mut state.maintenance_reconfigure,
                 MaintenanceTask::Alternatives => &mut state.maintenance_alternatives,
+                MaintenanceTask::HoldAll => &mut state.maintenance_hold_all,
+                MaintenanceTask::UnholdAll => &mut state.maintenance_unhold_all,
+                MaintenanceTask::SyncRepos => &mut state.maintenance_sync_repos,
             };
             action_state.running = false;
             action_state.last_success = Some(success);
@@ -196,17 +571,204 @@ impl AppController {
             // Update footer status
             state.tools_status_message = Some(status_message.clone());
             state.tools_status_is_error = !success;
+            state.hold_all_progress = None;
         }
 
         self.update_tools_actions();
 
+        // Batches before a failure were applied too.
+        if matches!(task, MaintenanceTask::HoldAll | MaintenanceTask::UnholdAll) {
+            self.refresh_held_packages();
+        }
+
+        if matches!(task, MaintenanceTask::Cleanup | MaintenanceTask::CacheClean) {
+            self.refresh_cache_size();
+            self.refresh_free_space();
+        }
+
+        // The full-repository list is rebuilt from the new index when next
+        // opened; searches read the index directly.
+        if success && matches!(task, MaintenanceTask::SyncRepos) {
+            self.state.borrow_mut().repository_index.clear();
+        }
+
         if success && matches!(task, MaintenanceTask::Alternatives) {
             if let Some(stdout) = stdout_store {
                 self.show_alternatives_dialog(&stdout);
             }
         }
 
-        self.show_toast(&toast_message);
+        // Cancelled or agent-less attempts were already reported.
+        if !reported {
+            self.show_toast(&toast_message);
+        }
+    }
+
+    pub(crate) fn finish_integrity_check(
+        self: &Rc<Self>,
+        result: Result<Vec<IntegrityIssue>, String>,
+    ) {
+        let issues = match result {
+            Ok(issues) => issues,
+            Err(err) => {
+                self.finish_maintenance(MaintenanceTask::Integrity, Err(err));
+                return;
+            }
+        };
+
+        let mut packages: Vec<&str> = Vec::new();
+        for issue in &issues {
+            if !packages.contains(&issue.package.as_str()) {
+                packages.push(issue.package.as_str());
+            }
+        }
+        let summary = if issues.is_empty() {
+            CommandResult {
+                code: Some(0),
+                stdout: String::new(),
+                stderr: String::new(),
+            }
+        } else {
+            CommandResult {
+                code: Some(1),
+                stdout: String::new(),
+                stderr: format!(
+                    "{} package{} with file problems: {}",
+                    packages.len(),
+                    if packages.len() == 1 { "" } else { "s" },
+                    packages.join(", ")
+                ),
+            }
+        };
+        self.finish_maintenance(MaintenanceTask::Integrity, Ok(summary));
+
+        if !issues.is_empty() {
+            self.show_integrity_dialog(&issues);
+        }
+    }
+
+    fn show_integrity_dialog(self: &Rc<Self>, issues: &[IntegrityIssue]) {
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title("Installed file problems")
+            .default_width(620)
+            .default_height(420)
+            .build();
+        dialog.add_button("Close", gtk::ResponseType::Close);
+        dialog.connect_response(|dialog, _| dialog.close());
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let info_label = gtk::Label::builder()
+            .label("These packages have files that differ from what was installed. Reinstalling a package restores its files; edited configuration files are usually expected.")
+            .halign(gtk::Align::Start)
+            .xalign(0.0)
+            .wrap(true)
+            .build();
+        info_label.add_css_class("dim-label");
+        content.append(&info_label);
+        content.append(&self.docs_link_button(MaintenanceTask::Integrity));
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .valign(gtk::Align::Start)
+            .build();
+        list.add_css_class("boxed-list");
+
+        let mut grouped: Vec<(&str, Vec<&IntegrityIssue>)> = Vec::new();
+        for issue in issues {
+            match grouped.iter_mut().find(|(name, _)| *name == issue.package) {
+                Some((_, entries)) => entries.push(issue),
+                None => grouped.push((issue.package.as_str(), vec![issue])),
+            }
+        }
+
+        for (package, entries) in grouped {
+            let row = adw::ExpanderRow::builder()
+                .title(glib::markup_escape_text(package).as_str())
+                .subtitle(integrity_summary(&entries))
+                .build();
+            for issue in entries {
+                let detail = adw::ActionRow::builder()
+                    .title(issue.kind.label())
+                    .subtitle(glib::markup_escape_text(&issue.detail).as_str())
+                    .build();
+                detail.set_subtitle_selectable(true);
+                row.add_row(&detail);
+            }
+            list.append(&row);
+        }
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .min_content_height(320)
+            .child(&list)
+            .build();
+        content.append(&scroller);
+
+        dialog.present();
+    }
+
+    /// Button opening the documentation for `task`, shown in the dialogs the
+    /// Tools page opens.
+    pub(crate) fn docs_link_button(self: &Rc<Self>, task: MaintenanceTask) -> gtk::Button {
+        let url = maintenance_copy(task).docs_url;
+        let button = gtk::Button::builder()
+            .label("Learn more in the Void documentation")
+            .halign(gtk::Align::Start)
+            .tooltip_text(url)
+            .build();
+        button.add_css_class("link");
+        let controller_weak = Rc::downgrade(self);
+        button.connect_clicked(move |_| {
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.open_docs_url(url);
+            }
+        });
+        button
+    }
+
+    pub(crate) fn open_docs_url(&self, url: &str) {
+        if let Err(err) = gio::AppInfo::launch_default_for_uri(url, None::<&gio::AppLaunchContext>)
+        {
+            self.show_toast(&format!("Couldn't open the documentation: {}", err));
+        }
+    }
+
+    /// Opens the handbook page for the page in view: troubleshooting for the
+    /// Tools page, the XBPS guide everywhere else.
+    pub(crate) fn open_contextual_docs(&self) {
+        let page = self.widgets.view_stack.visible_child_name();
+        let url = if page.as_deref() == Some("tools") {
+            VOID_DOCS_TROUBLESHOOTING
+        } else {
+            VOID_DOCS_XBPS
+        };
+        self.open_docs_url(url);
+    }
+
+    pub(crate) fn append_maintenance_log_line(&self, line: &str) {
+        let buffer = &self.widgets.tools.repair_log_buffer;
+        let mut iter = buffer.end_iter();
+        if buffer.char_count() > 0 {
+            buffer.insert(&mut iter, "\n");
+        }
+        buffer.insert(&mut iter, line);
+
+        let iter = buffer.end_iter();
+        let mark = buffer.create_mark(None, &iter, false);
+        self.widgets
+            .tools
+            .repair_log_view
+            .scroll_to_mark(&mark, 0.0, true, 1.0, 1.0);
+        buffer.delete_mark(&mark);
     }
 
     pub(crate) fn update_tools_actions(&self) {
@@ -223,12 +785,30 @@ impl AppController {
             &self.widgets.tools.cache_clean_button,
             &self.widgets.tools.cache_clean_spinner,
         );
+        self.update_maintenance_row(
+            MaintenanceTask::CacheClean,
+            &state.maintenance_cache_clean,
+            &self.widgets.tools.cache_clear_all_button,
+            &self.widgets.tools.cache_clear_all_spinner,
+        );
         self.update_maintenance_row(
             MaintenanceTask::Pkgdb,
             &state.maintenance_pkgdb,
             &self.widgets.tools.pkgdb_button,
             &self.widgets.tools.pkgdb_spinner,
         );
+        self.update_maintenance_row(
+            MaintenanceTask::Integrity,
+            &state.maintenance_integrity,
+            &self.widgets.tools.integrity_button,
+            &self.widgets.tools.integrity_spinner,
+        );
+        self.update_maintenance_row(
+            MaintenanceTask::PkgdbRepair,
+            &state.maintenance_pkgdb_repair,
+            &self.widgets.tools.pkgdb_repair_button,
+            &self.widgets.tools.pkgdb_repair_spinner,
+        );
         self.update_maintenance_row(
             MaintenanceTask::Reconfigure,
             &state.maintenance_reconfigure,
@@ -241,6 +821,24 @@ impl AppController {
             &self.widgets.tools.alternatives_button,
             &self.widgets.tools.alternatives_spinner,
         );
+        self.update_maintenance_row(
+            MaintenanceTask::HoldAll,
+            &state.maintenance_hold_all,
+            &self.widgets.tools.hold_all_button,
+            &self.widgets.tools.hold_all_spinner,
+        );
+        self.update_maintenance_row(
+            MaintenanceTask::UnholdAll,
+            &state.maintenance_unhold_all,
+            &self.widgets.tools.unhold_all_button,
+            &self.widgets.tools.unhold_all_spinner,
+        );
+        self.update_maintenance_row(
+            MaintenanceTask::SyncRepos,
+            &state.maintenance_sync_repos,
+            &self.widgets.tools.sync_repos_button,
+            &self.widgets.tools.sync_repos_spinner,
+        );
         drop(state);
         self.update_tools_status_footer();
     }
@@ -274,17 +872,36 @@ impl AppController {
             Some((MaintenanceTask::CacheClean, &state.maintenance_cache_clean))
         } else if state.maintenance_pkgdb.running {
             Some((MaintenanceTask::Pkgdb, &state.maintenance_pkgdb))
+        } else if state.maintenance_integrity.running {
+            Some((MaintenanceTask::Integrity, &state.maintenance_integrity))
+        } else if state.maintenance_pkgdb_repair.running {
+            Some((
+                MaintenanceTask::PkgdbRepair,
+                &state.maintenance_pkgdb_repair,
+            ))
         } else if state.maintenance_reconfigure.running {
             Some((MaintenanceTask::Reconfigure, &state.maintenance_reconfigure))
         } else if state.maintenance_alternatives.running {
             Some((MaintenanceTask::Alternatives, &state.maintenance_alternatives))
+        } else if state.maintenance_hold_all.running {
+            Some((MaintenanceTask::HoldAll, &state.maintenance_hold_all))
+        } else if state.maintenance_unhold_all.running {
+            Some((MaintenanceTask::UnholdAll, &state.maintenance_unhold_all))
+        } else if state.maintenance_sync_repos.running {
+            Some((MaintenanceTask::SyncRepos, &state.maintenance_sync_repos))
         } else {
             None
         };
 
         if let Some((task, _)) = running_task {
             let copy = maintenance_copy(task);
-            self.widgets.tools.status_label.set_text(copy.running_text);
+            let text = match (task, state.hold_all_progress) {
+                (MaintenanceTask::HoldAll | MaintenanceTask::UnholdAll, Some((done, total))) => {
+                    format!("{} {} of {}", copy.running_text, done, total)
+                }
+                _ => copy.running_text.to_string(),
+            };
+            self.widgets.tools.status_label.set_text(&text);
             self.widgets.tools.status_label.remove_css_class("success");
             self.widgets.tools.status_label.remove_css_class("error");
             self.widgets.tools.status_revealer.set_reveal_child(true);
@@ -302,15 +919,125 @@ impl AppController {
             self.widgets.tools.status_revealer.set_reveal_child(false);
         }
     }
+
+    /// Remembers `packages` as just removed, newest first, dropping the
+    /// oldest entries past [`RECENTLY_REMOVED_LIMIT`].
+    pub(crate) fn record_removed_packages(self: &Rc<Self>, packages: &[String]) {
+        if packages.is_empty() {
+            return;
+        }
+        {
+            let mut settings = self.settings.borrow_mut();
+            let removed = &mut settings.recently_removed;
+            removed.retain(|entry| !packages.contains(&entry.name));
+            let now = Utc::now();
+            for name in packages.iter().rev() {
+                removed.insert(
+                    0,
+                    RemovedPackage {
+                        name: name.clone(),
+                        removed_at: now,
+                    },
+                );
+            }
+            removed.truncate(RECENTLY_REMOVED_LIMIT);
+        }
+        self.persist_settings();
+        self.refresh_recently_removed_list();
+    }
+
+    /// Drops `package` from the recently removed list once it is back.
+    pub(crate) fn forget_removed_package(self: &Rc<Self>, package: &str) {
+        {
+            let mut settings = self.settings.borrow_mut();
+            let before = settings.recently_removed.len();
+            settings
+                .recently_removed
+                .retain(|entry| entry.name != package);
+            if settings.recently_removed.len() == before {
+                return;
+            }
+        }
+        self.persist_settings();
+        self.refresh_recently_removed_list();
+    }
+
+    pub(crate) fn refresh_recently_removed_list(self: &Rc<Self>) {
+        let list = &self.widgets.tools.recently_removed_list;
+        clear_listbox(list);
+
+        let entries = self.settings.borrow().recently_removed.clone();
+        self.widgets
+            .tools
+            .recently_removed_stack
+            .set_visible_child_name(if entries.is_empty() {
+                "placeholder"
+            } else {
+                "list"
+            });
+
+        for entry in entries {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&entry.name).as_str())
+                .subtitle(format!(
+                    "Removed {}",
+                    format_relative_time(entry.removed_at)
+                ))
+                .build();
+
+            let reinstall_button = gtk::Button::builder()
+                .label("Reinstall")
+                .valign(gtk::Align::Center)
+                .build();
+            let controller = Rc::downgrade(self);
+            let name = entry.name.clone();
+            reinstall_button.connect_clicked(move |_| {
+                if let Some(controller) = controller.upgrade() {
+                    controller.reinstall_removed_package(name.clone());
+                }
+            });
+            row.add_suffix(&reinstall_button);
+
+            let forget_button = gtk::Button::builder()
+                .icon_name("window-close-symbolic")
+                .tooltip_text("Remove from this list")
+                .valign(gtk::Align::Center)
+                .build();
+            forget_button.add_css_class("flat");
+            let controller = Rc::downgrade(self);
+            let name = entry.name;
+            forget_button.connect_clicked(move |_| {
+                if let Some(controller) = controller.upgrade() {
+                    controller.forget_removed_package(&name);
+                }
+            });
+            row.add_suffix(&forget_button);
+
+            list.append(&row);
+        }
+    }
+
+    fn reinstall_removed_package(self: &Rc<Self>, package: String) {
+        if self.state.borrow().install_in_progress {
+            self.show_toast("Wait for the current installation to finish.");
+            return;
+        }
+        self.execute_install_batch(vec![package]);
+    }
 }
 
 #[derive(Clone, Copy)]
 pub(crate) enum MaintenanceTask {
     Cleanup,
     Pkgdb,
+    Integrity,
+    PkgdbRepair,
     Reconfigure,
     Alternatives,
     CacheClean,
+    HoldAll,
+    UnholdAll,
+    SyncRepos,
 }
 
 #[derive(Default)]
@@ -332,6 +1059,8 @@ pub(crate) struct MaintenanceCopy {
     pub(crate) failure_prefix: &'static str,
     pub(crate) success_toast: &'static str,
     pub(crate) failure_toast: &'static str,
+    /// Documentation explaining what the tool does and when to use it.
+    pub(crate) docs_url: &'static str,
 }
 
 pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
@@ -343,6 +1072,7 @@ pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
             failure_prefix: "Cleanup ran into an issue",
             success_toast: "Cleanup complete.",
             failure_toast: "Cleanup failed.",
+            docs_url: VOID_DOCS_XBPS,
         },
         MaintenanceTask::Pkgdb => MaintenanceCopy {
             idle_text: "No database check yet.",
@@ -351,6 +1081,25 @@ pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
             failure_prefix: "Package database check hit a snag",
             success_toast: "Package database check complete.",
             failure_toast: "Package database check failed.",
+            docs_url: VOID_DOCS_TROUBLESHOOTING,
+        },
+        MaintenanceTask::Integrity => MaintenanceCopy {
+            idle_text: "No file check yet.",
+            running_text: "Checking installed files against their hashes...",
+            success_message: "Every installed file matches its package.",
+            failure_prefix: "File check found problems",
+            success_toast: "Installed files verified.",
+            failure_toast: "Some installed files have problems.",
+            docs_url: VOID_DOCS_TROUBLESHOOTING,
+        },
+        MaintenanceTask::PkgdbRepair => MaintenanceCopy {
+            idle_text: "No database repair yet.",
+            running_text: "Repairing the package database...",
+            success_message: "Package database repair finished.",
+            failure_prefix: "Package database repair hit a snag",
+            success_toast: "Package database repaired.",
+            failure_toast: "Package database repair failed.",
+            docs_url: VOID_DOCS_TROUBLESHOOTING,
         },
         MaintenanceTask::Reconfigure => MaintenanceCopy {
             idle_text: "Haven't reconfigured anything this session.",
@@ -359,6 +1108,7 @@ pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
             failure_prefix: "Reconfigure didn't finish",
             success_toast: "Reconfigure complete.",
             failure_toast: "Reconfigure failed.",
+            docs_url: XBPS_RECONFIGURE_MANUAL,
         },
         MaintenanceTask::Alternatives => MaintenanceCopy {
             idle_text: "Haven't opened the alternatives list yet.",
@@ -367,6 +1117,7 @@ pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
             failure_prefix: "Couldn't load alternatives",
             success_toast: "Alternatives list ready.",
             failure_toast: "Failed to load alternatives.",
+            docs_url: XBPS_ALTERNATIVES_MANUAL,
         },
         MaintenanceTask::CacheClean => MaintenanceCopy {
             idle_text: "Ready to clean cache.",
@@ -375,6 +1126,49 @@ pub(crate) fn maintenance_copy(task: MaintenanceTask) -> MaintenanceCopy {
             failure_prefix: "Cache cleaning encountered an issue",
             success_toast: "Package cache cleaned.",
             failure_toast: "Cache cleaning failed.",
+            docs_url: VOID_DOCS_XBPS,
+        },
+        MaintenanceTask::HoldAll => MaintenanceCopy {
+            idle_text: "Nothing held from here yet.",
+            running_text: "Holding packages...",
+            success_message: "Every installed package is now held at its current version.",
+            failure_prefix: "Holding packages stopped",
+            success_toast: "All packages held.",
+            failure_toast: "Holding packages failed.",
+            docs_url: VOID_DOCS_ADVANCED,
         },
+        MaintenanceTask::UnholdAll => MaintenanceCopy {
+            idle_text: "Nothing unheld from here yet.",
+            running_text: "Releasing held packages...",
+            success_message: "No packages are held anymore.",
+            failure_prefix: "Unholding packages stopped",
+            success_toast: "All packages unheld.",
+            failure_toast: "Unholding packages failed.",
+            docs_url: VOID_DOCS_ADVANCED,
+        },
+        MaintenanceTask::SyncRepos => MaintenanceCopy {
+            idle_text: "Repository indexes not synced from here yet.",
+            running_text: "Syncing repository indexes...",
+            success_message: "Repository indexes are up to date. Searches now use the fresh package lists.",
+            failure_prefix: "Repository sync stopped",
+            success_toast: "Repositories synced.",
+            failure_toast: "Repository sync failed.",
+            docs_url: VOID_DOCS_REPOSITORIES,
+        },
+    }
+}
+
+fn integrity_summary(issues: &[&IntegrityIssue]) -> String {
+    let count = |kind: IntegrityIssueKind| issues.iter().filter(|issue| issue.kind == kind).count();
+    let mut parts = Vec::new();
+    for (kind, noun) in [
+        (IntegrityIssueKind::Modified, "modified"),
+        (IntegrityIssueKind::Missing, "missing"),
+    ] {
+        let n = count(kind);
+        if n > 0 {
+            parts.push(format!("{} {}", n, noun));
+        }
     }
+    parts.join(", ")
 }
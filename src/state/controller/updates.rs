@@ -1,9 +1,17 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
 use std::io::{BufRead, BufReader};
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::rc::Rc;
 use std::sync::mpsc;
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use chrono::Utc;
 use gtk::gio;
 use gtk4 as gtk;
 use libadwaita as adw;
@@ -11,26 +19,101 @@ use libadwaita as adw;
 use adw::prelude::*;
 use gtk::glib;
 
-use crate::categories::icon_resource_for_package;
 use crate::details::InstalledDetail;
 use crate::helpers::{
-    clear_listbox, format_relative_time, glib_datetime_to_chrono, query_installed_detail,
-    sanitize_contact_field, select_row_if_attached, set_link_label,
+    DETAIL_TIMEOUT_ERROR, clear_listbox, compact_package_row, fetch_detail_with_timeout,
+    format_relative_time, glib_datetime_to_chrono, package_icon, package_match_score,
+    query_installed_detail, sanitize_contact_field, select_row_if_attached, set_link_label,
 };
+use crate::i18n::{tr, trn};
 use crate::mirrors::install_repository_args;
+use crate::restart_check::processes_needing_restart;
 use crate::state::controller::AppController;
-use crate::state::types::{AppMessage, AppState, UpdateStatus};
+use crate::state::types::{AppMessage, AppState, UpdateOptions, UpdateStatus};
 use crate::types::{CommandResult, PackageInfo};
-use crate::xbps::{format_download_size, run_xbps_check_updates, split_package_identifier};
+use crate::update_history::{
+    InFlightUpdate, UpdateHistoryEntry, append_update_history, clear_in_flight_update,
+    load_in_flight_update, save_in_flight_update,
+};
+use crate::xbps::{
+    ConfigConflict, ConfigResolution, DiffRow, DiffRowKind, HeldBlocker, TransactionSizes,
+    format_download_size, format_privileged_command, format_terminal_command,
+    parse_config_conflicts, parse_held_blockers, privilege_failure_reported,
+    privilege_is_noninteractive, read_config_conflict, requires_xbps_self_update,
+    resolve_config_conflict, run_system_reboot, run_xbps_check_updates, run_xbps_dry_run_updates,
+    run_xbps_pkgdb_set_hold, shell_quote, side_by_side_diff, split_package_identifier,
+};
+
+const LAUNCHER_ENTRY_APP_URI: &str = "application://tech.geektoshi.Nebula.desktop";
+const LAUNCHER_ENTRY_OBJECT_PATH: &str = "/tech/geektoshi/Nebula/LauncherEntry";
+
+/// Packages listed under "System & Critical" on the Updates page. A trailing
+/// `*` matches any package name with that prefix.
+const CRITICAL_UPDATE_PATTERNS: &[&str] = &[
+    "linux*",
+    "glibc*",
+    "musl*",
+    "xbps",
+    "base-system",
+    "runit*",
+    "dracut",
+    "eudev",
+    "elogind",
+];
+
+/// Kernel series and their headers, held back from "Update All" when the
+/// user prefers to update kernels one at a time. Same syntax as
+/// [`CRITICAL_UPDATE_PATTERNS`]; `linux*` alone would catch linux-firmware.
+const KERNEL_PACKAGE_PATTERNS: &[&str] = &[
+    "linux",
+    "linux-headers",
+    "linux-lts*",
+    "linux-mainline*",
+    "linux4*",
+    "linux5*",
+    "linux6*",
+];
+
+const CONFIG_OPEN_RESPONSE: gtk::ResponseType = gtk::ResponseType::Other(1);
+const CONFIG_KEEP_RESPONSE: gtk::ResponseType = gtk::ResponseType::Other(2);
+const CONFIG_USE_NEW_RESPONSE: gtk::ResponseType = gtk::ResponseType::Other(3);
+
+/// Terminal emulators that stay in the foreground until their command exits,
+/// with the arguments that introduce the command to run.
+const TERMINAL_CANDIDATES: &[(&str, &[&str])] = &[
+    ("foot", &[]),
+    ("alacritty", &["-e"]),
+    ("kitty", &[]),
+    ("wezterm", &["start", "--always-new-process", "--"]),
+    ("konsole", &["--nofork", "-e"]),
+    ("xfce4-terminal", &["--disable-server", "-x"]),
+    ("xterm", &["-e"]),
+];
 
 impl AppController {
     pub(crate) fn set_check_buttons_sensitive(&self, enabled: bool) {
+        let online = !self.state.borrow().offline;
+        let enabled = enabled && online;
         self.widgets.updates.check_button.set_sensitive(enabled);
         self.widgets.updates.refresh_button.set_sensitive(enabled);
         self.widgets
             .updates
             .update_all_button
             .set_sensitive(enabled);
+
+        let offline_tooltip = "You're offline. Reconnect to check for updates.";
+        self.widgets
+            .updates
+            .check_button
+            .set_tooltip_text((!online).then_some(offline_tooltip));
+        self.widgets
+            .updates
+            .refresh_button
+            .set_tooltip_text(Some(if online {
+                "Check for new updates"
+            } else {
+                offline_tooltip
+            }));
     }
 
 
@@ -50,16 +133,22 @@ impl AppController {
             let count = state.available_updates.len();
             if count > 0 {
                 let total_bytes = state.total_update_size;
-                if total_bytes > 0 {
-                    let megabytes = total_bytes as f64 / 1_000_000.0;
-                    format!("Update size {:.2} MB", megabytes)
-                } else {
-                    String::new()
+                match state.update_transaction_sizes {
+                    Some(sizes) if sizes.installed_bytes > 0 => tr!(
+                        "Download: {}, Disk: {}",
+                        format_download_size(total_bytes),
+                        format_download_size(sizes.installed_bytes)
+                    ),
+                    _ if total_bytes > 0 => {
+                        let megabytes = total_bytes as f64 / 1_000_000.0;
+                        tr!("Update size {} MB", format!("{:.2}", megabytes))
+                    }
+                    _ => String::new(),
                 }
             } else if state.last_update_check.is_some() {
                 String::new()
             } else {
-                "No updates checked yet.".to_string()
+                tr!("No updates checked yet.")
             }
         };
 
@@ -72,15 +161,15 @@ impl AppController {
             if let Some(message) = state.footer_message.clone() {
                 message
             } else if state.updates_loading {
-                "Checking for updates…".to_string()
+                tr!("Checking for updates…")
             } else if let Some(dt) = &state.last_update_check {
                 if let Some(chrono_dt) = glib_datetime_to_chrono(dt) {
-                    format!("Last checked {}", format_relative_time(chrono_dt))
+                    tr!("Last checked {}", format_relative_time(chrono_dt))
                 } else {
-                    "Last checked just now.".to_string()
+                    tr!("Last checked just now.")
                 }
             } else {
-                "Last checked — never.".to_string()
+                tr!("Last checked — never.")
             }
         };
 
@@ -90,6 +179,37 @@ impl AppController {
     pub(crate) fn update_updates_badge(&self) {
         let count = self.state.borrow().available_updates.len();
         self.widgets.updates_page.set_badge_number(count as u32);
+
+        let title = match count {
+            0 => "Nebula".to_string(),
+            1 => "Nebula (1 update)".to_string(),
+            n => format!("Nebula ({} updates)", n),
+        };
+        self.window.set_title(Some(&title));
+        self.publish_launcher_count(count);
+    }
+
+    /// Publishes the update count through the Unity LauncherEntry D-Bus API,
+    /// which docks and task managers that support it show as an icon badge.
+    fn publish_launcher_count(&self, count: usize) {
+        let Some(connection) = self.app.dbus_connection() else {
+            return;
+        };
+
+        let mut properties: HashMap<String, glib::Variant> = HashMap::new();
+        properties.insert("count".to_string(), (count as i64).to_variant());
+        properties.insert("count-visible".to_string(), (count > 0).to_variant());
+        let parameters = (LAUNCHER_ENTRY_APP_URI, properties).to_variant();
+
+        if let Err(err) = connection.emit_signal(
+            None,
+            LAUNCHER_ENTRY_OBJECT_PATH,
+            "com.canonical.Unity.LauncherEntry",
+            "Update",
+            Some(&parameters),
+        ) {
+            eprintln!("Failed to publish launcher badge: {}", err);
+        }
     }
 
     pub(crate) fn maybe_notify_new_updates(&self, count: usize) {
@@ -106,16 +226,18 @@ impl AppController {
             return;
         }
 
-        let summary = "New updates available!";
-        let body = if count == 1 {
-            "1 update is ready to install.".to_string()
-        } else {
-            format!("{} updates are ready to install.", count)
-        };
+        let summary = tr!("New updates available!");
+        let body = trn!(
+            "{} update is ready to install.",
+            "{} updates are ready to install.",
+            count,
+            count
+        );
 
-        let notification = gio::Notification::new(summary);
+        let notification = gio::Notification::new(&summary);
         notification.set_body(Some(&body));
         notification.set_default_action("app.show-updates");
+        notification.add_button(&tr!("Update Now"), "app.update-all");
         let icon = gio::ThemedIcon::new("software-update-available");
         notification.set_icon(&icon);
 
@@ -130,11 +252,312 @@ impl AppController {
         self.app.withdraw_notification("updates");
     }
 
+    /// Picks up `.new-<version>` configuration files reported during the
+    /// last update and keeps the banner in step with what is still on disk.
+    fn collect_config_conflicts(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            let found =
+                parse_config_conflicts(state.update_log.iter().map(|(_, line)| line.as_str()));
+            for conflict in found {
+                if !state.config_conflicts.contains(&conflict) {
+                    state.config_conflicts.push(conflict);
+                }
+            }
+            state
+                .config_conflicts
+                .retain(|conflict| conflict.new_file.exists());
+        }
+        self.refresh_config_banner();
+    }
+
+    fn refresh_config_banner(&self) {
+        let count = self.state.borrow().config_conflicts.len();
+        let banner = &self.widgets.updates.config_banner;
+        if count > 0 {
+            banner.set_title(&trn!(
+                "{} configuration file changed upstream and needs review.",
+                "{} configuration files changed upstream and need review.",
+                count,
+                count
+            ));
+        }
+        banner.set_revealed(count > 0);
+    }
+
+    pub(crate) fn show_config_conflicts_dialog(self: &Rc<Self>) {
+        let dialog = gtk::Dialog::builder()
+            .transient_for(&self.window)
+            .modal(true)
+            .title("Configuration files")
+            .default_width(640)
+            .default_height(360)
+            .build();
+        dialog.add_button("Dismiss All", gtk::ResponseType::Reject);
+        dialog.add_button("Close", gtk::ResponseType::Close);
+        dialog.connect_response(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |dialog, response| {
+                if response == gtk::ResponseType::Reject {
+                    controller.state.borrow_mut().config_conflicts.clear();
+                    controller.refresh_config_banner();
+                }
+                dialog.close();
+            }
+        ));
+
+        let content = dialog.content_area();
+        content.set_spacing(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        let intro = gtk::Label::builder()
+            .label(tr!("xbps kept your modified files and installed the packaged versions beside them. Compare each pair to keep your version, switch to the new one, or merge them in an editor."))
+            .wrap(true)
+            .xalign(0.0)
+            .build();
+        intro.add_css_class("dim-label");
+        content.append(&intro);
+
+        let list = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .valign(gtk::Align::Start)
+            .build();
+        list.add_css_class("boxed-list");
+        self.config_conflicts_list.replace(Some(list.clone()));
+        dialog.connect_close_request(glib::clone!(
+            #[strong(rename_to = controller)]
+            self,
+            move |_| {
+                controller.config_conflicts_list.replace(None);
+                glib::Propagation::Proceed
+            }
+        ));
+        self.populate_config_conflicts_list();
+
+        let scroller = gtk::ScrolledWindow::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .child(&list)
+            .build();
+        content.append(&scroller);
+
+        dialog.present();
+    }
+
+    fn populate_config_conflicts_list(self: &Rc<Self>) {
+        let Some(list) = self.config_conflicts_list.borrow().clone() else {
+            return;
+        };
+        clear_listbox(&list);
+
+        let conflicts = self.state.borrow().config_conflicts.clone();
+        for conflict in conflicts {
+            let title = conflict.original.display().to_string();
+            let subtitle = conflict.new_file.display().to_string();
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(glib::markup_escape_text(&subtitle).as_str())
+                .build();
+
+            let compare_button = gtk::Button::builder()
+                .label("Compare")
+                .valign(gtk::Align::Center)
+                .build();
+            let controller = Rc::downgrade(self);
+            let conflict_for_compare = conflict.clone();
+            compare_button.connect_clicked(move |button| {
+                let parent = button.root().and_downcast::<gtk::Window>();
+                if let Some(controller) = controller.upgrade() {
+                    controller.show_config_diff(parent.as_ref(), &conflict_for_compare);
+                }
+            });
+            row.add_suffix(&compare_button);
+
+            let open_button = gtk::Button::builder()
+                .label("Open")
+                .valign(gtk::Align::Center)
+                .build();
+            let controller = Rc::downgrade(self);
+            open_button.connect_clicked(move |_| {
+                if let Some(controller) = controller.upgrade() {
+                    controller.open_config_file(&conflict.new_file);
+                }
+            });
+            row.add_suffix(&open_button);
+
+            list.append(&row);
+        }
+    }
+
+    fn open_config_file(&self, path: &Path) {
+        let uri = gio::File::for_path(path).uri();
+        if let Err(err) = gio::AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>)
+        {
+            self.show_toast(&tr!("Couldn't open file: {}", err));
+        }
+    }
+
+    /// Shows the local file beside the packaged `.new` copy, with the lines
+    /// that differ highlighted, and lets the user settle the conflict.
+    fn show_config_diff(self: &Rc<Self>, parent: Option<&gtk::Window>, conflict: &ConfigConflict) {
+        let (current, new) = match read_config_conflict(conflict) {
+            Ok(texts) => texts,
+            Err(err) => {
+                self.show_error_dialog(&tr!("Couldn't Compare Files"), &err);
+                return;
+            }
+        };
+        let rows = side_by_side_diff(&current, &new);
+        let changed = rows.iter().any(|row| row.kind != DiffRowKind::Same);
+
+        let dialog = gtk::Dialog::builder()
+            .transient_for(parent.unwrap_or(self.window.upcast_ref()))
+            .modal(true)
+            .title(conflict.original.display().to_string())
+            .default_width(960)
+            .default_height(540)
+            .build();
+        dialog.add_button(&tr!("Open in Editor"), CONFIG_OPEN_RESPONSE);
+        dialog.add_button(&tr!("Keep Current"), CONFIG_KEEP_RESPONSE);
+        let use_new = dialog.add_button(&tr!("Use New"), CONFIG_USE_NEW_RESPONSE);
+        use_new.add_css_class("destructive-action");
+        dialog.add_button(&tr!("Close"), gtk::ResponseType::Close);
+
+        let controller = Rc::downgrade(self);
+        let conflict = conflict.clone();
+        dialog.connect_response(move |dialog, response| {
+            let Some(controller) = controller.upgrade() else {
+                dialog.close();
+                return;
+            };
+            match response {
+                CONFIG_OPEN_RESPONSE => {
+                    controller.open_config_file(&conflict.original);
+                    return;
+                }
+                CONFIG_KEEP_RESPONSE => {
+                    controller.resolve_config(conflict.clone(), ConfigResolution::KeepCurrent);
+                }
+                CONFIG_USE_NEW_RESPONSE => {
+                    let body = tr!(
+                        "{} will be replaced by the packaged version. Your local changes to it will be lost.",
+                        conflict.original.display()
+                    );
+                    let conflict = conflict.clone();
+                    controller.confirm_action(
+                        &tr!("Use the New File?"),
+                        &body,
+                        &tr!("Use New"),
+                        move |controller| {
+                            controller.resolve_config(conflict, ConfigResolution::UseNew);
+                        },
+                    );
+                }
+                _ => {}
+            }
+            dialog.close();
+        });
+
+        let content = dialog.content_area();
+        content.set_spacing(6);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+
+        if !changed {
+            let identical = gtk::Label::builder()
+                .label(tr!("The files are identical."))
+                .xalign(0.0)
+                .build();
+            identical.add_css_class("dim-label");
+            content.append(&identical);
+        }
+
+        let panes = gtk::Box::builder()
+            .orientation(gtk::Orientation::Horizontal)
+            .spacing(6)
+            .homogeneous(true)
+            .vexpand(true)
+            .build();
+        let (current_pane, current_scroller) = build_config_diff_pane(&tr!("Current"), &rows, true);
+        let (new_pane, new_scroller) = build_config_diff_pane(&tr!("New"), &rows, false);
+        // Both panes hold one line per row, so a shared adjustment keeps
+        // matching lines level.
+        new_scroller.set_vadjustment(Some(&current_scroller.vadjustment()));
+        panes.append(&current_pane);
+        panes.append(&new_pane);
+        content.append(&panes);
+
+        dialog.present();
+    }
+
+    fn resolve_config(self: &Rc<Self>, conflict: ConfigConflict, resolution: ConfigResolution) {
+        self.state.borrow_mut().config_resolution_in_progress = true;
+        self.refresh_busy_indicator();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = resolve_config_conflict(&conflict, resolution);
+            let _ = sender.send(AppMessage::ConfigConflictResolved {
+                conflict,
+                resolution,
+                result,
+            });
+        });
+    }
+
+    pub(crate) fn finish_config_resolution(
+        self: &Rc<Self>,
+        conflict: ConfigConflict,
+        resolution: ConfigResolution,
+        result: Result<CommandResult, String>,
+    ) {
+        self.state.borrow_mut().config_resolution_in_progress = false;
+        let path = conflict.original.display().to_string();
+        let reported = privilege_failure_reported(&result);
+        match result {
+            Ok(command) if command.success() => {
+                self.state
+                    .borrow_mut()
+                    .config_conflicts
+                    .retain(|existing| *existing != conflict);
+                self.refresh_config_banner();
+                self.populate_config_conflicts_list();
+                let message = match resolution {
+                    ConfigResolution::KeepCurrent => tr!("Kept your version of {}.", path),
+                    ConfigResolution::UseNew => tr!("Installed the new {}.", path),
+                };
+                self.show_toast(&message);
+            }
+            Ok(command) => {
+                let detail = command.stderr.trim();
+                let message = if detail.is_empty() {
+                    tr!("Failed to update {}.", path)
+                } else {
+                    tr!("Failed to update {}: {}", path, detail)
+                };
+                if !reported {
+                    self.show_error_dialog(&tr!("Configuration Not Changed"), &message);
+                }
+            }
+            Err(err) => {
+                let message = tr!("Failed to update {}: {}", path, err);
+                if !reported {
+                    self.show_error_dialog(&tr!("Configuration Not Changed"), &message);
+                }
+            }
+        }
+    }
+
     pub(crate) fn rebuild_updates_list(self: &Rc<Self>) {
         let list = &self.widgets.updates.list;
         clear_listbox(list);
 
-        let (updates, selected, busy, detail_open, statuses) = {
+        let (updates, selected, busy, detail_open, statuses, filter) = {
             let state = self.state.borrow();
             (
                 state.available_updates.clone(),
@@ -142,6 +565,7 @@ impl AppController {
                 state.update_in_progress || state.updates_loading,
                 state.updates_detail_package.is_some(),
                 state.update_statuses.clone(),
+                state.updates_filter.trim().to_lowercase(),
             )
         };
         self.update_buttons.borrow_mut().clear();
@@ -153,6 +577,35 @@ impl AppController {
             list.append(&row);
         }
 
+        // Rows are hidden rather than removed while filtering, so row indices
+        // keep matching `available_updates`.
+        let visible: Vec<bool> = updates
+            .iter()
+            .map(|pkg| package_match_score(pkg, &filter).is_some())
+            .collect();
+        list.set_filter_func(move |row| visible.get(row.index() as usize).copied().unwrap_or(true));
+
+        // Critical updates are sorted first when the list is loaded, so the
+        // group boundary is a row index. `before` is the previous visible row.
+        let critical_count = updates
+            .iter()
+            .take_while(|pkg| is_critical_update(&pkg.name))
+            .count();
+        list.set_header_func(move |row, before| {
+            let critical = (row.index() as usize) < critical_count;
+            let before_critical = before.map(|before| (before.index() as usize) < critical_count);
+            let title = if critical_count == 0 {
+                None
+            } else if critical && before_critical.is_none() {
+                Some(tr!("System & Critical"))
+            } else if !critical && before_critical != Some(false) {
+                Some(tr!("Other Updates"))
+            } else {
+                None
+            };
+            row.set_header(title.as_deref().map(build_update_group_header).as_ref());
+        });
+
         let detail_target = {
             let state = self.state.borrow();
             state.updates_detail_package.clone()
@@ -222,11 +675,14 @@ impl AppController {
             }
         ));
 
-        let icon = gtk::Image::from_resource(icon_resource_for_package(&pkg.name));
+        let icon = package_icon(&pkg.name);
         icon.set_pixel_size(32);
         icon.set_margin_start(8);
         icon.set_margin_end(6);
         icon.set_valign(gtk::Align::Center);
+        if self.compact_lists() {
+            compact_package_row(&row, &icon);
+        }
 
         let prefix_box = gtk::Box::builder()
             .orientation(gtk::Orientation::Horizontal)
@@ -301,18 +757,47 @@ impl AppController {
         self.update_update_controls();
     }
 
+    pub(crate) fn set_all_updates_selected(self: &Rc<Self>, selected: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.update_in_progress {
+                return;
+            }
+            if selected {
+                state.selected_updates = state
+                    .available_updates
+                    .iter()
+                    .map(|pkg| pkg.name.clone())
+                    .collect();
+            } else {
+                state.selected_updates.clear();
+            }
+        }
+        self.update_update_controls();
+        self.rebuild_updates_list();
+    }
+
     pub(crate) fn update_update_controls(self: &Rc<Self>) {
-        let (total, selected, loading, updating) = {
+        let (total, selected, loading, updating, offline) = {
             let state = self.state.borrow();
             (
                 state.available_updates.len(),
                 state.selected_updates.len(),
                 state.updates_loading,
                 state.update_in_progress,
+                state.offline,
             )
         };
 
         self.update_summary_text();
+        self.update_retry_failed_button();
+
+        let select_all = &self.widgets.updates.select_all_button;
+        let select_none = &self.widgets.updates.select_none_button;
+        select_all.set_visible(total > 1);
+        select_none.set_visible(total > 1);
+        select_all.set_sensitive(!updating && selected < total);
+        select_none.set_sensitive(!updating && selected > 0);
 
         if updating {
             self.widgets.updates.update_all_button.set_label("Updating");
@@ -335,12 +820,54 @@ impl AppController {
         self.widgets
             .updates
             .update_all_button
-            .set_sensitive(selected > 0 && !loading && !updating);
+            .set_sensitive(selected > 0 && !loading && !updating && !offline);
         self.widgets.updates.update_all_button.set_label(&label);
 
         self.update_updates_detail();
     }
 
+    fn failed_update_packages(&self) -> Vec<String> {
+        let state = self.state.borrow();
+        let mut failed: Vec<String> = state
+            .update_statuses
+            .iter()
+            .filter(|(_, status)| matches!(status, UpdateStatus::Failed))
+            .map(|(name, _)| name.clone())
+            .collect();
+        failed.sort();
+        failed
+    }
+
+    fn update_retry_failed_button(&self) {
+        let busy = {
+            let state = self.state.borrow();
+            state.update_in_progress || state.updates_loading
+        };
+        let has_failed = !self.failed_update_packages().is_empty();
+        let button = &self.widgets.updates.retry_failed_button;
+        button.set_visible(has_failed && !busy);
+        button.set_sensitive(has_failed && !busy);
+    }
+
+    pub(crate) fn retry_failed_updates(self: &Rc<Self>) {
+        {
+            let state = self.state.borrow();
+            if state.update_in_progress || state.updates_loading {
+                return;
+            }
+        }
+
+        let failed = self.failed_update_packages();
+        if failed.is_empty() {
+            return;
+        }
+
+        // Failed outranks Queued in UpdateStatus::should_replace, so drop the
+        // old status first and let execute_update_multiple re-queue them.
+        self.clear_package_status(&failed);
+        self.execute_update_multiple(failed, UpdateOptions::default());
+    }
+
     pub(crate) fn on_update_row_activated(self: &Rc<Self>, row: &gtk::ListBoxRow) {
         let index = row.index() as usize;
         let package = {
@@ -359,6 +886,17 @@ impl AppController {
         }
     }
 
+    pub(crate) fn on_updates_search_changed(self: &Rc<Self>, query: String) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.updates_filter == query {
+                return;
+            }
+            state.updates_filter = query;
+        }
+        self.rebuild_updates_list();
+    }
+
     pub(crate) fn on_update_row_selected(self: &Rc<Self>, row: Option<gtk::ListBoxRow>) {
         let (index, package) = {
             let state = self.state.borrow();
@@ -397,6 +935,7 @@ impl AppController {
         package: String,
         result: Result<InstalledDetail, String>,
     ) {
+        let timed_out = matches!(&result, Err(err) if err == DETAIL_TIMEOUT_ERROR);
         {
             let mut state = self.state.borrow_mut();
             state.updates_detail_loading.remove(&package);
@@ -411,6 +950,12 @@ impl AppController {
             }
         }
 
+        if timed_out {
+            self.show_toast(&tr!(
+                "Details for \"{}\" timed out. Select it again to retry.",
+                package
+            ));
+        }
         self.update_updates_detail();
     }
 
@@ -431,10 +976,14 @@ impl AppController {
             state.updates_detail_errors.remove(&package_name);
             state.updates_detail_loading.insert(package_name.clone());
         }
+        self.refresh_busy_indicator();
 
         let sender = self.sender.clone();
         thread::spawn(move || {
-            let result = query_installed_detail(&package_name, &installed_set);
+            let query_name = package_name.clone();
+            let result = fetch_detail_with_timeout(move || {
+                query_installed_detail(&query_name, &installed_set)
+            });
             let _ = sender.send(AppMessage::UpdatesDetailLoaded {
                 package: package_name,
                 result,
@@ -468,6 +1017,22 @@ impl AppController {
         self.set_all_update_row_buttons_visible(true);
     }
 
+    pub(crate) fn updates_detail_homepage(&self) -> Option<String> {
+        let state = self.state.borrow();
+        let package = state.updates_detail_package.as_ref()?;
+        state
+            .updates_detail_cache
+            .get(package)
+            .and_then(|detail| detail.homepage.clone())
+    }
+
+    pub(crate) fn updates_detail_command(&self) -> Option<String> {
+        let package = self.state.borrow().updates_detail_package.clone()?;
+        let args = build_update_packages_args(&[package]);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        Some(format_terminal_command("xbps-install", &args))
+    }
+
     pub(crate) fn update_updates_detail(self: &Rc<Self>) {
         let (package_name, pkg_info, detail, loading, error) = {
             let state = self.state.borrow();
@@ -541,6 +1106,8 @@ impl AppController {
                 }
             } else if loading {
                 "Loading…".to_string()
+            } else if error.as_deref() == Some(DETAIL_TIMEOUT_ERROR) {
+                "Unavailable (timed out)".to_string()
             } else if let Some(err) = error.as_ref() {
                 format!("Failed ({})", err)
             } else if let Some(pkg) = pkg_info.as_ref() {
@@ -656,11 +1223,12 @@ impl AppController {
 
     pub(crate) fn on_update_log_line(self: &Rc<Self>, line: String) {
         let cleaned = line.trim_end_matches('\r').to_string();
+        let received = Utc::now();
         {
             let mut state = self.state.borrow_mut();
-            state.update_log.push(cleaned.clone());
+            state.update_log.push((received, cleaned.clone()));
         }
-        self.append_update_log_buffer_line(&cleaned);
+        self.append_update_log_buffer_line(&received, &cleaned);
         self.update_status_from_log_line(&cleaned);
     }
 
@@ -680,6 +1248,10 @@ impl AppController {
         let lower_line = line.to_ascii_lowercase();
         let mut matched = self.detect_packages_in_line(line, &lower_line, &candidates);
 
+        if Self::is_download_failure(&lower_line) {
+            self.record_download_failure(&matched);
+        }
+
         if matched.is_empty() {
             if lower_line.contains("transaction aborted")
                 || lower_line.contains("failed to download")
@@ -788,6 +1360,180 @@ impl AppController {
         }
     }
 
+    /// Recognises xbps and libfetch messages for a download that was cut
+    /// off, as opposed to a package that failed to unpack or configure.
+    fn is_download_failure(lower_line: &str) -> bool {
+        lower_line.contains("failed to download")
+            || lower_line.contains("failed to fetch")
+            || lower_line.contains("operation timed out")
+            || lower_line.contains("connection reset")
+            || lower_line.contains("connection refused")
+            || lower_line.contains("no address record")
+    }
+
+    /// Remembers which packages a download failure hit. When the line names
+    /// no package, every package still downloading is affected.
+    fn record_download_failure(&self, matched: &[String]) {
+        let mut state = self.state.borrow_mut();
+        let affected: Vec<String> = if matched.is_empty() {
+            state
+                .update_statuses
+                .iter()
+                .filter(|(_, status)| matches!(status, UpdateStatus::Downloading))
+                .map(|(name, _)| name.clone())
+                .collect()
+        } else {
+            matched.to_vec()
+        };
+        state.update_download_failures.extend(affected);
+    }
+
+    fn download_retry_packages(&self, packages: &[String]) -> Vec<String> {
+        let state = self.state.borrow();
+        let mut retry: Vec<String> = packages
+            .iter()
+            .filter(|name| state.update_download_failures.contains(*name))
+            .cloned()
+            .collect();
+        retry.sort();
+        retry
+    }
+
+    /// Offers to re-run only the packages whose download was interrupted.
+    /// Archives that finished downloading stay in the xbps cache, so the
+    /// retry fetches just what is missing.
+    fn offer_download_retry(self: &Rc<Self>, message: &str, packages: Vec<String>) {
+        let body = format!(
+            "{}\n\nThe download of {} was interrupted. Retrying fetches only what is still missing.",
+            message,
+            packages.join(", ")
+        );
+        self.confirm_action(
+            "Download Interrupted",
+            &body,
+            "Retry Download",
+            move |controller| {
+                controller.retry_update_download(packages);
+            },
+        );
+    }
+
+    /// Names the held packages that kept an update from going ahead and
+    /// offers to release them and run the same update again.
+    fn offer_unhold_and_retry(
+        self: &Rc<Self>,
+        blockers: Vec<HeldBlocker>,
+        packages: Vec<String>,
+        all: bool,
+    ) {
+        let lines: Vec<String> = blockers
+            .iter()
+            .map(|blocker| {
+                if blocker.required_by.is_empty() {
+                    tr!("• {} is held at its current version", blocker.package)
+                } else {
+                    tr!(
+                        "• {} is held, but {} needs a newer version",
+                        blocker.package,
+                        blocker.required_by.join(", ")
+                    )
+                }
+            })
+            .collect();
+        let body = tr!(
+            "xbps will not update held packages, so the update could not go ahead:\n\n{}\n\nUnholding lets them update along with the rest. You can hold them again afterwards.",
+            lines.join("\n")
+        );
+        let held: Vec<String> = blockers
+            .into_iter()
+            .map(|blocker| blocker.package)
+            .collect();
+        self.confirm_action(
+            &tr!("Held Packages Blocked the Update"),
+            &body,
+            &tr!("Unhold and Retry"),
+            move |controller| {
+                controller.unhold_for_update(held, packages, all);
+            },
+        );
+    }
+
+    fn unhold_for_update(self: &Rc<Self>, held: Vec<String>, packages: Vec<String>, all: bool) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.pin_in_progress {
+                return;
+            }
+            state.pin_in_progress = true;
+        }
+        self.refresh_busy_indicator();
+        self.set_footer_message(Some(&tr!("Unholding {}…", held.join(", "))));
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_xbps_pkgdb_set_hold(&held, false);
+            let _ = sender.send(AppMessage::HeldBlockersUnheld {
+                held,
+                packages,
+                all,
+                result,
+            });
+        });
+    }
+
+    pub(crate) fn finish_unhold_for_update(
+        self: &Rc<Self>,
+        held: Vec<String>,
+        packages: Vec<String>,
+        all: bool,
+        result: Result<CommandResult, String>,
+    ) {
+        self.state.borrow_mut().pin_in_progress = false;
+        self.refresh_held_packages();
+
+        let detail = match result {
+            Ok(command) if command.success() => None,
+            Ok(command) => {
+                let detail = command.stderr.trim();
+                Some(if detail.is_empty() {
+                    command.stdout.trim().to_string()
+                } else {
+                    detail.to_string()
+                })
+            }
+            Err(err) => Some(err),
+        };
+        if let Some(detail) = detail {
+            let message = tr!("Failed to unhold {}: {}", held.join(", "), detail);
+            self.set_footer_message(Some(&message));
+            self.show_error_dialog(&tr!("Unhold Failed"), &message);
+            return;
+        }
+
+        self.show_toast(&tr!("Unheld {}. Retrying the update.", held.join(", ")));
+        self.clear_package_status(&packages);
+        // Go through the usual checks: free disk space and, for a full
+        // update, the pre-upgrade snapshot.
+        if all {
+            self.request_update(String::from("__all__"), true, UpdateOptions::default());
+        } else if let [package] = packages.as_slice() {
+            self.request_update(package.clone(), false, UpdateOptions::default());
+        } else {
+            self.request_update_multiple(packages, UpdateOptions::default());
+        }
+    }
+
+    fn retry_update_download(self: &Rc<Self>, packages: Vec<String>) {
+        {
+            let state = self.state.borrow();
+            if state.update_in_progress || state.updates_loading {
+                return;
+            }
+        }
+        self.clear_package_status(&packages);
+        self.execute_update_multiple(packages, UpdateOptions::default());
+    }
+
     fn maybe_mark_package_preparing(&self, package: &str) {
         let should_update = {
             let state = self.state.borrow();
@@ -803,6 +1549,7 @@ impl AppController {
 
     fn set_packages_status(&self, packages: &[String], status: UpdateStatus) {
         let mut changed = Vec::new();
+        let mut transitioned = false;
         {
             let mut state = self.state.borrow_mut();
             for name in packages {
@@ -811,32 +1558,99 @@ impl AppController {
                     _ => true,
                 };
                 if replace {
-                    state.update_statuses.insert(name.clone(), status);
+                    let previous = state.update_statuses.insert(name.clone(), status);
+                    transitioned |= previous != Some(status);
+                    if status == UpdateStatus::Completed
+                        && previous != Some(UpdateStatus::Completed)
+                        && state.update_started_at.is_some()
+                    {
+                        state.update_completions.push(Utc::now());
+                    }
                     changed.push(name.clone());
                 }
             }
+            // Progress ticks repeat the current status, so the record is
+            // only rewritten when a package actually moves on.
+            if let (Some(record), true) = (state.in_flight_update.as_mut(), transitioned) {
+                for name in &changed {
+                    record.statuses.insert(name.clone(), status);
+                }
+                persist_in_flight_update(record);
+            }
         }
         if !changed.is_empty() {
             self.update_package_status_buttons(&changed);
+            if status == UpdateStatus::Completed {
+                self.refresh_update_eta();
+            }
         }
     }
 
-    fn clear_package_status(&self, packages: &[String]) {
-        let mut changed = Vec::new();
+    fn begin_update_eta(&self, package_count: usize) {
         {
             let mut state = self.state.borrow_mut();
-            for name in packages {
-                if state.update_statuses.remove(name).is_some() {
-                    changed.push(name.clone());
-                }
-            }
-        }
-        if !changed.is_empty() {
-            self.update_package_status_buttons(&changed);
+            state.update_started_at = Some(Utc::now());
+            state.update_package_total = package_count;
+            state.update_completions.clear();
         }
+        self.refresh_update_eta();
     }
 
-    fn update_package_status_buttons(&self, packages: &[String]) {
+    fn clear_update_eta(&self) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.update_started_at = None;
+            state.update_package_total = 0;
+            state.update_completions.clear();
+        }
+        self.refresh_update_eta();
+    }
+
+    /// Estimates the time left from the average time each finished package
+    /// took. Single-package runs have nothing to average, so no ETA shows.
+    fn refresh_update_eta(&self) {
+        let remaining = {
+            let state = self.state.borrow();
+            let completed = state.update_completions.len();
+            match (state.update_started_at, state.update_completions.last()) {
+                (Some(started), Some(last))
+                    if state.update_in_progress
+                        && state.update_package_total > 1
+                        && completed < state.update_package_total =>
+                {
+                    let per_package = (*last - started).num_seconds() / completed as i64;
+                    Some(per_package * (state.update_package_total - completed) as i64)
+                }
+                _ => None,
+            }
+        };
+
+        let label = &self.widgets.updates.eta_label;
+        match remaining {
+            Some(seconds) => {
+                label.set_text(&format_eta(seconds));
+                label.set_visible(true);
+            }
+            None => label.set_visible(false),
+        }
+    }
+
+    fn clear_package_status(&self, packages: &[String]) {
+        let mut changed = Vec::new();
+        {
+            let mut state = self.state.borrow_mut();
+            for name in packages {
+                if state.update_statuses.remove(name).is_some() {
+                    changed.push(name.clone());
+                }
+            }
+        }
+        if !changed.is_empty() {
+            self.update_package_status_buttons(&changed);
+        }
+    }
+
+    fn update_package_status_buttons(&self, packages: &[String]) {
         let (statuses, updating) = {
             let state = self.state.borrow();
             (
@@ -1014,10 +1828,18 @@ impl AppController {
             }
         }
 
+        if self.state.borrow().offline {
+            if !silent {
+                self.set_summary_text(&tr!("You're offline. Reconnect to check for updates."));
+            }
+            return;
+        }
+
         {
             let mut state = self.state.borrow_mut();
             state.updates_loading = true;
         }
+        self.refresh_busy_indicator();
 
         self.update_footer_text();
 
@@ -1026,12 +1848,12 @@ impl AppController {
 
         if !silent || visible_updates {
             let message = if silent {
-                "Refreshing updates…"
+                tr!("Refreshing updates…")
             } else {
-                "Checking for updates…"
+                tr!("Checking for updates…")
             };
-            self.widgets.updates.placeholder_label.set_text(message);
-            self.set_summary_text(message);
+            self.widgets.updates.placeholder_label.set_text(&message);
+            self.set_summary_text(&message);
         }
 
         if !silent {
@@ -1049,8 +1871,16 @@ impl AppController {
         let sender = self.sender.clone();
         thread::spawn(move || match run_xbps_check_updates() {
             Ok(packages) => {
+                // The per-package sizes come from repository metadata and miss
+                // new dependencies, so total the real transaction as well.
+                let sizes = if packages.is_empty() {
+                    None
+                } else {
+                    run_xbps_dry_run_updates().ok()
+                };
                 let _ = sender.send(AppMessage::UpdatesRefreshed {
                     packages,
+                    sizes,
                     success: true,
                     error: None,
                 });
@@ -1058,6 +1888,7 @@ impl AppController {
             Err(err) => {
                 let _ = sender.send(AppMessage::UpdatesRefreshed {
                     packages: Vec::new(),
+                    sizes: None,
                     success: false,
                     error: Some(err),
                 });
@@ -1068,6 +1899,7 @@ impl AppController {
     pub(crate) fn finish_updates_refresh(
         self: &Rc<Self>,
         packages: Vec<PackageInfo>,
+        sizes: Option<TransactionSizes>,
         success: bool,
         error: Option<String>,
     ) {
@@ -1083,6 +1915,8 @@ impl AppController {
             let had_updates = !state.available_updates.is_empty();
             state.updates_loading = false;
             if success {
+                let mut packages = packages;
+                packages.sort_by_key(|pkg| !is_critical_update(&pkg.name));
                 state.available_updates = packages;
                 Self::refresh_available_update_names(&mut state);
                 let available_names_snapshot = state.available_update_names.clone();
@@ -1094,11 +1928,15 @@ impl AppController {
                     .iter()
                     .map(|pkg| pkg.name.clone())
                     .collect();
-                state.total_update_size = state
-                    .available_updates
-                    .iter()
-                    .filter_map(|pkg| pkg.download_bytes)
-                    .sum();
+                state.update_transaction_sizes = sizes;
+                state.total_update_size = match sizes {
+                    Some(sizes) if sizes.download_bytes > 0 => sizes.download_bytes,
+                    _ => state
+                        .available_updates
+                        .iter()
+                        .filter_map(|pkg| pkg.download_bytes)
+                        .sum(),
+                };
                 state.last_update_check = glib::DateTime::now_local().ok();
             }
             let available_names = state.available_update_names.clone();
@@ -1144,6 +1982,7 @@ impl AppController {
         }
 
         self.set_check_buttons_sensitive(!update_in_progress);
+        let unattended = std::mem::take(&mut self.state.borrow_mut().auto_install_pending);
 
         if success {
             self.sync_updates_detail_state();
@@ -1201,8 +2040,108 @@ impl AppController {
         self.update_update_controls();
         self.update_updates_badge();
         self.update_footer_text();
+        self.refresh_xbps_banner();
         self.rebuild_installed_list();
         self.update_installed_summary();
+        if self.marks_available_updates() {
+            self.rebuild_search_list();
+        }
+
+        if unattended && has_updates {
+            self.check_unattended_authorization();
+        }
+    }
+
+    /// Unattended upgrades must never pop up a password prompt, so polkit is
+    /// asked first whether pkexec would run without one.
+    fn check_unattended_authorization(&self) {
+        if self.state.borrow().run_updates_in_terminal {
+            self.notify_unattended_skipped(
+                "Updates are set to run in a terminal window, which needs you to answer prompts.",
+            );
+            return;
+        }
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let authorized = privilege_is_noninteractive();
+            let _ = sender.send(AppMessage::UnattendedAuthorizationChecked { authorized });
+        });
+    }
+
+    pub(crate) fn finish_unattended_authorization(self: &Rc<Self>, authorized: bool) {
+        if !authorized {
+            self.notify_unattended_skipped(
+                "Installing them needs your password. Open Nebula to apply them.",
+            );
+            return;
+        }
+
+        // Held packages are left alone by xbps-install -Su.
+        let ready = {
+            let state = self.state.borrow();
+            !state.available_updates.is_empty()
+                && !state.updates_loading
+                && !state.has_package_operation()
+        };
+        if !ready {
+            return;
+        }
+        let required_bytes = self.update_space_required(None);
+        if self.disk_space_warning(required_bytes).is_some() {
+            self.notify_unattended_skipped(
+                "There is not enough free disk space. Open Nebula to review them.",
+            );
+            return;
+        }
+        let all_names: Vec<String> = self
+            .state
+            .borrow()
+            .available_updates
+            .iter()
+            .map(|pkg| pkg.name.clone())
+            .collect();
+        let without_kernels = self.update_all_without_kernels(&all_names);
+        if matches!(&without_kernels, Some(packages) if packages.is_empty()) {
+            return;
+        }
+        self.show_toast("Installing updates automatically…");
+        let options = UpdateOptions {
+            unattended: true,
+            ..UpdateOptions::default()
+        };
+        match without_kernels {
+            Some(packages) => self.request_update_multiple(packages, options),
+            None => self.request_update(String::from("__all__"), true, options),
+        }
+    }
+
+    /// The packages "Update All" should touch when kernel updates are held
+    /// back, or `None` when the setting is off or no kernel is among
+    /// `packages`. Held-back kernels stay listed for updating one by one.
+    fn update_all_without_kernels(&self, packages: &[String]) -> Option<Vec<String>> {
+        if !self.settings.borrow().exclude_kernel_from_update_all {
+            return None;
+        }
+        if !packages.iter().any(|name| is_kernel_package(name)) {
+            return None;
+        }
+        Some(
+            packages
+                .iter()
+                .filter(|name| !is_kernel_package(name))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    fn notify_unattended_skipped(&self, reason: &str) {
+        let notification = gio::Notification::new("Automatic updates skipped");
+        notification.set_body(Some(reason));
+        notification.set_default_action("app.show-updates");
+        let icon = gio::ThemedIcon::new("software-update-available");
+        notification.set_icon(&icon);
+        self.app
+            .send_notification(Some("unattended-updates"), &notification);
     }
 
     fn refresh_available_update_names(state: &mut AppState) {
@@ -1242,20 +2181,259 @@ impl AppController {
             return;
         }
 
-        if selected == total {
-            self.start_update(String::from("__all__"), true);
+        if self.xbps_update_pending() && selected > 1 {
+            self.confirm_action(
+                &tr!("Update XBPS First?"),
+                &tr!(
+                    "A newer version of XBPS is available and must be installed before any other package can be updated. Update XBPS now, then check for updates again to install the rest."
+                ),
+                &tr!("Update XBPS"),
+                |controller| controller.update_xbps_first(),
+            );
+            return;
+        }
+
+        // The kernel exclusion only applies to Update All; an explicit
+        // selection is updated as chosen.
+        let excluded = if selected == total {
+            self.update_all_without_kernels(&packages)
+        } else {
+            None
+        };
+        let (packages, from_all) = match excluded {
+            Some(packages) if packages.is_empty() => {
+                self.set_footer_message(Some(&tr!(
+                    "Kernel updates are excluded from Update All. Update them individually."
+                )));
+                return;
+            }
+            Some(packages) => (packages, false),
+            None => (packages, selected == total),
+        };
+
+        if !self.state.borrow().should_confirm_install() {
+            self.launch_update_all(packages, from_all, UpdateOptions::default());
+            return;
+        }
+
+        let (total_bytes, unknown) = {
+            let state = self.state.borrow();
+            let targets = state
+                .available_updates
+                .iter()
+                .filter(|pkg| from_all || packages.contains(&pkg.name));
+            let mut total_bytes = 0;
+            let mut unknown = 0;
+            for pkg in targets {
+                match pkg.download_bytes {
+                    Some(bytes) => total_bytes += bytes,
+                    None => unknown += 1,
+                }
+            }
+            (total_bytes, unknown)
+        };
+        let count = packages.len();
+        let heading = trn!("Install {} update?", "Install {} updates?", count, count);
+        let mut body = tr!("Download size: {}", format_download_size(total_bytes));
+        if unknown > 0 {
+            body.push_str(&trn!(
+                ", plus {} package of unknown size",
+                ", plus {} packages of unknown size",
+                unknown,
+                unknown
+            ));
+        }
+        body.push('.');
+        let args = if from_all {
+            build_update_all_args()
+        } else {
+            build_update_packages_args(&packages)
+        };
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let command = format_terminal_command("xbps-install", &args);
+        // Offered only when a kernel, libc or similar update makes a restart
+        // worthwhile; it stays off unless ticked.
+        let reboot_check = packages
+            .iter()
+            .any(|name| is_critical_update(name))
+            .then(|| gtk::CheckButton::with_label(&tr!("Reboot when finished")));
+        let reboot_choice = reboot_check.clone();
+        let dialog = self.confirm_command(
+            &heading,
+            &body,
+            &tr!("Update"),
+            Some(command),
+            move |controller| {
+                let options = UpdateOptions {
+                    reboot: reboot_choice
+                        .as_ref()
+                        .is_some_and(|check| check.is_active()),
+                    ..UpdateOptions::default()
+                };
+                controller.launch_update_all(packages, from_all, options);
+            },
+        );
+        if let Some(check) = reboot_check {
+            if let Some(area) = dialog.message_area().downcast_ref::<gtk::Box>() {
+                area.append(&check);
+            }
+        }
+    }
+
+    /// Asks before restarting after an update that was set to reboot when
+    /// finished. Cancelling keeps the session running.
+    fn offer_reboot(self: &Rc<Self>, packages: &[String]) {
+        let critical: Vec<&str> = packages
+            .iter()
+            .map(String::as_str)
+            .filter(|name| is_critical_update(name))
+            .collect();
+        let body = tr!(
+            "Updates to {} are installed. Restart now to start using them, or cancel to restart later.",
+            critical.join(", ")
+        );
+        self.confirm_action(&tr!("Reboot Now?"), &body, &tr!("Reboot"), |controller| {
+            controller.request_reboot();
+        });
+    }
+
+    fn request_reboot(&self) {
+        self.set_footer_message(Some(&tr!("Rebooting…")));
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = run_system_reboot();
+            let _ = sender.send(AppMessage::RebootFinished { result });
+        });
+    }
+
+    pub(crate) fn finish_reboot_request(&self, result: Result<CommandResult, String>) {
+        let detail = match &result {
+            Ok(command) if command.success() => return,
+            Ok(command) if command.stderr.trim().is_empty() => command.stdout.trim().to_string(),
+            Ok(command) => command.stderr.trim().to_string(),
+            Err(err) => err.clone(),
+        };
+        self.set_footer_message(None);
+        // A cancelled or agent-less attempt was already reported.
+        if privilege_failure_reported(&result) {
+            return;
+        }
+        self.show_error_dialog(
+            &tr!("Reboot Failed"),
+            &tr!("Couldn't restart the system: {}", detail),
+        );
+    }
+
+    /// Warns when the previous session ended while an update was still
+    /// running, since packages may have been left half-installed.
+    pub(crate) fn warn_about_interrupted_update(self: &Rc<Self>) {
+        let Some(update) = load_in_flight_update() else {
+            return;
+        };
+        // Warn once; the next update run writes a fresh record.
+        clear_in_flight_update();
+
+        let mut body = tr!(
+            "Nebula closed while an update started {} was still running.",
+            format_relative_time(update.started_at)
+        );
+        let unfinished = update.unfinished_packages();
+        if !unfinished.is_empty() {
+            body.push_str("\n\n");
+            body.push_str(&tr!("Not finished: {}", unfinished.join(", ")));
+        }
+        body.push_str("\n\n");
+        body.push_str(&tr!(
+            "Check for updates to see what is still pending. If anything misbehaves, verify the package database from the Tools page."
+        ));
+        self.confirm_action(
+            &tr!("A previous update may have been interrupted"),
+            &body,
+            &tr!("Check for Updates"),
+            |controller| controller.refresh_updates(false),
+        );
+    }
+
+    fn launch_update_all(
+        self: &Rc<Self>,
+        packages: Vec<String>,
+        from_all: bool,
+        options: UpdateOptions,
+    ) {
+        if from_all {
+            self.request_update(String::from("__all__"), true, options);
         } else {
-            self.start_update_multiple(packages);
+            self.request_update_multiple(packages, options);
         }
     }
 
     pub(crate) fn start_update(self: &Rc<Self>, package: String, from_all: bool) {
+        self.request_update(package, from_all, UpdateOptions::default());
+    }
+
+    fn request_update(self: &Rc<Self>, package: String, from_all: bool, options: UpdateOptions) {
+        let required_bytes = if from_all {
+            self.update_space_required(None)
+        } else {
+            self.update_space_required(Some(std::slice::from_ref(&package)))
+        };
+        self.confirm_disk_space_then(required_bytes, move |controller| {
+            controller.begin_update(package, from_all, options);
+        });
+    }
+
+    /// Space an update of `packages` (every update when `None`) needs: the
+    /// download plus the unpacked size. The dry run only sizes the full
+    /// update, so a subset is given the same share of the unpacked size as
+    /// it has of the download.
+    fn update_space_required(&self, packages: Option<&[String]>) -> u64 {
+        let state = self.state.borrow();
+        let download: u64 = state
+            .available_updates
+            .iter()
+            .filter(|pkg| packages.is_none_or(|names| names.contains(&pkg.name)))
+            .filter_map(|pkg| pkg.download_bytes)
+            .sum();
+        let Some(sizes) = state.update_transaction_sizes else {
+            return download;
+        };
+        let installed = match packages {
+            None => sizes.installed_bytes,
+            Some(_) if sizes.download_bytes > 0 => {
+                let share = (download as f64 / sizes.download_bytes as f64).min(1.0);
+                (sizes.installed_bytes as f64 * share) as u64
+            }
+            Some(_) => 0,
+        };
+        download.saturating_add(installed)
+    }
+
+    /// Runs `proceed` straight away, or after the user accepts a warning
+    /// when the update would leave too little free disk space.
+    fn confirm_disk_space_then<F>(self: &Rc<Self>, required_bytes: u64, proceed: F)
+    where
+        F: FnOnce(&Rc<Self>) + 'static,
+    {
+        match self.disk_space_warning(required_bytes) {
+            Some(warning) => {
+                let body = format!(
+                    "{}\n\nAn update that runs out of space can leave packages half-installed.",
+                    warning
+                );
+                self.confirm_action("Low Disk Space", &body, "Update Anyway", proceed);
+            }
+            None => proceed(self),
+        }
+    }
+
+    fn begin_update(self: &Rc<Self>, package: String, from_all: bool, options: UpdateOptions) {
         // Check if we should create a waypoint snapshot before system updates
         if from_all && self.settings.borrow().waypoint_before_upgrades {
             let package_count = self.state.borrow().available_updates.len();
+            self.state.borrow_mut().snapshot_update_options = options;
             self.create_snapshot_then_update(package, from_all, package_count);
         } else {
-            self.execute_update(package, from_all);
+            self.execute_update(package, from_all, options);
         }
     }
 
@@ -1281,19 +2459,34 @@ impl AppController {
     }
 
     pub(crate) fn start_update_multiple(self: &Rc<Self>, packages: Vec<String>) {
+        self.request_update_multiple(packages, UpdateOptions::default());
+    }
+
+    fn request_update_multiple(self: &Rc<Self>, packages: Vec<String>, options: UpdateOptions) {
         if packages.is_empty() {
             return;
         }
-        self.execute_update_multiple(packages);
+        let required_bytes = self.update_space_required(Some(packages.as_slice()));
+        self.confirm_disk_space_then(required_bytes, move |controller| {
+            controller.execute_update_multiple(packages, options);
+        });
     }
 
-    pub(crate) fn execute_update(self: &Rc<Self>, package: String, from_all: bool) {
+    pub(crate) fn execute_update(
+        self: &Rc<Self>,
+        package: String,
+        from_all: bool,
+        options: UpdateOptions,
+    ) {
         {
             let state = self.state.borrow();
             if state.update_in_progress || state.updates_loading {
                 return;
             }
         }
+        if !self.ensure_update_terminal() {
+            return;
+        }
 
         let affected_packages = if from_all {
             self.state
@@ -1310,7 +2503,9 @@ impl AppController {
             let mut state = self.state.borrow_mut();
             state.update_in_progress = true;
             state.update_log.clear();
+            state.update_download_failures.clear();
         }
+        self.refresh_busy_indicator();
         self.refresh_update_log_buffer();
 
         if affected_packages.is_empty() {
@@ -1324,6 +2519,7 @@ impl AppController {
         }
 
         self.set_packages_status(&affected_packages, UpdateStatus::Queued);
+        self.begin_update_eta(affected_packages.len());
 
         let footer_message = if from_all {
             let message = "Installing all available updates…".to_string();
@@ -1376,33 +2572,77 @@ impl AppController {
             );
         }
 
-        let sender = self.sender.clone();
-        if from_all {
-            let packages_for_thread = affected_packages.clone();
-            let args = build_update_all_args();
-            thread::spawn(move || {
-                let result = run_update_command(args, &sender);
-                let _ = sender.send(AppMessage::UpdateFinished {
-                    packages: packages_for_thread,
-                    result,
-                    all: true,
-                });
-            });
+        let args = if from_all {
+            build_update_all_args()
         } else {
-            let packages_for_thread = affected_packages.clone();
-            let args = build_update_packages_args(&packages_for_thread);
-            thread::spawn(move || {
-                let result = run_update_command(args, &sender);
-                let _ = sender.send(AppMessage::UpdateFinished {
-                    packages: packages_for_thread,
-                    result,
-                    all: false,
-                });
-            });
+            build_update_packages_args(&affected_packages)
+        };
+        self.spawn_update_worker(affected_packages, args, from_all, options);
+    }
+
+    /// Checks that a terminal is available when updates should run in one,
+    /// explaining the problem instead of starting the update otherwise.
+    fn ensure_update_terminal(&self) -> bool {
+        if !self.state.borrow().run_updates_in_terminal || detect_terminal().is_some() {
+            return true;
+        }
+        self.show_error_dialog(
+            "No terminal found",
+            "Updates are set to run in a terminal window, but no supported terminal \
+             (foot, alacritty, kitty, wezterm, konsole, xfce4-terminal or xterm) was \
+             found. Install one, set $TERMINAL, or turn the option off in Preferences.",
+        );
+        false
+    }
+
+    fn spawn_update_worker(
+        &self,
+        packages: Vec<String>,
+        args: Vec<String>,
+        all: bool,
+        options: UpdateOptions,
+    ) {
+        let terminal = if self.state.borrow().run_updates_in_terminal {
+            detect_terminal()
+        } else {
+            None
+        };
+        {
+            let mut state = self.state.borrow_mut();
+            state.unattended_update_running = options.unattended;
+            state.reboot_after_update = options.reboot;
+            state.update_log_command = Some(update_command_line(&args));
+            let record = InFlightUpdate {
+                started_at: Utc::now(),
+                packages: packages.clone(),
+                all,
+                statuses: packages
+                    .iter()
+                    .filter_map(|name| {
+                        let status = state.update_statuses.get(name)?;
+                        Some((name.clone(), status.label().to_string()))
+                    })
+                    .collect(),
+            };
+            persist_in_flight_update(&record);
+            state.in_flight_update = Some(record);
         }
+
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = match terminal {
+                Some(terminal) => run_update_in_terminal(&terminal, args, &sender),
+                None => run_update_command(args, &sender),
+            };
+            let _ = sender.send(AppMessage::UpdateFinished {
+                packages,
+                result,
+                all,
+            });
+        });
     }
 
-    fn execute_update_multiple(self: &Rc<Self>, packages: Vec<String>) {
+    fn execute_update_multiple(self: &Rc<Self>, packages: Vec<String>, options: UpdateOptions) {
         if packages.is_empty() {
             return;
         }
@@ -1413,15 +2653,21 @@ impl AppController {
                 return;
             }
         }
+        if !self.ensure_update_terminal() {
+            return;
+        }
 
         {
             let mut state = self.state.borrow_mut();
             state.update_in_progress = true;
             state.update_log.clear();
+            state.update_download_failures.clear();
         }
+        self.refresh_busy_indicator();
         self.refresh_update_log_buffer();
 
         self.set_packages_status(&packages, UpdateStatus::Queued);
+        self.begin_update_eta(packages.len());
 
         let message = format!(
             "Updating {} selected package{}…",
@@ -1466,17 +2712,8 @@ impl AppController {
             );
         }
 
-        let affected = packages.clone();
-        let args = build_update_packages_args(&affected);
-        let sender = self.sender.clone();
-        thread::spawn(move || {
-            let result = run_update_command(args, &sender);
-            let _ = sender.send(AppMessage::UpdateFinished {
-                packages: affected,
-                result,
-                all: false,
-            });
-        });
+        let args = build_update_packages_args(&packages);
+        self.spawn_update_worker(packages, args, false, options);
     }
 
     pub(crate) fn finish_update(
@@ -1488,9 +2725,16 @@ impl AppController {
         {
             let mut state = self.state.borrow_mut();
             state.update_in_progress = false;
+            state.in_flight_update = None;
         }
+        clear_in_flight_update();
 
         self.set_check_buttons_sensitive(true);
+        self.clear_update_eta();
+        let unattended = std::mem::take(&mut self.state.borrow_mut().unattended_update_running);
+        let reported = privilege_failure_reported(&result);
+        record_update_history(&packages, all, unattended, &result);
+        self.collect_config_conflicts();
 
         match result {
             Ok(ref command) => {
@@ -1512,6 +2756,7 @@ impl AppController {
                             Self::refresh_available_update_names(&mut state);
                             state.selected_updates.clear();
                             state.total_update_size = 0;
+                            state.update_transaction_sizes = None;
                         }
                     } else if packages.len() == 1 {
                         let name = packages.first().cloned().unwrap_or_default();
@@ -1529,6 +2774,7 @@ impl AppController {
                                 .iter()
                                 .filter_map(|pkg| pkg.download_bytes)
                                 .sum();
+                            state.update_transaction_sizes = None;
                         }
                     } else {
                         let message = "Selected updates installed successfully.";
@@ -1549,6 +2795,7 @@ impl AppController {
                                 .iter()
                                 .filter_map(|pkg| pkg.download_bytes)
                                 .sum();
+                            state.update_transaction_sizes = None;
                         }
                     }
                     self.refresh_installed_packages();
@@ -1595,7 +2842,20 @@ impl AppController {
                     };
                     self.set_summary_text("");
                     self.set_footer_message(Some(&message));
-                    self.show_error_dialog("Update Failed", &message);
+                    let blockers = {
+                        let output = format!("{}\n{}", command.stderr, command.stdout);
+                        parse_held_blockers(&output, &self.state.borrow().held_set)
+                    };
+                    let retry = self.download_retry_packages(&packages);
+                    if !reported {
+                        if !blockers.is_empty() {
+                            self.offer_unhold_and_retry(blockers, packages.clone(), all);
+                        } else if retry.is_empty() {
+                            self.show_error_dialog("Update Failed", &message);
+                        } else {
+                            self.offer_download_retry(&message, retry);
+                        }
+                    }
                     self.rebuild_updates_list();
                     self.update_update_controls();
                 }
@@ -1616,7 +2876,9 @@ impl AppController {
                 };
                 self.set_summary_text("");
                 self.set_footer_message(Some(&message));
-                self.show_error_dialog("Update Failed", &message);
+                if !reported {
+                    self.show_error_dialog("Update Failed", &message);
+                }
                 self.rebuild_updates_list();
                 self.update_update_controls();
             }
@@ -1646,6 +2908,95 @@ impl AppController {
         self.refresh_update_log_buffer();
         self.update_updates_badge();
         self.update_footer_text();
+        self.refresh_xbps_banner();
+        // Updates held back by the old xbps only show up once it is replaced,
+        // and a refusal to update without it means the list is stale.
+        let updated_xbps = packages.iter().any(|name| name == "xbps")
+            && matches!(&result, Ok(command) if command.success());
+        let blocked_by_xbps = matches!(
+            &result,
+            Ok(command) if !command.success() && requires_xbps_self_update(&command.stderr)
+        );
+        if updated_xbps || blocked_by_xbps {
+            self.refresh_updates(true);
+        }
+        let reboot_requested = std::mem::take(&mut self.state.borrow_mut().reboot_after_update);
+        if reboot_requested && matches!(&result, Ok(command) if command.success()) {
+            self.offer_reboot(&packages);
+        }
+        if matches!(&result, Ok(command) if command.success()) {
+            self.start_restart_check();
+        }
+        self.start_next_queued_operation();
+    }
+
+    /// Looks for processes still using libraries the update replaced. The
+    /// scan reads every process's memory map, so it runs off the main thread.
+    fn start_restart_check(&self) {
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let processes = processes_needing_restart();
+            let _ = sender.send(AppMessage::RestartCheckFinished { processes });
+        });
+    }
+
+    pub(crate) fn finish_restart_check(&self, processes: Vec<String>) {
+        self.state.borrow_mut().restart_needed = processes;
+        self.refresh_restart_banner();
+    }
+
+    pub(crate) fn dismiss_restart_banner(&self) {
+        self.state.borrow_mut().restart_needed.clear();
+        self.refresh_restart_banner();
+    }
+
+    fn refresh_restart_banner(&self) {
+        const SHOWN_NAMES: usize = 5;
+        // Banner titles are markup, and process names are arbitrary text.
+        let processes = self.state.borrow().restart_needed.clone();
+        let banner = &self.widgets.updates.restart_banner;
+        if !processes.is_empty() {
+            let mut names = processes
+                .iter()
+                .take(SHOWN_NAMES)
+                .map(|name| glib::markup_escape_text(name).to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if processes.len() > SHOWN_NAMES {
+                names = tr!("{} and {} more", names, processes.len() - SHOWN_NAMES);
+            }
+            banner.set_title(&trn!(
+                "Restart to use the updated libraries: {}",
+                "Restart these to use the updated libraries: {}",
+                processes.len(),
+                names
+            ));
+        }
+        banner.set_revealed(!processes.is_empty());
+    }
+
+    fn xbps_update_pending(&self) -> bool {
+        self.state.borrow().available_update_names.contains("xbps")
+    }
+
+    /// xbps refuses to update anything else while a newer xbps is available,
+    /// so the banner stays up until that update has gone through.
+    fn refresh_xbps_banner(&self) {
+        self.widgets
+            .updates
+            .xbps_banner
+            .set_revealed(self.xbps_update_pending());
+    }
+
+    pub(crate) fn update_xbps_first(self: &Rc<Self>) {
+        let busy = {
+            let state = self.state.borrow();
+            state.updates_loading || state.update_in_progress
+        };
+        if busy || !self.xbps_update_pending() {
+            return;
+        }
+        self.start_update(String::from("xbps"), false);
     }
 }
 
@@ -1666,10 +3017,155 @@ fn build_update_packages_args(packages: &[String]) -> Vec<String> {
     args
 }
 
+fn update_command_line(args: &[String]) -> String {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    format_privileged_command("xbps-install", &args)
+}
+
+struct TerminalCommand {
+    program: String,
+    exec_args: Vec<String>,
+}
+
+/// Finds a terminal emulator to run interactive updates in, preferring the
+/// one named by `$TERMINAL`.
+fn detect_terminal() -> Option<TerminalCommand> {
+    if let Ok(preferred) = env::var("TERMINAL") {
+        let preferred = preferred.trim();
+        if !preferred.is_empty() && find_in_path(preferred) {
+            let exec_args = TERMINAL_CANDIDATES
+                .iter()
+                .find(|(name, _)| Path::new(preferred).file_name() == Some(OsStr::new(name)))
+                .map(|(_, args)| args.iter().map(|arg| arg.to_string()).collect())
+                .unwrap_or_else(|| vec!["-e".to_string()]);
+            return Some(TerminalCommand {
+                program: preferred.to_string(),
+                exec_args,
+            });
+        }
+    }
+
+    TERMINAL_CANDIDATES
+        .iter()
+        .find(|(name, _)| find_in_path(name))
+        .map(|(name, args)| TerminalCommand {
+            program: name.to_string(),
+            exec_args: args.iter().map(|arg| arg.to_string()).collect(),
+        })
+}
+
+fn find_in_path(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Runs xbps-install in a terminal window so the user can answer its prompts.
+/// Terminals don't reliably pass on the exit code, so the script writes it to
+/// a status file that is read back once the window closes. Its output never
+/// reaches Nebula, so per-package statuses and configuration conflicts are
+/// not tracked for these runs.
+fn run_update_in_terminal(
+    terminal: &TerminalCommand,
+    args: Vec<String>,
+    sender: &mpsc::Sender<AppMessage>,
+) -> Result<CommandResult, String> {
+    let status_dir = create_private_dir("nebula-update")?;
+    let status_path = status_dir.join("status");
+
+    // Without -y, xbps-install asks before applying the transaction.
+    let interactive_args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "-y")
+        .collect();
+    let command_line = format_privileged_command("xbps-install", &interactive_args);
+    let script = format!(
+        "{command_line}; status=$?; \
+         printf '%s' \"$status\" > {status_file}; echo; \
+         echo \"xbps-install exited with status $status.\"; \
+         printf 'Press Enter to close this window.'; read _; exit $status",
+        status_file = shell_quote(&status_path.to_string_lossy()),
+    );
+
+    let _ = sender.send(AppMessage::UpdateLogLine {
+        line: command_line.clone(),
+    });
+    let _ = sender.send(AppMessage::UpdateLogLine {
+        line: format!("Running in {}…", terminal.program),
+    });
+    let _ = sender.send(AppMessage::UpdateLogLine {
+        line: "Output stays in the terminal, so per-package progress and configuration \
+               conflicts aren't shown here."
+            .to_string(),
+    });
+
+    let status = Command::new(&terminal.program)
+        .args(&terminal.exec_args)
+        .arg("sh")
+        .arg("-c")
+        .arg(&script)
+        .stdin(Stdio::null())
+        .status()
+        .map_err(|err| format!("Failed to launch {}: {}", terminal.program, err))?;
+
+    let code = fs::read_to_string(&status_path)
+        .ok()
+        .and_then(|value| value.trim().parse::<i32>().ok());
+    let _ = fs::remove_dir_all(&status_dir);
+
+    let Some(code) = code else {
+        let message = format!(
+            "The terminal closed before xbps-install finished ({}).",
+            status
+        );
+        let _ = sender.send(AppMessage::UpdateLogLine {
+            line: message.clone(),
+        });
+        return Err(message);
+    };
+
+    let _ = sender.send(AppMessage::UpdateLogLine {
+        line: format!("xbps-install exited with status {}.", code),
+    });
+    Ok(CommandResult {
+        code: Some(code),
+        stdout: String::new(),
+        stderr: String::new(),
+    })
+}
+
+/// Creates a fresh directory only the current user can enter, under
+/// `$XDG_RUNTIME_DIR` when set and the temporary directory otherwise. It
+/// never reuses an existing path, so another user can't plant a file there.
+fn create_private_dir(prefix: &str) -> Result<PathBuf, String> {
+    let base = env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(env::temp_dir);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    let dir = base.join(format!("{}-{}-{}", prefix, std::process::id(), nanos));
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    Ok(dir)
+}
+
 fn run_update_command(
     args: Vec<String>,
     sender: &mpsc::Sender<AppMessage>,
 ) -> Result<CommandResult, String> {
+    let _ = sender.send(AppMessage::UpdateLogLine {
+        line: update_command_line(&args),
+    });
+
     let mut command = Command::new("pkexec");
     command.arg("xbps-install");
     for arg in &args {
@@ -1778,3 +3274,138 @@ fn run_update_command(
         stderr: stderr_accum,
     })
 }
+
+fn format_eta(seconds: i64) -> String {
+    let minutes = (seconds + 30) / 60;
+    if minutes < 1 {
+        "Less than a minute remaining".to_string()
+    } else {
+        format!("~{} min remaining", minutes)
+    }
+}
+
+fn is_critical_update(name: &str) -> bool {
+    matches_package_pattern(name, CRITICAL_UPDATE_PATTERNS)
+}
+
+fn is_kernel_package(name: &str) -> bool {
+    matches_package_pattern(name, KERNEL_PACKAGE_PATTERNS)
+}
+
+fn matches_package_pattern(name: &str, patterns: &[&str]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == *pattern,
+        })
+}
+
+fn build_update_group_header(title: &str) -> gtk::Label {
+    let label = gtk::Label::builder()
+        .label(title)
+        .xalign(0.0)
+        .margin_top(12)
+        .margin_bottom(6)
+        .margin_start(12)
+        .build();
+    label.add_css_class("heading");
+    label
+}
+
+fn persist_in_flight_update(record: &InFlightUpdate) {
+    if let Err(err) = save_in_flight_update(record) {
+        eprintln!("Failed to save update state: {}", err);
+    }
+}
+
+fn record_update_history(
+    packages: &[String],
+    all: bool,
+    unattended: bool,
+    result: &Result<CommandResult, String>,
+) {
+    let (success, error) = match result {
+        Ok(command) if command.success() => (true, None),
+        Ok(command) => {
+            let detail = [&command.stderr, &command.stdout]
+                .iter()
+                .flat_map(|output| output.lines().rev())
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(str::to_string);
+            (false, detail)
+        }
+        Err(err) => (false, Some(err.clone())),
+    };
+
+    let entry = UpdateHistoryEntry {
+        finished_at: Utc::now(),
+        packages: packages.to_vec(),
+        all,
+        success,
+        error,
+        unattended,
+    };
+    if let Err(err) = append_update_history(entry) {
+        eprintln!("Failed to save update history: {}", err);
+    }
+}
+
+/// One side of the config diff: a heading over a text view with a line per
+/// diff row, blank where the other side has lines this one lacks.
+fn build_config_diff_pane(
+    title: &str,
+    rows: &[DiffRow],
+    current_side: bool,
+) -> (gtk::Box, gtk::ScrolledWindow) {
+    let buffer = gtk::TextBuffer::new(None);
+    // Removed lines are tinted red on the left, added lines green on the right.
+    let color = if current_side {
+        "rgba(224, 27, 36, 0.18)"
+    } else {
+        "rgba(46, 194, 126, 0.18)"
+    };
+    let highlight = buffer.create_tag(Some("changed"), &[("paragraph-background", &color)]);
+
+    let last = rows.len().saturating_sub(1);
+    for (index, row) in rows.iter().enumerate() {
+        let text = if current_side { &row.current } else { &row.new };
+        let mut line = text.clone().unwrap_or_default();
+        if index < last {
+            line.push('\n');
+        }
+        let mut end = buffer.end_iter();
+        match (&highlight, row.kind, text) {
+            (Some(tag), kind, Some(_)) if kind != DiffRowKind::Same => {
+                buffer.insert_with_tags(&mut end, &line, &[tag]);
+            }
+            _ => buffer.insert(&mut end, &line),
+        }
+    }
+
+    let text_view = gtk::TextView::builder()
+        .buffer(&buffer)
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::None)
+        .build();
+    text_view.set_cursor_visible(false);
+
+    let scroller = gtk::ScrolledWindow::builder()
+        .hexpand(true)
+        .vexpand(true)
+        .child(&text_view)
+        .build();
+
+    let heading = gtk::Label::builder().label(title).xalign(0.0).build();
+    heading.add_css_class("heading");
+
+    let pane = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(6)
+        .build();
+    pane.append(&heading);
+    pane.append(&scroller);
+    (pane, scroller)
+}
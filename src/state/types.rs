@@ -1,13 +1,20 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 
 use gtk::glib;
 use gtk4 as gtk;
+use serde::{Deserialize, Serialize};
 
 use crate::details::{DiscoverDetail, InstalledDetail};
-use crate::settings::{StartPagePreference, UpdateCheckFrequency};
+use crate::mirrors::RepositoryClass;
+use crate::settings::{MirrorStat, StartPagePreference, UpdateCheckFrequency};
 use crate::spotlight::{SpotlightCache, SpotlightCategory};
 use crate::state::controller::tools::{MaintenanceActionState, MaintenanceTask};
 use crate::types::{CommandResult, PackageInfo};
+use crate::update_history::InFlightUpdate;
+use crate::xbps::{
+    CacheCleanupPlan, ConfigConflict, ConfigResolution, FreeSpace, IntegrityIssue, TransactionSizes,
+};
 use chrono::{DateTime, Utc};
 
 /// Type of package operation
@@ -56,13 +63,31 @@ impl PackageOperation {
     }
 }
 
+/// How an update was started. It travels with the request and only lands in
+/// `AppState` once the update worker spawns, so an update that never starts
+/// leaves nothing behind for the next one.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct UpdateOptions {
+    /// Started by the automatic updater rather than the user.
+    pub(crate) unattended: bool,
+    /// Offer a reboot once the update succeeds.
+    pub(crate) reboot: bool,
+}
+
 #[derive(Default)]
 pub(crate) struct AppState {
     pub(crate) search_results: Vec<PackageInfo>,
     pub(crate) installed_packages: Vec<PackageInfo>,
     pub(crate) installed_set: HashSet<String>,
+    pub(crate) held_set: HashSet<String>,
+    pub(crate) offline: bool,
+    pub(crate) pending_search: bool,
     pub(crate) installed_filter: String,
     pub(crate) installed_filtered: Vec<usize>,
+    /// Subpackages folded under their installed parent, keyed by parent
+    /// name, while subpackage grouping is enabled.
+    pub(crate) installed_variant_groups: HashMap<String, Vec<String>>,
+    pub(crate) installed_expanded_groups: HashSet<String>,
     pub(crate) installed_selected: HashSet<String>,
     pub(crate) installed_filter_mode: InstalledFilter,
     pub(crate) installed_last_refresh: Option<glib::DateTime>,
@@ -76,31 +101,105 @@ pub(crate) struct AppState {
     pub(crate) installed_status_message: Option<String>,
     pub(crate) installed_row_buttons_visible: bool,
     pub(crate) discover_row_buttons_visible: bool,
+    /// Set while the window is too narrow to keep the discover detail pane
+    /// open beside the results; the pane then only appears for a selection.
+    pub(crate) discover_narrow_layout: bool,
     pub(crate) available_updates: Vec<PackageInfo>,
     pub(crate) available_update_names: HashSet<String>,
     pub(crate) update_statuses: HashMap<String, UpdateStatus>,
-    pub(crate) update_log: Vec<String>,
+    /// Packages whose download was cut off during the current update run.
+    pub(crate) update_download_failures: HashSet<String>,
+    pub(crate) update_log: Vec<(DateTime<Utc>, String)>,
+    pub(crate) update_log_timestamps: bool,
+    /// Whether the update log view keeps scrolling to new lines. Cleared
+    /// while the user has scrolled up to read earlier output.
+    pub(crate) update_log_follow_tail: bool,
+    pub(crate) update_log_command: Option<String>,
+    pub(crate) config_conflicts: Vec<ConfigConflict>,
+    /// Set while a configuration conflict is being resolved.
+    pub(crate) config_resolution_in_progress: bool,
+    pub(crate) discover_selection: Vec<String>,
     pub(crate) updates_loading: bool,
     pub(crate) update_in_progress: bool,
+    pub(crate) update_started_at: Option<DateTime<Utc>>,
+    pub(crate) update_package_total: usize,
+    pub(crate) update_completions: Vec<DateTime<Utc>>,
+    /// On-disk record of the running update, kept in step with
+    /// `update_statuses` so an interrupted run is noticed at next launch.
+    pub(crate) in_flight_update: Option<InFlightUpdate>,
     pub(crate) selected_updates: HashSet<String>,
     pub(crate) selected_update: Option<usize>,
+    pub(crate) updates_filter: String,
     pub(crate) total_update_size: u64,
+    /// Download and disk totals from a dry run of the full update, when it
+    /// succeeded for the current list of updates.
+    pub(crate) update_transaction_sizes: Option<TransactionSizes>,
+    /// Free space on the tightest filesystem a transaction writes to,
+    /// measured on a worker whenever the installed list is refreshed.
+    pub(crate) free_space: Option<FreeSpace>,
     pub(crate) last_update_check: Option<glib::DateTime>,
     pub(crate) auto_check_enabled: bool,
     pub(crate) auto_check_frequency: UpdateCheckFrequency,
     pub(crate) auto_check_source: Option<glib::SourceId>,
+    pub(crate) auto_install_updates: bool,
+    pub(crate) auto_install_pending: bool,
+    pub(crate) unattended_update_running: bool,
+    /// The user asked to reboot once the running update succeeds.
+    pub(crate) reboot_after_update: bool,
+    /// Options of the update waiting on a pre-upgrade snapshot.
+    pub(crate) snapshot_update_options: UpdateOptions,
+    /// Processes still running code an update replaced, as found by the last
+    /// post-update scan.
+    pub(crate) restart_needed: Vec<String>,
+    /// Set when the window was closed during a transaction; the app quits
+    /// once it finishes.
+    pub(crate) close_when_idle: bool,
+    /// Every repository package, loaded the first time "Browse All" opens.
+    pub(crate) repository_index: Vec<PackageInfo>,
+    pub(crate) repository_index_loading: bool,
+    /// Repository chosen in the detail pane for packages that several
+    /// repositories offer, keyed by package name.
+    pub(crate) install_sources: HashMap<String, String>,
     pub(crate) selected_search: Option<usize>,
     pub(crate) search_in_progress: bool,
+    pub(crate) search_sequence: u64,
+    pub(crate) last_search_query: Option<String>,
+    /// Query of the last search that failed, offered again by the toast's
+    /// Retry button.
+    pub(crate) failed_search_query: Option<String>,
+    pub(crate) search_debounce_source: Option<glib::SourceId>,
+    pub(crate) search_descriptions: bool,
     pub(crate) install_in_progress: bool,
     pub(crate) installing_package: Option<String>,
     pub(crate) remove_in_progress: bool,
     pub(crate) removing_packages: HashSet<String>,
+    /// Packages from the last successful removal, kept while its toast is
+    /// shown so the removal can be undone.
+    pub(crate) undo_removal_packages: Vec<String>,
+    /// Installs and removals requested while another package operation
+    /// was running, started in order as each one finishes.
+    pub(crate) operation_queue: VecDeque<QueuedOperation>,
+    /// Installs and removals collected across pages, applied together in
+    /// one transaction from the review bar.
+    pub(crate) pending_changes: Vec<PendingChange>,
+    pub(crate) pending_changes_in_progress: bool,
+    /// Set once the missing-polkit-agent help has been shown this session.
+    pub(crate) privilege_help_shown: bool,
     pub(crate) pin_in_progress: bool,
+    /// Open `begin_batch_ui_update` calls; list rebuilds wait while nonzero.
+    pub(crate) ui_batch_depth: usize,
+    /// A rebuild was skipped during the batch and is due when it ends.
+    pub(crate) ui_batch_dirty: bool,
     pub(crate) installed_refresh_in_progress: bool,
     pub(crate) spotlight_cache: SpotlightCache,
     pub(crate) spotlight_recent: Vec<PackageInfo>,
+    pub(crate) spotlight_new: Vec<PackageInfo>,
+    pub(crate) spotlight_show_new: bool,
+    pub(crate) last_visit: Option<DateTime<Utc>>,
     pub(crate) spotlight_categories: HashMap<SpotlightCategory, Vec<PackageInfo>>,
     pub(crate) spotlight_loading: bool,
+    pub(crate) spotlight_retry_attempt: u32,
+    pub(crate) spotlight_retry_source: Option<glib::SourceId>,
     pub(crate) spotlight_last_refresh: Option<DateTime<Utc>>,
     pub(crate) active_spotlight_category: Option<SpotlightCategory>,
     pub(crate) spotlight_search_backup: Option<Vec<PackageInfo>>,
@@ -110,10 +209,16 @@ pub(crate) struct AppState {
     pub(crate) discover_detail_cache: HashMap<String, DiscoverDetail>,
     pub(crate) discover_detail_loading: HashSet<String>,
     pub(crate) discover_detail_errors: HashMap<String, String>,
+    pub(crate) screenshot_cache: HashMap<String, PathBuf>,
+    pub(crate) screenshot_loading: HashSet<String>,
+    pub(crate) screenshot_failed: HashSet<String>,
     pub(crate) discover_detail_history: Vec<String>,
+    /// Packages whose Discover detail was opened this session, newest first.
+    pub(crate) recently_viewed: Vec<String>,
     pub(crate) discover_detail_navigation_active: bool,
     pub(crate) discover_detail_package: Option<String>,
     pub(crate) pending_discover_target: Option<String>,
+    pub(crate) requested_package: Option<String>,
     pub(crate) discover_detail_focus: Option<PackageInfo>,
     pub(crate) updates_detail_package: Option<String>,
     pub(crate) updates_detail_cache: HashMap<String, InstalledDetail>,
@@ -122,22 +227,123 @@ pub(crate) struct AppState {
     pub(crate) start_page_preference: StartPagePreference,
     pub(crate) confirm_install: bool,
     pub(crate) confirm_remove: bool,
+    pub(crate) install_summary: bool,
     pub(crate) footer_message: Option<String>,
     pub(crate) notify_updates: bool,
+    pub(crate) show_spotlight: bool,
+    pub(crate) run_updates_in_terminal: bool,
     pub(crate) updates_notification_sent: bool,
     pub(crate) maintenance_cleanup: MaintenanceActionState,
     pub(crate) maintenance_pkgdb: MaintenanceActionState,
+    pub(crate) maintenance_integrity: MaintenanceActionState,
+    pub(crate) maintenance_pkgdb_repair: MaintenanceActionState,
     pub(crate) maintenance_reconfigure: MaintenanceActionState,
     pub(crate) maintenance_alternatives: MaintenanceActionState,
+    /// Set while `xbps-alternatives -s` switches a group's provider.
+    pub(crate) alternative_set_in_progress: bool,
     pub(crate) maintenance_cache_clean: MaintenanceActionState,
+    pub(crate) maintenance_hold_all: MaintenanceActionState,
+    pub(crate) maintenance_unhold_all: MaintenanceActionState,
+    pub(crate) maintenance_sync_repos: MaintenanceActionState,
+    /// Packages processed so far and in total while holding or unholding
+    /// everything.
+    pub(crate) hold_all_progress: Option<(usize, usize)>,
+    pub(crate) cache_clean_planning: bool,
+    pub(crate) removal_impact_pending: bool,
     pub(crate) tools_status_message: Option<String>,
     pub(crate) tools_status_is_error: bool,
     pub(crate) selected_mirror_ids: Vec<String>,
+    pub(crate) repository_classes: Vec<RepositoryClass>,
+    pub(crate) mirror_stats_refreshing: bool,
     pub(crate) operation_history: Vec<PackageOperation>,
     pub(crate) max_operation_history: usize,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+impl AppState {
+    /// The list shown in the spotlight column: recent updates, or new
+    /// additions since the last visit when that view is toggled on.
+    pub(crate) fn visible_spotlight_recent(&self) -> &[PackageInfo] {
+        if self.spotlight_show_new {
+            &self.spotlight_new
+        } else {
+            &self.spotlight_recent
+        }
+    }
+
+    /// Summary mode skips the yes/no dialog and reports the transaction in a
+    /// toast once it finishes instead.
+    pub(crate) fn should_confirm_install(&self) -> bool {
+        self.confirm_install && !self.install_summary
+    }
+
+    pub(crate) fn should_confirm_remove(&self) -> bool {
+        self.confirm_remove && !self.install_summary
+    }
+
+    /// True while something is changing packages, system configuration or
+    /// the package database, or verifying installed files against it.
+    pub(crate) fn has_package_operation(&self) -> bool {
+        self.install_in_progress
+            || self.remove_in_progress
+            || self.pin_in_progress
+            || self.update_in_progress
+            || self.maintenance_cleanup.running
+            || self.maintenance_pkgdb_repair.running
+            || self.maintenance_reconfigure.running
+            || self.maintenance_cache_clean.running
+            || self.maintenance_hold_all.running
+            || self.maintenance_unhold_all.running
+            || self.maintenance_sync_repos.running
+            || self.maintenance_integrity.running
+            || self.alternative_set_in_progress
+            || self.config_resolution_in_progress
+    }
+
+    /// True while an install, removal or update holds the package database,
+    /// so new single-package requests have to wait in the queue.
+    pub(crate) fn package_transaction_running(&self) -> bool {
+        self.install_in_progress || self.remove_in_progress || self.update_in_progress
+    }
+
+    pub(crate) fn is_queued(&self, package: &str) -> bool {
+        self.operation_queue
+            .iter()
+            .any(|operation| operation.package() == package)
+    }
+
+    /// True while any worker thread is running on behalf of the UI.
+    pub(crate) fn has_background_work(&self) -> bool {
+        self.search_in_progress
+            || self.repository_index_loading
+            || self.install_in_progress
+            || self.remove_in_progress
+            || self.pin_in_progress
+            || self.installed_refresh_in_progress
+            || self.updates_loading
+            || self.update_in_progress
+            || self.spotlight_loading
+            || !self.discover_detail_loading.is_empty()
+            || !self.installed_detail_loading.is_empty()
+            || !self.updates_detail_loading.is_empty()
+            || self.maintenance_cleanup.running
+            || self.maintenance_pkgdb.running
+            || self.maintenance_integrity.running
+            || self.maintenance_pkgdb_repair.running
+            || self.maintenance_reconfigure.running
+            || self.maintenance_alternatives.running
+            || self.maintenance_cache_clean.running
+            || self.maintenance_hold_all.running
+            || self.maintenance_unhold_all.running
+            || self.maintenance_sync_repos.running
+            || self.alternative_set_in_progress
+            || self.config_resolution_in_progress
+            || self.cache_clean_planning
+    }
+}
+
+/// Progress of one package in an update run. The variant names are also
+/// what [`crate::update_history::InFlightUpdate`] stores, so keep them stable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub(crate) enum UpdateStatus {
     Queued,
     Preparing,
@@ -187,7 +393,11 @@ pub(crate) enum DiscoverMode {
 
 #[allow(dead_code)]
 pub(crate) enum AppMessage {
+    RepositoryIndexLoaded {
+        result: Result<Vec<PackageInfo>, String>,
+    },
     SearchFinished {
+        sequence: u64,
         query: String,
         result: Result<Vec<PackageInfo>, String>,
     },
@@ -202,25 +412,67 @@ pub(crate) enum AppMessage {
         package: String,
         result: Result<CommandResult, String>,
     },
+    InstallBatchFinished {
+        packages: Vec<String>,
+        /// Requested names no enabled repository provides; left out of the
+        /// transaction.
+        skipped: Vec<String>,
+        /// Architectures that do build some of the skipped packages.
+        other_architectures: HashMap<String, Vec<String>>,
+        result: Result<CommandResult, String>,
+    },
+    InstallLogLine {
+        line: String,
+    },
     RemoveBatchFinished {
         packages: Vec<String>,
         result: Result<CommandResult, String>,
     },
+    PendingChangesImpactLoaded {
+        result: Result<Vec<String>, String>,
+    },
+    PendingChangesFinished {
+        installs: Vec<String>,
+        removes: Vec<String>,
+        result: Result<CommandResult, String>,
+    },
+    ConfigConflictResolved {
+        conflict: ConfigConflict,
+        resolution: ConfigResolution,
+        result: Result<CommandResult, String>,
+    },
+    RemovalImpactLoaded {
+        request: RemovalRequest,
+        result: Result<Vec<String>, String>,
+    },
     PinOperationFinished {
         package: String,
         target_pinned: bool,
         result: Result<CommandResult, String>,
     },
+    PinBatchFinished {
+        packages: Vec<String>,
+        target_pinned: bool,
+        result: Result<CommandResult, String>,
+    },
+    HeldPackagesLoaded {
+        result: Result<HashSet<String>, String>,
+    },
     InstalledDetailsLoaded {
         package: String,
         result: Result<InstalledDetail, String>,
     },
+    PackageFilesLoaded {
+        package: String,
+        result: Result<Vec<String>, String>,
+    },
     UpdatesDetailLoaded {
         package: String,
         result: Result<InstalledDetail, String>,
     },
     UpdatesRefreshed {
         packages: Vec<PackageInfo>,
+        sizes: Option<TransactionSizes>,
         success: bool,
         error: Option<String>,
     },
@@ -232,10 +484,29 @@ pub(crate) enum AppMessage {
     UpdateLogLine {
         line: String,
     },
+    UpdateLogSaved {
+        result: Result<PathBuf, String>,
+    },
+    UnattendedAuthorizationChecked {
+        authorized: bool,
+    },
     DiscoverDetailLoaded {
         package: String,
         result: Result<DiscoverDetail, String>,
     },
+    ScreenshotLoaded {
+        url: String,
+        result: Result<PathBuf, String>,
+    },
+    AppStreamCatalogLoaded,
+    PackageUriResolved {
+        uri: String,
+        package: Option<String>,
+    },
+    PackageTemplateResolved {
+        package: String,
+        source: Option<String>,
+    },
     SpotlightLoaded {
         recent: Vec<PackageInfo>,
         categories: HashMap<SpotlightCategory, Vec<PackageInfo>>,
@@ -249,9 +520,51 @@ pub(crate) enum AppMessage {
         task: MaintenanceTask,
         result: Result<CommandResult, String>,
     },
+    RebootFinished {
+        result: Result<CommandResult, String>,
+    },
+    RestartCheckFinished {
+        processes: Vec<String>,
+    },
+    HeldBlockersUnheld {
+        held: Vec<String>,
+        packages: Vec<String>,
+        all: bool,
+        result: Result<CommandResult, String>,
+    },
+    HoldAllProgress {
+        done: usize,
+        total: usize,
+    },
+    AlternativeSetFinished {
+        group: String,
+        package: String,
+        result: Result<CommandResult, String>,
+    },
+    IntegrityCheckFinished {
+        result: Result<Vec<IntegrityIssue>, String>,
+    },
+    CacheCleanupPlanned {
+        result: Result<CacheCleanupPlan, String>,
+    },
+    CacheSizeLoaded {
+        result: Result<u64, String>,
+    },
+    FreeSpaceMeasured {
+        result: Result<FreeSpace, String>,
+    },
+    MaintenanceLogLine {
+        line: String,
+    },
     MirrorsDetected {
         mirrors: Vec<String>,
     },
+    RepositoryClassesDetected {
+        classes: Vec<RepositoryClass>,
+    },
+    MirrorStatsMeasured {
+        stats: Vec<(String, MirrorStat)>,
+    },
     SnapshotComplete {
         result: crate::waypoint::SnapshotResult,
     },
@@ -263,6 +576,61 @@ pub(crate) enum RemoveOrigin {
     Installed,
 }
 
+/// A single-package operation waiting for the running one to finish.
+#[derive(Clone)]
+pub(crate) enum QueuedOperation {
+    Install(PackageInfo),
+    Remove {
+        package: String,
+        origin: RemoveOrigin,
+    },
+}
+
+impl QueuedOperation {
+    pub(crate) fn package(&self) -> &str {
+        match self {
+            QueuedOperation::Install(pkg) => &pkg.name,
+            QueuedOperation::Remove { package, .. } => package,
+        }
+    }
+}
+
+/// A change collected in the review bar, waiting to be applied.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PendingChange {
+    Install(String),
+    Remove(String),
+}
+
+impl PendingChange {
+    pub(crate) fn package(&self) -> &str {
+        match self {
+            PendingChange::Install(package) | PendingChange::Remove(package) => package,
+        }
+    }
+}
+
+/// A removal waiting on its reverse-dependency check before confirmation.
+#[derive(Clone)]
+pub(crate) enum RemovalRequest {
+    Single {
+        package: String,
+        origin: RemoveOrigin,
+    },
+    Batch {
+        packages: Vec<String>,
+    },
+}
+
+impl RemovalRequest {
+    pub(crate) fn packages(&self) -> Vec<String> {
+        match self {
+            RemovalRequest::Single { package, .. } => vec![package.clone()],
+            RemovalRequest::Batch { packages } => packages.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Default)]
 pub(crate) enum InstalledFilter {
     #[default]
@@ -53,6 +53,25 @@ impl CommandResult {
     }
 }
 
+/// Extra cleanup `xbps-remove` does along with a removal, chosen in
+/// Preferences.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RemoveOptions {
+    /// Also remove dependencies no other package needs (`-R`).
+    pub recursive: bool,
+    /// Also remove every orphaned package on the system (`-o`).
+    pub orphans: bool,
+}
+
+/// A repository that carries a package, with the version it offers there.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PackageSource {
+    pub repository: String,
+    pub version: String,
+    /// The repository is signed, so xbps verifies what it installs from it.
+    pub signed: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct DependencyInfo {
     pub name: String,
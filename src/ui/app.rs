@@ -6,17 +6,19 @@ use gtk4 as gtk;
 use libadwaita as adw;
 
 use adw::prelude::*;
+use chrono::Utc;
 use glib::{Variant, VariantTy};
-use gtk::{gdk, gio, glib};
+use gtk::{gdk, gio, glib, pango};
 
 use crate::settings::{ThemePreference, load_app_settings, save_app_settings};
 use crate::state::controller::AppController;
 use crate::state::types::AppMessage;
 use crate::ui::{
-    DiscoverWidgets, InstalledWidgets, ThemeGlyph, ToolsWidgets, UpdatesWidgets,
-    apply_theme_css_class, build_discover_page, build_installed_page, build_theme_icon,
-    build_tools_page, build_updates_page,
+    DiscoverWidgets, InstalledWidgets, ThemeGlyph, ToolsWidgets, UpdatesWidgets, apply_font_scale,
+    apply_high_contrast_css_class, apply_theme_css_class, build_discover_page,
+    build_installed_page, build_theme_icon, build_tools_page, build_updates_page,
 };
+use crate::xbps::ensure_xbps_available;
 
 pub(crate) struct AppWidgets {
     pub(crate) toast_overlay: adw::ToastOverlay,
@@ -26,6 +28,12 @@ pub(crate) struct AppWidgets {
     pub(crate) updates: UpdatesWidgets,
     pub(crate) tools: ToolsWidgets,
     pub(crate) updates_page: adw::ViewStackPage,
+    pub(crate) busy_spinner: gtk::Spinner,
+    pub(crate) offline_banner: adw::Banner,
+    pub(crate) changes_bar: gtk::ActionBar,
+    pub(crate) changes_label: gtk::Label,
+    pub(crate) changes_clear_button: gtk::Button,
+    pub(crate) changes_apply_button: gtk::Button,
 }
 
 pub(crate) fn build_ui(app: &adw::Application) {
@@ -42,13 +50,19 @@ pub(crate) fn build_ui(app: &adw::Application) {
         theme.add_resource_path("/tech/geektoshi/Nebula/icons");
     }
 
+    if let Err(err) = ensure_xbps_available() {
+        show_missing_xbps_dialog(app, &err);
+        return;
+    }
+
     let settings = Rc::new(RefCell::new(load_app_settings()));
-    let (initial_width, initial_height) = {
+    let (initial_width, initial_height, initial_maximized) = {
         let settings = settings.borrow();
-        (
+        let (width, height) = clamp_to_monitor(
             settings.window_width.unwrap_or(1080),
             settings.window_height.unwrap_or(720),
-        )
+        );
+        (width, height, settings.window_maximized)
     };
 
     let window = adw::ApplicationWindow::builder()
@@ -56,6 +70,9 @@ pub(crate) fn build_ui(app: &adw::Application) {
         .title("Nebula")
         .default_width(initial_width)
         .default_height(initial_height)
+        .maximized(initial_maximized)
+        .width_request(360)
+        .height_request(294)
         .build();
     window.add_css_class("nebula-window");
 
@@ -110,6 +127,11 @@ pub(crate) fn build_ui(app: &adw::Application) {
     stored_theme.apply(&style_manager);
     let current_theme = stored_theme.key().to_string();
     apply_theme_css_class(&window, style_manager.is_dark());
+    {
+        let settings = settings.borrow();
+        apply_high_contrast_css_class(&window, settings.high_contrast);
+        apply_font_scale(settings.font_scale_percent);
+    }
     style_manager.connect_dark_notify(glib::clone!(
         #[weak]
         window,
@@ -157,12 +179,30 @@ pub(crate) fn build_ui(app: &adw::Application) {
     let show_updates_action = gio::SimpleAction::new("show-updates", None);
     app.add_action(&show_updates_action);
 
+    let update_all_action = gio::SimpleAction::new("update-all", None);
+    app.add_action(&update_all_action);
+
+    let check_updates_action = gio::SimpleAction::new("check-updates", None);
+    app.add_action(&check_updates_action);
+    app.set_accels_for_action("app.check-updates", &["<Primary>r", "F5"]);
+
+    let show_package_action = gio::SimpleAction::new("show-package", Some(&VariantTy::STRING));
+    app.add_action(&show_package_action);
+
+    let open_uri_action = gio::SimpleAction::new("open-uri", Some(&VariantTy::STRING));
+    app.add_action(&open_uri_action);
+
     let about_action = gio::SimpleAction::new("about", None);
     app.add_action(&about_action);
 
     let operations_action = gio::SimpleAction::new("recent-operations", None);
     app.add_action(&operations_action);
 
+    let diagnostics_action = gio::SimpleAction::new("diagnostics", None);
+    app.add_action(&diagnostics_action);
+    let documentation_action = gio::SimpleAction::new("documentation", None);
+    app.add_action(&documentation_action);
+
     let menu_button = gtk::MenuButton::builder()
         .icon_name("open-menu-symbolic")
         .halign(gtk::Align::Center)
@@ -272,6 +312,14 @@ pub(crate) fn build_ui(app: &adw::Application) {
     mirrors_row.set_action_name(Some("app.mirrors"));
     menu_list.append(&mirrors_row);
 
+    let check_updates_row = adw::ActionRow::builder()
+        .title("Check for Updates")
+        .subtitle("Ctrl+R")
+        .activatable(true)
+        .build();
+    check_updates_row.set_action_name(Some("app.check-updates"));
+    menu_list.append(&check_updates_row);
+
     let prefs_row = adw::ActionRow::builder()
         .title("Preferences")
         .activatable(true)
@@ -286,6 +334,20 @@ pub(crate) fn build_ui(app: &adw::Application) {
     operations_row.set_action_name(Some("app.recent-operations"));
     menu_list.append(&operations_row);
 
+    let diagnostics_row = adw::ActionRow::builder()
+        .title("Report a Problem")
+        .activatable(true)
+        .build();
+    diagnostics_row.set_action_name(Some("app.diagnostics"));
+    menu_list.append(&diagnostics_row);
+
+    let documentation_row = adw::ActionRow::builder()
+        .title("Void Documentation")
+        .activatable(true)
+        .build();
+    documentation_row.set_action_name(Some("app.documentation"));
+    menu_list.append(&documentation_row);
+
     let about_row = adw::ActionRow::builder()
         .title("About Nebula")
         .activatable(true)
@@ -298,10 +360,16 @@ pub(crate) fn build_ui(app: &adw::Application) {
     popover.set_child(Some(&popover_box));
     menu_button.set_popover(Some(&popover));
 
+    let busy_spinner = gtk::Spinner::new();
+    busy_spinner.set_visible(false);
+    busy_spinner.set_valign(gtk::Align::Center);
+    busy_spinner.set_tooltip_text(Some("Working in the background…"));
+
     let header_controls_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
         .build();
+    header_controls_box.append(&busy_spinner);
     header_controls_box.append(&menu_button);
     header_controls_box.append(&header_logo_end);
     header_controls_box.append(&end_controls);
@@ -365,8 +433,34 @@ pub(crate) fn build_ui(app: &adw::Application) {
     content.set_vexpand(true);
     content.set_hexpand(true);
     content.add_css_class("nebula-content");
+
+    let offline_banner = adw::Banner::new(
+        "You're offline. Searching and update checks will resume once the network is back.",
+    );
+    offline_banner.set_revealed(false);
+    root_box.append(&offline_banner);
     root_box.append(&content);
 
+    let changes_label = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .ellipsize(pango::EllipsizeMode::End)
+        .build();
+    let changes_clear_button = gtk::Button::builder()
+        .label("Clear")
+        .tooltip_text("Discard the pending changes")
+        .build();
+    let changes_apply_button = gtk::Button::builder()
+        .label("Apply")
+        .tooltip_text("Install and remove the pending packages in one transaction")
+        .build();
+    changes_apply_button.add_css_class("suggested-action");
+    let changes_bar = gtk::ActionBar::new();
+    changes_bar.pack_start(&changes_label);
+    changes_bar.pack_end(&changes_apply_button);
+    changes_bar.pack_end(&changes_clear_button);
+    changes_bar.set_revealed(false);
+    root_box.append(&changes_bar);
+
     let (discover_page, discover_widgets) = build_discover_page();
     let (installed_page, installed_widgets) = build_installed_page();
     let (updates_page, updates_widgets) = build_updates_page();
@@ -397,6 +491,12 @@ pub(crate) fn build_ui(app: &adw::Application) {
         updates: updates_widgets,
         tools: tools_widgets,
         updates_page: updates_page_ref,
+        busy_spinner,
+        offline_banner,
+        changes_bar,
+        changes_label,
+        changes_clear_button,
+        changes_apply_button,
     };
 
     let (sender, receiver) = mpsc::channel::<AppMessage>();
@@ -420,6 +520,31 @@ pub(crate) fn build_ui(app: &adw::Application) {
     });
 
     controller.setup_connections();
+    controller.setup_network_monitor();
+    controller.refresh_spotlight_recent_placeholder();
+    controller.refresh_recently_removed_list();
+    controller.apply_list_density();
+    controller.restore_pane_positions();
+
+    // Below this width the discover results need the whole row, so the
+    // detail pane only opens while a package is selected.
+    let narrow_breakpoint = adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+        adw::BreakpointConditionLengthType::MaxWidth,
+        860.0,
+        adw::LengthUnit::Sp,
+    ));
+    narrow_breakpoint.connect_apply(glib::clone!(
+        #[weak]
+        controller,
+        move |_| controller.set_discover_narrow_layout(true)
+    ));
+    narrow_breakpoint.connect_unapply(glib::clone!(
+        #[weak]
+        controller,
+        move |_| controller.set_discover_narrow_layout(false)
+    ));
+    window.add_breakpoint(narrow_breakpoint);
+
     controller.apply_start_page_preference();
     controller.initialize_mirrors();
 
@@ -433,6 +558,54 @@ pub(crate) fn build_ui(app: &adw::Application) {
         });
     }
 
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        update_all_action.connect_activate(move |_, _| {
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.set_active_page("updates");
+                controller.window.present();
+                controller.update_all_packages();
+            }
+        });
+    }
+
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        let popover_clone = popover.clone();
+        check_updates_action.connect_activate(move |_, _| {
+            popover_clone.popdown();
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.set_active_page("updates");
+                controller.window.present();
+                controller.refresh_updates(false);
+            }
+        });
+    }
+
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        show_package_action.connect_activate(move |_, parameter| {
+            let Some(package) = parameter.and_then(|value| value.get::<String>()) else {
+                return;
+            };
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.show_requested_package(package);
+            }
+        });
+    }
+
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        open_uri_action.connect_activate(move |_, parameter| {
+            let Some(uri) = parameter.and_then(|value| value.get::<String>()) else {
+                return;
+            };
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.open_package_uri(uri);
+            }
+        });
+    }
+
     {
         let controller_weak = Rc::downgrade(&controller);
         let popover_clone = popover.clone();
@@ -466,6 +639,28 @@ pub(crate) fn build_ui(app: &adw::Application) {
         });
     }
 
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        let popover_clone = popover.clone();
+        diagnostics_action.connect_activate(move |_, _| {
+            popover_clone.popdown();
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.show_diagnostics_dialog();
+            }
+        });
+    }
+
+    {
+        let controller_weak = Rc::downgrade(&controller);
+        let popover_clone = popover.clone();
+        documentation_action.connect_activate(move |_, _| {
+            popover_clone.popdown();
+            if let Some(controller) = controller_weak.upgrade() {
+                controller.open_contextual_docs();
+            }
+        });
+    }
+
     {
         let controller_weak = Rc::downgrade(&controller);
         let window_clone = window.clone();
@@ -478,8 +673,10 @@ pub(crate) fn build_ui(app: &adw::Application) {
         });
     }
 
+    controller.load_appstream_catalog();
     controller.initialize_spotlight();
     controller.refresh_installed_packages();
+    controller.refresh_cache_size();
     {
         let controller_weak = Rc::downgrade(&controller);
         glib::idle_add_local(move || {
@@ -491,23 +688,34 @@ pub(crate) fn build_ui(app: &adw::Application) {
     }
 
     let settings_for_close = Rc::clone(&settings);
+    let controller_for_close = Rc::downgrade(&controller);
     window.connect_close_request(glib::clone!(
         #[strong]
         app,
         #[strong]
         settings_for_close,
         move |win| {
-            let width = win.width();
-            let height = win.height();
-            if width > 0 && height > 0 {
-                {
-                    let mut data = settings_for_close.borrow_mut();
+            let busy = controller_for_close
+                .upgrade()
+                .filter(|controller| controller.close_would_interrupt());
+            if let Some(controller) = busy {
+                controller.confirm_close_during_operation();
+                return glib::Propagation::Stop;
+            }
+            // default_size() tracks the unmaximized geometry, so a maximized
+            // window doesn't overwrite the size it should restore to.
+            let (width, height) = win.default_size();
+            {
+                let mut data = settings_for_close.borrow_mut();
+                if width > 0 && height > 0 {
                     data.window_width = Some(width);
                     data.window_height = Some(height);
                 }
-                if let Err(err) = save_app_settings(&settings_for_close.borrow()) {
-                    eprintln!("Failed to save settings: {}", err);
-                }
+                data.window_maximized = win.is_maximized();
+                data.last_visit_at = Some(Utc::now());
+            }
+            if let Err(err) = save_app_settings(&settings_for_close.borrow()) {
+                eprintln!("Failed to save settings: {}", err);
             }
             app.quit();
             glib::Propagation::Stop
@@ -515,4 +723,45 @@ pub(crate) fn build_ui(app: &adw::Application) {
     ));
 
     window.present();
+    controller.show_welcome_if_first_run();
+    controller.warn_about_interrupted_update();
+}
+
+/// Stands in for the main window when xbps is missing, since every page
+/// would only produce command errors.
+fn show_missing_xbps_dialog(app: &adw::Application, detail: &str) {
+    let dialog = adw::MessageDialog::builder()
+        .heading("XBPS Not Found")
+        .body(format!(
+            "Nebula manages packages with XBPS, the Void Linux package manager, and cannot run without it.\n\n{}",
+            detail
+        ))
+        .build();
+    dialog.set_application(Some(app));
+    dialog.add_response("quit", "Quit");
+    dialog.set_default_response(Some("quit"));
+    dialog.set_close_response("quit");
+    dialog.connect_response(
+        None,
+        glib::clone!(
+            #[strong]
+            app,
+            move |_, _| app.quit()
+        ),
+    );
+    dialog.present();
+}
+
+fn clamp_to_monitor(width: i32, height: i32) -> (i32, i32) {
+    let geometry = gdk::Display::default()
+        .and_then(|display| display.monitors().item(0))
+        .and_downcast::<gdk::Monitor>()
+        .map(|monitor| monitor.geometry());
+
+    match geometry {
+        Some(geometry) if geometry.width() > 0 && geometry.height() > 0 => {
+            (width.min(geometry.width()), height.min(geometry.height()))
+        }
+        _ => (width, height),
+    }
 }
@@ -5,7 +5,13 @@ use libadwaita as adw;
 use adw::prelude::*;
 use gtk::prelude::WidgetExt;
 
-fn build_category_button(icon_name: &str, label: &str) -> gtk::ToggleButton {
+use crate::helpers::{
+    build_copy_command_button, build_copy_link_button, build_template_link_button,
+};
+use crate::i18n::tr;
+use crate::spotlight::SPOTLIGHT_WINDOW_DAYS;
+
+pub(crate) fn build_category_button(icon_name: &str, label: &str) -> gtk::ToggleButton {
     let button = gtk::ToggleButton::builder().build();
     button.add_css_class("pill");
     button.add_css_class("flat");
@@ -41,23 +47,53 @@ fn build_category_button(icon_name: &str, label: &str) -> gtk::ToggleButton {
     button
 }
 
+fn build_favorite_button() -> gtk::Button {
+    let button = gtk::Button::builder()
+        .icon_name("non-starred-symbolic")
+        .tooltip_text(tr!("Add to favorites"))
+        .has_frame(false)
+        .visible(false)
+        .build();
+    button.add_css_class("flat");
+    button.set_focus_on_click(false);
+    button.set_valign(gtk::Align::Center);
+    button
+}
+
 pub(crate) struct DiscoverWidgets {
     pub(crate) search_entry: gtk::SearchEntry,
     pub(crate) search_spinner: gtk::Spinner,
+    pub(crate) search_descriptions_toggle: gtk::ToggleButton,
+    pub(crate) browse_button: gtk::Button,
+    pub(crate) compare_button: gtk::Button,
+    pub(crate) install_selected_button: gtk::Button,
+    pub(crate) queue_selected_button: gtk::Button,
     pub(crate) status_label: gtk::Label,
     pub(crate) list: gtk::ListBox,
     pub(crate) search_results_stack: gtk::Stack,
     pub(crate) no_results_page: adw::StatusPage,
-    pub(crate) content_row: gtk::Box,
+    pub(crate) no_results_repo_button: gtk::Button,
+    pub(crate) content_row: gtk::Paned,
     pub(crate) detail_stack: gtk::Stack,
     pub(crate) detail_name: gtk::Label,
     pub(crate) detail_back_button: gtk::Button,
     pub(crate) detail_close_button: gtk::Button,
+    pub(crate) detail_favorite_button: gtk::Button,
+    pub(crate) detail_template_button: gtk::Button,
+    pub(crate) detail_command_button: gtk::Button,
     pub(crate) detail_version_value: gtk::Label,
     pub(crate) detail_description: gtk::Label,
+    pub(crate) detail_summary: gtk::Label,
+    pub(crate) detail_screenshots_scroller: gtk::ScrolledWindow,
+    pub(crate) detail_screenshots_box: gtk::Box,
     pub(crate) detail_download_value: gtk::Label,
+    pub(crate) detail_source_row: gtk::Box,
+    pub(crate) detail_source_dropdown: gtk::DropDown,
+    pub(crate) detail_signature_row: gtk::Box,
+    pub(crate) detail_signature_value: gtk::Label,
     pub(crate) detail_homepage_row: gtk::Box,
     pub(crate) detail_homepage_link: gtk::Label,
+    pub(crate) detail_homepage_copy_button: gtk::Button,
     pub(crate) detail_maintainer_row: gtk::Box,
     pub(crate) detail_maintainer_value: gtk::Label,
     pub(crate) detail_license_row: gtk::Box,
@@ -70,15 +106,22 @@ pub(crate) struct DiscoverWidgets {
     pub(crate) detail_dependencies_list: gtk::ListBox,
     pub(crate) detail_dependencies_placeholder: gtk::Label,
     pub(crate) detail_frame: gtk::Frame,
+    pub(crate) spotlight_status_row: gtk::Box,
     pub(crate) spotlight_spinner: gtk::Spinner,
     pub(crate) spotlight_status: gtk::Label,
     pub(crate) spotlight_recent_stack: gtk::Stack,
+    pub(crate) spotlight_recent_heading: gtk::Label,
+    pub(crate) spotlight_recent_placeholder: adw::StatusPage,
+    pub(crate) spotlight_new_toggle: gtk::ToggleButton,
     pub(crate) spotlight_recent_list: gtk::ListBox,
     pub(crate) spotlight_recent_scroller: gtk::ScrolledWindow,
     pub(crate) spotlight_recent_detail_revealer: gtk::Revealer,
     pub(crate) spotlight_recent_detail_container: gtk::Box,
     pub(crate) spotlight_recent_back_button: gtk::Button,
     pub(crate) spotlight_recent_close_button: gtk::Button,
+    pub(crate) spotlight_recent_favorite_button: gtk::Button,
+    pub(crate) spotlight_recent_template_button: gtk::Button,
+    pub(crate) spotlight_recent_command_button: gtk::Button,
     pub(crate) spotlight_recent_detail_name: gtk::Label,
     pub(crate) spotlight_recent_detail_spinner: gtk::Spinner,
     pub(crate) spotlight_recent_detail_version_value: gtk::Label,
@@ -87,6 +130,7 @@ pub(crate) struct DiscoverWidgets {
     pub(crate) spotlight_recent_detail_updated_value: gtk::Label,
     pub(crate) spotlight_recent_detail_homepage_row: gtk::Box,
     pub(crate) spotlight_recent_detail_homepage_link: gtk::Label,
+    pub(crate) spotlight_recent_detail_homepage_copy_button: gtk::Button,
     pub(crate) spotlight_recent_detail_maintainer_row: gtk::Box,
     pub(crate) spotlight_recent_detail_maintainer_value: gtk::Label,
     pub(crate) spotlight_recent_detail_license_row: gtk::Box,
@@ -99,15 +143,11 @@ pub(crate) struct DiscoverWidgets {
     pub(crate) spotlight_recent_detail_dependencies_placeholder: gtk::Label,
     pub(crate) spotlight_recent_action_button: gtk::Button,
     pub(crate) spotlight_section_box: gtk::Box,
-    pub(crate) category_browsers_button: gtk::ToggleButton,
-    pub(crate) category_chat_button: gtk::ToggleButton,
-    pub(crate) category_email_button: gtk::ToggleButton,
-    pub(crate) category_games_button: gtk::ToggleButton,
-    pub(crate) category_graphics_button: gtk::ToggleButton,
-    pub(crate) category_music_button: gtk::ToggleButton,
-    pub(crate) category_productivity_button: gtk::ToggleButton,
-    pub(crate) category_utilities_button: gtk::ToggleButton,
-    pub(crate) category_video_button: gtk::ToggleButton,
+    pub(crate) favorites_section: gtk::Box,
+    pub(crate) favorites_list: gtk::ListBox,
+    pub(crate) recently_viewed_section: gtk::Box,
+    pub(crate) recently_viewed_list: gtk::ListBox,
+    pub(crate) categories_list: gtk::Box,
     pub(crate) spotlight_refresh_button: gtk::Button,
 }
 
@@ -123,7 +163,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     container.set_vexpand(true);
 
     let search_entry = gtk::SearchEntry::builder()
-        .placeholder_text("Search the Void Linux repositories")
+        .placeholder_text(tr!("Search the Void Linux repositories"))
         .hexpand(true)
         .build();
     search_entry.set_valign(gtk::Align::Center);
@@ -140,12 +180,55 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     search_spinner.set_visible(false);
     search_spinner.set_valign(gtk::Align::Center);
 
+    let search_descriptions_toggle = gtk::ToggleButton::builder()
+        .icon_name("format-justify-left-symbolic")
+        .tooltip_text(tr!("Search package descriptions"))
+        .valign(gtk::Align::Center)
+        .build();
+    search_descriptions_toggle.add_css_class("flat");
+
+    let browse_button = gtk::Button::builder()
+        .icon_name("view-list-symbolic")
+        .tooltip_text(tr!("Browse all packages"))
+        .valign(gtk::Align::Center)
+        .build();
+    browse_button.add_css_class("flat");
+
+    let compare_button = gtk::Button::builder()
+        .label(tr!("Compare"))
+        .tooltip_text(tr!("Compare the selected packages side by side"))
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+
+    let install_selected_button = gtk::Button::builder()
+        .label(tr!("Install Selected"))
+        .tooltip_text(tr!("Install the selected packages in one transaction"))
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+    install_selected_button.add_css_class("suggested-action");
+
+    let queue_selected_button = gtk::Button::builder()
+        .label(tr!("Add to Changes"))
+        .tooltip_text(tr!(
+            "Collect the selected packages to install later with other changes"
+        ))
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+
     let search_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
         .hexpand(true)
         .build();
     search_row.append(&search_bar);
+    search_row.append(&search_descriptions_toggle);
+    search_row.append(&browse_button);
+    search_row.append(&compare_button);
+    search_row.append(&queue_selected_button);
+    search_row.append(&install_selected_button);
     search_row.append(&search_spinner);
 
     let categories_list = gtk::Box::builder()
@@ -156,46 +239,6 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     categories_list.set_valign(gtk::Align::Start);
     categories_list.set_hexpand(false);
 
-    let category_browsers_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/browsers.svg", "Browsers");
-    let category_chat_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/chat.svg", "Chat");
-    let category_email_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/email.svg", "E-mail");
-    let category_games_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/games.svg", "Games");
-    let category_graphics_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/graphics.svg", "Graphics");
-    let category_productivity_button = build_category_button(
-        "/tech/geektoshi/Nebula/icons/productivity.svg",
-        "Productivity",
-    );
-    let category_music_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/music.svg", "Music");
-    let category_utilities_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/utilities.svg", "Utilities");
-    let category_video_button =
-        build_category_button("/tech/geektoshi/Nebula/icons/video.svg", "Video");
-
-    category_chat_button.set_group(Some(&category_browsers_button));
-    category_email_button.set_group(Some(&category_browsers_button));
-    category_games_button.set_group(Some(&category_browsers_button));
-    category_graphics_button.set_group(Some(&category_browsers_button));
-    category_productivity_button.set_group(Some(&category_browsers_button));
-    category_music_button.set_group(Some(&category_browsers_button));
-    category_utilities_button.set_group(Some(&category_browsers_button));
-    category_video_button.set_group(Some(&category_browsers_button));
-
-    categories_list.append(&category_browsers_button);
-    categories_list.append(&category_chat_button);
-    categories_list.append(&category_email_button);
-    categories_list.append(&category_games_button);
-    categories_list.append(&category_graphics_button);
-    categories_list.append(&category_music_button);
-    categories_list.append(&category_productivity_button);
-    categories_list.append(&category_utilities_button);
-    categories_list.append(&category_video_button);
-
     let spotlight_status_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -220,7 +263,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .build();
     spotlight_status.set_valign(gtk::Align::Center);
     spotlight_status.add_css_class("dim-label");
-    spotlight_status.set_text("Loading spotlight metadata…");
+    spotlight_status.set_text(&tr!("Loading spotlight metadata…"));
     spotlight_status.set_xalign(0.5);
 
     spotlight_status_row.append(&spotlight_spinner);
@@ -252,8 +295,11 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     spotlight_recent_scroller.set_child(Some(&spotlight_recent_list));
 
     let spotlight_recent_placeholder = adw::StatusPage::builder()
-        .title("Nothing updated recently")
-        .description("Packages updated in the past 7 days will appear here.")
+        .title(tr!("Nothing updated recently"))
+        .description(tr!(
+            "Packages updated in the past {} days will appear here.",
+            SPOTLIGHT_WINDOW_DAYS
+        ))
         .build();
 
     let spotlight_recent_stack = gtk::Stack::builder()
@@ -264,7 +310,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     let recent_detail_back_button = gtk::Button::builder()
         .icon_name("go-previous-symbolic")
         .has_frame(false)
-        .tooltip_text("Back to recently updated")
+        .tooltip_text(tr!("Back to recently updated"))
         .visible(false)
         .build();
     recent_detail_back_button.add_css_class("flat");
@@ -279,7 +325,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     recent_detail_name.add_css_class("title-2");
 
     let recent_detail_action_button = gtk::Button::builder()
-        .label("Install")
+        .label(tr!("Install"))
         .width_request(140)
         .visible(false)
         .build();
@@ -294,7 +340,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     let recent_detail_close_button = gtk::Button::builder()
         .icon_name("window-close-symbolic")
         .has_frame(false)
-        .tooltip_text("Close details")
+        .tooltip_text(tr!("Close details"))
         .visible(false)
         .sensitive(false)
         .build();
@@ -302,6 +348,10 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     recent_detail_close_button.set_focus_on_click(false);
     recent_detail_close_button.set_valign(gtk::Align::Center);
 
+    let recent_detail_favorite_button = build_favorite_button();
+    let recent_detail_template_button = build_template_link_button();
+    let recent_detail_command_button = build_copy_command_button();
+
     let recent_detail_header = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -316,6 +366,9 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .build();
     recent_detail_header.append(&recent_detail_header_spacer);
     recent_detail_header.append(&recent_detail_spinner);
+    recent_detail_header.append(&recent_detail_command_button);
+    recent_detail_header.append(&recent_detail_template_button);
+    recent_detail_header.append(&recent_detail_favorite_button);
     recent_detail_header.append(&recent_detail_close_button);
 
     let make_recent_metadata_label = |text: &str| {
@@ -340,7 +393,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let recent_detail_version_title = make_recent_metadata_label("Version");
+    let recent_detail_version_title = make_recent_metadata_label(&tr!("Version"));
     let recent_detail_version_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -358,7 +411,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let recent_detail_download_title = make_recent_metadata_label("Download size");
+    let recent_detail_download_title = make_recent_metadata_label(&tr!("Download size"));
     let recent_detail_download_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -377,7 +430,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let recent_detail_license_title = make_recent_metadata_label("License");
+    let recent_detail_license_title = make_recent_metadata_label(&tr!("License"));
     let recent_detail_license_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .wrap(true)
@@ -398,7 +451,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let recent_detail_maintainer_title = make_recent_metadata_label("Maintainer");
+    let recent_detail_maintainer_title = make_recent_metadata_label(&tr!("Maintainer"));
     let recent_detail_maintainer_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .wrap(true)
@@ -419,7 +472,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let recent_detail_homepage_title = make_recent_metadata_label("Homepage");
+    let recent_detail_homepage_title = make_recent_metadata_label(&tr!("Homepage"));
     let recent_detail_homepage_link = gtk::Label::builder()
         .use_markup(true)
         .wrap(true)
@@ -432,6 +485,8 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     recent_detail_homepage_link.set_selectable(false);
     recent_detail_homepage_row.append(&recent_detail_homepage_title);
     recent_detail_homepage_row.append(&recent_detail_homepage_link);
+    let recent_detail_homepage_copy_button = build_copy_link_button();
+    recent_detail_homepage_row.append(&recent_detail_homepage_copy_button);
     recent_detail_metadata_box.append(&recent_detail_homepage_row);
 
     let recent_detail_updated_row = gtk::Box::builder()
@@ -440,7 +495,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let recent_detail_updated_title = make_recent_metadata_label("Updated");
+    let recent_detail_updated_title = make_recent_metadata_label(&tr!("Updated"));
     let recent_detail_updated_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -476,7 +531,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .hexpand(true)
         .justify(gtk::Justification::Left)
         .build();
-    recent_detail_description.set_text("Select a package to see details.");
+    recent_detail_description.set_text(&tr!("Select a package to see details."));
     recent_detail_description.set_ellipsize(pango::EllipsizeMode::None);
     recent_detail_description.set_single_line_mode(false);
 
@@ -486,7 +541,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .hexpand(true)
         .build();
-    let recent_detail_description_title = make_recent_metadata_label("Description");
+    let recent_detail_description_title = make_recent_metadata_label(&tr!("Description"));
     let recent_detail_description_container = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .hexpand(true)
@@ -516,7 +571,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     recent_detail_dependencies_placeholder.add_css_class("dim-label");
-    recent_detail_dependencies_placeholder.set_text("No runtime dependencies.");
+    recent_detail_dependencies_placeholder.set_text(&tr!("No runtime dependencies."));
 
     let recent_detail_dependencies_stack = gtk::Stack::new();
     recent_detail_dependencies_stack
@@ -525,7 +580,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     recent_detail_dependencies_stack.set_visible_child_name("placeholder");
 
     let recent_detail_dependencies_group = adw::PreferencesGroup::builder()
-        .title("Dependencies")
+        .title(tr!("Dependencies"))
         .build();
     recent_detail_dependencies_group.add(&recent_detail_dependencies_stack);
 
@@ -590,7 +645,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     spotlight_recent_stack.set_visible_child_name("placeholder");
 
     let recent_heading = gtk::Label::builder()
-        .label("Recent package updates")
+        .label(tr!("Recent package updates"))
         .halign(gtk::Align::Start)
         .build();
     recent_heading.add_css_class("title-2");
@@ -599,7 +654,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
 
     let recent_refresh_button = gtk::Button::builder()
         .icon_name("view-refresh-symbolic")
-        .tooltip_text("Refresh recently updated")
+        .tooltip_text(tr!("Refresh recently updated"))
         .build();
     recent_refresh_button.add_css_class("flat");
     recent_refresh_button.set_focus_on_click(false);
@@ -610,9 +665,20 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .spacing(6)
         .halign(gtk::Align::Start)
         .build();
+    let spotlight_new_toggle = gtk::ToggleButton::builder()
+        .label(tr!("Newly Seen"))
+        .tooltip_text(tr!(
+            "Show packages Nebula has first seen in the repositories since you last opened it"
+        ))
+        .build();
+    spotlight_new_toggle.add_css_class("flat");
+    spotlight_new_toggle.set_focus_on_click(false);
+    spotlight_new_toggle.set_valign(gtk::Align::Center);
+
     recent_header_row.set_valign(gtk::Align::Center);
     recent_header_row.append(&recent_heading);
     recent_header_row.append(&recent_refresh_button);
+    recent_header_row.append(&spotlight_new_toggle);
 
     let recent_column = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
@@ -635,6 +701,48 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     spotlight_columns.set_vexpand(true);
     recent_column.set_vexpand(true);
 
+    let favorites_heading = gtk::Label::builder()
+        .label(tr!("Favorites"))
+        .halign(gtk::Align::Start)
+        .build();
+    favorites_heading.add_css_class("title-2");
+
+    let favorites_list = gtk::ListBox::new();
+    favorites_list.add_css_class("boxed-list");
+    favorites_list.set_selection_mode(gtk::SelectionMode::None);
+    favorites_list.set_activate_on_single_click(true);
+
+    let favorites_section = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .hexpand(true)
+        .visible(false)
+        .build();
+    favorites_section.set_margin_top(6);
+    favorites_section.append(&favorites_heading);
+    favorites_section.append(&favorites_list);
+
+    let recently_viewed_heading = gtk::Label::builder()
+        .label(tr!("Recently Viewed"))
+        .halign(gtk::Align::Start)
+        .build();
+    recently_viewed_heading.add_css_class("title-2");
+
+    let recently_viewed_list = gtk::ListBox::new();
+    recently_viewed_list.add_css_class("boxed-list");
+    recently_viewed_list.set_selection_mode(gtk::SelectionMode::None);
+    recently_viewed_list.set_activate_on_single_click(true);
+
+    let recently_viewed_section = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(8)
+        .hexpand(true)
+        .visible(false)
+        .build();
+    recently_viewed_section.append(&recently_viewed_heading);
+    recently_viewed_section.append(&recently_viewed_list);
+
+    spotlight_section_box.append(&recently_viewed_section);
     spotlight_section_box.append(&spotlight_columns);
 
     let status_label = gtk::Label::builder()
@@ -663,10 +771,17 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     // Empty state for no search results
     let no_results_page = adw::StatusPage::builder()
         .icon_name("system-search-symbolic")
-        .title("No Packages Found")
+        .title(tr!("No Packages Found"))
         .vexpand(true)
         .hexpand(true)
         .build();
+    let no_results_repo_button = gtk::Button::builder()
+        .label(tr!("Open Repository Settings"))
+        .halign(gtk::Align::Center)
+        .visible(false)
+        .build();
+    no_results_repo_button.add_css_class("pill");
+    no_results_page.set_child(Some(&no_results_repo_button));
 
     // Stack to switch between list and no-results
     let search_results_stack = gtk::Stack::builder()
@@ -690,7 +805,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
 
     let detail_back_button = gtk::Button::builder()
         .icon_name("go-previous-symbolic")
-        .tooltip_text("Go back to the previous package")
+        .tooltip_text(tr!("Go back to the previous package"))
         .has_frame(false)
         .visible(false)
         .sensitive(false)
@@ -700,7 +815,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_back_button.set_valign(gtk::Align::Center);
 
     let detail_action_button = gtk::Button::builder()
-        .label("Install")
+        .label(tr!("Install"))
         .width_request(140)
         .build();
     detail_action_button.add_css_class("suggested-action");
@@ -709,7 +824,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
 
     let detail_action_progress = gtk::ProgressBar::builder()
         .show_text(true)
-        .text("Working…")
+        .text(tr!("Working…"))
         .visible(false)
         .hexpand(true)
         .build();
@@ -727,7 +842,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
 
     let detail_close_button = gtk::Button::builder()
         .icon_name("window-close-symbolic")
-        .tooltip_text("Close details")
+        .tooltip_text(tr!("Close details"))
         .has_frame(false)
         .visible(false)
         .sensitive(false)
@@ -736,6 +851,10 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_close_button.set_focus_on_click(false);
     detail_close_button.set_valign(gtk::Align::Center);
 
+    let detail_favorite_button = build_favorite_button();
+    let detail_template_button = build_template_link_button();
+    let detail_command_button = build_copy_command_button();
+
     let detail_header_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -749,6 +868,9 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_header_row.append(&detail_back_button);
     detail_header_row.append(&detail_name);
     detail_header_row.append(&detail_header_spacer);
+    detail_header_row.append(&detail_command_button);
+    detail_header_row.append(&detail_template_button);
+    detail_header_row.append(&detail_favorite_button);
     detail_header_row.append(&detail_close_button);
 
     let detail_metadata_box = gtk::Box::builder()
@@ -774,7 +896,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let detail_version_title = make_metadata_label("Version");
+    let detail_version_title = make_metadata_label(&tr!("Version"));
     let detail_version_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -791,7 +913,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let detail_download_title = make_metadata_label("Download size");
+    let detail_download_title = make_metadata_label(&tr!("Download size"));
     let detail_download_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -804,13 +926,49 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_download_row.append(&detail_download_value);
     detail_metadata_box.append(&detail_download_row);
 
+    let detail_source_row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .halign(gtk::Align::Fill)
+        .visible(false)
+        .build();
+    let detail_source_title = make_metadata_label(&tr!("Install from"));
+    let detail_source_dropdown = gtk::DropDown::builder()
+        .model(&gtk::StringList::new(&[]))
+        .halign(gtk::Align::Start)
+        .tooltip_text(tr!(
+            "This package is available from more than one repository"
+        ))
+        .build();
+    detail_source_dropdown.set_hexpand(true);
+    detail_source_row.append(&detail_source_title);
+    detail_source_row.append(&detail_source_dropdown);
+    detail_metadata_box.append(&detail_source_row);
+
+    let detail_signature_row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .halign(gtk::Align::Fill)
+        .visible(false)
+        .build();
+    let detail_signature_title = make_metadata_label(&tr!("Signature"));
+    let detail_signature_value = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .single_line_mode(true)
+        .build();
+    detail_signature_value.set_hexpand(true);
+    detail_signature_value.set_xalign(0.0);
+    detail_signature_row.append(&detail_signature_title);
+    detail_signature_row.append(&detail_signature_value);
+    detail_metadata_box.append(&detail_signature_row);
+
     let detail_license_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_license_title = make_metadata_label("License");
+    let detail_license_title = make_metadata_label(&tr!("License"));
     let detail_license_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .wrap(true)
@@ -831,7 +989,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_maintainer_title = make_metadata_label("Maintainer");
+    let detail_maintainer_title = make_metadata_label(&tr!("Maintainer"));
     let detail_maintainer_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .wrap(true)
@@ -862,9 +1020,11 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_homepage_title = make_metadata_label("Homepage");
+    let detail_homepage_title = make_metadata_label(&tr!("Homepage"));
     detail_homepage_row.append(&detail_homepage_title);
     detail_homepage_row.append(&detail_homepage_link);
+    let detail_homepage_copy_button = build_copy_link_button();
+    detail_homepage_row.append(&detail_homepage_copy_button);
     detail_metadata_box.append(&detail_homepage_row);
 
     let detail_update_label = gtk::Label::builder()
@@ -876,7 +1036,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_update_label.set_visible(false);
 
     let detail_update_button = gtk::Button::builder()
-        .label("Update")
+        .label(tr!("Update"))
         .width_request(120)
         .build();
     detail_update_button.add_css_class("suggested-action");
@@ -884,7 +1044,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_update_button.set_halign(gtk::Align::Start);
     detail_update_button.set_valign(gtk::Align::Center);
     detail_update_button.set_margin_start(0);
-    detail_update_button.set_tooltip_text(Some("Install this update."));
+    detail_update_button.set_tooltip_text(Some(tr!("Install this update.").as_str()));
 
     let detail_description = gtk::Label::builder()
         .halign(gtk::Align::Start)
@@ -893,7 +1053,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .hexpand(true)
         .justify(gtk::Justification::Left)
         .build();
-    detail_description.set_text("Select a package to see details.");
+    detail_description.set_text(&tr!("Select a package to see details."));
     detail_description.set_ellipsize(pango::EllipsizeMode::None);
     detail_description.set_single_line_mode(false);
 
@@ -903,7 +1063,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .halign(gtk::Align::Fill)
         .hexpand(true)
         .build();
-    let detail_description_title = make_metadata_label("Description");
+    let detail_description_title = make_metadata_label(&tr!("Description"));
     let detail_description_container = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .hexpand(true)
@@ -913,6 +1073,28 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_description_row.append(&detail_description_title);
     detail_description_row.append(&detail_description_container);
 
+    let detail_summary = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .wrap_mode(pango::WrapMode::WordChar)
+        .visible(false)
+        .build();
+    detail_summary.add_css_class("dim-label");
+    detail_summary.set_xalign(0.0);
+
+    let detail_screenshots_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(8)
+        .build();
+
+    let detail_screenshots_scroller = gtk::ScrolledWindow::builder()
+        .hexpand(true)
+        .min_content_height(160)
+        .visible(false)
+        .build();
+    detail_screenshots_scroller.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Never);
+    detail_screenshots_scroller.set_child(Some(&detail_screenshots_box));
+
     let detail_actions_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(8)
@@ -935,7 +1117,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     detail_dependencies_placeholder.add_css_class("dim-label");
-    detail_dependencies_placeholder.set_text("No runtime dependencies.");
+    detail_dependencies_placeholder.set_text(&tr!("No runtime dependencies."));
 
     let detail_dependencies_stack = gtk::Stack::new();
     detail_dependencies_stack.add_named(&detail_dependencies_placeholder, Some("placeholder"));
@@ -943,7 +1125,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_dependencies_stack.set_visible_child_name("placeholder");
 
     let detail_dependencies_group = adw::PreferencesGroup::builder()
-        .title("Dependencies")
+        .title(tr!("Dependencies"))
         .build();
     detail_dependencies_group.add(&detail_dependencies_stack);
 
@@ -959,8 +1141,10 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_box.add_css_class("nebula-card");
     detail_box.add_css_class("compact");
     detail_box.append(&detail_header_row);
+    detail_box.append(&detail_summary);
     detail_box.append(&detail_metadata_box);
     detail_box.append(&detail_update_label);
+    detail_box.append(&detail_screenshots_scroller);
     detail_box.append(&detail_description_row);
     detail_box.append(&detail_actions_row);
     detail_box.append(&detail_dependencies_group);
@@ -980,7 +1164,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     detail_placeholder.add_css_class("dim-label");
-    detail_placeholder.set_text("Select a package to see details.");
+    detail_placeholder.set_text(&tr!("Select a package to see details."));
     detail_placeholder.set_hexpand(true);
     detail_placeholder.set_vexpand(true);
 
@@ -996,18 +1180,22 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     detail_frame.set_child(Some(&detail_stack));
     detail_frame.set_visible(false);
 
-    let content_row = gtk::Box::builder()
+    search_results_stack.set_margin_end(6);
+    detail_frame.set_margin_start(6);
+    let content_row = gtk::Paned::builder()
         .orientation(gtk::Orientation::Horizontal)
-        .spacing(12)
         .hexpand(true)
         .vexpand(true)
+        .start_child(&search_results_stack)
+        .end_child(&detail_frame)
+        .shrink_start_child(false)
+        .shrink_end_child(false)
         .build();
-    content_row.set_homogeneous(false);
-    content_row.append(&search_results_stack);
-    content_row.append(&detail_frame);
     content_row.set_visible(false);
 
+    // Favorites stay reachable when the spotlight is turned off.
     container.append(&search_row);
+    container.append(&favorites_section);
     container.append(&spotlight_section_box);
     container.append(&status_label);
     container.append(&content_row);
@@ -1016,20 +1204,37 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
     let widgets = DiscoverWidgets {
         search_entry,
         search_spinner,
+        search_descriptions_toggle,
+        browse_button,
+        compare_button,
+        install_selected_button,
+        queue_selected_button,
         status_label,
         list,
         search_results_stack,
         no_results_page,
+        no_results_repo_button,
         content_row,
         detail_stack,
         detail_name,
         detail_back_button,
         detail_close_button,
+        detail_favorite_button,
+        detail_template_button,
+        detail_command_button,
         detail_version_value,
         detail_description,
+        detail_summary,
+        detail_screenshots_scroller,
+        detail_screenshots_box,
         detail_download_value,
+        detail_source_row,
+        detail_source_dropdown,
+        detail_signature_row,
+        detail_signature_value,
         detail_homepage_row,
         detail_homepage_link,
+        detail_homepage_copy_button,
         detail_maintainer_row,
         detail_maintainer_value,
         detail_license_row,
@@ -1042,15 +1247,22 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         detail_dependencies_list,
         detail_dependencies_placeholder,
         detail_frame,
+        spotlight_status_row,
         spotlight_spinner,
         spotlight_status,
         spotlight_recent_stack,
+        spotlight_recent_heading: recent_heading.clone(),
+        spotlight_recent_placeholder: spotlight_recent_placeholder.clone(),
+        spotlight_new_toggle,
         spotlight_recent_list,
         spotlight_recent_scroller: spotlight_recent_scroller.clone(),
         spotlight_recent_detail_revealer: recent_detail_revealer.clone(),
         spotlight_recent_detail_container: recent_detail_box.clone(),
         spotlight_recent_back_button: recent_detail_back_button.clone(),
         spotlight_recent_close_button: recent_detail_close_button.clone(),
+        spotlight_recent_favorite_button: recent_detail_favorite_button,
+        spotlight_recent_template_button: recent_detail_template_button,
+        spotlight_recent_command_button: recent_detail_command_button,
         spotlight_recent_detail_name: recent_detail_name.clone(),
         spotlight_recent_detail_spinner: recent_detail_spinner.clone(),
         spotlight_recent_detail_version_value: recent_detail_version_value.clone(),
@@ -1059,6 +1271,7 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
         spotlight_recent_detail_updated_value: recent_detail_updated_value.clone(),
         spotlight_recent_detail_homepage_row: recent_detail_homepage_row.clone(),
         spotlight_recent_detail_homepage_link: recent_detail_homepage_link.clone(),
+        spotlight_recent_detail_homepage_copy_button: recent_detail_homepage_copy_button.clone(),
         spotlight_recent_detail_maintainer_row: recent_detail_maintainer_row.clone(),
         spotlight_recent_detail_maintainer_value: recent_detail_maintainer_value.clone(),
         spotlight_recent_detail_license_row: recent_detail_license_row.clone(),
@@ -1072,15 +1285,11 @@ pub(crate) fn build_page() -> (gtk::Box, DiscoverWidgets) {
             .clone(),
         spotlight_recent_action_button: recent_detail_action_button.clone(),
         spotlight_section_box,
-        category_browsers_button,
-        category_chat_button,
-        category_email_button,
-        category_games_button,
-        category_graphics_button,
-        category_music_button,
-        category_productivity_button,
-        category_utilities_button,
-        category_video_button,
+        favorites_section,
+        favorites_list,
+        recently_viewed_section,
+        recently_viewed_list,
+        categories_list,
         spotlight_refresh_button: recent_refresh_button,
     };
 
@@ -7,13 +7,21 @@ use libadwaita as adw;
 use adw::prelude::*;
 use gtk::{BaselinePosition, Justification};
 
+use crate::helpers::{
+    build_copy_command_button, build_copy_link_button, build_template_link_button,
+};
+
 pub(crate) struct InstalledWidgets {
     pub(crate) refresh_button: gtk::Button,
     pub(crate) search_entry: gtk::SearchEntry,
     pub(crate) status_label: gtk::Label,
     pub(crate) spinner: gtk::Spinner,
     pub(crate) filter_dropdown: gtk::DropDown,
+    pub(crate) group_variants_button: gtk::ToggleButton,
     pub(crate) remove_selected_button: gtk::Button,
+    pub(crate) queue_selected_button: gtk::Button,
+    pub(crate) hold_selected_button: gtk::Button,
+    pub(crate) unhold_selected_button: gtk::Button,
     pub(crate) list_store: gio::ListStore,
     pub(crate) list_selection: gtk::SingleSelection,
     pub(crate) list_view: gtk::ListView,
@@ -22,21 +30,28 @@ pub(crate) struct InstalledWidgets {
     pub(crate) no_results_page: adw::StatusPage,
     pub(crate) detail_stack: gtk::Stack,
     pub(crate) detail_frame: gtk::Frame,
+    pub(crate) content_row: gtk::Paned,
     pub(crate) detail_remove_button: gtk::Button,
     pub(crate) detail_update_button: gtk::Button,
     pub(crate) detail_pin_button: gtk::Button,
     pub(crate) detail_back_button: gtk::Button,
     pub(crate) detail_close_button: gtk::Button,
+    pub(crate) detail_template_button: gtk::Button,
+    pub(crate) detail_command_button: gtk::Button,
+    pub(crate) detail_files_button: gtk::Button,
     pub(crate) detail_name: gtk::Label,
     pub(crate) detail_version_value: gtk::Label,
     pub(crate) detail_description: gtk::Label,
     pub(crate) detail_download_value: gtk::Label,
     pub(crate) detail_homepage_row: gtk::Box,
     pub(crate) detail_homepage_link: gtk::Label,
+    pub(crate) detail_homepage_copy_button: gtk::Button,
     pub(crate) detail_maintainer_row: gtk::Box,
     pub(crate) detail_maintainer_value: gtk::Label,
     pub(crate) detail_license_row: gtk::Box,
     pub(crate) detail_license_value: gtk::Label,
+    pub(crate) detail_install_date_row: gtk::Box,
+    pub(crate) detail_install_date_value: gtk::Label,
     pub(crate) detail_required_by_stack: gtk::Stack,
     pub(crate) detail_required_by_list: gtk::ListBox,
     pub(crate) detail_required_by_placeholder: gtk::Label,
@@ -86,6 +101,13 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     controls_row.append(&search_bar);
     controls_row.append(&filter_dropdown);
 
+    let group_variants_button = gtk::ToggleButton::builder()
+        .label("Group Variants")
+        .tooltip_text("Fold -devel, -doc, -dbg and -32bit packages under their parent package")
+        .valign(gtk::Align::Center)
+        .build();
+    controls_row.append(&group_variants_button);
+
     let status_label = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .wrap(false)
@@ -115,6 +137,27 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
         .build();
     remove_selected_button.add_css_class("destructive-action");
 
+    let queue_selected_button = gtk::Button::builder()
+        .label("Add to Changes")
+        .halign(gtk::Align::End)
+        .valign(gtk::Align::Center)
+        .tooltip_text("Collect the selected packages to remove later with other changes")
+        .build();
+
+    let hold_selected_button = gtk::Button::builder()
+        .label("Hold Selected")
+        .halign(gtk::Align::End)
+        .valign(gtk::Align::Center)
+        .tooltip_text("Keep the selected packages at their current versions.")
+        .build();
+
+    let unhold_selected_button = gtk::Button::builder()
+        .label("Unhold Selected")
+        .halign(gtk::Align::End)
+        .valign(gtk::Align::Center)
+        .tooltip_text("Let the selected packages receive updates again.")
+        .build();
+
     let status_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -125,6 +168,9 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     status_row.append(&refresh_button);
     status_row.append(&status_label);
     status_row.append(&spinner);
+    status_row.append(&hold_selected_button);
+    status_row.append(&unhold_selected_button);
+    status_row.append(&queue_selected_button);
     status_row.append(&remove_selected_button);
 
     let list_store = gio::ListStore::new::<glib::BoxedAnyObject>();
@@ -156,7 +202,32 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
         .hexpand(true)
         .build();
 
-    // Stack to switch between list and no-results
+    // Shown while the first package listing is still running
+    let loading_spinner = gtk::Spinner::builder()
+        .spinning(true)
+        .width_request(32)
+        .height_request(32)
+        .halign(gtk::Align::Center)
+        .build();
+    let loading_label = gtk::Label::builder()
+        .label("Loading installed packages…")
+        .halign(gtk::Align::Center)
+        .wrap(true)
+        .wrap_mode(pango::WrapMode::WordChar)
+        .build();
+    loading_label.add_css_class("dim-label");
+    let loading_page = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(12)
+        .halign(gtk::Align::Center)
+        .valign(gtk::Align::Center)
+        .hexpand(true)
+        .vexpand(true)
+        .build();
+    loading_page.append(&loading_spinner);
+    loading_page.append(&loading_label);
+
+    // Stack to switch between list, loading and no-results
     let installed_results_stack = gtk::Stack::builder()
         .transition_type(gtk::StackTransitionType::Crossfade)
         .hexpand(true)
@@ -164,6 +235,7 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
         .build();
     installed_results_stack.add_named(&scroller, Some("list"));
     installed_results_stack.add_named(&no_results_page, Some("no-results"));
+    installed_results_stack.add_named(&loading_page, Some("loading"));
     installed_results_stack.set_visible_child_name("list");
 
     let detail_name = gtk::Label::builder()
@@ -198,6 +270,18 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     detail_close_button.set_focus_on_click(false);
     detail_close_button.set_valign(gtk::Align::Center);
 
+    let detail_template_button = build_template_link_button();
+    let detail_command_button = build_copy_command_button();
+
+    let detail_files_button = gtk::Button::builder()
+        .icon_name("folder-symbolic")
+        .tooltip_text("Browse installed files")
+        .has_frame(false)
+        .build();
+    detail_files_button.add_css_class("flat");
+    detail_files_button.set_focus_on_click(false);
+    detail_files_button.set_valign(gtk::Align::Center);
+
     let detail_header_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -211,6 +295,9 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     detail_header_row.append(&detail_back_button);
     detail_header_row.append(&detail_name);
     detail_header_row.append(&detail_header_spacer);
+    detail_header_row.append(&detail_files_button);
+    detail_header_row.append(&detail_command_button);
+    detail_header_row.append(&detail_template_button);
     detail_header_row.append(&detail_close_button);
 
     let detail_metadata_box = gtk::Box::builder()
@@ -308,6 +395,23 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     detail_maintainer_row.append(&detail_maintainer_value);
     detail_metadata_box.append(&detail_maintainer_row);
 
+    let detail_install_date_value = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .visible(false)
+        .build();
+    detail_install_date_value.set_hexpand(true);
+    detail_install_date_value.set_xalign(0.0);
+    let detail_install_date_row = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .halign(gtk::Align::Fill)
+        .visible(false)
+        .build();
+    let detail_install_date_title = make_metadata_label("Installed");
+    detail_install_date_row.append(&detail_install_date_title);
+    detail_install_date_row.append(&detail_install_date_value);
+    detail_metadata_box.append(&detail_install_date_row);
+
     let detail_homepage_link = gtk::Label::builder()
         .use_markup(true)
         .wrap(true)
@@ -327,6 +431,8 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     let detail_homepage_title = make_metadata_label("Homepage");
     detail_homepage_row.append(&detail_homepage_title);
     detail_homepage_row.append(&detail_homepage_link);
+    let detail_homepage_copy_button = build_copy_link_button();
+    detail_homepage_row.append(&detail_homepage_copy_button);
     detail_metadata_box.append(&detail_homepage_row);
 
     let detail_update_label = gtk::Label::builder()
@@ -489,15 +595,17 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
     detail_frame.set_child(Some(&detail_stack));
     detail_frame.set_visible(false);
 
-    let content_row = gtk::Box::builder()
+    installed_results_stack.set_margin_end(6);
+    detail_frame.set_margin_start(6);
+    let content_row = gtk::Paned::builder()
         .orientation(gtk::Orientation::Horizontal)
-        .spacing(12)
         .hexpand(true)
         .vexpand(true)
+        .start_child(&installed_results_stack)
+        .end_child(&detail_frame)
+        .shrink_start_child(false)
+        .shrink_end_child(false)
         .build();
-    content_row.set_homogeneous(false);
-    content_row.append(&installed_results_stack);
-    content_row.append(&detail_frame);
 
     container.append(&controls_row);
     container.append(&status_row);
@@ -522,7 +630,11 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
         status_label,
         spinner,
         filter_dropdown,
+        group_variants_button,
         remove_selected_button,
+        queue_selected_button,
+        hold_selected_button,
+        unhold_selected_button,
         list_store,
         list_selection,
         list_view,
@@ -531,21 +643,28 @@ pub(crate) fn build_page() -> (gtk::Box, InstalledWidgets) {
         no_results_page,
         detail_stack,
         detail_frame,
+        content_row,
         detail_remove_button,
         detail_update_button,
         detail_pin_button,
         detail_back_button,
         detail_close_button,
+        detail_template_button,
+        detail_command_button,
+        detail_files_button,
         detail_name,
         detail_version_value,
         detail_description,
         detail_download_value,
         detail_homepage_row,
         detail_homepage_link,
+        detail_homepage_copy_button,
         detail_maintainer_row,
         detail_maintainer_value,
         detail_license_row,
         detail_license_value,
+        detail_install_date_row,
+        detail_install_date_value,
         detail_required_by_stack,
         detail_required_by_list,
         detail_required_by_placeholder,
@@ -7,9 +7,14 @@ pub(crate) mod tools;
 pub(crate) mod updates;
 
 pub(crate) use app::{AppWidgets, build_ui};
-pub(crate) use discover::{DiscoverWidgets, build_page as build_discover_page};
+pub(crate) use discover::{
+    DiscoverWidgets, build_category_button, build_page as build_discover_page,
+};
 pub(crate) use installed::{InstalledWidgets, build_page as build_installed_page};
 pub(crate) use operations::show_operations_dialog;
-pub(crate) use theme::{ThemeGlyph, apply_theme_css_class, build_theme_icon};
+pub(crate) use theme::{
+    FONT_SCALE_STEPS, ThemeGlyph, apply_font_scale, apply_high_contrast_css_class,
+    apply_theme_css_class, build_theme_icon,
+};
 pub(crate) use tools::{ToolsWidgets, build_page as build_tools_page};
 pub(crate) use updates::{UpdatesWidgets, build_page as build_updates_page};
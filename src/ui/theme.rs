@@ -1,10 +1,18 @@
+use std::cell::RefCell;
 use std::f64::consts::PI;
 
 use gtk4 as gtk;
-use gtk4::cairo;
 use gtk4::prelude::{DrawingAreaExtManual, WidgetExt};
+use gtk4::{cairo, gdk};
 use libadwaita as adw;
 
+/// Text sizes offered in Preferences, as a percentage of the system font.
+pub(crate) const FONT_SCALE_STEPS: [u32; 5] = [100, 115, 130, 150, 175];
+
+thread_local! {
+    static FONT_SCALE_PROVIDER: RefCell<Option<gtk::CssProvider>> = const { RefCell::new(None) };
+}
+
 #[derive(Clone, Copy)]
 pub(crate) enum ThemeGlyph {
     System,
@@ -22,6 +30,40 @@ pub(crate) fn apply_theme_css_class(window: &adw::ApplicationWindow, is_dark: bo
     }
 }
 
+pub(crate) fn apply_high_contrast_css_class(window: &adw::ApplicationWindow, enabled: bool) {
+    if enabled {
+        window.add_css_class("nebula-high-contrast");
+    } else {
+        window.remove_css_class("nebula-high-contrast");
+    }
+}
+
+/// Scales the base font size of every Nebula window. Widgets size
+/// themselves from the font, so rows and detail panes grow with the text.
+pub(crate) fn apply_font_scale(percent: u32) {
+    let Some(display) = gdk::Display::default() else {
+        return;
+    };
+    let css = if percent == 100 {
+        String::new()
+    } else {
+        format!("window {{ font-size: {}%; }}", percent)
+    };
+    FONT_SCALE_PROVIDER.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let provider = slot.get_or_insert_with(|| {
+            let provider = gtk::CssProvider::new();
+            gtk::style_context_add_provider_for_display(
+                &display,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+            );
+            provider
+        });
+        provider.load_from_data(&css);
+    });
+}
+
 pub(crate) fn build_theme_icon(mode: ThemeGlyph) -> gtk::DrawingArea {
     let area = gtk::DrawingArea::builder()
         .content_width(24)
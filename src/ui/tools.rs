@@ -12,12 +12,30 @@ pub(crate) struct ToolsWidgets {
     pub(crate) cache_clean_button: gtk::Button,
     pub(crate) cache_clean_spinner: gtk::Spinner,
     pub(crate) cache_clean_spin_button: gtk::SpinButton,
+    pub(crate) cache_clear_all_button: gtk::Button,
+    pub(crate) cache_clear_all_spinner: gtk::Spinner,
+    pub(crate) cache_size_label: gtk::Label,
     pub(crate) pkgdb_button: gtk::Button,
     pub(crate) pkgdb_spinner: gtk::Spinner,
+    pub(crate) integrity_button: gtk::Button,
+    pub(crate) integrity_spinner: gtk::Spinner,
+    pub(crate) pkgdb_repair_button: gtk::Button,
+    pub(crate) pkgdb_repair_spinner: gtk::Spinner,
+    pub(crate) repair_log_revealer: gtk::Revealer,
+    pub(crate) repair_log_view: gtk::TextView,
+    pub(crate) repair_log_buffer: gtk::TextBuffer,
     pub(crate) reconfigure_button: gtk::Button,
     pub(crate) reconfigure_spinner: gtk::Spinner,
     pub(crate) alternatives_button: gtk::Button,
     pub(crate) alternatives_spinner: gtk::Spinner,
+    pub(crate) hold_all_button: gtk::Button,
+    pub(crate) hold_all_spinner: gtk::Spinner,
+    pub(crate) unhold_all_button: gtk::Button,
+    pub(crate) unhold_all_spinner: gtk::Spinner,
+    pub(crate) sync_repos_button: gtk::Button,
+    pub(crate) sync_repos_spinner: gtk::Spinner,
+    pub(crate) recently_removed_list: gtk::ListBox,
+    pub(crate) recently_removed_stack: gtk::Stack,
     pub(crate) status_label: gtk::Label,
     pub(crate) status_revealer: gtk::Revealer,
 }
@@ -127,12 +145,20 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
     cache_clean_button.set_focus_on_click(false);
     cache_clean_button.set_tooltip_text(Some("Clean package cache, keeping the selected number of versions"));
 
+    let cache_size_label = gtk::Label::builder()
+        .label("Calculating size…")
+        .valign(gtk::Align::Center)
+        .build();
+    cache_size_label.add_css_class("dim-label");
+    cache_size_label.set_tooltip_text(Some("Current size of /var/cache/xbps"));
+
     let cache_controls = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
         .halign(gtk::Align::End)
         .valign(gtk::Align::Center)
         .build();
+    cache_controls.append(&cache_size_label);
     cache_controls.append(&keep_label);
     cache_controls.append(&cache_clean_spin_button);
     cache_controls.append(&cache_clean_spinner);
@@ -141,8 +167,34 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
 
     quick_group.add(&cache_clean_row);
 
+    let (cache_clear_all_row, cache_clear_all_button, cache_clear_all_spinner) =
+        build_tools_action_row(
+            "Clear entire package cache",
+            "Delete every downloaded package file. Packages are downloaded again when next needed.",
+            "Clear all",
+            "Remove all cached packages, including the current versions",
+        );
+    cache_clear_all_button.add_css_class("destructive-action");
+    quick_group.add(&cache_clear_all_row);
+
     content.append(&quick_group);
 
+    let repositories_group = adw::PreferencesGroup::builder()
+        .title("Repositories")
+        .description(
+            "Refresh the package lists after switching mirrors so searches see the new source.",
+        )
+        .build();
+
+    let (sync_repos_row, sync_repos_button, sync_repos_spinner) = build_tools_action_row(
+        "Sync repository indexes",
+        "Downloads the latest package lists without checking for or installing updates.",
+        "Sync now",
+        "Runs \"xbps-install -S\" with root privileges.",
+    );
+    repositories_group.add(&sync_repos_row);
+    content.append(&repositories_group);
+
     let repair_group = adw::PreferencesGroup::builder()
         .title("Repair &amp; recovery")
         .description(
@@ -152,12 +204,29 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
 
     let (pkgdb_row, pkgdb_button, pkgdb_spinner) = build_tools_action_row(
         "Verify package database",
-        "Checks and repairs package metadata. Handy after forced power-offs.",
+        "Looks for packages left half-installed or half-removed. Handy after forced power-offs.",
         "Run verification",
-        "Runs \"xbps-pkgdb -a\" to verify package metadata.",
+        "Runs \"xbps-query -l\" and reports packages that are not fully installed.",
     );
     repair_group.add(&pkgdb_row);
 
+    let (integrity_row, integrity_button, integrity_spinner) = build_tools_action_row(
+        "Verify installed files",
+        "Compares installed files with their recorded hashes to find modified or missing files. Useful after disk trouble.",
+        "Check files",
+        "Reads the file lists in /var/db/xbps without root privileges and lists packages with file problems.",
+    );
+    repair_group.add(&integrity_row);
+
+    let (pkgdb_repair_row, pkgdb_repair_button, pkgdb_repair_spinner) = build_tools_action_row(
+        "Repair package database",
+        "Recovery tool. Rewrites package metadata as root; only use it when verification reports problems.",
+        "Repair",
+        "Runs \"xbps-pkgdb -a\" with root privileges to repair package metadata.",
+    );
+    pkgdb_repair_button.add_css_class("destructive-action");
+    repair_group.add(&pkgdb_repair_row);
+
     let (reconfigure_row, reconfigure_button, reconfigure_spinner) =
         build_tools_action_row(
             "Reconfigure everything",
@@ -168,6 +237,32 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
     repair_group.add(&reconfigure_row);
     content.append(&repair_group);
 
+    let repair_log_buffer = gtk::TextBuffer::new(None);
+    let repair_log_view = gtk::TextView::builder()
+        .buffer(&repair_log_buffer)
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::WordChar)
+        .build();
+    repair_log_view.set_cursor_visible(false);
+
+    let repair_log_scroller = gtk::ScrolledWindow::builder()
+        .hexpand(true)
+        .min_content_height(160)
+        .build();
+    repair_log_scroller.set_child(Some(&repair_log_view));
+
+    let repair_log_frame = gtk::Frame::builder().build();
+    repair_log_frame.set_child(Some(&repair_log_scroller));
+
+    let repair_log_revealer = gtk::Revealer::builder()
+        .transition_type(gtk::RevealerTransitionType::SlideDown)
+        .transition_duration(200)
+        .reveal_child(false)
+        .build();
+    repair_log_revealer.set_child(Some(&repair_log_frame));
+    content.append(&repair_log_revealer);
+
     let alternatives_group = adw::PreferencesGroup::builder()
         .title("Alternatives")
         .description("See which providers are currently registered before you switch defaults.")
@@ -183,6 +278,56 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
     alternatives_group.add(&alternatives_row);
     content.append(&alternatives_group);
 
+    let holds_group = adw::PreferencesGroup::builder()
+        .title("Version holds")
+        .description(
+            "Freeze the whole system before experimenting so later updates leave every package where it is.",
+        )
+        .build();
+
+    let (hold_all_row, hold_all_button, hold_all_spinner) = build_tools_action_row(
+        "Hold all installed packages",
+        "Keeps every installed package at its current version until it is unheld.",
+        "Hold all",
+        "Runs \"xbps-pkgdb -m hold\" as root for every installed package.",
+    );
+    holds_group.add(&hold_all_row);
+
+    let (unhold_all_row, unhold_all_button, unhold_all_spinner) = build_tools_action_row(
+        "Unhold all packages",
+        "Releases every held package so updates apply to it again.",
+        "Unhold all",
+        "Runs \"xbps-pkgdb -m unhold\" as root for every held package.",
+    );
+    holds_group.add(&unhold_all_row);
+    content.append(&holds_group);
+
+    let recently_removed_group = adw::PreferencesGroup::builder()
+        .title("Recently removed")
+        .description(
+            "Packages removed with Nebula, newest first. Reinstall any of them in one click.",
+        )
+        .build();
+
+    let recently_removed_placeholder = gtk::Label::builder()
+        .label("Nothing has been removed yet.")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .wrap_mode(pango::WrapMode::WordChar)
+        .build();
+    recently_removed_placeholder.add_css_class("dim-label");
+
+    let recently_removed_list = gtk::ListBox::new();
+    recently_removed_list.add_css_class("boxed-list");
+    recently_removed_list.set_selection_mode(gtk::SelectionMode::None);
+
+    let recently_removed_stack = gtk::Stack::new();
+    recently_removed_stack.add_named(&recently_removed_placeholder, Some("placeholder"));
+    recently_removed_stack.add_named(&recently_removed_list, Some("list"));
+    recently_removed_stack.set_visible_child_name("placeholder");
+    recently_removed_group.add(&recently_removed_stack);
+    content.append(&recently_removed_group);
+
     // Footer status area
     let status_revealer = gtk::Revealer::builder()
         .transition_type(gtk::RevealerTransitionType::SlideUp)
@@ -217,12 +362,30 @@ pub(crate) fn build_page() -> (gtk::Box, ToolsWidgets) {
         cache_clean_button,
         cache_clean_spinner,
         cache_clean_spin_button,
+        cache_clear_all_button,
+        cache_clear_all_spinner,
+        cache_size_label,
         pkgdb_button,
         pkgdb_spinner,
+        integrity_button,
+        integrity_spinner,
+        pkgdb_repair_button,
+        pkgdb_repair_spinner,
+        repair_log_revealer,
+        repair_log_view,
+        repair_log_buffer,
         reconfigure_button,
         reconfigure_spinner,
         alternatives_button,
         alternatives_spinner,
+        hold_all_button,
+        hold_all_spinner,
+        unhold_all_button,
+        unhold_all_spinner,
+        sync_repos_button,
+        sync_repos_spinner,
+        recently_removed_list,
+        recently_removed_stack,
         status_label,
         status_revealer,
     };
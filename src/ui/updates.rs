@@ -4,8 +4,14 @@ use libadwaita as adw;
 
 use adw::prelude::*;
 
+use crate::helpers::{
+    build_copy_command_button, build_copy_link_button, build_template_link_button,
+};
+use crate::i18n::tr;
+
 pub(crate) struct UpdatesWidgets {
     pub(crate) summary_row: gtk::Box,
+    pub(crate) search_entry: gtk::SearchEntry,
     pub(crate) list: gtk::ListBox,
     pub(crate) scroller: gtk::ScrolledWindow,
     pub(crate) content_row: gtk::Box,
@@ -13,18 +19,30 @@ pub(crate) struct UpdatesWidgets {
     pub(crate) placeholder_label: gtk::Label,
     pub(crate) check_button: gtk::Button,
     pub(crate) refresh_button: gtk::Button,
+    pub(crate) log_button: gtk::Button,
+    pub(crate) history_button: gtk::Button,
+    pub(crate) config_banner: adw::Banner,
+    pub(crate) xbps_banner: adw::Banner,
+    pub(crate) restart_banner: adw::Banner,
     pub(crate) update_all_button: gtk::Button,
+    pub(crate) retry_failed_button: gtk::Button,
+    pub(crate) select_all_button: gtk::Button,
+    pub(crate) select_none_button: gtk::Button,
     pub(crate) spinner: gtk::Spinner,
     pub(crate) summary_label: gtk::Label,
+    pub(crate) eta_label: gtk::Label,
     pub(crate) footer_label: gtk::Label,
     pub(crate) detail_frame: gtk::Frame,
     pub(crate) detail_stack: gtk::Stack,
     pub(crate) detail_name: gtk::Label,
     pub(crate) detail_close_button: gtk::Button,
+    pub(crate) detail_template_button: gtk::Button,
+    pub(crate) detail_command_button: gtk::Button,
     pub(crate) detail_version_value: gtk::Label,
     pub(crate) detail_download_value: gtk::Label,
     pub(crate) detail_homepage_row: gtk::Box,
     pub(crate) detail_homepage_link: gtk::Label,
+    pub(crate) detail_homepage_copy_button: gtk::Button,
     pub(crate) detail_maintainer_row: gtk::Box,
     pub(crate) detail_maintainer_value: gtk::Label,
     pub(crate) detail_license_row: gtk::Box,
@@ -62,9 +80,18 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     summary_label.add_css_class("dim-label");
     summary_label.set_hexpand(true);
     summary_label.set_valign(gtk::Align::Center);
-    summary_label.set_text("No updates checked yet.");
+    summary_label.set_text(&tr!("No updates checked yet."));
     summary_row.append(&summary_label);
 
+    let eta_label = gtk::Label::builder()
+        .halign(gtk::Align::End)
+        .valign(gtk::Align::Center)
+        .visible(false)
+        .build();
+    eta_label.add_css_class("dim-label");
+    eta_label.add_css_class("numeric");
+    summary_row.append(&eta_label);
+
     let spinner = gtk::Spinner::new();
     spinner.set_visible(false);
     spinner.set_hexpand(false);
@@ -86,10 +113,12 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     placeholder_label.add_css_class("title-4");
-    placeholder_label.set_text("Your system is up to date!");
+    placeholder_label.set_text(&tr!("Your system is up to date!"));
     placeholder.append(&placeholder_label);
 
-    let check_button = gtk::Button::builder().label("Check for updates").build();
+    let check_button = gtk::Button::builder()
+        .label(tr!("Check for updates"))
+        .build();
     check_button.add_css_class("suggested-action");
     placeholder.append(&check_button);
 
@@ -97,13 +126,31 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .icon_name("view-refresh-symbolic")
         .halign(gtk::Align::Start)
         .valign(gtk::Align::Center)
-        .tooltip_text("Check for new updates")
+        .tooltip_text(tr!("Check for new updates"))
         .build();
     refresh_button.set_focus_on_click(false);
     refresh_button.add_css_class("flat");
 
+    let log_button = gtk::Button::builder()
+        .icon_name("utilities-terminal-symbolic")
+        .halign(gtk::Align::Start)
+        .valign(gtk::Align::Center)
+        .tooltip_text(tr!("Show update log"))
+        .build();
+    log_button.set_focus_on_click(false);
+    log_button.add_css_class("flat");
+
+    let history_button = gtk::Button::builder()
+        .icon_name("document-open-recent-symbolic")
+        .halign(gtk::Align::Start)
+        .valign(gtk::Align::Center)
+        .tooltip_text(tr!("Show update history"))
+        .build();
+    history_button.set_focus_on_click(false);
+    history_button.add_css_class("flat");
+
     let update_all_button = gtk::Button::builder()
-        .label("Update All")
+        .label(tr!("Update All"))
         .halign(gtk::Align::End)
         .valign(gtk::Align::Center)
         .build();
@@ -111,6 +158,31 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     update_all_button.set_visible(false);
     update_all_button.set_margin_start(12);
 
+    let retry_failed_button = gtk::Button::builder()
+        .label(tr!("Retry Failed"))
+        .halign(gtk::Align::End)
+        .valign(gtk::Align::Center)
+        .tooltip_text(tr!("Run every failed update again"))
+        .build();
+    retry_failed_button.set_visible(false);
+    retry_failed_button.set_margin_start(12);
+
+    let select_all_button = gtk::Button::builder()
+        .label(tr!("Select All"))
+        .valign(gtk::Align::Center)
+        .tooltip_text(tr!("Include every update in the next run"))
+        .build();
+    select_all_button.add_css_class("flat");
+    select_all_button.set_visible(false);
+
+    let select_none_button = gtk::Button::builder()
+        .label(tr!("Select None"))
+        .valign(gtk::Align::Center)
+        .tooltip_text(tr!("Clear the update selection"))
+        .build();
+    select_none_button.add_css_class("flat");
+    select_none_button.set_visible(false);
+
     let controls_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -119,7 +191,12 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     controls_row.set_valign(gtk::Align::Center);
     controls_row.set_halign(gtk::Align::Fill);
     controls_row.append(&refresh_button);
+    controls_row.append(&log_button);
+    controls_row.append(&history_button);
     controls_row.append(&summary_row);
+    controls_row.append(&select_all_button);
+    controls_row.append(&select_none_button);
+    controls_row.append(&retry_failed_button);
     controls_row.append(&update_all_button);
 
     let list = gtk::ListBox::new();
@@ -145,7 +222,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
 
     let detail_close_button = gtk::Button::builder()
         .icon_name("window-close-symbolic")
-        .tooltip_text("Close details")
+        .tooltip_text(tr!("Close details"))
         .has_frame(false)
         .visible(false)
         .sensitive(false)
@@ -155,6 +232,9 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     detail_close_button.set_valign(gtk::Align::Center);
     detail_close_button.set_halign(gtk::Align::End);
 
+    let detail_template_button = build_template_link_button();
+    let detail_command_button = build_copy_command_button();
+
     let detail_header_row = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
@@ -167,6 +247,8 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .build();
     detail_header_row.append(&detail_name);
     detail_header_row.append(&detail_header_row_spacer);
+    detail_header_row.append(&detail_command_button);
+    detail_header_row.append(&detail_template_button);
     detail_header_row.append(&detail_close_button);
 
     let make_metadata_label = |text: &str| {
@@ -192,7 +274,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let detail_version_title = make_metadata_label("Version");
+    let detail_version_title = make_metadata_label(&tr!("Version"));
     let detail_version_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -209,7 +291,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .spacing(6)
         .halign(gtk::Align::Fill)
         .build();
-    let detail_download_title = make_metadata_label("Install size");
+    let detail_download_title = make_metadata_label(&tr!("Install size"));
     let detail_download_value = gtk::Label::builder()
         .halign(gtk::Align::Start)
         .single_line_mode(true)
@@ -238,7 +320,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_license_title = make_metadata_label("License");
+    let detail_license_title = make_metadata_label(&tr!("License"));
     detail_license_row.append(&detail_license_title);
     detail_license_row.append(&detail_license_value);
     detail_metadata_box.append(&detail_license_row);
@@ -259,7 +341,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_maintainer_title = make_metadata_label("Maintainer");
+    let detail_maintainer_title = make_metadata_label(&tr!("Maintainer"));
     detail_maintainer_row.append(&detail_maintainer_title);
     detail_maintainer_row.append(&detail_maintainer_value);
     detail_metadata_box.append(&detail_maintainer_row);
@@ -280,9 +362,11 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .halign(gtk::Align::Fill)
         .visible(false)
         .build();
-    let detail_homepage_title = make_metadata_label("Homepage");
+    let detail_homepage_title = make_metadata_label(&tr!("Homepage"));
     detail_homepage_row.append(&detail_homepage_title);
     detail_homepage_row.append(&detail_homepage_link);
+    let detail_homepage_copy_button = build_copy_link_button();
+    detail_homepage_row.append(&detail_homepage_copy_button);
     detail_metadata_box.append(&detail_homepage_row);
 
     let detail_update_label = gtk::Label::builder()
@@ -294,7 +378,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     detail_update_label.set_visible(false);
 
     let detail_update_button = gtk::Button::builder()
-        .label("Update")
+        .label(tr!("Update"))
         .width_request(120)
         .build();
     detail_update_button.add_css_class("suggested-action");
@@ -302,7 +386,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     detail_update_button.set_halign(gtk::Align::Start);
     detail_update_button.set_valign(gtk::Align::Center);
     detail_update_button.set_margin_start(0);
-    detail_update_button.set_tooltip_text(Some("Install this update."));
+    detail_update_button.set_tooltip_text(Some(tr!("Install this update.").as_str()));
 
     let detail_description = gtk::Label::builder()
         .halign(gtk::Align::Start)
@@ -311,7 +395,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .hexpand(true)
         .justify(gtk::Justification::Left)
         .build();
-    detail_description.set_text("Select an update to see details.");
+    detail_description.set_text(&tr!("Select an update to see details."));
     detail_description.set_ellipsize(pango::EllipsizeMode::None);
     detail_description.set_single_line_mode(false);
 
@@ -321,7 +405,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .halign(gtk::Align::Fill)
         .hexpand(true)
         .build();
-    let detail_description_title = make_metadata_label("Description");
+    let detail_description_title = make_metadata_label(&tr!("Description"));
     let detail_description_container = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .hexpand(true)
@@ -353,7 +437,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     detail_required_by_placeholder.add_css_class("dim-label");
-    detail_required_by_placeholder.set_text("Not required by any installed package.");
+    detail_required_by_placeholder.set_text(&tr!("Not required by any installed package."));
 
     let detail_required_by_list = gtk::ListBox::new();
     detail_required_by_list.add_css_class("boxed-list");
@@ -367,7 +451,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
     detail_required_by_stack.set_visible_child_name("placeholder");
 
     let detail_required_by_group = adw::PreferencesGroup::builder()
-        .title("Required By")
+        .title(tr!("Required By"))
         .build();
     detail_required_by_group.add(&detail_required_by_stack);
 
@@ -402,7 +486,7 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .wrap_mode(pango::WrapMode::WordChar)
         .build();
     detail_placeholder.add_css_class("dim-label");
-    detail_placeholder.set_text("Select an update to see details.");
+    detail_placeholder.set_text(&tr!("Select an update to see details."));
     detail_placeholder.set_hexpand(true);
     detail_placeholder.set_vexpand(true);
 
@@ -425,10 +509,41 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .vexpand(true)
         .build();
     content_row.set_homogeneous(false);
-    content_row.append(&scroller);
+
+    let search_entry = gtk::SearchEntry::builder()
+        .placeholder_text(tr!("Filter updates"))
+        .hexpand(true)
+        .build();
+    let list_column = gtk::Box::builder()
+        .orientation(gtk::Orientation::Vertical)
+        .spacing(6)
+        .hexpand(true)
+        .build();
+    list_column.append(&search_entry);
+    list_column.append(&scroller);
+    content_row.append(&list_column);
     content_row.append(&detail_frame);
     content_row.set_visible(false);
 
+    let config_banner = adw::Banner::builder()
+        .button_label(tr!("Review"))
+        .revealed(false)
+        .build();
+
+    let xbps_banner = adw::Banner::builder()
+        .title(tr!("XBPS must be updated before other packages can be."))
+        .button_label(tr!("Update XBPS"))
+        .revealed(false)
+        .build();
+
+    let restart_banner = adw::Banner::builder()
+        .button_label(tr!("Dismiss"))
+        .revealed(false)
+        .build();
+
+    container.append(&xbps_banner);
+    container.append(&config_banner);
+    container.append(&restart_banner);
     container.append(&controls_row);
     container.append(&placeholder);
     let footer_label = gtk::Label::builder()
@@ -440,13 +555,14 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         .margin_bottom(6)
         .build();
     footer_label.add_css_class("dim-label");
-    footer_label.set_text("Last checked — never.");
+    footer_label.set_text(&tr!("Last checked — never."));
 
     container.append(&content_row);
     container.append(&footer_label);
 
     let widgets = UpdatesWidgets {
         summary_row: summary_row.clone(),
+        search_entry,
         list,
         scroller,
         content_row,
@@ -454,18 +570,30 @@ pub(crate) fn build_page() -> (gtk::Box, UpdatesWidgets) {
         placeholder_label,
         check_button,
         refresh_button,
+        log_button,
+        history_button,
+        config_banner,
+        xbps_banner,
+        restart_banner,
         update_all_button,
+        retry_failed_button,
+        select_all_button,
+        select_none_button,
         spinner,
         summary_label,
+        eta_label,
         footer_label,
         detail_frame,
         detail_stack,
         detail_name,
         detail_close_button,
+        detail_template_button,
+        detail_command_button,
         detail_version_value,
         detail_download_value,
         detail_homepage_row,
         detail_homepage_link,
+        detail_homepage_copy_button,
         detail_maintainer_row,
         detail_maintainer_value,
         detail_license_row,
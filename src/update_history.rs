@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::app_config_dir;
+use crate::state::types::UpdateStatus;
+
+const UPDATE_HISTORY_FILE: &str = "update-history.jsonl";
+const UPDATE_IN_PROGRESS_FILE: &str = "update-in-progress.json";
+
+/// Oldest entries are dropped once the history grows past this many runs.
+pub(crate) const MAX_UPDATE_HISTORY_ENTRIES: usize = 200;
+
+/// One finished update run, stored as a line of JSON.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct UpdateHistoryEntry {
+    pub finished_at: DateTime<Utc>,
+    pub packages: Vec<String>,
+    #[serde(default)]
+    pub all: bool,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Applied by a scheduled check rather than on request.
+    #[serde(default)]
+    pub unattended: bool,
+}
+
+/// Reads the saved history, oldest first. Lines that fail to parse are
+/// skipped so one bad write doesn't hide the rest.
+pub(crate) fn load_update_history() -> Vec<UpdateHistoryEntry> {
+    let Some(path) = update_history_path() else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    parse_update_history(&content)
+}
+
+pub(crate) fn append_update_history(entry: UpdateHistoryEntry) -> Result<(), String> {
+    let Some(path) = update_history_path() else {
+        return Err("Unable to determine settings directory".to_string());
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create settings directory: {}", err))?;
+    }
+
+    let mut entries = fs::read_to_string(&path)
+        .map(|content| parse_update_history(&content))
+        .unwrap_or_default();
+    entries.push(entry);
+    trim_update_history(&mut entries, MAX_UPDATE_HISTORY_ENTRIES);
+
+    let mut data = String::new();
+    for entry in &entries {
+        let line = serde_json::to_string(entry)
+            .map_err(|err| format!("Failed to serialize update history: {}", err))?;
+        data.push_str(&line);
+        data.push('\n');
+    }
+
+    fs::write(&path, data).map_err(|err| format!("Failed to write update history: {}", err))
+}
+
+fn parse_update_history(content: &str) -> Vec<UpdateHistoryEntry> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn trim_update_history(entries: &mut Vec<UpdateHistoryEntry>, max: usize) {
+    if entries.len() > max {
+        let excess = entries.len() - max;
+        entries.drain(..excess);
+    }
+}
+
+fn update_history_path() -> Option<PathBuf> {
+    app_config_dir().map(|dir| dir.join(UPDATE_HISTORY_FILE))
+}
+
+/// The update run currently underway. It is written when the run starts and
+/// removed when it finishes, so finding it at launch means Nebula stopped
+/// mid-update.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct InFlightUpdate {
+    pub started_at: DateTime<Utc>,
+    pub packages: Vec<String>,
+    #[serde(default)]
+    pub all: bool,
+    /// Last status of each package. Stored by variant name, which matches
+    /// the labels earlier versions wrote.
+    #[serde(default)]
+    pub statuses: BTreeMap<String, UpdateStatus>,
+}
+
+impl InFlightUpdate {
+    /// Packages the run had not finished when it was cut off.
+    pub(crate) fn unfinished_packages(&self) -> Vec<&str> {
+        self.packages
+            .iter()
+            .filter(|name| self.statuses.get(*name) != Some(&UpdateStatus::Completed))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+pub(crate) fn save_in_flight_update(update: &InFlightUpdate) -> Result<(), String> {
+    let Some(path) = in_flight_update_path() else {
+        return Err("Unable to determine settings directory".to_string());
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|err| format!("Failed to create settings directory: {}", err))?;
+    }
+
+    let data = serde_json::to_string(update)
+        .map_err(|err| format!("Failed to serialize update state: {}", err))?;
+    fs::write(&path, data).map_err(|err| format!("Failed to write update state: {}", err))
+}
+
+pub(crate) fn load_in_flight_update() -> Option<InFlightUpdate> {
+    let path = in_flight_update_path()?;
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub(crate) fn clear_in_flight_update() {
+    if let Some(path) = in_flight_update_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn in_flight_update_path() -> Option<PathBuf> {
+    app_config_dir().map(|dir| dir.join(UPDATE_IN_PROGRESS_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(package: &str) -> UpdateHistoryEntry {
+        UpdateHistoryEntry {
+            finished_at: Utc::now(),
+            packages: vec![package.to_string()],
+            all: false,
+            success: true,
+            error: None,
+            unattended: false,
+        }
+    }
+
+    #[test]
+    fn parse_skips_malformed_lines() {
+        let good = serde_json::to_string(&entry("firefox")).unwrap();
+        let content = format!("{}\nnot json\n\n{}\n", good, good);
+        let entries = parse_update_history(&content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].packages, vec!["firefox".to_string()]);
+    }
+
+    #[test]
+    fn trim_drops_oldest_entries() {
+        let mut entries: Vec<_> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| entry(name))
+            .collect();
+        trim_update_history(&mut entries, 2);
+        let names: Vec<_> = entries.iter().map(|e| e.packages[0].as_str()).collect();
+        assert_eq!(names, vec!["c", "d"]);
+    }
+
+    #[test]
+    fn unfinished_packages_skip_completed_ones() {
+        let update = InFlightUpdate {
+            started_at: Utc::now(),
+            packages: vec!["glibc".to_string(), "mesa".to_string(), "zstd".to_string()],
+            all: true,
+            statuses: BTreeMap::from([
+                ("glibc".to_string(), UpdateStatus::Completed),
+                ("mesa".to_string(), UpdateStatus::Installing),
+            ]),
+        };
+        assert_eq!(update.unfinished_packages(), vec!["mesa", "zstd"]);
+    }
+}
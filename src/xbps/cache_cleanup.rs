@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use crate::types::CommandResult;
@@ -8,11 +9,47 @@ use crate::types::CommandResult;
 const XBPS_CACHE_DIR: &str = "/var/cache/xbps";
 
 #[derive(Debug, Clone)]
-struct CachedPackageFile {
-    path: PathBuf,
-    package_name: String,
-    mtime: std::time::SystemTime,
-    size: u64,
+pub(crate) struct CachedPackageFile {
+    pub path: PathBuf,
+    pub package_name: String,
+    pub mtime: std::time::SystemTime,
+    pub size: u64,
+}
+
+/// Files a cache cleanup would delete, computed without touching the cache.
+#[derive(Debug, Clone)]
+pub(crate) struct CacheCleanupPlan {
+    pub keep_n: u32,
+    pub files: Vec<CachedPackageFile>,
+}
+
+impl CacheCleanupPlan {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub(crate) fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+
+    /// Files grouped by package name, packages sorted alphabetically and
+    /// files oldest first.
+    pub(crate) fn by_package(&self) -> Vec<(&str, Vec<&CachedPackageFile>)> {
+        let mut grouped: BTreeMap<&str, Vec<&CachedPackageFile>> = BTreeMap::new();
+        for file in &self.files {
+            grouped
+                .entry(file.package_name.as_str())
+                .or_default()
+                .push(file);
+        }
+        grouped
+            .into_iter()
+            .map(|(name, mut files)| {
+                files.sort_by(|a, b| a.mtime.cmp(&b.mtime));
+                (name, files)
+            })
+            .collect()
+    }
 }
 
 /// Check if xbps cache is currently locked by another process
@@ -31,6 +68,30 @@ pub(crate) fn is_cache_locked() -> bool {
     false
 }
 
+/// Total size in bytes of everything under the xbps package cache
+pub(crate) fn cache_size_bytes() -> io::Result<u64> {
+    let cache_path = Path::new(XBPS_CACHE_DIR);
+    if !cache_path.exists() {
+        return Ok(0);
+    }
+    directory_size(cache_path)
+}
+
+fn directory_size(dir: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        // Don't follow symlinks out of the cache
+        let metadata = entry.path().symlink_metadata()?;
+        if metadata.is_dir() {
+            total += directory_size(&entry.path())?;
+        } else if metadata.is_file() {
+            total += metadata.len();
+        }
+    }
+    Ok(total)
+}
+
 /// Extract package base name from cache filename
 /// Format: packagename-version_revision.arch.xbps
 /// Examples:
@@ -201,24 +262,35 @@ fn remove_files(files: &[CachedPackageFile]) -> Result<CommandResult, String> {
     })
 }
 
-/// Clean package cache, keeping N latest versions of each package
-/// If keep_n is 1, this behaves like `xbps-remove -o`
+/// Work out which cached packages a cleanup keeping the `keep_n` newest
+/// versions of each package would remove. A `keep_n` of zero plans the
+/// removal of every cached package. Nothing is deleted.
+pub(crate) fn plan_cache_cleanup(keep_n: u32) -> Result<CacheCleanupPlan, String> {
+    let files = list_cached_files()?;
+    Ok(CacheCleanupPlan {
+        keep_n,
+        files: select_files_to_remove(files, keep_n),
+    })
+}
+
+/// Delete the files listed in a cleanup plan
 /// Returns the number of files removed and total size freed
-pub(crate) fn clean_cache_keep_n(keep_n: u32) -> Result<(usize, u64), String> {
+pub(crate) fn execute_cache_cleanup(plan: &CacheCleanupPlan) -> Result<(usize, u64), String> {
     // Check if cache is locked
     if is_cache_locked() {
-        return Err("Package cache is currently in use by another xbps process. Please wait and try again.".to_string());
-    }
-
-    // List all cached files
-    let files = list_cached_files()?;
-
-    if files.is_empty() {
-        return Ok((0, 0));
+        return Err(
+            "Package cache is currently in use by another xbps process. Please wait and try again."
+                .to_string(),
+        );
     }
 
-    // Select files to remove
-    let to_remove = select_files_to_remove(files, keep_n);
+    // Files may have vanished since the plan was made; skip those
+    let to_remove: Vec<CachedPackageFile> = plan
+        .files
+        .iter()
+        .filter(|f| f.path.exists())
+        .cloned()
+        .collect();
 
     if to_remove.is_empty() {
         return Ok((0, 0));
@@ -261,4 +333,37 @@ mod tests {
             Some("some-package-with-dashes".to_string())
         );
     }
+
+    fn cached(name: &str, age_secs: u64, size: u64) -> CachedPackageFile {
+        CachedPackageFile {
+            path: PathBuf::from(format!(
+                "/var/cache/xbps/{}-{}_1.x86_64.xbps",
+                name, age_secs
+            )),
+            package_name: name.to_string(),
+            mtime: std::time::SystemTime::UNIX_EPOCH
+                + std::time::Duration::from_secs(1_000_000 - age_secs),
+            size,
+        }
+    }
+
+    #[test]
+    fn plan_keeps_newest_versions_per_package() {
+        let files = vec![
+            cached("foo", 30, 10),
+            cached("foo", 10, 20),
+            cached("foo", 20, 30),
+            cached("bar", 5, 40),
+        ];
+        let plan = CacheCleanupPlan {
+            keep_n: 2,
+            files: select_files_to_remove(files, 2),
+        };
+
+        assert_eq!(plan.total_size(), 10);
+        let grouped = plan.by_package();
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped[0].0, "foo");
+        assert_eq!(grouped[0].1[0].size, 10);
+    }
 }
@@ -1,22 +1,42 @@
 use std::collections::{HashMap, HashSet};
+use std::env;
 use std::process::Command;
+use std::thread;
+use std::time::Duration;
 
-use crate::mirrors::{configure_query_command, install_repository_args};
-use crate::types::{CommandResult, DependencyInfo, PackageInfo, lowercase_cache};
+use regex::RegexBuilder;
 
+use crate::mirrors::{
+    VOID_ARCHITECTURES, architecture_repositories, configure_query_command,
+    install_repository_args, native_architecture,
+};
+use crate::types::{
+    CommandResult, DependencyInfo, PackageInfo, PackageSource, RemoveOptions, lowercase_cache,
+};
+
+use super::deadline::{command_output, with_deadline};
 use super::parser::{
-    parse_bytes, parse_bytes_from_field, parse_installed_output, parse_long_description,
-    parse_query_output, split_package_identifier, strip_ansi_codes,
+    TransactionSizes, parse_bytes, parse_bytes_from_field, parse_installed_output,
+    parse_long_description, parse_query_output, parse_repository_list, parse_transaction_sizes,
+    requires_xbps_self_update, search_term_patterns, split_package_identifier, strip_ansi_codes,
+};
+use super::privilege::{
+    format_terminal_command, run_privileged_command, run_privileged_command_streaming, shell_quote,
 };
-use super::privilege::run_privileged_command;
+
+/// Upper bound on the remote index lookups of [`find_other_architectures`].
+const OTHER_ARCHITECTURES_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Error from [`query_repo_package_info`] when no enabled repository has
+/// the package, as opposed to the query itself failing.
+pub(crate) const PACKAGE_NOT_FOUND_ERROR: &str = "package not found";
 
 pub(crate) fn run_xbps_query_dependencies(package: &str) -> Result<Vec<DependencyInfo>, String> {
     let mut command = Command::new("xbps-query");
     command.arg("-R");
     configure_query_command(&mut command);
     command.args(["--show", package]);
-    let output = command
-        .output()
+    let output = command_output(&mut command)
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
@@ -90,13 +110,55 @@ pub(crate) fn run_xbps_query_dependencies(package: &str) -> Result<Vec<Dependenc
     Ok(dependencies)
 }
 
+/// Lists every package in the configured repositories, sorted by name, for
+/// browsing without a search term.
+pub(crate) fn run_xbps_query_all_packages() -> Result<Vec<PackageInfo>, String> {
+    let mut command = Command::new("xbps-query");
+    command.arg("-R");
+    configure_query_command(&mut command);
+    command.args(["--regex", "-s", "."]);
+    let output = command_output(&mut command)
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut packages = parse_query_output(&stdout);
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Searches for packages whose name contains every word of `query`, in
+/// any order.
 pub(crate) fn run_xbps_query_search(query: &str) -> Result<Vec<PackageInfo>, String> {
+    search_repository(query, false)
+}
+
+/// Searches for packages whose name or description contains every word of
+/// `query`, in any order.
+pub(crate) fn run_xbps_query_search_descriptions(query: &str) -> Result<Vec<PackageInfo>, String> {
+    search_repository(query, true)
+}
+
+/// xbps-query only matches a single pattern, so the longest term is sent to
+/// it; it matches both names and descriptions. With `match_descriptions`
+/// its results are kept as they are for a single term, and with several
+/// each term must also match the name or description. Otherwise every term
+/// must match the name. Terms may use `*` and `?` wildcards.
+fn search_repository(query: &str, match_descriptions: bool) -> Result<Vec<PackageInfo>, String> {
+    let patterns = search_term_patterns(query);
+    let Some(longest) = patterns.iter().max_by_key(|pattern| pattern.len()) else {
+        return Ok(Vec::new());
+    };
+
     let mut command = Command::new("xbps-query");
     command.arg("-R");
     configure_query_command(&mut command);
-    command.args(["--regex", "-s", query]);
-    let output = command
-        .output()
+    command.args(["--regex", "-s", longest]);
+    let output = command_output(&mut command)
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
@@ -105,13 +167,33 @@ pub(crate) fn run_xbps_query_search(query: &str) -> Result<Vec<PackageInfo>, Str
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(parse_query_output(&stdout))
+    let packages = parse_query_output(&stdout);
+    if match_descriptions && patterns.len() == 1 {
+        return Ok(packages);
+    }
+
+    let matchers = patterns
+        .iter()
+        .filter_map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .ok()
+        })
+        .collect::<Vec<_>>();
+    Ok(packages
+        .into_iter()
+        .filter(|pkg| {
+            matchers.iter().all(|matcher| {
+                matcher.is_match(&pkg.name_lower)
+                    || (match_descriptions && matcher.is_match(&pkg.description_lower))
+            })
+        })
+        .collect())
 }
 
 pub(crate) fn run_xbps_list_installed() -> Result<Vec<PackageInfo>, String> {
-    let output = Command::new("xbps-query")
-        .arg("-l")
-        .output()
+    let output = command_output(Command::new("xbps-query").arg("-l"))
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
@@ -123,19 +205,200 @@ pub(crate) fn run_xbps_list_installed() -> Result<Vec<PackageInfo>, String> {
     Ok(parse_installed_output(&stdout))
 }
 
-pub(crate) fn run_xbps_install(package: &str) -> Result<CommandResult, String> {
-    let mut args = install_repository_args();
+/// `repository`, when given, goes first so xbps takes the package from it
+/// rather than from whichever repository lists it first.
+fn install_args(packages: &[String], repository: Option<&str>) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(repository) = repository {
+        args.push("-R".to_string());
+        args.push(repository.to_string());
+    }
+    args.extend(install_repository_args());
+    args.push("-y".to_string());
+    args.extend(packages.iter().cloned());
+    args
+}
+
+fn remove_args(packages: &[String], options: RemoveOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    if options.recursive {
+        args.push("-R".to_string());
+    }
+    if options.orphans {
+        args.push("-o".to_string());
+    }
     args.push("-y".to_string());
-    args.push(package.to_string());
+    args.extend(packages.iter().cloned());
+    args
+}
+
+/// The `xbps-install` command a user could run in a terminal to install
+/// `packages` the same way Nebula does.
+pub(crate) fn install_command_line(packages: &[String]) -> String {
+    pinned_install_command_line(packages, None)
+}
+
+/// [`install_command_line`] taking the packages from `repository`.
+pub(crate) fn pinned_install_command_line(packages: &[String], repository: Option<&str>) -> String {
+    let args = install_args(packages, repository);
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    format_terminal_command("xbps-install", &arg_refs)
+}
+
+/// The `xbps-remove` counterpart of [`install_command_line`].
+pub(crate) fn remove_command_line(packages: &[String], options: RemoveOptions) -> String {
+    let args = remove_args(packages, options);
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    format_terminal_command("xbps-remove", &arg_refs)
+}
+
+/// Terminal equivalent of [`run_xbps_apply_changes`], removals first.
+pub(crate) fn apply_changes_command_line(
+    installs: &[String],
+    removes: &[String],
+    options: RemoveOptions,
+) -> String {
+    let mut steps = Vec::new();
+    if !removes.is_empty() {
+        steps.push(remove_command_line(removes, options));
+    }
+    if !installs.is_empty() {
+        steps.push(install_command_line(installs));
+    }
+    steps.join(" && ")
+}
+
+pub(crate) fn run_xbps_install(
+    package: &str,
+    repository: Option<&str>,
+) -> Result<CommandResult, String> {
+    let args = install_args(&[package.to_string()], repository);
     let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
     run_privileged_command("xbps-install", &arg_refs)
 }
 
-pub(crate) fn run_xbps_remove(package: &str) -> Result<CommandResult, String> {
-    run_xbps_remove_packages(&[package.to_string()])
+/// Installs several packages in one transaction, handing each output line to
+/// `on_line` as it arrives.
+pub(crate) fn run_xbps_install_packages<F>(
+    packages: &[String],
+    on_line: F,
+) -> Result<CommandResult, String>
+where
+    F: FnMut(String),
+{
+    let args = install_args(packages, None);
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    run_privileged_command_streaming("xbps-install", &arg_refs, on_line)
 }
 
-pub(crate) fn run_xbps_remove_packages(packages: &[String]) -> Result<CommandResult, String> {
+/// Splits `packages` into the names the enabled repositories can provide and
+/// those they cannot, so a batch with a typo or a package missing on this
+/// machine still installs the rest. Returns `(available, unavailable)`.
+pub(crate) fn split_available_packages(packages: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut command = Command::new("xbps-query");
+    command.arg("-R");
+    configure_query_command(&mut command);
+    let found = search_exact_names(command, packages);
+
+    let mut available = Vec::new();
+    let mut unavailable = Vec::new();
+    for package in packages {
+        // Virtual packages never match the search, so the rest are looked
+        // up one by one.
+        if found.contains(package) || repository_provides(package) {
+            available.push(package.clone());
+        } else {
+            unavailable.push(package.clone());
+        }
+    }
+    (available, unavailable)
+}
+
+/// For packages the enabled repositories lack, the other architectures that
+/// do build them, keyed by package name. Names missing everywhere are left
+/// out, as those are most likely typos. Each architecture's repository index
+/// is downloaded into memory, so this only runs once a batch has misses, and
+/// gives up after `OTHER_ARCHITECTURES_TIMEOUT`; the remaining packages then
+/// count as not found.
+pub(crate) fn find_other_architectures(packages: &[String]) -> HashMap<String, Vec<String>> {
+    let native = native_architecture();
+    let mut found: HashMap<String, Vec<String>> = HashMap::new();
+    with_deadline(OTHER_ARCHITECTURES_TIMEOUT, || {
+        for arch in VOID_ARCHITECTURES.iter().filter(|arch| **arch != native) {
+            let mut command = Command::new("xbps-query");
+            command.env("XBPS_ARCH", arch).args(["-M", "-i", "-R"]);
+            for repository in architecture_repositories(arch) {
+                command.args(["--repository", &repository]);
+            }
+            for name in search_exact_names(command, packages) {
+                found.entry(name).or_default().push(arch.to_string());
+            }
+        }
+    });
+    found
+}
+
+/// Names out of `packages` that the repositories `command` queries carry.
+/// One anchored search covers them all; xbps matches it against
+/// "name-version", so the version is allowed but not a longer name.
+fn search_exact_names(mut command: Command, packages: &[String]) -> HashSet<String> {
+    let alternatives = packages
+        .iter()
+        .map(|name| escape_extended_regex(name))
+        .collect::<Vec<_>>()
+        .join("|");
+    let pattern = format!("^({})-[^-]+$", alternatives);
+    command.args(["--regex", "-s", &pattern]);
+    command_output(&mut command)
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            parse_query_output(&String::from_utf8_lossy(&output.stdout))
+                .into_iter()
+                .map(|pkg| pkg.name)
+                .filter(|name| packages.contains(name))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn repository_provides(package: &str) -> bool {
+    let mut command = Command::new("xbps-query");
+    command.arg("-R");
+    configure_query_command(&mut command);
+    command.args(["-p", "pkgver", package]);
+    command_output(&mut command)
+        .map(|output| output.status.success() && !output.stdout.trim_ascii().is_empty())
+        .unwrap_or(false)
+}
+
+/// xbps compiles search patterns as POSIX extended regexes, which have no
+/// escape for `-`, so only the operators are escaped.
+fn escape_extended_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '.' | '[' | ']' | '(' | ')' | '*' | '+' | '?' | '{' | '}' | '|' | '^' | '$' | '\\'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+pub(crate) fn run_xbps_remove(
+    package: &str,
+    options: RemoveOptions,
+) -> Result<CommandResult, String> {
+    run_xbps_remove_packages(&[package.to_string()], options)
+}
+
+pub(crate) fn run_xbps_remove_packages(
+    packages: &[String],
+    options: RemoveOptions,
+) -> Result<CommandResult, String> {
     if packages.is_empty() {
         return Ok(CommandResult {
             code: Some(0),
@@ -144,16 +407,77 @@ pub(crate) fn run_xbps_remove_packages(packages: &[String]) -> Result<CommandRes
         });
     }
 
-    let mut args = vec!["-y"];
-    let package_refs: Vec<&str> = packages.iter().map(|pkg| pkg.as_str()).collect();
-    args.extend(package_refs);
-    run_privileged_command("xbps-remove", &args)
+    let args = remove_args(packages, options);
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    run_privileged_command("xbps-remove", &arg_refs)
+}
+
+/// Applies queued removals and installs behind a single authorization
+/// prompt. Removals run first so packages being replaced are gone before
+/// their successors are installed.
+pub(crate) fn run_xbps_apply_changes(
+    installs: &[String],
+    removes: &[String],
+    options: RemoveOptions,
+) -> Result<CommandResult, String> {
+    let mut steps = Vec::new();
+    if !removes.is_empty() {
+        let mut parts = vec!["xbps-remove".to_string()];
+        parts.extend(
+            remove_args(removes, options)
+                .iter()
+                .map(|arg| shell_quote(arg)),
+        );
+        steps.push(parts.join(" "));
+    }
+    if !installs.is_empty() {
+        let mut parts = vec!["xbps-install".to_string()];
+        parts.extend(
+            install_args(installs, None)
+                .iter()
+                .map(|arg| shell_quote(arg)),
+        );
+        steps.push(parts.join(" "));
+    }
+    if steps.is_empty() {
+        return Ok(CommandResult {
+            code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    let script = steps.join(" && ");
+    run_privileged_command("sh", &["-c", &script])
+}
+
+/// Paths an installed package owns, from `xbps-query -f`. Symlinks are
+/// listed as "link -> target"; only the link itself is kept.
+pub(crate) fn run_xbps_query_files(package: &str) -> Result<Vec<String>, String> {
+    let output = command_output(Command::new("xbps-query").args(["-f", package]))
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut files: Vec<String> = stdout
+        .lines()
+        .map(|line| match line.split_once(" -> ") {
+            Some((link, _)) => link.trim(),
+            None => line.trim(),
+        })
+        .filter(|path| !path.is_empty())
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    Ok(files)
 }
 
 pub(crate) fn run_xbps_query_required_by(package: &str) -> Result<Vec<String>, String> {
-    let output = Command::new("xbps-query")
-        .args(["-X", package])
-        .output()
+    let output = command_output(Command::new("xbps-query").args(["-X", package]))
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
@@ -178,6 +502,56 @@ pub(crate) fn run_xbps_query_required_by(package: &str) -> Result<Vec<String>, S
     Ok(required)
 }
 
+/// Installed packages that would be left with a missing dependency if
+/// `packages` were removed, following reverse dependencies transitively.
+/// Packages already in `packages` are not reported.
+pub(crate) fn query_removal_dependents(packages: &[String]) -> Result<Vec<String>, String> {
+    let targets: HashSet<&str> = packages.iter().map(|pkg| pkg.as_str()).collect();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut level: Vec<String> = packages.to_vec();
+    let mut dependents = Vec::new();
+
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for found in query_required_by_parallel(&level) {
+            for dependent in found? {
+                if targets.contains(dependent.as_str()) || !seen.insert(dependent.clone()) {
+                    continue;
+                }
+                dependents.push(dependent.clone());
+                next.push(dependent);
+            }
+        }
+        level = next;
+    }
+
+    dependents.sort();
+    Ok(dependents)
+}
+
+/// Runs `xbps-query -X` for each package, a few at a time, since every
+/// lookup is a separate process.
+fn query_required_by_parallel(packages: &[String]) -> Vec<Result<Vec<String>, String>> {
+    const CONCURRENT_QUERIES: usize = 8;
+    let mut results = Vec::with_capacity(packages.len());
+    for chunk in packages.chunks(CONCURRENT_QUERIES) {
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|package| scope.spawn(move || run_xbps_query_required_by(package)))
+                .collect();
+            for handle in handles {
+                results.push(
+                    handle
+                        .join()
+                        .unwrap_or_else(|_| Err("xbps-query lookup panicked".to_string())),
+                );
+            }
+        });
+    }
+    results
+}
+
 pub(crate) fn query_pkgsize_bytes(package: &str) -> Result<Option<u64>, String> {
     if let Some(bytes) = query_size_property(package, "installed_size")? {
         return Ok(Some(bytes));
@@ -185,13 +559,30 @@ pub(crate) fn query_pkgsize_bytes(package: &str) -> Result<Option<u64>, String>
     query_size_property(package, "pkgsize")
 }
 
+/// The source package `package` is built from, which names its template in
+/// void-packages. The installed package is checked before the repositories.
+pub(crate) fn query_source_package(package: &str) -> Option<String> {
+    [false, true].into_iter().find_map(|remote| {
+        let mut command = Command::new("xbps-query");
+        if remote {
+            command.arg("-R");
+            configure_query_command(&mut command);
+        }
+        command.args(["-p", "sourcepkg", package]);
+        let output = command_output(&mut command)
+            .ok()
+            .filter(|output| output.status.success())?;
+        let source = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!source.is_empty()).then_some(source)
+    })
+}
+
 fn query_size_property(package: &str, property: &str) -> Result<Option<u64>, String> {
     let mut command = Command::new("xbps-query");
     command.arg("-R");
     configure_query_command(&mut command);
     command.args(["-p", property, package]);
-    let output = command
-        .output()
+    let output = command_output(&mut command)
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
@@ -222,15 +613,17 @@ pub(crate) struct PackageMetadata {
     pub maintainer: Option<String>,
     pub license: Option<String>,
     pub repository: Option<String>,
+    pub install_date: Option<String>,
 }
 
 pub(crate) fn query_package_metadata(package: &str) -> PackageMetadata {
-    const PROPERTIES: [&str; 5] = [
+    const PROPERTIES: [&str; 6] = [
         "long_desc",
         "homepage",
         "maintainer",
         "license",
         "repository",
+        "install-date",
     ];
     let mut metadata = PackageMetadata::default();
 
@@ -278,6 +671,11 @@ fn apply_package_metadata(values: &HashMap<String, String>, metadata: &mut Packa
             metadata.repository = Some(repository);
         }
     }
+    if metadata.install_date.is_none() {
+        if let Some(install_date) = values.get("install-date").and_then(clean_simple_property) {
+            metadata.install_date = Some(install_date);
+        }
+    }
 }
 
 fn clean_simple_property(raw: &String) -> Option<String> {
@@ -308,7 +706,7 @@ fn query_properties_from_show(
     command.arg("--show");
     command.arg(package);
 
-    let output = command.output().ok()?;
+    let output = command_output(&mut command).ok()?;
     if !output.status.success() {
         return None;
     }
@@ -379,18 +777,61 @@ fn normalize_property_text(value: &str) -> String {
     }
 }
 
+/// Every repository that carries `package`, in the order xbps consults them,
+/// so the first entry is what a plain install would use.
+pub(crate) fn query_package_sources(package: &str) -> Result<Vec<PackageSource>, String> {
+    let mut command = Command::new("xbps-query");
+    configure_query_command(&mut command);
+    command.arg("-L");
+    let output = command_output(&mut command)
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let repositories = parse_repository_list(&String::from_utf8_lossy(&output.stdout));
+    let mut sources = Vec::new();
+    for listing in repositories {
+        // -i limits the lookup to this one repository.
+        let Ok(output) = command_output(
+            Command::new("xbps-query")
+                .args(["-i", "-R", "--repository", &listing.url])
+                .args(["-p", "pkgver", package]),
+        ) else {
+            continue;
+        };
+        let pkgver = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || pkgver.is_empty() {
+            continue;
+        }
+        let (_, version) = split_package_identifier(&pkgver);
+        sources.push(PackageSource {
+            repository: listing.url,
+            version,
+            signed: listing.signed,
+        });
+    }
+    Ok(sources)
+}
+
 pub(crate) fn query_repo_package_info(name: &str) -> Result<PackageInfo, String> {
     let mut command = Command::new("xbps-query");
     command.arg("-R");
     configure_query_command(&mut command);
     command.arg(name);
-    let output = command
-        .output()
+    let output = command_output(&mut command)
         .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
 
     if !output.status.success() {
+        // xbps-query exits quietly when no repository has the package.
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(stderr.trim().to_string());
+        let stderr = stderr.trim();
+        return Err(if stderr.is_empty() {
+            PACKAGE_NOT_FOUND_ERROR.to_string()
+        } else {
+            stderr.to_string()
+        });
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -478,10 +919,7 @@ pub(crate) fn query_repo_package_info(name: &str) -> Result<PackageInfo, String>
 }
 
 pub(crate) fn query_installed_package_version(name: &str) -> Option<String> {
-    let output = Command::new("xbps-query")
-        .args(["-p", "pkgver", name])
-        .output()
-        .ok()?;
+    let output = command_output(Command::new("xbps-query").args(["-p", "pkgver", name])).ok()?;
 
     if !output.status.success() {
         return None;
@@ -523,8 +961,177 @@ pub(crate) fn run_xbps_pkgdb_unhold(package: &str) -> Result<CommandResult, Stri
     run_privileged_command("xbps-pkgdb", &["-m", "unhold", package])
 }
 
+pub(crate) fn run_xbps_pkgdb_set_hold(
+    packages: &[String],
+    hold: bool,
+) -> Result<CommandResult, String> {
+    let mode = if hold { "hold" } else { "unhold" };
+    let mut args = vec!["-m", mode];
+    args.extend(packages.iter().map(|pkg| pkg.as_str()));
+    run_privileged_command("xbps-pkgdb", &args)
+}
+
+/// Line echoed by [`run_xbps_pkgdb_set_hold_batches`] after each batch,
+/// followed by how many packages are done.
+const HOLD_PROGRESS_MARKER: &str = "nebula-hold-progress";
+
+/// Holds or unholds `packages` a batch at a time inside one privileged
+/// shell, so there is a single authorization prompt. `progress` gets the
+/// number of packages done after each batch; the shell stops at the first
+/// batch that fails.
+pub(crate) fn run_xbps_pkgdb_set_hold_batches(
+    packages: &[String],
+    hold: bool,
+    batch_size: usize,
+    mut progress: impl FnMut(usize),
+) -> Result<CommandResult, String> {
+    let mode = if hold { "hold" } else { "unhold" };
+    let mut done = 0;
+    let steps: Vec<String> = packages
+        .chunks(batch_size.max(1))
+        .map(|batch| {
+            done += batch.len();
+            let names: Vec<String> = batch.iter().map(|pkg| shell_quote(pkg)).collect();
+            format!(
+                "xbps-pkgdb -m {} {} && echo {} {}",
+                mode,
+                names.join(" "),
+                HOLD_PROGRESS_MARKER,
+                done
+            )
+        })
+        .collect();
+    if steps.is_empty() {
+        return Ok(CommandResult {
+            code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    let script = steps.join(" && ");
+    let mut result = run_privileged_command_streaming("sh", &["-c", &script], |line| {
+        if let Some(count) = line
+            .strip_prefix(HOLD_PROGRESS_MARKER)
+            .and_then(|rest| rest.trim().parse().ok())
+        {
+            progress(count);
+        }
+    })?;
+    result.stdout = result
+        .stdout
+        .lines()
+        .filter(|line| !line.starts_with(HOLD_PROGRESS_MARKER))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(result)
+}
+
+/// Names of the packages currently on hold, as reported by `xbps-query -H`.
+pub(crate) fn run_xbps_query_held() -> Result<HashSet<String>, String> {
+    let output = command_output(Command::new("xbps-query").arg("-H"))
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| split_package_identifier(line).0)
+        .collect())
+}
+
+/// Read-only health check: lists every package whose pkgdb state is not
+/// "installed" (e.g. half-unpacked or half-removed after an interrupted run).
 pub(crate) fn run_xbps_pkgdb_check() -> Result<CommandResult, String> {
-    run_privileged_command("xbps-pkgdb", &["-a"])
+    let output = command_output(Command::new("xbps-query").arg("-l"))
+        .map_err(|err| format!("Failed to launch xbps-query: {}", err))?;
+
+    if !output.status.success() {
+        return Ok(CommandResult {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let broken = parse_inconsistent_packages(&stdout);
+    if broken.is_empty() {
+        return Ok(CommandResult {
+            code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+        });
+    }
+
+    Ok(CommandResult {
+        code: Some(1),
+        stdout: String::new(),
+        stderr: format!(
+            "{} package(s) in an inconsistent state: {}",
+            broken.len(),
+            broken.join(", ")
+        ),
+    })
+}
+
+/// Picks the packages out of `xbps-query -l` lines ("ii foo-1.0_1 ...")
+/// whose two-letter state isn't "ii". Lines without a state are ignored.
+fn parse_inconsistent_packages(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let status = parts.next()?;
+            let identifier = parts.next()?;
+            let is_state = status.len() == 2 && status.chars().all(|c| c.is_ascii_lowercase());
+            if !is_state || status == "ii" {
+                None
+            } else {
+                Some(identifier.to_string())
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn run_xbps_pkgdb_repair<F>(on_line: F) -> Result<CommandResult, String>
+where
+    F: FnMut(String),
+{
+    run_privileged_command_streaming("xbps-pkgdb", &["-a"], on_line)
+}
+
+/// Downloads fresh repository indexes from the active mirrors without
+/// installing or updating anything.
+pub(crate) fn run_xbps_sync_repos<F>(on_line: F) -> Result<CommandResult, String>
+where
+    F: FnMut(String),
+{
+    let mut args = install_repository_args();
+    args.push("-S".to_string());
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    run_privileged_command_streaming("xbps-install", &arg_refs, on_line)
+}
+
+/// Restarts the machine. elogind's `loginctl reboot` lets the active
+/// session reboot without a password; failing that, `reboot` runs as root.
+pub(crate) fn run_system_reboot() -> Result<CommandResult, String> {
+    if let Ok(output) = Command::new("loginctl").arg("reboot").output() {
+        if output.status.success() {
+            return Ok(CommandResult {
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            });
+        }
+    }
+    run_privileged_command("reboot", &[])
 }
 
 pub(crate) fn run_xbps_reconfigure_all() -> Result<CommandResult, String> {
@@ -544,6 +1151,52 @@ pub(crate) fn run_xbps_alternatives_list() -> Result<CommandResult, String> {
     })
 }
 
+pub(crate) fn run_xbps_alternatives_set(
+    group: &str,
+    package: &str,
+) -> Result<CommandResult, String> {
+    run_privileged_command("xbps-alternatives", &["-g", group, "-s", package])
+}
+
+/// Programs every page relies on; without them each action fails with a
+/// spawn error.
+const REQUIRED_XBPS_TOOLS: [&str; 3] = ["xbps-install", "xbps-query", "xbps-remove"];
+
+/// Checks that the core xbps programs can be found on `PATH`, naming the
+/// missing ones otherwise.
+pub(crate) fn ensure_xbps_available() -> Result<(), String> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    let missing: Vec<&str> = REQUIRED_XBPS_TOOLS
+        .iter()
+        .copied()
+        .filter(|tool| !env::split_paths(&path).any(|dir| dir.join(tool).is_file()))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Not found on PATH: {}.", missing.join(", ")))
+    }
+}
+
+/// Returns the first line of `xbps-install --version`, e.g. "XBPS: 0.59.2 ...".
+pub(crate) fn run_xbps_version() -> Result<String, String> {
+    let output = Command::new("xbps-install")
+        .arg("--version")
+        .output()
+        .map_err(|err| format!("Failed to launch xbps-install: {}", err))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout.lines().next().unwrap_or("").trim();
+    if version.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+    if version.starts_with("XBPS") {
+        Ok(version.to_string())
+    } else {
+        Ok(format!("XBPS: {}", version))
+    }
+}
+
 pub(crate) fn run_xbps_check_updates() -> Result<Vec<PackageInfo>, String> {
     let repo_args = install_repository_args();
     let mut command = Command::new("xbps-install");
@@ -553,12 +1206,19 @@ pub(crate) fn run_xbps_check_updates() -> Result<Vec<PackageInfo>, String> {
         command.args(&repo_args);
     }
     command.arg("-Sun");
-    let output = command
-        .output()
+    let output = command_output(&mut command)
         .map_err(|err| format!("Failed to launch xbps-install: {}", err))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
+        // xbps stops the whole check when it has to update itself first;
+        // report just that update so the UI can offer it on its own.
+        if requires_xbps_self_update(&stderr) {
+            let updates = check_xbps_self_update(&repo_args);
+            if !updates.is_empty() {
+                return Ok(updates);
+            }
+        }
         return Err(stderr.trim().to_string());
     }
 
@@ -567,6 +1227,44 @@ pub(crate) fn run_xbps_check_updates() -> Result<Vec<PackageInfo>, String> {
     Ok(parse_updates_output(&cleaned))
 }
 
+/// Totals the pending system update from a dry run against the already
+/// synced repository indexes.
+pub(crate) fn run_xbps_dry_run_updates() -> Result<TransactionSizes, String> {
+    let output = Command::new("xbps-install")
+        .env("NO_COLOR", "1")
+        .args(install_repository_args())
+        .arg("-un")
+        .output()
+        .map_err(|err| format!("Failed to launch xbps-install: {}", err))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr.trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_transaction_sizes(&strip_ansi_codes(&stdout)))
+}
+
+fn check_xbps_self_update(repo_args: &[String]) -> Vec<PackageInfo> {
+    let output = Command::new("xbps-install")
+        .env("NO_COLOR", "1")
+        .env("XBPS_INSTALL_VERBOSE", "2")
+        .args(repo_args)
+        .args(["-un", "xbps"])
+        .output();
+    match output {
+        Ok(output) => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            parse_updates_output(&strip_ansi_codes(&stdout))
+                .into_iter()
+                .filter(|pkg| pkg.name == "xbps")
+                .collect()
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
 fn parse_updates_output(text: &str) -> Vec<PackageInfo> {
     let mut updates = Vec::new();
 
@@ -809,3 +1507,32 @@ pub(crate) fn truncate_for_summary(text: &str, max_chars: usize) -> String {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_packages_that_are_not_fully_installed() {
+        let output = "\
+ii bash-5.2.32_1                   GNU Bourne Again Shell
+uu mesa-24.1.5_1                   Graphics library similar to SGI's OpenGL
+hr zstd-1.5.6_1                    Fast real-time compression algorithm
+ii
+warning: pkgdb is locked
+uu
+";
+        assert_eq!(
+            parse_inconsistent_packages(output),
+            vec!["mesa-24.1.5_1".to_string(), "zstd-1.5.6_1".to_string()]
+        );
+    }
+
+    #[test]
+    fn clean_package_lists_have_no_inconsistent_packages() {
+        assert!(
+            parse_inconsistent_packages("ii bash-5.2.32_1 GNU Bourne Again Shell\n").is_empty()
+        );
+        assert!(parse_inconsistent_packages("").is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+use std::fs;
+
+use similar::{DiffTag, TextDiff};
+
+use crate::types::CommandResult;
+
+use super::parser::ConfigConflict;
+use super::privilege::run_privileged_command;
+
+/// How a configuration file conflict is settled.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ConfigResolution {
+    /// Keep the local file and delete the packaged `.new` copy.
+    KeepCurrent,
+    /// Replace the local file with the packaged copy.
+    UseNew,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DiffRowKind {
+    Same,
+    Changed,
+    Removed,
+    Added,
+}
+
+/// One line of a side-by-side diff. A side is `None` where the other file
+/// has lines this one lacks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct DiffRow {
+    pub kind: DiffRowKind,
+    pub current: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Reads the local and packaged versions of a conflicting file.
+pub(crate) fn read_config_conflict(conflict: &ConfigConflict) -> Result<(String, String), String> {
+    let read = |path: &std::path::Path| {
+        fs::read(path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))
+    };
+    Ok((read(&conflict.original)?, read(&conflict.new_file)?))
+}
+
+/// Lines up `current` and `new` for a two-pane view, pairing replaced lines
+/// so edits appear side by side.
+pub(crate) fn side_by_side_diff(current: &str, new: &str) -> Vec<DiffRow> {
+    let diff = TextDiff::from_lines(current, new);
+    let old_lines = diff.old_slices();
+    let new_lines = diff.new_slices();
+    let line = |text: &str| text.trim_end_matches(['\n', '\r']).to_string();
+
+    let mut rows = Vec::new();
+    for op in diff.ops() {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        let kind = match tag {
+            DiffTag::Equal => DiffRowKind::Same,
+            DiffTag::Delete => DiffRowKind::Removed,
+            DiffTag::Insert => DiffRowKind::Added,
+            DiffTag::Replace => DiffRowKind::Changed,
+        };
+        let count = old_range.len().max(new_range.len());
+        for offset in 0..count {
+            let current =
+                (offset < old_range.len()).then(|| line(old_lines[old_range.start + offset]));
+            let new = (offset < new_range.len()).then(|| line(new_lines[new_range.start + offset]));
+            // A replacement of unequal length leaves some lines unpaired.
+            let kind = match (kind, &current, &new) {
+                (DiffRowKind::Changed, None, _) => DiffRowKind::Added,
+                (DiffRowKind::Changed, _, None) => DiffRowKind::Removed,
+                _ => kind,
+            };
+            rows.push(DiffRow { kind, current, new });
+        }
+    }
+    rows
+}
+
+/// Settles a conflict as root, since both files usually live under /etc.
+pub(crate) fn resolve_config_conflict(
+    conflict: &ConfigConflict,
+    resolution: ConfigResolution,
+) -> Result<CommandResult, String> {
+    let original = conflict.original.to_string_lossy();
+    let new_file = conflict.new_file.to_string_lossy();
+    match resolution {
+        ConfigResolution::KeepCurrent => run_privileged_command("rm", &["-f", "--", &new_file]),
+        ConfigResolution::UseNew => {
+            run_privileged_command("mv", &["-f", "--", &new_file, &original])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_changed_lines_side_by_side() {
+        let rows = side_by_side_diff("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].kind, DiffRowKind::Same);
+        assert_eq!(rows[1].kind, DiffRowKind::Changed);
+        assert_eq!(rows[1].current.as_deref(), Some("b"));
+        assert_eq!(rows[1].new.as_deref(), Some("B"));
+        assert_eq!(rows[3].kind, DiffRowKind::Added);
+        assert_eq!(rows[3].current, None);
+        assert_eq!(rows[3].new.as_deref(), Some("d"));
+    }
+
+    #[test]
+    fn identical_files_have_no_changes() {
+        let rows = side_by_side_diff("x = 1\n", "x = 1\n");
+        assert!(rows.iter().all(|row| row.kind == DiffRowKind::Same));
+    }
+}
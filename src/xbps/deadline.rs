@@ -0,0 +1,81 @@
+use std::cell::Cell;
+use std::io::{self, Read};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a bounded command is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Runs `work` with every query it starts on this thread bounded by
+/// `timeout`. A query still running at the deadline is killed and fails
+/// with a timeout error, as does any query started after it.
+pub(crate) fn with_deadline<T>(timeout: Duration, work: impl FnOnce() -> T) -> T {
+    let previous = DEADLINE.with(|deadline| deadline.replace(Some(Instant::now() + timeout)));
+    let result = work();
+    DEADLINE.with(|deadline| deadline.set(previous));
+    result
+}
+
+/// `Command::output`, except that under [`with_deadline`] the child is
+/// killed once the deadline passes.
+pub(crate) fn command_output(command: &mut Command) -> io::Result<Output> {
+    let Some(deadline) = DEADLINE.with(Cell::get) else {
+        return command.output();
+    };
+    if Instant::now() >= deadline {
+        return Err(timed_out());
+    }
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Drain both pipes while waiting so a chatty child can't block on a
+    // full pipe.
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Output {
+                status,
+                stdout: join_drain(stdout),
+                stderr: join_drain(stderr),
+            });
+        }
+        if Instant::now() >= deadline {
+            kill(&mut child);
+            return Err(timed_out());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = pipe.read_to_end(&mut buffer);
+        buffer
+    })
+}
+
+fn join_drain(handle: Option<thread::JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default()
+}
+
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn timed_out() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "timed out")
+}
@@ -0,0 +1,75 @@
+use std::process::Command;
+
+/// Filesystems a transaction writes to: packages unpack into the root
+/// filesystem and their archives are downloaded into the xbps cache, which
+/// may live on a separate partition.
+const TRANSACTION_PATHS: [&str; 2] = ["/", "/var/cache/xbps"];
+
+/// Free space on the tightest filesystem a transaction touches.
+#[derive(Clone, Debug)]
+pub(crate) struct FreeSpace {
+    pub mount_point: String,
+    pub available_bytes: u64,
+}
+
+pub(crate) fn transaction_free_space() -> Result<FreeSpace, String> {
+    let output = Command::new("df")
+        .arg("-Pk")
+        .args(TRANSACTION_PATHS)
+        .output()
+        .map_err(|err| format!("Failed to launch df: {}", err))?;
+
+    // df still prints the paths it could stat when another is missing.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_df_output(&stdout)
+        .into_iter()
+        .min_by_key(|space| space.available_bytes)
+        .ok_or_else(|| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            stderr.trim().to_string()
+        })
+}
+
+/// Parses POSIX `df -Pk` output, one entry per filesystem row.
+fn parse_df_output(output: &str) -> Vec<FreeSpace> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let available_kib = fields[3].parse::<u64>().ok()?;
+            Some(FreeSpace {
+                mount_point: fields[5..].join(" "),
+                available_bytes: available_kib.saturating_mul(1024),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_available_space_per_filesystem() {
+        let output = "\
+Filesystem     1024-blocks      Used Available Capacity Mounted on
+/dev/nvme0n1p2    98299524  61234112  32015148      66% /
+/dev/sdb1        480589544 120000000 336165000      27% /var/cache/xbps
+";
+        let spaces = parse_df_output(output);
+        assert_eq!(spaces.len(), 2);
+        assert_eq!(spaces[0].mount_point, "/");
+        assert_eq!(spaces[0].available_bytes, 32015148 * 1024);
+        assert_eq!(spaces[1].mount_point, "/var/cache/xbps");
+    }
+
+    #[test]
+    fn skips_malformed_rows() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\nbogus line\n";
+        assert!(parse_df_output(output).is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Where xbps keeps the package database and one `.<pkgname>-files.plist`
+/// per installed package.
+const PKGDB_DIR: &str = "/var/db/xbps";
+/// Files hashed per `sha256sum` call, to stay well below the argument limit.
+const HASH_BATCH_SIZE: usize = 256;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum IntegrityIssueKind {
+    Modified,
+    Missing,
+}
+
+impl IntegrityIssueKind {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            IntegrityIssueKind::Modified => "Modified",
+            IntegrityIssueKind::Missing => "Missing",
+        }
+    }
+}
+
+/// One problem found with an installed package's files.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct IntegrityIssue {
+    pub package: String,
+    pub kind: IntegrityIssueKind,
+    pub detail: String,
+}
+
+/// Checks every installed package's files against the hashes recorded in
+/// its files list. Those lists are world-readable, so unlike `xbps-pkgdb -a`
+/// this runs unprivileged and never writes to the package database. Files
+/// the user can't read are skipped.
+pub(crate) fn run_xbps_verify_integrity() -> Result<Vec<IntegrityIssue>, String> {
+    let entries =
+        fs::read_dir(PKGDB_DIR).map_err(|err| format!("Failed to read {}: {}", PKGDB_DIR, err))?;
+    let mut lists: Vec<(String, PathBuf)> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let package = name.strip_prefix('.')?.strip_suffix("-files.plist")?;
+            Some((package.to_string(), entry.path()))
+        })
+        .collect();
+    lists.sort();
+
+    let mut issues = Vec::new();
+    for (package, path) in lists {
+        let Ok(plist) = fs::read_to_string(&path) else {
+            continue;
+        };
+        issues.extend(check_file_hashes(&package, &parse_file_hashes(&plist))?);
+    }
+    Ok(issues)
+}
+
+fn check_file_hashes(
+    package: &str,
+    files: &[(String, String)],
+) -> Result<Vec<IntegrityIssue>, String> {
+    let mut issues = Vec::new();
+    let mut present = Vec::new();
+    for (path, hash) in files {
+        match fs::symlink_metadata(path) {
+            Ok(_) => present.push((path.as_str(), hash.as_str())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => issues.push(IntegrityIssue {
+                package: package.to_string(),
+                kind: IntegrityIssueKind::Missing,
+                detail: format!("missing file {}", path),
+            }),
+            Err(_) => {}
+        }
+    }
+
+    for batch in present.chunks(HASH_BATCH_SIZE) {
+        let output = Command::new("sha256sum")
+            .arg("--")
+            .args(batch.iter().map(|(path, _)| *path))
+            .output()
+            .map_err(|err| format!("Failed to launch sha256sum: {}", err))?;
+        // Unreadable files only show up on stderr, so they are skipped here.
+        let hashes = parse_sha256sum_output(&String::from_utf8_lossy(&output.stdout));
+        for (path, expected) in batch {
+            let Some(actual) = hashes.get(*path) else {
+                continue;
+            };
+            if !actual.eq_ignore_ascii_case(expected) {
+                issues.push(IntegrityIssue {
+                    package: package.to_string(),
+                    kind: IntegrityIssueKind::Modified,
+                    detail: format!("hash mismatch for {}", path),
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Pulls `(path, sha256)` pairs out of the `files` array of a package's
+/// files list. Configuration files are left out, since they are expected to
+/// be edited.
+fn parse_file_hashes(plist: &str) -> Vec<(String, String)> {
+    let Some(start) = plist.find("<key>files</key>") else {
+        return Vec::new();
+    };
+    let section = &plist[start..];
+    let section = section
+        .find("</array>")
+        .map_or(section, |end| &section[..end]);
+    section
+        .split("<dict>")
+        .skip(1)
+        .filter_map(|entry| {
+            let path = plist_string(entry, "file")?;
+            let hash = plist_string(entry, "sha256")?;
+            Some((path, hash))
+        })
+        .collect()
+}
+
+fn plist_string(entry: &str, key: &str) -> Option<String> {
+    let marker = format!("<key>{}</key>", key);
+    let rest = entry[entry.find(&marker)? + marker.len()..].trim_start();
+    let value = rest.strip_prefix("<string>")?;
+    let value = &value[..value.find("</string>")?];
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// Parses `sha256sum` lines of the form "<hash>  <path>". Escaped lines
+/// (paths with a backslash or newline) start with `\` and are skipped.
+fn parse_sha256sum_output(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('\\'))
+        .filter_map(|line| {
+            let (hash, path) = line.split_once("  ")?;
+            Some((path.to_string(), hash.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_file_hashes_but_not_conf_files() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>conf_files</key>
+	<array>
+		<dict>
+			<key>file</key>
+			<string>/etc/bash/bashrc</string>
+			<key>sha256</key>
+			<string>aaaa</string>
+		</dict>
+	</array>
+	<key>files</key>
+	<array>
+		<dict>
+			<key>file</key>
+			<string>/usr/bin/bash</string>
+			<key>sha256</key>
+			<string>bbbb</string>
+			<key>size</key>
+			<integer>1024</integer>
+		</dict>
+		<dict>
+			<key>file</key>
+			<string>/usr/share/doc/R&amp;D.txt</string>
+			<key>sha256</key>
+			<string>cccc</string>
+		</dict>
+	</array>
+	<key>links</key>
+	<array>
+		<dict>
+			<key>file</key>
+			<string>/usr/bin/sh</string>
+			<key>target</key>
+			<string>bash</string>
+		</dict>
+	</array>
+</dict>
+</plist>"#;
+        assert_eq!(
+            parse_file_hashes(plist),
+            vec![
+                ("/usr/bin/bash".to_string(), "bbbb".to_string()),
+                ("/usr/share/doc/R&D.txt".to_string(), "cccc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_without_files_have_no_hashes() {
+        assert!(parse_file_hashes("<plist><dict></dict></plist>").is_empty());
+    }
+
+    #[test]
+    fn skips_escaped_sha256sum_lines() {
+        let output = "abcd  /usr/bin/bash\n\\ef01  /tmp/odd\\nname\n";
+        assert_eq!(
+            parse_sha256sum_output(output),
+            HashMap::from([("/usr/bin/bash".to_string(), "abcd".to_string())])
+        );
+    }
+}
@@ -1,16 +1,44 @@
 mod cache_cleanup;
 mod commands;
+mod config_files;
+mod deadline;
+mod disk_space;
+mod integrity;
 mod parser;
 mod privilege;
 
-pub(crate) use cache_cleanup::clean_cache_keep_n;
+pub(crate) use cache_cleanup::{
+    CacheCleanupPlan, cache_size_bytes, execute_cache_cleanup, plan_cache_cleanup,
+};
 pub(crate) use commands::{
-    format_download_size, format_size, query_package_metadata, query_pkgsize_bytes,
-    query_repo_package_info, run_xbps_alternatives_list, run_xbps_check_updates, run_xbps_install,
-    run_xbps_list_installed, run_xbps_pkgdb_check, run_xbps_pkgdb_hold, run_xbps_pkgdb_unhold,
-    run_xbps_query_dependencies, run_xbps_query_required_by, run_xbps_query_search,
-    run_xbps_reconfigure_all, run_xbps_remove, run_xbps_remove_cache, run_xbps_remove_orphans,
-    run_xbps_remove_packages, summarize_output_line,
+    PACKAGE_NOT_FOUND_ERROR, apply_changes_command_line, ensure_xbps_available,
+    find_other_architectures, format_download_size, format_size, install_command_line,
+    pinned_install_command_line, query_package_metadata, query_package_sources,
+    query_pkgsize_bytes, query_removal_dependents, query_repo_package_info, query_source_package,
+    remove_command_line, run_system_reboot, run_xbps_alternatives_list, run_xbps_alternatives_set,
+    run_xbps_apply_changes, run_xbps_check_updates, run_xbps_dry_run_updates, run_xbps_install,
+    run_xbps_install_packages, run_xbps_list_installed, run_xbps_pkgdb_check, run_xbps_pkgdb_hold,
+    run_xbps_pkgdb_repair, run_xbps_pkgdb_set_hold, run_xbps_pkgdb_set_hold_batches,
+    run_xbps_pkgdb_unhold, run_xbps_query_all_packages, run_xbps_query_dependencies,
+    run_xbps_query_files, run_xbps_query_held, run_xbps_query_required_by, run_xbps_query_search,
+    run_xbps_query_search_descriptions, run_xbps_reconfigure_all, run_xbps_remove,
+    run_xbps_remove_cache, run_xbps_remove_orphans, run_xbps_remove_packages, run_xbps_sync_repos,
+    run_xbps_version, split_available_packages, summarize_output_line,
+};
+pub(crate) use config_files::{
+    ConfigResolution, DiffRow, DiffRowKind, read_config_conflict, resolve_config_conflict,
+    side_by_side_diff,
+};
+pub(crate) use deadline::with_deadline;
+pub(crate) use disk_space::{FreeSpace, transaction_free_space};
+pub(crate) use integrity::{IntegrityIssue, IntegrityIssueKind, run_xbps_verify_integrity};
+pub(crate) use parser::{
+    ConfigConflict, HeldBlocker, TransactionSizes, parse_alternatives_list, parse_config_conflicts,
+    parse_held_blockers, parse_installed_package_names, parse_removed_packages,
+    requires_xbps_self_update, search_term_patterns, split_package_identifier, subpackage_parent,
+};
+pub(crate) use privilege::{
+    PrivilegeFailure, classify_privilege_failure, find_polkit_agent, format_privileged_command,
+    format_terminal_command, privilege_failure_reported, privilege_is_noninteractive,
+    run_privileged_command, shell_quote, start_polkit_agent,
 };
-pub(crate) use parser::split_package_identifier;
-pub(crate) use privilege::run_privileged_command;
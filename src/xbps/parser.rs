@@ -1,5 +1,6 @@
 use crate::types::{PackageInfo, lowercase_cache};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 
 pub(crate) fn parse_bytes_from_field(text: &str) -> Option<u64> {
     let trimmed = text.trim().trim_end_matches(|c| c == ',' || c == '.');
@@ -196,6 +197,32 @@ pub(crate) fn parse_installed_output(output: &str) -> Vec<PackageInfo> {
         .collect()
 }
 
+/// Suffixes of the subpackages split out of a main package, such as
+/// headers, documentation, debug symbols and multilib builds.
+const SUBPACKAGE_SUFFIXES: &[&str] = &["-32bit", "-devel", "-doc", "-dbg"];
+
+/// Returns the package a subpackage like `libfoo-devel` or
+/// `libfoo-devel-32bit` was split from, or `None` for a main package.
+pub(crate) fn subpackage_parent(name: &str) -> Option<&str> {
+    let mut base = name;
+    while let Some(parent) = SUBPACKAGE_SUFFIXES
+        .iter()
+        .find_map(|suffix| base.strip_suffix(suffix))
+        .filter(|parent| !parent.is_empty())
+    {
+        base = parent;
+    }
+    (base != name).then_some(base)
+}
+
+/// True when xbps refused a transaction because the `xbps` package itself
+/// has to be updated first.
+pub(crate) fn requires_xbps_self_update(output: &str) -> bool {
+    output
+        .to_ascii_lowercase()
+        .contains("'xbps' package must be updated")
+}
+
 pub(crate) fn split_package_identifier(identifier: &str) -> (String, String) {
     if let Some(pos) = identifier.rfind('-') {
         let (name, version_part) = identifier.split_at(pos);
@@ -230,3 +257,515 @@ pub(crate) fn strip_ansi_codes(text: &str) -> String {
     }
     result
 }
+
+/// A configuration file xbps left beside the one in use because the local
+/// copy had been modified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ConfigConflict {
+    pub original: PathBuf,
+    pub new_file: PathBuf,
+}
+
+/// Collects the `.new-<version>` files reported by lines such as
+/// "Installing new configuration file to `/etc/foo.conf.new-1.2_1'".
+pub(crate) fn parse_config_conflicts<'a, I>(lines: I) -> Vec<ConfigConflict>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    const MARKER: &str = "Installing new configuration file to";
+
+    let mut conflicts: Vec<ConfigConflict> = Vec::new();
+    for line in lines {
+        let Some((_, rest)) = line.split_once(MARKER) else {
+            continue;
+        };
+        let path = rest
+            .trim()
+            .trim_end_matches('.')
+            .trim_matches(|c| c == '`' || c == '\'' || c == '"');
+        let Some(index) = path.rfind(".new-") else {
+            continue;
+        };
+        let conflict = ConfigConflict {
+            original: PathBuf::from(&path[..index]),
+            new_file: PathBuf::from(path),
+        };
+        if !conflicts.contains(&conflict) {
+            conflicts.push(conflict);
+        }
+    }
+    conflicts
+}
+
+/// Collects the package names xbps reported as "installed successfully",
+/// which includes any dependencies pulled into the transaction.
+pub(crate) fn parse_installed_package_names<'a, I>(lines: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    const MARKER: &str = ": installed successfully";
+
+    let mut names: Vec<String> = Vec::new();
+    for line in lines {
+        let Some((identifier, _)) = line.trim().split_once(MARKER) else {
+            continue;
+        };
+        let (name, _) = split_package_identifier(identifier.trim());
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Turns a search box query into one regex per whitespace separated term. `*` and `?` act as shell-style wildcards and every other
+/// character is matched literally. Terms made only of wildcards match
+/// everything, so they are dropped.
+pub(crate) fn search_term_patterns(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .filter(|term| term.chars().any(|c| c != '*' && c != '?'))
+        .map(|term| {
+            let mut pattern = String::new();
+            for c in term.chars() {
+                match c {
+                    '*' => pattern.push_str(".*"),
+                    '?' => pattern.push('.'),
+                    '.' | '[' | ']' | '(' | ')' | '{' | '}' | '+' | '|' | '^' | '$' | '\\' => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                    _ => pattern.push(c),
+                }
+            }
+            pattern
+        })
+        .collect()
+}
+
+/// One alternatives group from `xbps-alternatives -l`, such as `cc`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AlternativesGroup {
+    pub name: String,
+    pub providers: Vec<AlternativeProvider>,
+}
+
+/// A package able to supply an alternatives group. `pkgver` is the full
+/// identifier xbps printed; `package` is the name `-s` expects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AlternativeProvider {
+    pub package: String,
+    pub pkgver: String,
+    pub current: bool,
+}
+
+/// Parses `xbps-alternatives -l`, which lists each group at column zero,
+/// its providers as " - pkgver" (the active one marked "(current)") and the
+/// provider's symlinks one level deeper.
+pub(crate) fn parse_alternatives_list(output: &str) -> Vec<AlternativesGroup> {
+    let mut groups: Vec<AlternativesGroup> = Vec::new();
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            groups.push(AlternativesGroup {
+                name: line.trim().to_string(),
+                providers: Vec::new(),
+            });
+            continue;
+        }
+        let Some(entry) = line.strip_prefix(" - ") else {
+            continue;
+        };
+        let Some(group) = groups.last_mut() else {
+            continue;
+        };
+        let (pkgver, current) = match entry.trim().strip_suffix("(current)") {
+            Some(rest) => (rest.trim(), true),
+            None => (entry.trim(), false),
+        };
+        let (package, _) = split_package_identifier(pkgver);
+        group.providers.push(AlternativeProvider {
+            package: if package.is_empty() {
+                pkgver.to_string()
+            } else {
+                package
+            },
+            pkgver: pkgver.to_string(),
+            current,
+        });
+    }
+    groups
+}
+
+/// Byte totals of an xbps transaction: what has to be fetched and what the
+/// new packages occupy once unpacked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct TransactionSizes {
+    pub download_bytes: u64,
+    pub installed_bytes: u64,
+}
+
+/// Sums a dry-run transaction. `xbps-install -n` prints one
+/// "pkgver action arch repository installed_size download_size" row per
+/// package; the interactive "Size to download" and "Size required on disk"
+/// summary lines take precedence when present.
+pub(crate) fn parse_transaction_sizes(output: &str) -> TransactionSizes {
+    let mut rows = TransactionSizes::default();
+    let mut summary_download = None;
+    let mut summary_installed = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some((label, value)) = trimmed.split_once(':') {
+            let label = label.trim();
+            if label.eq_ignore_ascii_case("Size to download") {
+                summary_download = parse_bytes_from_field(value);
+                continue;
+            }
+            if label.eq_ignore_ascii_case("Size required on disk")
+                || label.eq_ignore_ascii_case("Space needed")
+            {
+                summary_installed = parse_bytes_from_field(value);
+                continue;
+            }
+        }
+
+        let fields: Vec<&str> = trimmed.split_whitespace().collect();
+        if fields.len() < 6 {
+            continue;
+        }
+        if !matches!(fields[1], "install" | "update" | "reinstall" | "downgrade") {
+            continue;
+        }
+        let (Ok(installed), Ok(download)) = (fields[4].parse::<u64>(), fields[5].parse::<u64>())
+        else {
+            continue;
+        };
+        rows.installed_bytes += installed;
+        rows.download_bytes += download;
+    }
+
+    TransactionSizes {
+        download_bytes: summary_download.unwrap_or(rows.download_bytes),
+        installed_bytes: summary_installed.unwrap_or(rows.installed_bytes),
+    }
+}
+
+/// A synced repository as listed by `xbps-query -L`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RepositoryListing {
+    pub url: String,
+    /// The repository index carries an RSA signature xbps verifies.
+    pub signed: bool,
+}
+
+/// Repositories from `xbps-query -L`, in the order xbps consults them.
+/// Each row starts with the repository's package count, which is -1 for a
+/// repository that has not been synced, and ends with its signing state.
+pub(crate) fn parse_repository_list(output: &str) -> Vec<RepositoryListing> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let count = fields.next()?.parse::<i64>().ok()?;
+            let url = fields.next()?;
+            (count >= 0).then(|| RepositoryListing {
+                url: url.to_string(),
+                signed: line.trim_end().ends_with("(RSA signed)"),
+            })
+        })
+        .collect()
+}
+
+/// A held package that stopped an update, with the packages that needed it
+/// updated when xbps names them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct HeldBlocker {
+    pub package: String,
+    pub required_by: Vec<String>,
+}
+
+/// Finds the held packages behind a refused transaction. xbps does not
+/// update a held package to satisfy another, so it either reports the
+/// package as on hold or lists it among the unresolved dependencies, as in
+/// "MISSING: libfoo>=2.0_1 (required by bar-1.1_1)".
+pub(crate) fn parse_held_blockers(output: &str, held: &HashSet<String>) -> Vec<HeldBlocker> {
+    let mut blockers: Vec<HeldBlocker> = Vec::new();
+    for line in output.lines() {
+        let line = strip_ansi_codes(line);
+        let lower = line.to_ascii_lowercase();
+        let (subject, required_by) = match line.split_once("(required by ") {
+            Some((subject, rest)) => (
+                subject,
+                rest.trim_end_matches(')')
+                    .split(',')
+                    .map(|entry| held_candidate_name(entry.trim(), held))
+                    .filter(|name| !name.is_empty())
+                    .collect(),
+            ),
+            None => (line.as_str(), Vec::new()),
+        };
+        let mentions_dependency = subject.contains(['<', '>', '=']);
+        if !lower.contains("hold") && !lower.contains("held") && !mentions_dependency {
+            continue;
+        }
+
+        let tokens = subject.split(|ch: char| ch.is_whitespace() || "'`\",:".contains(ch));
+        for token in tokens {
+            let name = held_candidate_name(token, held);
+            if !held.contains(&name) {
+                continue;
+            }
+            match blockers.iter_mut().find(|blocker| blocker.package == name) {
+                Some(blocker) => {
+                    for package in &required_by {
+                        if !blocker.required_by.contains(package) {
+                            blocker.required_by.push(package.clone());
+                        }
+                    }
+                }
+                None => blockers.push(HeldBlocker {
+                    package: name,
+                    required_by: required_by.clone(),
+                }),
+            }
+        }
+    }
+    blockers
+}
+
+/// Package name in a dependency pattern (`libfoo>=2.0`) or a package
+/// version (`libfoo-1.9_1`). A bare name is kept when it is in `held`, since
+/// names such as `gtk+3` contain no version to strip.
+fn held_candidate_name(token: &str, held: &HashSet<String>) -> String {
+    if let Some(pos) = token.find(['<', '>', '=']) {
+        return token[..pos].to_string();
+    }
+    if held.contains(token) {
+        return token.to_string();
+    }
+    let (name, version) = split_package_identifier(token);
+    if version.contains('_') {
+        name
+    } else {
+        token.to_string()
+    }
+}
+
+/// Names of the packages `xbps-remove` reports as removed, from lines such
+/// as "foo-1.0_1: removed successfully." (or "Removed `foo-1.0_1'
+/// successfully." from older xbps). With `-R` these include the
+/// dependencies removed along with the requested packages.
+pub(crate) fn parse_removed_packages(output: &str) -> Vec<String> {
+    let mut removed = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        let pkgver = line.strip_suffix(": removed successfully.").or_else(|| {
+            line.strip_prefix("Removed `")?
+                .strip_suffix("' successfully.")
+        });
+        let Some(pkgver) = pkgver else {
+            continue;
+        };
+        let (name, _) = split_package_identifier(pkgver);
+        if !name.is_empty() && !removed.contains(&name) {
+            removed.push(name);
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_new_configuration_file_lines() {
+        let lines = [
+            "openssh-9.6p1_1: updating to 9.7p1_1 ...",
+            "openssh-9.7p1_1: Installing new configuration file to `/etc/ssh/sshd_config.new-9.7p1_1'",
+            "openssh-9.7p1_1: Installing new configuration file to `/etc/ssh/sshd_config.new-9.7p1_1'",
+            "Keeping modified configuration file `/etc/ssh/ssh_config'.",
+        ];
+        let conflicts = parse_config_conflicts(lines);
+        assert_eq!(
+            conflicts,
+            vec![ConfigConflict {
+                original: PathBuf::from("/etc/ssh/sshd_config"),
+                new_file: PathBuf::from("/etc/ssh/sshd_config.new-9.7p1_1"),
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_lines_without_new_suffix() {
+        let lines = ["Installing new configuration file to `/etc/foo.conf'"];
+        assert!(parse_config_conflicts(lines).is_empty());
+    }
+
+    #[test]
+    fn collects_installed_package_names() {
+        let lines = [
+            "[*] Configuring unpacked packages",
+            "libfoo-1.2_1: configuring ...",
+            "libfoo-1.2_1: installed successfully.",
+            "foo-bar-3.0_2: installed successfully.",
+            "foo-bar-3.0_2: installed successfully.",
+        ];
+        assert_eq!(
+            parse_installed_package_names(lines),
+            vec!["libfoo".to_string(), "foo-bar".to_string()]
+        );
+    }
+
+    #[test]
+    fn converts_search_wildcards_to_patterns() {
+        assert_eq!(
+            search_term_patterns("  python3-* Qt5  "),
+            vec!["python3-.*".to_string(), "Qt5".to_string()]
+        );
+        assert_eq!(
+            search_term_patterns("c++ lib?.so"),
+            vec!["c\\+\\+".to_string(), "lib.\\.so".to_string()]
+        );
+        assert!(search_term_patterns(" * ** ").is_empty());
+    }
+
+    #[test]
+    fn parses_alternatives_groups_and_providers() {
+        let output = "\
+cc
+ - gcc-13.2.0_1 (current)
+  - /usr/bin/cc:/usr/bin/gcc
+ - clang17-17.0.6_1
+  - /usr/bin/cc:/usr/bin/clang
+vi
+ - nvi-1.81.6_24 (current)
+  - /usr/bin/vi:/usr/bin/nvi
+";
+        let groups = parse_alternatives_list(output);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "cc");
+        assert_eq!(groups[0].providers.len(), 2);
+        assert_eq!(groups[0].providers[0].package, "gcc");
+        assert!(groups[0].providers[0].current);
+        assert_eq!(groups[0].providers[1].package, "clang17");
+        assert_eq!(groups[0].providers[1].pkgver, "clang17-17.0.6_1");
+        assert!(!groups[0].providers[1].current);
+        assert_eq!(groups[1].providers[0].package, "nvi");
+    }
+
+    #[test]
+    fn resolves_subpackage_parents() {
+        assert_eq!(subpackage_parent("libfoo-devel"), Some("libfoo"));
+        assert_eq!(subpackage_parent("libfoo-32bit"), Some("libfoo"));
+        assert_eq!(subpackage_parent("libfoo-devel-32bit"), Some("libfoo"));
+        assert_eq!(subpackage_parent("libfoo"), None);
+        assert_eq!(subpackage_parent("-devel"), None);
+    }
+
+    #[test]
+    fn detects_xbps_self_update_requirement() {
+        let stderr = "The 'xbps' package must be updated, please run `xbps-install -u xbps`\n";
+        assert!(requires_xbps_self_update(stderr));
+        assert!(!requires_xbps_self_update(
+            "Transaction aborted due to unresolved shlibs."
+        ));
+    }
+
+    #[test]
+    fn sums_dry_run_transaction_rows() {
+        let output = "\
+linux6.6-6.6.30_1 update x86_64 https://repo-default.voidlinux.org/current 152043520 120586240
+libfoo-1.2_1 install x86_64 https://repo-default.voidlinux.org/current 2048 1024
+xbps-0.59.2_1 configure x86_64 https://repo-default.voidlinux.org/current 4096 0
+";
+        let sizes = parse_transaction_sizes(output);
+        assert_eq!(sizes.installed_bytes, 152043520 + 2048);
+        assert_eq!(sizes.download_bytes, 120586240 + 1024);
+    }
+
+    #[test]
+    fn prefers_transaction_summary_lines() {
+        let output = "\
+foo-1.0_1 update x86_64 /repo 100 50
+Size to download:             12 MB
+Size required on disk:        40 MB
+";
+        let sizes = parse_transaction_sizes(output);
+        assert_eq!(sizes.download_bytes, 12 * 1024 * 1024);
+        assert_eq!(sizes.installed_bytes, 40 * 1024 * 1024);
+    }
+
+    #[test]
+    fn lists_synced_repositories_in_order() {
+        let output = "\
+ 14000 https://repo-default.voidlinux.org/current (RSA signed)
+  1200 https://repo-default.voidlinux.org/current/nonfree (RSA signed)
+    -1 https://example.org/unsynced (RSA maybe-signed)
+    32 /home/user/void-packages/hostdir/binpkgs (RSA unsigned)
+";
+        let repositories = parse_repository_list(output);
+        let urls: Vec<&str> = repositories.iter().map(|repo| repo.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://repo-default.voidlinux.org/current",
+                "https://repo-default.voidlinux.org/current/nonfree",
+                "/home/user/void-packages/hostdir/binpkgs",
+            ]
+        );
+        assert!(repositories[0].signed);
+        assert!(!repositories[2].signed);
+    }
+
+    #[test]
+    fn finds_held_packages_behind_unresolved_dependencies() {
+        let held: HashSet<String> = ["libfoo".to_string(), "firefox".to_string()].into();
+        let output = "\
+MISSING: libfoo>=2.0_1 (required by bar-1.1_1)
+MISSING: libfoo>=2.0_1 (required by baz-3.0_2)
+Transaction aborted due to unresolved dependencies.
+";
+        assert_eq!(
+            parse_held_blockers(output, &held),
+            vec![HeldBlocker {
+                package: "libfoo".to_string(),
+                required_by: vec!["bar".to_string(), "baz".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn ignores_held_packages_the_output_only_lists() {
+        let held: HashSet<String> = ["libfoo".to_string()].into();
+        let output = "libfoo-1.9_1 is up to date.\nbar-1.1_1 update\n";
+        assert!(parse_held_blockers(output, &held).is_empty());
+    }
+
+    #[test]
+    fn lists_every_package_xbps_remove_removed() {
+        let output = "\
+Name      Action    Version
+foo       remove    1.0_1
+libfoo    remove    2.0_1
+
+foo-1.0_1: removing ...
+foo-1.0_1: removed successfully.
+libfoo-2.0_1: removing ...
+libfoo-2.0_1: removed successfully.
+";
+        assert_eq!(
+            parse_removed_packages(output),
+            vec!["foo".to_string(), "libfoo".to_string()]
+        );
+    }
+
+    #[test]
+    fn understands_older_removal_messages() {
+        let output = "Removing `foo-1.0_1' ...\nRemoved `foo-1.0_1' successfully.\n";
+        assert_eq!(parse_removed_packages(output), vec!["foo".to_string()]);
+    }
+}
@@ -1,7 +1,142 @@
-use std::process::Command;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
 
 use crate::types::CommandResult;
 
+/// Renders the `pkexec` invocation for `program` the way a user would type
+/// it, so logs show exactly what Nebula ran.
+pub(crate) fn format_privileged_command(program: &str, args: &[&str]) -> String {
+    let mut parts = vec!["pkexec".to_string(), shell_quote(program)];
+    parts.extend(args.iter().map(|arg| shell_quote(arg)));
+    parts.join(" ")
+}
+
+/// Renders the command a user would type to do the same with `sudo` in a
+/// terminal. `-y` is left out so xbps still asks before changing anything.
+pub(crate) fn format_terminal_command(program: &str, args: &[&str]) -> String {
+    let mut parts = vec!["sudo".to_string(), shell_quote(program)];
+    parts.extend(
+        args.iter()
+            .filter(|arg| **arg != "-y")
+            .map(|arg| shell_quote(arg)),
+    );
+    parts.join(" ")
+}
+
+/// Quotes `value` for a POSIX shell, leaving plain words untouched.
+pub(crate) fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:=+,@%".contains(ch));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// True when polkit would let Nebula run `pkexec` without showing a
+/// password prompt, e.g. because of a rule granting the exec action.
+pub(crate) fn privilege_is_noninteractive() -> bool {
+    Command::new("pkcheck")
+        .args(["--action-id", "org.freedesktop.policykit.exec", "--process"])
+        .arg(std::process::id().to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Why a privileged command did not succeed, as far as pkexec tells us.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum PrivilegeFailure {
+    /// The password prompt was dismissed or authorization was refused.
+    Cancelled,
+    /// No polkit agent could show a prompt.
+    NoAgent,
+    /// pkexec itself could not be started, usually because it isn't
+    /// installed.
+    LaunchFailed,
+    /// Authorization worked but the command returned an error.
+    CommandFailed,
+}
+
+/// Sorts a failed privileged command into a `PrivilegeFailure`. Returns
+/// `None` when the command succeeded.
+pub(crate) fn classify_privilege_failure(
+    result: &Result<CommandResult, String>,
+) -> Option<PrivilegeFailure> {
+    let command = match result {
+        Ok(command) if command.success() => return None,
+        Ok(command) => command,
+        Err(err) if err.starts_with("Failed to launch pkexec") => {
+            return Some(PrivilegeFailure::LaunchFailed);
+        }
+        Err(_) => return Some(PrivilegeFailure::CommandFailed),
+    };
+
+    // pkexec exits with 126 or 127 when it never ran the command.
+    if !matches!(command.code, Some(126) | Some(127)) {
+        return Some(PrivilegeFailure::CommandFailed);
+    }
+    let stderr = command.stderr.to_lowercase();
+    if stderr.contains("no authentication agent") {
+        Some(PrivilegeFailure::NoAgent)
+    } else if stderr.contains("dismissed") || stderr.contains("not authorized") {
+        Some(PrivilegeFailure::Cancelled)
+    } else {
+        Some(PrivilegeFailure::CommandFailed)
+    }
+}
+
+/// Whether the command never ran because the prompt was dismissed or no
+/// agent could show it. The controller reports those itself, so callers skip
+/// their own error dialog.
+pub(crate) fn privilege_failure_reported(result: &Result<CommandResult, String>) -> bool {
+    matches!(
+        classify_privilege_failure(result),
+        Some(PrivilegeFailure::Cancelled | PrivilegeFailure::NoAgent)
+    )
+}
+
+/// Polkit agents shipped by Void packages, checked in order.
+const POLKIT_AGENTS: &[&str] = &[
+    "/usr/libexec/polkit-gnome-authentication-agent-1",
+    "/usr/libexec/polkit-mate-authentication-agent-1",
+    "/usr/libexec/polkit-kde-authentication-agent-1",
+    "/usr/libexec/xfce-polkit",
+    "/usr/libexec/hyprpolkitagent",
+    "/usr/bin/lxpolkit",
+    "/usr/bin/polkit-efl-authentication-agent-1",
+];
+
+/// An installed polkit agent that could be started for this session.
+pub(crate) fn find_polkit_agent() -> Option<PathBuf> {
+    POLKIT_AGENTS
+        .iter()
+        .map(Path::new)
+        .find(|path| path.is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Starts `agent` in the background. It keeps running after Nebula exits,
+/// like an agent launched from the session's autostart would.
+pub(crate) fn start_polkit_agent(agent: &Path) -> Result<(), String> {
+    Command::new(agent)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|err| format!("Failed to start {}: {}", agent.display(), err))
+}
+
 pub(crate) fn run_privileged_command(
     program: &str,
     args: &[&str],
@@ -18,3 +153,136 @@ pub(crate) fn run_privileged_command(
         stderr: String::from_utf8_lossy(&output.stderr).to_string(),
     })
 }
+
+/// Runs a privileged command and hands every stdout/stderr line to `on_line`
+/// as it arrives, while still collecting the full output for the result.
+pub(crate) fn run_privileged_command_streaming<F>(
+    program: &str,
+    args: &[&str],
+    mut on_line: F,
+) -> Result<CommandResult, String>
+where
+    F: FnMut(String),
+{
+    on_line(format_privileged_command(program, args));
+
+    let mut child = Command::new("pkexec")
+        .arg(program)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("Failed to launch pkexec: {}", err))?;
+
+    enum StreamEvent {
+        Stdout(String),
+        Stderr(String),
+    }
+
+    let (tx, rx) = mpsc::channel::<StreamEvent>();
+
+    if let Some(stdout) = child.stdout.take() {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let text = line.trim_end_matches('\r').to_string();
+                if !text.is_empty() {
+                    let _ = tx.send(StreamEvent::Stdout(text));
+                }
+            }
+        });
+    }
+
+    if let Some(stderr) = child.stderr.take() {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let text = line.trim_end_matches('\r').to_string();
+                if !text.is_empty() {
+                    let _ = tx.send(StreamEvent::Stderr(text));
+                }
+            }
+        });
+    }
+
+    drop(tx);
+
+    let mut stdout_accum = String::new();
+    let mut stderr_accum = String::new();
+
+    for event in rx {
+        let (accum, line) = match event {
+            StreamEvent::Stdout(line) => (&mut stdout_accum, line),
+            StreamEvent::Stderr(line) => (&mut stderr_accum, line),
+        };
+        if !accum.is_empty() {
+            accum.push('\n');
+        }
+        accum.push_str(&line);
+        on_line(line);
+    }
+
+    let status = child
+        .wait()
+        .map_err(|err| format!("Failed to wait for pkexec: {}", err))?;
+
+    Ok(CommandResult {
+        code: status.code(),
+        stdout: stdout_accum,
+        stderr: stderr_accum,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failed(code: i32, stderr: &str) -> Result<CommandResult, String> {
+        Ok(CommandResult {
+            code: Some(code),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        })
+    }
+
+    #[test]
+    fn classifies_pkexec_failures() {
+        assert_eq!(classify_privilege_failure(&failed(0, "")), None);
+        assert_eq!(
+            classify_privilege_failure(&failed(
+                127,
+                "Error executing command as another user: No authentication agent found."
+            )),
+            Some(PrivilegeFailure::NoAgent)
+        );
+        assert_eq!(
+            classify_privilege_failure(&failed(
+                126,
+                "Error executing command as another user: Request dismissed"
+            )),
+            Some(PrivilegeFailure::Cancelled)
+        );
+        assert_eq!(
+            classify_privilege_failure(&failed(1, "ERROR: foo: not authorized")),
+            Some(PrivilegeFailure::CommandFailed)
+        );
+        assert_eq!(
+            classify_privilege_failure(&Err("Failed to launch pkexec: not found".to_string())),
+            Some(PrivilegeFailure::LaunchFailed)
+        );
+    }
+
+    #[test]
+    fn terminal_command_uses_sudo_and_keeps_prompt() {
+        assert_eq!(
+            format_terminal_command("xbps-install", &["-y", "-S", "firefox"]),
+            "sudo xbps-install -S firefox"
+        );
+        assert_eq!(
+            format_terminal_command("xbps-remove", &["-y", "my pkg"]),
+            "sudo xbps-remove 'my pkg'"
+        );
+    }
+}